@@ -0,0 +1,136 @@
+//! Cancellation of in-flight agent runs.
+//!
+//! Each `process_message` call registers its run here for the duration of
+//! the call. A `/stop` command (or Ctrl-C in the CLI) signals the run, which
+//! notices at its next await point, drops whatever it was waiting on, and
+//! wraps up with a partial transcript.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::watch;
+
+/// Registry of the agent runs currently in flight, keyed by session.
+#[derive(Debug, Default)]
+pub(crate) struct ActiveRuns {
+    runs: Mutex<HashMap<String, watch::Sender<bool>>>,
+}
+
+impl ActiveRuns {
+    /// Register a run for `session_key`.
+    ///
+    /// Runs for one session are serialized by the session lock, so a new
+    /// registration simply replaces any stale entry. The run is removed
+    /// again when the returned handle is dropped.
+    pub(crate) fn begin(self: &Arc<Self>, session_key: &str) -> RunHandle {
+        let (tx, rx) = watch::channel(false);
+        self.lock().insert(session_key.to_string(), tx);
+        RunHandle {
+            runs: Arc::clone(self),
+            session_key: session_key.to_string(),
+            cancelled: rx,
+        }
+    }
+
+    /// Signal the run for `session_key` to stop.
+    ///
+    /// Returns `false` if no run is in flight for that session.
+    pub(crate) fn cancel(&self, session_key: &str) -> bool {
+        match self.lock().get(session_key) {
+            Some(tx) => {
+                tx.send_replace(true);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, watch::Sender<bool>>> {
+        self.runs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A registered run. Unregisters itself when dropped.
+#[derive(Debug)]
+pub(crate) struct RunHandle {
+    runs: Arc<ActiveRuns>,
+    session_key: String,
+    cancelled: watch::Receiver<bool>,
+}
+
+impl RunHandle {
+    /// Drive `fut` to completion unless the run is cancelled first.
+    ///
+    /// On cancellation `fut` is dropped, which kills any child processes
+    /// it spawned with `kill_on_drop`, and `None` is returned.
+    pub(crate) async fn until_cancelled<F: Future>(&self, fut: F) -> Option<F::Output> {
+        let mut cancelled = self.cancelled.clone();
+        tokio::select! {
+            biased;
+            Ok(_) = cancelled.wait_for(|c| *c) => None,
+            out = fut => Some(out),
+        }
+    }
+}
+
+impl Drop for RunHandle {
+    fn drop(&mut self) {
+        let mut runs = self.runs.lock();
+        // Only remove our own entry, never a newer run's.
+        if runs
+            .get(&self.session_key)
+            .is_some_and(|tx| self.cancelled.same_channel(&tx.subscribe()))
+        {
+            runs.remove(&self.session_key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cancel_interrupts_pending_future() {
+        let runs = Arc::new(ActiveRuns::default());
+        let run = runs.begin("s1");
+
+        let canceller = Arc::clone(&runs);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            assert!(canceller.cancel("s1"));
+        });
+
+        let out = run
+            .until_cancelled(tokio::time::sleep(Duration::from_secs(30)))
+            .await;
+        assert!(out.is_none());
+
+        // Later awaits in the same run are cancelled immediately.
+        assert!(run.until_cancelled(async { 1 }).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_uncancelled_future_completes() {
+        let runs = Arc::new(ActiveRuns::default());
+        let run = runs.begin("s1");
+        assert_eq!(run.until_cancelled(async { 7 }).await, Some(7));
+        assert!(!runs.cancel("other"));
+    }
+
+    #[test]
+    fn test_dropped_handle_unregisters() {
+        let runs = Arc::new(ActiveRuns::default());
+        let run = runs.begin("s1");
+        drop(run);
+        assert!(!runs.cancel("s1"));
+
+        // A stale handle does not remove a newer run.
+        let old = runs.begin("s2");
+        let _new = runs.begin("s2");
+        drop(old);
+        assert!(runs.cancel("s2"));
+    }
+}
@@ -55,6 +55,7 @@
 //! ```
 
 pub mod budget;
+mod cancel;
 pub mod compaction;
 mod context;
 pub mod context_monitor;
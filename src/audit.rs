@@ -23,6 +23,8 @@ pub enum AuditCategory {
     MountSecurity,
     /// Plugin integrity check failure.
     PluginIntegrity,
+    /// Outbound network request blocked by the egress policy.
+    NetworkEgress,
 }
 
 impl std::fmt::Display for AuditCategory {
@@ -35,6 +37,7 @@ impl std::fmt::Display for AuditCategory {
             Self::PathSecurity => write!(f, "path_security"),
             Self::MountSecurity => write!(f, "mount_security"),
             Self::PluginIntegrity => write!(f, "plugin_integrity"),
+            Self::NetworkEgress => write!(f, "network_egress"),
         }
     }
 }
@@ -129,6 +132,7 @@ mod tests {
             AuditCategory::PluginIntegrity.to_string(),
             "plugin_integrity"
         );
+        assert_eq!(AuditCategory::NetworkEgress.to_string(), "network_egress");
     }
 
     #[test]
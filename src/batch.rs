@@ -50,6 +50,9 @@ pub struct BatchResult {
     pub prompt: String,
     /// The response text, if successful.
     pub response: Option<String>,
+    /// The validated JSON answer, for runs with an output schema.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<serde_json::Value>,
     /// The error message, if failed.
     pub error: Option<String>,
     /// Time taken to process this prompt, in milliseconds.
@@ -213,6 +216,7 @@ mod tests {
             index: 0,
             prompt: "Hello".to_string(),
             response: Some("Hi there".to_string()),
+            output: None,
             error: None,
             duration_ms: 150,
         };
@@ -344,6 +348,7 @@ mod tests {
                 index: 0,
                 prompt: "What is Rust?".to_string(),
                 response: Some("A systems programming language.".to_string()),
+                output: None,
                 error: None,
                 duration_ms: 100,
             },
@@ -351,6 +356,7 @@ mod tests {
                 index: 1,
                 prompt: "What is Cargo?".to_string(),
                 response: Some("The Rust package manager.".to_string()),
+                output: None,
                 error: None,
                 duration_ms: 80,
             },
@@ -372,6 +378,7 @@ mod tests {
             index: 0,
             prompt: "Hello".to_string(),
             response: Some("Hi".to_string()),
+            output: None,
             error: None,
             duration_ms: 50,
         }];
@@ -385,12 +392,43 @@ mod tests {
         assert!(parsed.error.is_none());
     }
 
+    #[test]
+    fn test_format_results_jsonl_includes_structured_output() {
+        let results = vec![
+            BatchResult {
+                index: 0,
+                prompt: "Describe Alice".to_string(),
+                response: Some(r#"{"name":"Alice"}"#.to_string()),
+                output: Some(serde_json::json!({"name": "Alice"})),
+                error: None,
+                duration_ms: 50,
+            },
+            BatchResult {
+                index: 1,
+                prompt: "Hello".to_string(),
+                response: Some("Hi".to_string()),
+                output: None,
+                error: None,
+                duration_ms: 10,
+            },
+        ];
+
+        let output = format_results(&results, &BatchOutputFormat::Jsonl);
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines[0]["output"]["name"], "Alice");
+        assert!(lines[1].get("output").is_none());
+    }
+
     #[test]
     fn test_format_results_with_errors() {
         let results = vec![BatchResult {
             index: 0,
             prompt: "Bad prompt".to_string(),
             response: None,
+            output: None,
             error: Some("Provider timeout".to_string()),
             duration_ms: 30000,
         }];
@@ -6,6 +6,14 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Metadata key marking an inbound message as passive context: it is stored
+/// in the session but does not trigger an agent run.
+pub const PASSIVE_METADATA_KEY: &str = "passive";
+
+/// Metadata key asking the agent to publish the reply incrementally as
+/// partial outbound messages before the final one.
+pub const STREAM_METADATA_KEY: &str = "stream";
+
 /// Represents an incoming message from a channel (e.g., Telegram, Discord, etc.)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InboundMessage {
@@ -19,8 +27,16 @@ pub struct InboundMessage {
     pub content: String,
     /// Optional media attachment
     pub media: Option<MediaAttachment>,
-    /// Session key for routing (format: "channel:chat_id")
+    /// Further attachments after `media`, for messages that carry several
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_media: Vec<MediaAttachment>,
+    /// Session key for routing (format: "channel:chat_id" unless the channel
+    /// applies a narrower session scope)
     pub session_key: String,
+    /// Optional thread the message belongs to (e.g., Slack `thread_ts`,
+    /// Discord thread channel ID). Replies are routed back into this thread.
+    #[serde(default)]
+    pub thread_id: Option<String>,
     /// Additional metadata key-value pairs
     pub metadata: HashMap<String, String>,
 }
@@ -36,6 +52,16 @@ pub struct OutboundMessage {
     pub content: String,
     /// Optional message ID to reply to
     pub reply_to: Option<String>,
+    /// Optional thread to post into (mirrors `InboundMessage::thread_id`)
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    /// Optional media attachment, for channels that can send files
+    #[serde(default)]
+    pub media: Option<MediaAttachment>,
+    /// Incremental chunk of a streamed reply; the complete reply follows as
+    /// a normal message. Only sent for inbound messages that asked to stream.
+    #[serde(default)]
+    pub partial: bool,
 }
 
 /// Represents a media attachment (image, audio, video, or document)
@@ -89,13 +115,50 @@ impl InboundMessage {
             chat_id: chat_id.to_string(),
             content: content.to_string(),
             media: None,
+            extra_media: Vec::new(),
             session_key: format!("{}:{}", channel, chat_id),
+            thread_id: None,
             metadata: HashMap::new(),
         }
     }
 
+    /// Overrides the session key (builder pattern).
+    ///
+    /// Channels use this to apply a narrower session scope than the default
+    /// "channel:chat_id", e.g. one session per thread.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::bus::message::InboundMessage;
+    ///
+    /// let msg = InboundMessage::new("slack", "U1", "C1", "Hello")
+    ///     .with_session_key("slack:C1:1700000000.000100");
+    /// assert_eq!(msg.session_key, "slack:C1:1700000000.000100");
+    /// ```
+    pub fn with_session_key(mut self, session_key: &str) -> Self {
+        self.session_key = session_key.to_string();
+        self
+    }
+
+    /// Sets the thread this message belongs to (builder pattern).
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::bus::message::InboundMessage;
+    ///
+    /// let msg = InboundMessage::new("slack", "U1", "C1", "Hello")
+    ///     .with_thread("1700000000.000100");
+    /// assert_eq!(msg.thread_id.as_deref(), Some("1700000000.000100"));
+    /// ```
+    pub fn with_thread(mut self, thread_id: &str) -> Self {
+        self.thread_id = Some(thread_id.to_string());
+        self
+    }
+
     /// Attaches media to the message (builder pattern).
     ///
+    /// The first call sets `media`; later calls add to `extra_media`.
+    ///
     /// # Example
     /// ```
     /// use zeptoclaw::bus::message::{InboundMessage, MediaAttachment, MediaType};
@@ -106,10 +169,19 @@ impl InboundMessage {
     /// assert!(msg.media.is_some());
     /// ```
     pub fn with_media(mut self, media: MediaAttachment) -> Self {
-        self.media = Some(media);
+        if self.media.is_none() {
+            self.media = Some(media);
+        } else {
+            self.extra_media.push(media);
+        }
         self
     }
 
+    /// Iterates over every attachment: `media` first, then `extra_media`.
+    pub fn all_media(&self) -> impl Iterator<Item = &MediaAttachment> {
+        self.media.iter().chain(&self.extra_media)
+    }
+
     /// Adds a metadata key-value pair to the message (builder pattern).
     ///
     /// # Example
@@ -130,6 +202,73 @@ impl InboundMessage {
     pub fn has_media(&self) -> bool {
         self.media.is_some()
     }
+
+    /// Marks the message as passive context (builder pattern).
+    ///
+    /// Passive messages are recorded in the session without an LLM call, so
+    /// the agent has context from unaddressed group chatter.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::bus::message::InboundMessage;
+    ///
+    /// let msg = InboundMessage::new("telegram", "user1", "group1", "lunch?").into_passive();
+    /// assert!(msg.is_passive());
+    /// ```
+    pub fn into_passive(self) -> Self {
+        self.with_metadata(PASSIVE_METADATA_KEY, "true")
+    }
+
+    /// Checks if this message is passive context only.
+    pub fn is_passive(&self) -> bool {
+        self.metadata
+            .get(PASSIVE_METADATA_KEY)
+            .is_some_and(|v| v == "true")
+    }
+
+    /// Checks if this message is the `/stop` command, which cancels the
+    /// session's in-flight agent run. Telegram's `/stop@botname` form is
+    /// accepted too.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::bus::message::InboundMessage;
+    ///
+    /// let msg = InboundMessage::new("telegram", "user1", "chat1", "/stop@zepto_bot");
+    /// assert!(msg.is_stop_command());
+    /// ```
+    pub fn is_stop_command(&self) -> bool {
+        let content = self.content.trim();
+        let command = content.split_once('@').map_or(content, |(c, _)| c);
+        !self.is_passive() && command.eq_ignore_ascii_case("/stop")
+    }
+
+    /// Asks the agent to stream its reply as partial outbound messages
+    /// (builder pattern).
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::bus::message::InboundMessage;
+    ///
+    /// let msg = InboundMessage::new("webhook", "svc", "job-1", "hi").into_streaming();
+    /// assert!(msg.wants_streaming());
+    /// ```
+    pub fn into_streaming(self) -> Self {
+        self.with_metadata(STREAM_METADATA_KEY, "true")
+    }
+
+    /// Checks if the sender asked for a streamed reply.
+    pub fn wants_streaming(&self) -> bool {
+        self.metadata
+            .get(STREAM_METADATA_KEY)
+            .is_some_and(|v| v == "true")
+    }
+
+    /// Formats a passive message for the session transcript, attributing it
+    /// to its sender since group chats have many participants.
+    pub fn passive_context(&self) -> String {
+        format!("[{}] {}", self.sender_id, self.content)
+    }
 }
 
 impl OutboundMessage {
@@ -153,6 +292,9 @@ impl OutboundMessage {
             chat_id: chat_id.to_string(),
             content: content.to_string(),
             reply_to: None,
+            thread_id: None,
+            media: None,
+            partial: false,
         }
     }
 
@@ -171,8 +313,56 @@ impl OutboundMessage {
         self
     }
 
+    /// Sets the thread to post into (builder pattern).
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::bus::message::OutboundMessage;
+    ///
+    /// let msg = OutboundMessage::new("slack", "C1", "In thread").with_thread("1700000000.000100");
+    /// assert_eq!(msg.thread_id.as_deref(), Some("1700000000.000100"));
+    /// ```
+    pub fn with_thread(mut self, thread_id: &str) -> Self {
+        self.thread_id = Some(thread_id.to_string());
+        self
+    }
+
+    /// Attaches media to the message (builder pattern).
+    ///
+    /// Channels without media support send only the text content.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::bus::message::{MediaAttachment, MediaType, OutboundMessage};
+    ///
+    /// let media = MediaAttachment::new(MediaType::Image).with_url("mxc://example.org/abc");
+    /// let msg = OutboundMessage::new("matrix", "!room:example.org", "Chart").with_media(media);
+    /// assert!(msg.media.is_some());
+    /// ```
+    pub fn with_media(mut self, media: MediaAttachment) -> Self {
+        self.media = Some(media);
+        self
+    }
+
+    /// Marks the message as a partial chunk of a streamed reply (builder pattern).
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::bus::message::OutboundMessage;
+    ///
+    /// let chunk = OutboundMessage::new("webhook", "job-1", "Hel").into_partial();
+    /// assert!(chunk.partial);
+    /// ```
+    pub fn into_partial(mut self) -> Self {
+        self.partial = true;
+        self
+    }
+
     /// Creates an outbound message as a response to an inbound message.
     ///
+    /// The response is posted into the same thread as the inbound message,
+    /// if it had one.
+    ///
     /// # Example
     /// ```
     /// use zeptoclaw::bus::message::{InboundMessage, OutboundMessage};
@@ -183,7 +373,9 @@ impl OutboundMessage {
     /// assert_eq!(response.chat_id, "chat456");
     /// ```
     pub fn reply_to(msg: &InboundMessage, content: &str) -> Self {
-        Self::new(&msg.channel, &msg.chat_id, content)
+        let mut outbound = Self::new(&msg.channel, &msg.chat_id, content);
+        outbound.thread_id = msg.thread_id.clone();
+        outbound
     }
 }
 
@@ -225,6 +417,47 @@ impl MediaAttachment {
     pub fn has_data(&self) -> bool {
         self.data.is_some()
     }
+
+    /// Best-effort MIME type for images and PDFs, from the filename
+    /// extension or, failing that, the leading bytes of the data.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::bus::{MediaAttachment, MediaType};
+    ///
+    /// let doc = MediaAttachment::new(MediaType::Document).with_filename("report.PDF");
+    /// assert_eq!(doc.mime_type(), Some("application/pdf"));
+    /// ```
+    pub fn mime_type(&self) -> Option<&'static str> {
+        let by_extension = self
+            .filename
+            .as_deref()
+            .and_then(|name| name.rsplit_once('.'))
+            .and_then(|(_, ext)| match ext.to_ascii_lowercase().as_str() {
+                "png" => Some("image/png"),
+                "jpg" | "jpeg" => Some("image/jpeg"),
+                "gif" => Some("image/gif"),
+                "webp" => Some("image/webp"),
+                "pdf" => Some("application/pdf"),
+                _ => None,
+            });
+        by_extension.or_else(|| {
+            let data = self.data.as_deref()?;
+            if data.starts_with(b"\x89PNG") {
+                Some("image/png")
+            } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+                Some("image/jpeg")
+            } else if data.starts_with(b"GIF8") {
+                Some("image/gif")
+            } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+                Some("image/webp")
+            } else if data.starts_with(b"%PDF") {
+                Some("application/pdf")
+            } else {
+                None
+            }
+        })
+    }
 }
 
 #[cfg(test)]
@@ -276,6 +509,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_inbound_message_passive() {
+        let msg = InboundMessage::new("discord", "user1", "ch1", "just chatting");
+        assert!(!msg.is_passive());
+
+        let passive = msg.into_passive();
+        assert!(passive.is_passive());
+        assert_eq!(passive.passive_context(), "[user1] just chatting");
+    }
+
+    #[test]
+    fn test_inbound_message_stop_command() {
+        assert!(InboundMessage::new("telegram", "u", "c", " /stop ").is_stop_command());
+        assert!(InboundMessage::new("telegram", "u", "c", "/STOP").is_stop_command());
+        assert!(!InboundMessage::new("telegram", "u", "c", "/stop now").is_stop_command());
+        assert!(!InboundMessage::new("telegram", "u", "c", "please stop").is_stop_command());
+        assert!(!InboundMessage::new("telegram", "u", "c", "/stop")
+            .into_passive()
+            .is_stop_command());
+    }
+
     #[test]
     fn test_outbound_message_creation() {
         let msg = OutboundMessage::new("telegram", "chat456", "Response");
@@ -301,6 +555,18 @@ mod tests {
         assert_eq!(response.channel, "telegram");
         assert_eq!(response.chat_id, "chat456");
         assert_eq!(response.content, "Hello back!");
+        assert!(response.thread_id.is_none());
+    }
+
+    #[test]
+    fn test_outbound_reply_to_inbound_keeps_thread() {
+        let inbound = InboundMessage::new("slack", "U1", "C1", "Hello")
+            .with_thread("1700000000.000100")
+            .with_session_key("slack:C1:1700000000.000100");
+        let response = OutboundMessage::reply_to(&inbound, "In thread");
+
+        assert_eq!(response.chat_id, "C1");
+        assert_eq!(response.thread_id.as_deref(), Some("1700000000.000100"));
     }
 
     #[test]
@@ -316,6 +582,20 @@ mod tests {
         assert_eq!(media.filename, Some("audio.mp3".to_string()));
     }
 
+    #[test]
+    fn test_media_attachment_mime_type_sniffs_data() {
+        let photo = MediaAttachment::new(MediaType::Image).with_data(vec![0xFF, 0xD8, 0xFF, 0xE0]);
+        assert_eq!(photo.mime_type(), Some("image/jpeg"));
+
+        let named = MediaAttachment::new(MediaType::Image)
+            .with_filename("scan.png")
+            .with_data(b"%PDF-1.7".to_vec());
+        assert_eq!(named.mime_type(), Some("image/png"));
+
+        let voice = MediaAttachment::new(MediaType::Audio).with_data(vec![1, 2, 3]);
+        assert_eq!(voice.mime_type(), None);
+    }
+
     #[test]
     fn test_media_type_equality() {
         assert_eq!(MediaType::Image, MediaType::Image);
@@ -337,6 +617,13 @@ mod tests {
         assert_eq!(deserialized.metadata.get("key"), Some(&"value".to_string()));
     }
 
+    #[test]
+    fn test_message_deserialization_without_thread_id() {
+        let json = r#"{"channel":"telegram","sender_id":"u","chat_id":"c","content":"hi","media":null,"session_key":"telegram:c","metadata":{}}"#;
+        let msg: InboundMessage = serde_json::from_str(json).expect("Failed to deserialize");
+        assert!(msg.thread_id.is_none());
+    }
+
     #[test]
     fn test_outbound_message_serialization() {
         let msg =
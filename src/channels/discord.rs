@@ -13,13 +13,28 @@
 //! 5. Start a periodic heartbeat task (opcode 1).
 //! 6. Listen for opcode 0 (DISPATCH) events, specifically `MESSAGE_CREATE`.
 //! 7. Reconnect with exponential backoff on disconnection.
+//!
+//! # Threads
+//!
+//! Discord threads are channels of their own. The gateway loop records each
+//! thread's parent channel (from `GUILD_CREATE`, `THREAD_CREATE`, etc.) so
+//! that thread messages can be scoped to per-thread sessions. In
+//! `session_scope = "thread"` mode, a top-level guild message starts a new
+//! thread from that message for the reply.
+//!
+//! # Group policy
+//!
+//! Guild messages pass through the configured `group_policy`. The bot's user
+//! ID is learned from `READY` so that `<@id>` mentions and replies to the bot
+//! count as addressing it.
 
 use async_trait::async_trait;
 use futures::{SinkExt, StreamExt};
 use serde::Deserialize;
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 use tokio::sync::watch;
 use tokio_tungstenite::connect_async;
@@ -27,7 +42,7 @@ use tokio_tungstenite::tungstenite::Message as WsMessage;
 use tracing::{debug, error, info, warn};
 
 use crate::bus::{InboundMessage, MessageBus, OutboundMessage};
-use crate::config::DiscordConfig;
+use crate::config::{DiscordConfig, SessionScope};
 use crate::error::{Result, ZeptoError};
 
 use super::{BaseChannelConfig, Channel};
@@ -53,6 +68,15 @@ const GATEWAY_INTENTS: u64 = (1 << 0) | (1 << 9) | (1 << 12) | (1 << 15);
 /// Discord message content length limit.
 const DISCORD_MAX_MESSAGE_LENGTH: usize = 2000;
 
+/// Discord thread name length limit.
+const DISCORD_MAX_THREAD_NAME_LENGTH: usize = 100;
+
+/// Channel types that are threads (announcement, public and private threads).
+const DISCORD_THREAD_CHANNEL_TYPES: [u64; 3] = [10, 11, 12];
+
+/// Known thread channel IDs mapped to their parent channel IDs.
+type ThreadParents = Arc<RwLock<HashMap<String, String>>>;
+
 // ---------------------------------------------------------------------------
 // Gateway payload types (deserialization)
 // ---------------------------------------------------------------------------
@@ -87,10 +111,25 @@ struct MessageCreateData {
     content: String,
     /// The Discord channel ID this message was sent in.
     channel_id: String,
+    /// The guild (server) ID; absent for direct messages.
+    #[serde(default)]
+    guild_id: Option<String>,
     /// The message author.
     author: MessageAuthor,
     /// The unique message ID.
     id: String,
+    /// Users mentioned in the message.
+    #[serde(default)]
+    mentions: Vec<MessageAuthor>,
+    /// The message this one replies to, if any.
+    #[serde(default)]
+    referenced_message: Option<Box<ReferencedMessage>>,
+}
+
+/// The message a reply refers to; only its author is needed.
+#[derive(Debug, Deserialize)]
+struct ReferencedMessage {
+    author: MessageAuthor,
 }
 
 /// Author of a Discord message.
@@ -103,6 +142,29 @@ struct MessageAuthor {
     bot: Option<bool>,
 }
 
+/// Minimal Discord channel object, as sent in thread events and returned
+/// when starting a thread.
+#[derive(Debug, Deserialize)]
+struct ChannelObject {
+    id: String,
+    #[serde(rename = "type", default)]
+    channel_type: u64,
+    #[serde(default)]
+    parent_id: Option<String>,
+}
+
+/// Where an outbound message should be posted.
+#[derive(Debug, PartialEq, Eq)]
+enum SendTarget {
+    /// Post directly to this channel (or thread) ID.
+    Channel(String),
+    /// Start a thread from `message_id` in `channel_id`, then post into it.
+    StartThread {
+        channel_id: String,
+        message_id: String,
+    },
+}
+
 /// Response from GET /gateway.
 #[derive(Debug, Deserialize)]
 struct GatewayResponse {
@@ -122,6 +184,7 @@ pub struct DiscordChannel {
     running: Arc<AtomicBool>,
     shutdown_tx: Option<watch::Sender<bool>>,
     http_client: reqwest::Client,
+    thread_parents: ThreadParents,
 }
 
 impl DiscordChannel {
@@ -131,6 +194,8 @@ impl DiscordChannel {
             name: "discord".to_string(),
             allowlist: config.allow_from.clone(),
             deny_by_default: config.deny_by_default,
+            session_scope: config.session_scope,
+            group_policy: config.group_policy.clone(),
         };
 
         Self {
@@ -140,6 +205,7 @@ impl DiscordChannel {
             running: Arc::new(AtomicBool::new(false)),
             shutdown_tx: None,
             http_client: reqwest::Client::new(),
+            thread_parents: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -235,10 +301,15 @@ impl DiscordChannel {
     /// Parses a MESSAGE_CREATE dispatch event into an `InboundMessage`,
     /// returning `None` if the message should be ignored (bot author, empty
     /// content, disallowed user, etc.).
+    ///
+    /// `thread_parents` maps known thread channel IDs to their parent channel
+    /// and is used to apply the configured session scope. `bot_user_id` (from
+    /// READY) is used to detect mentions for the guild group policy.
     fn parse_message_create(
         data: &Value,
-        allowlist: &[String],
-        deny_by_default: bool,
+        base_config: &BaseChannelConfig,
+        thread_parents: &HashMap<String, String>,
+        bot_user_id: Option<&str>,
     ) -> Option<InboundMessage> {
         let msg: MessageCreateData = serde_json::from_value(data.clone()).ok()?;
 
@@ -247,7 +318,13 @@ impl DiscordChannel {
             return None;
         }
 
-        let content = msg.content.trim().to_string();
+        let addressed = bot_user_id.is_some_and(|bot_id| Self::addresses_bot(&msg, bot_id));
+        let content = match bot_user_id {
+            Some(bot_id) if addressed => Self::strip_bot_mention(&msg.content, bot_id),
+            _ => msg.content.clone(),
+        }
+        .trim()
+        .to_string();
         if content.is_empty() {
             return None;
         }
@@ -258,12 +335,7 @@ impl DiscordChannel {
         }
 
         // Allowlist check with deny_by_default support.
-        let allowed = if allowlist.is_empty() {
-            !deny_by_default
-        } else {
-            allowlist.contains(&sender_id)
-        };
-        if !allowed {
+        if !base_config.is_allowed(&sender_id) {
             info!(
                 "Discord: user {} not in allowlist, ignoring message",
                 sender_id
@@ -276,12 +348,171 @@ impl DiscordChannel {
             return None;
         }
 
-        let inbound = InboundMessage::new("discord", &sender_id, &channel_id, &content)
+        let parent_id = thread_parents.get(&channel_id);
+        let thread_id = match (parent_id, base_config.session_scope) {
+            // Already inside a thread: replies stay in it.
+            (Some(_), _) => Some(channel_id.as_str()),
+            // Thread scope: a top-level guild message starts a thread from itself.
+            // Discord gives such threads the same ID as the starter message.
+            (None, SessionScope::Thread) if msg.guild_id.is_some() => Some(msg.id.as_str()),
+            (None, _) => None,
+        };
+        let session_key = match base_config.session_scope {
+            SessionScope::Thread => {
+                base_config.session_key(parent_id.unwrap_or(&channel_id), thread_id, &sender_id)
+            }
+            _ => base_config.session_key(&channel_id, thread_id, &sender_id),
+        };
+
+        let mut inbound = InboundMessage::new("discord", &sender_id, &channel_id, &content)
+            .with_session_key(&session_key)
             .with_metadata("discord_message_id", &msg.id);
+        if let Some(thread_id) = thread_id {
+            inbound = inbound.with_thread(thread_id);
+        }
+        if let Some(parent_id) = parent_id {
+            inbound = inbound.with_metadata("discord_parent_channel_id", parent_id);
+        }
+
+        // Direct messages carry no guild ID and are never gated.
+        if msg.guild_id.is_some() {
+            return base_config.filter_group_message(inbound, addressed);
+        }
 
         Some(inbound)
     }
 
+    /// Whether a message mentions the bot or replies to one of its messages.
+    fn addresses_bot(msg: &MessageCreateData, bot_id: &str) -> bool {
+        msg.mentions.iter().any(|user| user.id == bot_id)
+            || msg.content.contains(&format!("<@{}>", bot_id))
+            || msg.content.contains(&format!("<@!{}>", bot_id))
+            || msg
+                .referenced_message
+                .as_ref()
+                .is_some_and(|referenced| referenced.author.id == bot_id)
+    }
+
+    /// Removes `<@id>` / `<@!id>` mentions of the bot from message content.
+    fn strip_bot_mention(content: &str, bot_id: &str) -> String {
+        content
+            .replace(&format!("<@{}>", bot_id), "")
+            .replace(&format!("<@!{}>", bot_id), "")
+    }
+
+    /// Records thread channels announced by gateway events so thread messages
+    /// can be mapped back to their parent channel.
+    fn track_thread_event(event_name: &str, data: &Value, thread_parents: &ThreadParents) {
+        let threads: Vec<&Value> = match event_name {
+            "THREAD_CREATE" | "THREAD_UPDATE" => vec![data],
+            "GUILD_CREATE" | "THREAD_LIST_SYNC" => data
+                .get("threads")
+                .and_then(Value::as_array)
+                .map(|threads| threads.iter().collect())
+                .unwrap_or_default(),
+            "THREAD_DELETE" => {
+                if let Some(id) = data.get("id").and_then(Value::as_str) {
+                    if let Ok(mut parents) = thread_parents.write() {
+                        parents.remove(id);
+                    }
+                }
+                return;
+            }
+            _ => return,
+        };
+
+        let Ok(mut parents) = thread_parents.write() else {
+            return;
+        };
+        for thread in threads {
+            let Ok(channel) = serde_json::from_value::<ChannelObject>(thread.clone()) else {
+                continue;
+            };
+            if !DISCORD_THREAD_CHANNEL_TYPES.contains(&channel.channel_type) {
+                continue;
+            }
+            if let Some(parent_id) = channel.parent_id {
+                parents.insert(channel.id, parent_id);
+            }
+        }
+    }
+
+    /// Decides where an outbound message goes based on its thread, if any.
+    fn resolve_send_target(
+        msg: &OutboundMessage,
+        thread_parents: &HashMap<String, String>,
+    ) -> SendTarget {
+        let channel_id = msg.chat_id.trim();
+        match msg.thread_id.as_deref().map(str::trim) {
+            Some(thread_id)
+                if !thread_id.is_empty()
+                    && thread_id != channel_id
+                    && !thread_parents.contains_key(thread_id) =>
+            {
+                SendTarget::StartThread {
+                    channel_id: channel_id.to_string(),
+                    message_id: thread_id.to_string(),
+                }
+            }
+            Some(thread_id) if !thread_id.is_empty() => SendTarget::Channel(thread_id.to_string()),
+            _ => SendTarget::Channel(channel_id.to_string()),
+        }
+    }
+
+    /// Derives a thread name from the reply content (first non-empty line).
+    fn thread_name(content: &str) -> String {
+        let first_line = content
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or("ZeptoClaw");
+        first_line
+            .chars()
+            .take(DISCORD_MAX_THREAD_NAME_LENGTH)
+            .collect()
+    }
+
+    /// Starts a thread from an existing message and returns the thread ID.
+    async fn start_thread(
+        &self,
+        token: &str,
+        channel_id: &str,
+        message_id: &str,
+        name: &str,
+    ) -> Result<String> {
+        let url = format!(
+            "{}/channels/{}/messages/{}/threads",
+            DISCORD_API_BASE, channel_id, message_id
+        );
+        let response = self
+            .http_client
+            .post(&url)
+            .header("Authorization", format!("Bot {}", token))
+            .json(&json!({ "name": name }))
+            .send()
+            .await
+            .map_err(|e| ZeptoError::Channel(format!("Failed to start Discord thread: {}", e)))?;
+
+        let status = response.status();
+        let body = response.text().await.map_err(|e| {
+            ZeptoError::Channel(format!("Failed to read Discord thread response: {}", e))
+        })?;
+        if !status.is_success() {
+            return Err(ZeptoError::Channel(format!(
+                "Discord thread creation returned HTTP {}: {}",
+                status, body
+            )));
+        }
+
+        let thread: ChannelObject = serde_json::from_str(&body).map_err(|e| {
+            ZeptoError::Channel(format!("Invalid Discord thread response JSON: {}", e))
+        })?;
+        if let Ok(mut parents) = self.thread_parents.write() {
+            parents.insert(thread.id.clone(), channel_id.to_string());
+        }
+        Ok(thread.id)
+    }
+
     /// Calculates the exponential backoff delay for a given attempt number.
     fn backoff_delay(attempt: u32) -> Duration {
         let delay_secs = BASE_RECONNECT_DELAY_SECS
@@ -338,11 +569,13 @@ impl DiscordChannel {
         client: reqwest::Client,
         token: String,
         bus: Arc<MessageBus>,
-        allowlist: Vec<String>,
-        deny_by_default: bool,
+        base_config: BaseChannelConfig,
+        thread_parents: ThreadParents,
         mut shutdown_rx: watch::Receiver<bool>,
     ) {
         let mut reconnect_attempt: u32 = 0;
+        // Learned from READY; used for mention gating in guild channels.
+        let mut bot_user_id: Option<String> = None;
 
         loop {
             // Check shutdown before each connection attempt.
@@ -555,9 +788,16 @@ impl DiscordChannel {
                                                 if let Some(event_name) = payload.t.as_deref() {
                                                     if event_name == "MESSAGE_CREATE" {
                                                         if let Some(ref data) = payload.d {
-                                                            if let Some(inbound) =
-                                                                Self::parse_message_create(data, &allowlist, deny_by_default)
-                                                            {
+                                                            let parsed = match thread_parents.read() {
+                                                                Ok(parents) => Self::parse_message_create(
+                                                                    data,
+                                                                    &base_config,
+                                                                    &parents,
+                                                                    bot_user_id.as_deref(),
+                                                                ),
+                                                                Err(_) => None,
+                                                            };
+                                                            if let Some(inbound) = parsed {
                                                                 if let Err(e) =
                                                                     bus.publish_inbound(inbound).await
                                                                 {
@@ -570,6 +810,18 @@ impl DiscordChannel {
                                                         }
                                                     } else if event_name == "READY" {
                                                         info!("Discord gateway READY");
+                                                        bot_user_id = payload
+                                                            .d
+                                                            .as_ref()
+                                                            .and_then(|d| d.pointer("/user/id"))
+                                                            .and_then(Value::as_str)
+                                                            .map(str::to_string);
+                                                    } else if event_name.starts_with("THREAD_")
+                                                        || event_name == "GUILD_CREATE"
+                                                    {
+                                                        if let Some(ref data) = payload.d {
+                                                            Self::track_thread_event(event_name, data, &thread_parents);
+                                                        }
                                                     } else {
                                                         debug!("Discord: ignoring event {}", event_name);
                                                     }
@@ -684,8 +936,8 @@ impl Channel for DiscordChannel {
             self.http_client.clone(),
             token,
             Arc::clone(&self.bus),
-            self.config.allow_from.clone(),
-            self.config.deny_by_default,
+            self.base_config.clone(),
+            Arc::clone(&self.thread_parents),
             shutdown_rx,
         ));
 
@@ -726,7 +978,30 @@ impl Channel for DiscordChannel {
         }
 
         let payload = Self::build_send_payload(&msg)?;
-        let url = format!("{}/channels/{}/messages", DISCORD_API_BASE, channel_id);
+        let target = match self.thread_parents.read() {
+            Ok(parents) => Self::resolve_send_target(&msg, &parents),
+            Err(_) => SendTarget::Channel(channel_id.to_string()),
+        };
+        let target_id = match target {
+            SendTarget::Channel(id) => id,
+            SendTarget::StartThread {
+                channel_id,
+                message_id,
+            } => {
+                let name = Self::thread_name(&msg.content);
+                match self
+                    .start_thread(token, &channel_id, &message_id, &name)
+                    .await
+                {
+                    Ok(thread_id) => thread_id,
+                    Err(e) => {
+                        warn!("Discord: {}; replying in channel instead", e);
+                        channel_id
+                    }
+                }
+            }
+        };
+        let url = format!("{}/channels/{}/messages", DISCORD_API_BASE, target_id);
 
         let response = self
             .http_client
@@ -769,11 +1044,16 @@ impl Channel for DiscordChannel {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::GroupPolicy;
 
     fn test_bus() -> Arc<MessageBus> {
         Arc::new(MessageBus::new())
     }
 
+    fn test_base() -> BaseChannelConfig {
+        BaseChannelConfig::new("discord")
+    }
+
     fn test_config() -> DiscordConfig {
         DiscordConfig {
             enabled: true,
@@ -869,7 +1149,8 @@ mod tests {
             }
         });
 
-        let inbound = DiscordChannel::parse_message_create(&data, &[], false);
+        let inbound =
+            DiscordChannel::parse_message_create(&data, &test_base(), &HashMap::new(), None);
         assert!(inbound.is_some());
         let msg = inbound.unwrap();
         assert_eq!(msg.channel, "discord");
@@ -891,15 +1172,326 @@ mod tests {
             "author": { "id": "allowed-user", "bot": false }
         });
 
-        let allowed =
-            DiscordChannel::parse_message_create(&data, &["allowed-user".to_string()], false);
+        let allowed = DiscordChannel::parse_message_create(
+            &data,
+            &BaseChannelConfig::with_allowlist("discord", vec!["allowed-user".to_string()]),
+            &HashMap::new(),
+            None,
+        );
         assert!(allowed.is_some());
 
-        let denied =
-            DiscordChannel::parse_message_create(&data, &["someone-else".to_string()], false);
+        let denied = DiscordChannel::parse_message_create(
+            &data,
+            &BaseChannelConfig::with_allowlist("discord", vec!["someone-else".to_string()]),
+            &HashMap::new(),
+            None,
+        );
         assert!(denied.is_none());
     }
 
+    fn thread_base(session_scope: SessionScope) -> BaseChannelConfig {
+        BaseChannelConfig {
+            name: "discord".to_string(),
+            session_scope,
+            ..Default::default()
+        }
+    }
+
+    fn thread_parents(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(thread, parent)| (thread.to_string(), parent.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_message_create_chat_scope_keeps_channel_session() {
+        let data = json!({
+            "id": "msg-1",
+            "content": "hi",
+            "channel_id": "thread-9",
+            "guild_id": "g-1",
+            "author": { "id": "user-1" }
+        });
+
+        let inbound = DiscordChannel::parse_message_create(
+            &data,
+            &test_base(),
+            &thread_parents(&[("thread-9", "ch-100")]),
+            None,
+        )
+        .unwrap();
+        assert_eq!(inbound.chat_id, "thread-9");
+        assert_eq!(inbound.session_key, "discord:thread-9");
+        assert_eq!(inbound.thread_id.as_deref(), Some("thread-9"));
+        assert_eq!(
+            inbound.metadata.get("discord_parent_channel_id"),
+            Some(&"ch-100".to_string())
+        );
+    }
+
+    #[test]
+    fn test_message_create_thread_scope_top_level_starts_thread() {
+        let data = json!({
+            "id": "msg-1",
+            "content": "hi",
+            "channel_id": "ch-100",
+            "guild_id": "g-1",
+            "author": { "id": "user-1" }
+        });
+
+        let inbound = DiscordChannel::parse_message_create(
+            &data,
+            &thread_base(SessionScope::Thread),
+            &HashMap::new(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(inbound.chat_id, "ch-100");
+        assert_eq!(inbound.thread_id.as_deref(), Some("msg-1"));
+        assert_eq!(inbound.session_key, "discord:ch-100:msg-1");
+    }
+
+    #[test]
+    fn test_message_create_thread_scope_follow_up_shares_session() {
+        // Threads started from a message share the starter message's ID.
+        let data = json!({
+            "id": "msg-2",
+            "content": "follow up",
+            "channel_id": "msg-1",
+            "guild_id": "g-1",
+            "author": { "id": "user-2" }
+        });
+
+        let inbound = DiscordChannel::parse_message_create(
+            &data,
+            &thread_base(SessionScope::Thread),
+            &thread_parents(&[("msg-1", "ch-100")]),
+            None,
+        )
+        .unwrap();
+        assert_eq!(inbound.chat_id, "msg-1");
+        assert_eq!(inbound.thread_id.as_deref(), Some("msg-1"));
+        assert_eq!(inbound.session_key, "discord:ch-100:msg-1");
+    }
+
+    #[test]
+    fn test_message_create_thread_scope_dm_has_no_thread() {
+        let data = json!({
+            "id": "msg-1",
+            "content": "hi",
+            "channel_id": "dm-1",
+            "author": { "id": "user-1" }
+        });
+
+        let inbound = DiscordChannel::parse_message_create(
+            &data,
+            &thread_base(SessionScope::Thread),
+            &HashMap::new(),
+            None,
+        )
+        .unwrap();
+        assert!(inbound.thread_id.is_none());
+        assert_eq!(inbound.session_key, "discord:dm-1");
+    }
+
+    #[test]
+    fn test_message_create_user_scope() {
+        let data = json!({
+            "id": "msg-1",
+            "content": "hi",
+            "channel_id": "ch-100",
+            "guild_id": "g-1",
+            "author": { "id": "user-7" }
+        });
+
+        let inbound = DiscordChannel::parse_message_create(
+            &data,
+            &thread_base(SessionScope::User),
+            &HashMap::new(),
+            None,
+        )
+        .unwrap();
+        assert!(inbound.thread_id.is_none());
+        assert_eq!(inbound.session_key, "discord:ch-100:user-7");
+    }
+
+    fn mention_gated() -> BaseChannelConfig {
+        BaseChannelConfig {
+            name: "discord".to_string(),
+            group_policy: GroupPolicy {
+                require_mention: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn guild_message(content: &str) -> Value {
+        json!({
+            "id": "msg-1",
+            "content": content,
+            "channel_id": "ch-100",
+            "guild_id": "g-1",
+            "author": { "id": "user-7" }
+        })
+    }
+
+    #[test]
+    fn test_message_create_mention_gate_ignores_unaddressed() {
+        let data = guild_message("talking amongst ourselves");
+        let inbound = DiscordChannel::parse_message_create(
+            &data,
+            &mention_gated(),
+            &HashMap::new(),
+            Some("bot-1"),
+        );
+        assert!(inbound.is_none());
+    }
+
+    #[test]
+    fn test_message_create_mention_gate_strips_mention() {
+        for content in ["<@bot-1> status?", "<@!bot-1> status?"] {
+            let inbound = DiscordChannel::parse_message_create(
+                &guild_message(content),
+                &mention_gated(),
+                &HashMap::new(),
+                Some("bot-1"),
+            )
+            .expect("mentioned message should pass");
+            assert_eq!(inbound.content, "status?");
+        }
+    }
+
+    #[test]
+    fn test_message_create_mention_gate_accepts_reply_to_bot() {
+        let mut data = guild_message("thanks, and the other one?");
+        data["referenced_message"] = json!({ "author": { "id": "bot-1", "bot": true } });
+
+        let inbound = DiscordChannel::parse_message_create(
+            &data,
+            &mention_gated(),
+            &HashMap::new(),
+            Some("bot-1"),
+        );
+        assert!(inbound.is_some());
+    }
+
+    #[test]
+    fn test_message_create_mention_gate_skips_direct_messages() {
+        let data = json!({
+            "id": "msg-1",
+            "content": "hello",
+            "channel_id": "dm-1",
+            "author": { "id": "user-7" }
+        });
+
+        let inbound = DiscordChannel::parse_message_create(
+            &data,
+            &mention_gated(),
+            &HashMap::new(),
+            Some("bot-1"),
+        );
+        assert!(inbound.is_some());
+    }
+
+    #[test]
+    fn test_message_create_passive_capture() {
+        let mut base = mention_gated();
+        base.group_policy.passive_capture = true;
+
+        let inbound = DiscordChannel::parse_message_create(
+            &guild_message("background chatter"),
+            &base,
+            &HashMap::new(),
+            Some("bot-1"),
+        )
+        .expect("passive message expected");
+        assert!(inbound.is_passive());
+    }
+
+    #[test]
+    fn test_track_thread_events() {
+        let parents: ThreadParents = Arc::new(RwLock::new(HashMap::new()));
+
+        DiscordChannel::track_thread_event(
+            "THREAD_CREATE",
+            &json!({ "id": "t-1", "type": 11, "parent_id": "ch-1" }),
+            &parents,
+        );
+        DiscordChannel::track_thread_event(
+            "GUILD_CREATE",
+            &json!({
+                "id": "g-1",
+                "threads": [
+                    { "id": "t-2", "type": 12, "parent_id": "ch-2" },
+                    { "id": "not-a-thread", "type": 0, "parent_id": "cat-1" }
+                ]
+            }),
+            &parents,
+        );
+        {
+            let map = parents.read().unwrap();
+            assert_eq!(map.get("t-1"), Some(&"ch-1".to_string()));
+            assert_eq!(map.get("t-2"), Some(&"ch-2".to_string()));
+            assert!(!map.contains_key("not-a-thread"));
+        }
+
+        DiscordChannel::track_thread_event(
+            "THREAD_DELETE",
+            &json!({ "id": "t-1", "type": 11, "parent_id": "ch-1" }),
+            &parents,
+        );
+        assert!(!parents.read().unwrap().contains_key("t-1"));
+    }
+
+    #[test]
+    fn test_resolve_send_target() {
+        let parents = thread_parents(&[("t-1", "ch-100")]);
+
+        let plain = OutboundMessage::new("discord", "ch-100", "hi");
+        assert_eq!(
+            DiscordChannel::resolve_send_target(&plain, &parents),
+            SendTarget::Channel("ch-100".to_string())
+        );
+
+        let known_thread = OutboundMessage::new("discord", "ch-100", "hi").with_thread("t-1");
+        assert_eq!(
+            DiscordChannel::resolve_send_target(&known_thread, &parents),
+            SendTarget::Channel("t-1".to_string())
+        );
+
+        let in_thread = OutboundMessage::new("discord", "t-9", "hi").with_thread("t-9");
+        assert_eq!(
+            DiscordChannel::resolve_send_target(&in_thread, &parents),
+            SendTarget::Channel("t-9".to_string())
+        );
+
+        let new_thread = OutboundMessage::new("discord", "ch-100", "hi").with_thread("msg-1");
+        assert_eq!(
+            DiscordChannel::resolve_send_target(&new_thread, &parents),
+            SendTarget::StartThread {
+                channel_id: "ch-100".to_string(),
+                message_id: "msg-1".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_thread_name_from_content() {
+        assert_eq!(
+            DiscordChannel::thread_name("\n  First line  \nsecond"),
+            "First line"
+        );
+        assert_eq!(DiscordChannel::thread_name(""), "ZeptoClaw");
+        assert_eq!(
+            DiscordChannel::thread_name(&"x".repeat(300))
+                .chars()
+                .count(),
+            DISCORD_MAX_THREAD_NAME_LENGTH
+        );
+    }
+
     // -----------------------------------------------------------------------
     // 6. Heartbeat interval extraction from HELLO payload
     // -----------------------------------------------------------------------
@@ -932,7 +1524,8 @@ mod tests {
             "author": { "id": "bot-user", "bot": true }
         });
 
-        let result = DiscordChannel::parse_message_create(&data, &[], false);
+        let result =
+            DiscordChannel::parse_message_create(&data, &test_base(), &HashMap::new(), None);
         assert!(result.is_none());
     }
 
@@ -945,7 +1538,8 @@ mod tests {
             "author": { "id": "user-1", "bot": false }
         });
 
-        let result = DiscordChannel::parse_message_create(&data, &[], false);
+        let result =
+            DiscordChannel::parse_message_create(&data, &test_base(), &HashMap::new(), None);
         assert!(result.is_none());
     }
 
@@ -958,7 +1552,8 @@ mod tests {
             "author": { "id": "user-2" }
         });
 
-        let result = DiscordChannel::parse_message_create(&data, &[], false);
+        let result =
+            DiscordChannel::parse_message_create(&data, &test_base(), &HashMap::new(), None);
         assert!(result.is_some());
     }
 
@@ -1236,7 +1831,8 @@ mod tests {
             "author": { "id": "  ", "bot": false }
         });
 
-        let result = DiscordChannel::parse_message_create(&data, &[], false);
+        let result =
+            DiscordChannel::parse_message_create(&data, &test_base(), &HashMap::new(), None);
         // Empty (whitespace-only) sender_id should be rejected.
         assert!(result.is_none());
     }
@@ -1250,7 +1846,8 @@ mod tests {
             "author": { "id": "user-42", "bot": false }
         });
 
-        let result = DiscordChannel::parse_message_create(&data, &[], false);
+        let result =
+            DiscordChannel::parse_message_create(&data, &test_base(), &HashMap::new(), None);
         // Empty content should be filtered out.
         assert!(result.is_none());
     }
@@ -1264,7 +1861,8 @@ mod tests {
             "author": { "id": "user-42", "bot": false }
         });
 
-        let result = DiscordChannel::parse_message_create(&data, &[], false);
+        let result =
+            DiscordChannel::parse_message_create(&data, &test_base(), &HashMap::new(), None);
         // Empty (whitespace-only) channel_id should be rejected.
         assert!(result.is_none());
     }
@@ -1278,7 +1876,9 @@ mod tests {
             "author": { "id": "user-1" }
         });
 
-        let inbound = DiscordChannel::parse_message_create(&data, &[], false).unwrap();
+        let inbound =
+            DiscordChannel::parse_message_create(&data, &test_base(), &HashMap::new(), None)
+                .unwrap();
         assert_eq!(inbound.content, "padded message");
     }
 
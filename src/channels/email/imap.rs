@@ -0,0 +1,250 @@
+//! Minimal IMAP4rev1 client covering what the email channel needs:
+//! login, select, searching for unseen mail, fetching, flagging and IDLE.
+
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tracing::{debug, warn};
+
+use crate::error::{Result, ZeptoError};
+
+use super::transport::{self, BoxedStream};
+
+/// Response to a tagged command: the untagged lines plus any literals
+/// (`{n}` payloads such as message bodies), in order of arrival. Literals
+/// over the client's limit are discarded unread.
+#[derive(Debug, Default)]
+pub(super) struct ImapResponse {
+    pub untagged: Vec<String>,
+    pub literals: Vec<Vec<u8>>,
+}
+
+pub(super) struct ImapClient {
+    stream: BufReader<BoxedStream>,
+    next_tag: u32,
+    capabilities: Vec<String>,
+    /// Largest literal kept in memory, in bytes.
+    max_literal: usize,
+}
+
+impl ImapClient {
+    /// Connects and waits for the server greeting.
+    pub async fn connect(host: &str, port: u16, tls: bool) -> Result<Self> {
+        let stream = transport::connect(host, port, tls).await?;
+        let mut client = Self {
+            stream: BufReader::new(stream),
+            next_tag: 0,
+            capabilities: Vec::new(),
+            max_literal: usize::MAX,
+        };
+
+        let greeting = client.read_line().await?;
+        if !greeting.starts_with("* OK") && !greeting.starts_with("* PREAUTH") {
+            return Err(ZeptoError::Channel(format!(
+                "Unexpected IMAP greeting: {}",
+                greeting.trim_end()
+            )));
+        }
+        Ok(client)
+    }
+
+    /// Caps the size of literals read into memory (builder pattern).
+    pub fn with_max_literal(mut self, bytes: usize) -> Self {
+        self.max_literal = bytes;
+        self
+    }
+
+    /// Logs in and refreshes the capability list.
+    pub async fn login(&mut self, username: &str, password: &str) -> Result<()> {
+        self.command(&format!("LOGIN {} {}", quote(username), quote(password)))
+            .await?;
+
+        let response = self.command("CAPABILITY").await?;
+        self.capabilities = response
+            .untagged
+            .iter()
+            .filter_map(|line| line.strip_prefix("* CAPABILITY "))
+            .flat_map(|caps| caps.split_whitespace())
+            .map(str::to_ascii_uppercase)
+            .collect();
+        Ok(())
+    }
+
+    /// Whether the server advertised `capability` (e.g. `IDLE`).
+    pub fn supports(&self, capability: &str) -> bool {
+        self.capabilities
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(capability))
+    }
+
+    pub async fn select(&mut self, mailbox: &str) -> Result<()> {
+        self.command(&format!("SELECT {}", quote(mailbox)))
+            .await
+            .map(|_| ())
+    }
+
+    /// Returns the UIDs of all messages without the `\Seen` flag.
+    pub async fn search_unseen(&mut self) -> Result<Vec<u32>> {
+        let response = self.command("UID SEARCH UNSEEN").await?;
+        Ok(response
+            .untagged
+            .iter()
+            .filter_map(|line| line.strip_prefix("* SEARCH"))
+            .flat_map(|ids| ids.split_whitespace())
+            .filter_map(|id| id.parse().ok())
+            .collect())
+    }
+
+    /// Fetches the raw RFC 5322 message without setting `\Seen`.
+    ///
+    /// Returns `None` when the message is gone or larger than the literal cap.
+    pub async fn fetch(&mut self, uid: u32) -> Result<Option<Vec<u8>>> {
+        let mut response = self
+            .command(&format!("UID FETCH {} BODY.PEEK[]", uid))
+            .await?;
+        Ok(response.literals.pop())
+    }
+
+    pub async fn mark_seen(&mut self, uid: u32) -> Result<()> {
+        self.command(&format!("UID STORE {} +FLAGS.SILENT (\\Seen)", uid))
+            .await
+            .map(|_| ())
+    }
+
+    /// Waits in IDLE until the server reports new mail or `timeout` elapses.
+    ///
+    /// Returns `true` when new mail was signalled.
+    pub async fn idle(&mut self, timeout: Duration) -> Result<bool> {
+        let tag = self.send_command("IDLE").await?;
+        loop {
+            let line = self.read_line().await?;
+            if line.starts_with('+') {
+                break;
+            }
+            if line.starts_with(&tag) {
+                return Err(ZeptoError::Channel(format!(
+                    "IMAP IDLE rejected: {}",
+                    line.trim_end()
+                )));
+            }
+        }
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut new_mail = false;
+        while !new_mail {
+            // `fill_buf` does not consume input, so timing it out loses nothing.
+            match tokio::time::timeout_at(deadline, self.stream.fill_buf()).await {
+                Err(_) => break,
+                Ok(Err(e)) => {
+                    return Err(ZeptoError::Channel(format!("IMAP read failed: {}", e)));
+                }
+                Ok(Ok(_)) => {}
+            }
+            let line = self.read_line().await?;
+            debug!("IMAP IDLE: {}", line.trim_end());
+            new_mail = line.starts_with("* ")
+                && (line.trim_end().ends_with(" EXISTS") || line.trim_end().ends_with(" RECENT"));
+        }
+
+        self.stream.write_all(b"DONE\r\n").await?;
+        self.stream.flush().await?;
+        self.read_response(&tag).await?;
+        Ok(new_mail)
+    }
+
+    async fn command(&mut self, command: &str) -> Result<ImapResponse> {
+        let tag = self.send_command(command).await?;
+        self.read_response(&tag).await
+    }
+
+    async fn send_command(&mut self, command: &str) -> Result<String> {
+        self.next_tag += 1;
+        let tag = format!("z{}", self.next_tag);
+        self.stream
+            .write_all(format!("{} {}\r\n", tag, command).as_bytes())
+            .await?;
+        self.stream.flush().await?;
+        Ok(tag)
+    }
+
+    /// Reads until the tagged completion for `tag`, failing on `NO`/`BAD`.
+    async fn read_response(&mut self, tag: &str) -> Result<ImapResponse> {
+        let mut response = ImapResponse::default();
+        loop {
+            let line = self.read_line().await?;
+            if let Some(status) = line.strip_prefix(tag).and_then(|s| s.strip_prefix(' ')) {
+                return if status.starts_with("OK") {
+                    Ok(response)
+                } else {
+                    Err(ZeptoError::Channel(format!(
+                        "IMAP command failed: {}",
+                        status.trim_end()
+                    )))
+                };
+            }
+            if line.starts_with('*') {
+                response.untagged.push(line.trim_end().to_string());
+            }
+            if let Some(size) = literal_size(&line) {
+                if size > self.max_literal {
+                    warn!(
+                        "IMAP: skipping {} byte literal (limit {})",
+                        size, self.max_literal
+                    );
+                    let mut literal = (&mut self.stream).take(size as u64);
+                    let skipped = tokio::io::copy(&mut literal, &mut tokio::io::sink()).await?;
+                    if skipped < size as u64 {
+                        return Err(ZeptoError::Channel(
+                            "IMAP server closed the connection".to_string(),
+                        ));
+                    }
+                    continue;
+                }
+                let mut literal = vec![0u8; size];
+                self.stream.read_exact(&mut literal).await?;
+                response.literals.push(literal);
+            }
+        }
+    }
+
+    async fn read_line(&mut self) -> Result<String> {
+        let mut buf = Vec::new();
+        let n = self.stream.read_until(b'\n', &mut buf).await?;
+        if n == 0 {
+            return Err(ZeptoError::Channel(
+                "IMAP server closed the connection".to_string(),
+            ));
+        }
+        Ok(String::from_utf8_lossy(&buf).to_string())
+    }
+}
+
+/// Returns `n` if the line ends with a literal announcement `{n}`.
+fn literal_size(line: &str) -> Option<usize> {
+    let line = line.trim_end();
+    let start = line.rfind('{')?;
+    line.strip_suffix('}')?[start + 1..].parse().ok()
+}
+
+/// Formats an IMAP quoted string.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal_size() {
+        assert_eq!(literal_size("* 1 FETCH (UID 7 BODY[] {342}\r\n"), Some(342));
+        assert_eq!(literal_size("* 1 FETCH (FLAGS (\\Seen))\r\n"), None);
+        assert_eq!(literal_size("{abc}"), None);
+    }
+
+    #[test]
+    fn test_quote_escapes() {
+        assert_eq!(quote("INBOX"), "\"INBOX\"");
+        assert_eq!(quote(r#"pa"ss\word"#), r#""pa\"ss\\word""#);
+    }
+}
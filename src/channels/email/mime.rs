@@ -0,0 +1,730 @@
+//! Just enough RFC 5322 / MIME parsing for inbound mail: headers with
+//! encoded words, multipart bodies, transfer encodings, attachments, and
+//! stripping of quoted replies and signatures.
+
+use std::collections::HashMap;
+
+use base64::Engine;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::bus::{MediaAttachment, MediaType};
+
+static ENCODED_WORD_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"=\?([^?]+)\?([BbQq])\?([^?]*)\?=").unwrap());
+static ADJACENT_ENCODED_WORDS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\?=)\s+(=\?)").unwrap());
+static MESSAGE_ID_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^<>\s]+>").unwrap());
+static HTML_BREAK_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)<br\s*/?>|</p>|</div>|</li>|</tr>").unwrap());
+static HTML_DROP_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?is)<(style|script|head)[^>]*>.*?</(style|script|head)>").unwrap());
+static HTML_TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<[^>]*>").unwrap());
+static HEADER_COMMENT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\([^()]*\)").unwrap());
+
+/// The parts of an inbound email the channel cares about.
+#[derive(Debug, Default)]
+pub(super) struct ParsedEmail {
+    /// Sender address, lowercased, without display name.
+    pub from: String,
+    pub from_name: Option<String>,
+    pub subject: String,
+    /// `Message-ID`, including angle brackets.
+    pub message_id: Option<String>,
+    pub in_reply_to: Option<String>,
+    /// `References`, oldest first.
+    pub references: Vec<String>,
+    /// Plain-text body (HTML converted to text when there is no text part).
+    pub text: String,
+    pub attachments: Vec<MediaAttachment>,
+    /// Set for auto-replies, bounces and list traffic that must not be answered.
+    pub automated: bool,
+    /// `Authentication-Results` values, topmost (most recently added) first.
+    pub auth_results: Vec<String>,
+}
+
+impl ParsedEmail {
+    /// The `Message-ID` of the first message in the thread this one belongs to.
+    pub fn thread_root(&self) -> Option<&str> {
+        self.references
+            .first()
+            .or(self.in_reply_to.as_ref())
+            .or(self.message_id.as_ref())
+            .map(String::as_str)
+    }
+
+    /// Whether a trusted server vouched for the `From:` address.
+    ///
+    /// Only the topmost `Authentication-Results` header whose authserv-id is
+    /// in `trusted` counts: the receiving server prepends its own and should
+    /// strip forged copies carrying its id (RFC 8601 §5). The sender passes
+    /// when that header reports `dmarc=pass` for the `From:` domain, or
+    /// `dkim=pass` for a signing domain aligned with it.
+    pub fn sender_authenticated(&self, trusted: &[String]) -> bool {
+        let Some((_, domain)) = self.from.rsplit_once('@') else {
+            return false;
+        };
+        let Some(results) = self.auth_results.iter().find_map(|value| {
+            let (authserv_id, results) = split_auth_results(value);
+            trusted
+                .iter()
+                .any(|id| id.trim().eq_ignore_ascii_case(&authserv_id))
+                .then_some(results)
+        }) else {
+            return false;
+        };
+
+        let aligned = |d: &str| domain == d || domain.ends_with(&format!(".{}", d));
+        results.iter().any(|result| {
+            if result.result != "pass" {
+                return false;
+            }
+            match result.method.as_str() {
+                "dmarc" => result.property("header.from").is_some_and(|d| d == domain),
+                "dkim" => result
+                    .property("header.d")
+                    .or_else(|| {
+                        result
+                            .property("header.i")
+                            .and_then(|i| i.rsplit_once('@').map(|(_, d)| d))
+                    })
+                    .is_some_and(aligned),
+                _ => false,
+            }
+        })
+    }
+}
+
+/// One `method=result` entry of an `Authentication-Results` header.
+#[derive(Debug)]
+struct AuthResult {
+    method: String,
+    result: String,
+    /// `ptype.property=value` pairs, lowercased.
+    properties: Vec<(String, String)>,
+}
+
+impl AuthResult {
+    fn property(&self, name: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Splits an `Authentication-Results` value into its authserv-id and results.
+fn split_auth_results(value: &str) -> (String, Vec<AuthResult>) {
+    let value = HEADER_COMMENT_RE
+        .replace_all(value, " ")
+        .to_ascii_lowercase();
+    let mut statements = value.split(';');
+    let authserv_id = statements
+        .next()
+        .and_then(|s| s.split_whitespace().next())
+        .unwrap_or_default()
+        .to_string();
+    let results = statements
+        .filter_map(|statement| {
+            let mut tokens = statement.split_whitespace();
+            let (method, result) = tokens.next()?.split_once('=')?;
+            let properties = tokens
+                .filter_map(|t| t.split_once('='))
+                .map(|(k, v)| (k.to_string(), v.trim_matches('"').to_string()))
+                .collect();
+            Some(AuthResult {
+                method: method.split('/').next().unwrap_or(method).to_string(),
+                result: result.to_string(),
+                properties,
+            })
+        })
+        .collect();
+    (authserv_id, results)
+}
+
+/// Parses a raw RFC 5322 message.
+pub(super) fn parse_email(raw: &[u8]) -> ParsedEmail {
+    let (header_bytes, body) = split_header_body(raw);
+    let headers = parse_headers(header_bytes);
+
+    let (from_name, from) = header(&headers, "from")
+        .map(parse_address)
+        .unwrap_or_default();
+    let automated = header(&headers, "auto-submitted")
+        .is_some_and(|v| !v.eq_ignore_ascii_case("no"))
+        || header(&headers, "precedence").is_some_and(|v| {
+            matches!(
+                v.to_ascii_lowercase().as_str(),
+                "bulk" | "list" | "junk" | "auto_reply"
+            )
+        })
+        || header(&headers, "list-id").is_some()
+        || from.starts_with("mailer-daemon@")
+        || from.starts_with("postmaster@");
+
+    let mut parts = BodyParts::default();
+    collect_parts(&headers, body, &mut parts);
+    let text = match (parts.plain, parts.html) {
+        (Some(plain), _) => plain,
+        (None, Some(html)) => html_to_text(&html),
+        (None, None) => String::new(),
+    };
+
+    ParsedEmail {
+        from,
+        from_name,
+        subject: header(&headers, "subject")
+            .map(decode_encoded_words)
+            .unwrap_or_default(),
+        message_id: header(&headers, "message-id").and_then(|v| parse_message_ids(v).pop()),
+        in_reply_to: header(&headers, "in-reply-to").and_then(|v| parse_message_ids(v).pop()),
+        references: header(&headers, "references")
+            .map(parse_message_ids)
+            .unwrap_or_default(),
+        text,
+        attachments: parts.attachments,
+        automated,
+        auth_results: headers
+            .iter()
+            .filter(|(name, _)| name == "authentication-results")
+            .map(|(_, value)| value.clone())
+            .collect(),
+    }
+}
+
+/// Removes quoted history and the signature from a reply body.
+///
+/// Cuts at the first reply header (`On ... wrote:`, Outlook's
+/// `-----Original Message-----` / `From:`+`Sent:` block) or signature
+/// delimiter (`-- `), and drops `>`-quoted lines.
+pub(super) fn strip_quoted_reply(text: &str) -> String {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let mut kept: Vec<&str> = Vec::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        let next = lines.get(i + 1).copied().unwrap_or("");
+        let is_reply_header = (line.starts_with("On ")
+            && (line.ends_with("wrote:") || next.trim().ends_with("wrote:")))
+            || line.starts_with("-----Original Message-----")
+            || line.starts_with("________________________________")
+            || (line.starts_with("From: ") && next.starts_with("Sent: "));
+        if *line == "--" || is_reply_header {
+            break;
+        }
+        if line.starts_with('>') {
+            continue;
+        }
+        kept.push(line);
+    }
+
+    // Mobile clients append a one-line signature without a delimiter.
+    while kept
+        .last()
+        .is_some_and(|l| l.trim().is_empty() || l.starts_with("Sent from my "))
+    {
+        kept.pop();
+    }
+    kept.join("\n").trim().to_string()
+}
+
+// ---------------------------------------------------------------------------
+// Headers
+// ---------------------------------------------------------------------------
+
+type Headers = Vec<(String, String)>;
+
+fn split_header_body(raw: &[u8]) -> (&[u8], &[u8]) {
+    if let Some(pos) = raw.windows(4).position(|w| w == b"\r\n\r\n") {
+        (&raw[..pos], &raw[pos + 4..])
+    } else if let Some(pos) = raw.windows(2).position(|w| w == b"\n\n") {
+        (&raw[..pos], &raw[pos + 2..])
+    } else {
+        (raw, &[])
+    }
+}
+
+/// Parses a header block, unfolding continuation lines. Names are lowercased.
+fn parse_headers(bytes: &[u8]) -> Headers {
+    let text = String::from_utf8_lossy(bytes);
+    let mut headers: Headers = Vec::new();
+    for line in text.lines() {
+        if line.starts_with(' ') || line.starts_with('\t') {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    headers
+}
+
+fn header<'a>(headers: &'a Headers, name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+/// Splits `"Name" <addr@example.com>` into display name and lowercased address.
+fn parse_address(value: &str) -> (Option<String>, String) {
+    let decoded = decode_encoded_words(value);
+    match (decoded.find('<'), decoded.rfind('>')) {
+        (Some(start), Some(end)) if start < end => {
+            let name = decoded[..start].trim().trim_matches('"').trim();
+            (
+                (!name.is_empty()).then(|| name.to_string()),
+                decoded[start + 1..end].trim().to_ascii_lowercase(),
+            )
+        }
+        _ => (None, decoded.trim().to_ascii_lowercase()),
+    }
+}
+
+fn parse_message_ids(value: &str) -> Vec<String> {
+    MESSAGE_ID_RE
+        .find_iter(value)
+        .map(|m| m.as_str().to_string())
+        .collect()
+}
+
+/// Decodes RFC 2047 encoded words (`=?utf-8?B?...?=` / `=?utf-8?Q?...?=`).
+///
+/// Control characters smuggled in through an encoded word (e.g. `=0D=0A`)
+/// are replaced with spaces so decoded values can never span lines.
+fn decode_encoded_words(value: &str) -> String {
+    let joined = ADJACENT_ENCODED_WORDS_RE.replace_all(value, "$1$2");
+    ENCODED_WORD_RE
+        .replace_all(&joined, |caps: &regex::Captures| {
+            let bytes = if caps[2].eq_ignore_ascii_case("b") {
+                base64::engine::general_purpose::STANDARD
+                    .decode(caps[3].as_bytes())
+                    .unwrap_or_default()
+            } else {
+                decode_quoted_printable(caps[3].replace('_', " ").as_bytes())
+            };
+            decode_charset(&bytes, &caps[1])
+        })
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Bodies
+// ---------------------------------------------------------------------------
+
+#[derive(Default)]
+struct BodyParts {
+    plain: Option<String>,
+    html: Option<String>,
+    attachments: Vec<MediaAttachment>,
+}
+
+/// Parses `type/subtype; key=value; ...` into the lowercased MIME type and
+/// its parameters (keys lowercased, RFC 2231 `key*` values decoded).
+fn parse_content_params(value: &str) -> (String, HashMap<String, String>) {
+    let mut segments = value.split(';');
+    let mime = segments.next().unwrap_or("").trim().to_ascii_lowercase();
+    let params = segments
+        .filter_map(|segment| {
+            let (key, value) = segment.split_once('=')?;
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim().trim_matches('"');
+            Some(match key.strip_suffix('*') {
+                // RFC 2231: charset'language'percent-encoded
+                Some(base) => {
+                    let encoded = value.splitn(3, '\'').last().unwrap_or(value);
+                    (base.to_string(), percent_decode(encoded))
+                }
+                None => (key, decode_encoded_words(value)),
+            })
+        })
+        .collect();
+    (mime, params)
+}
+
+fn collect_parts(headers: &Headers, body: &[u8], parts: &mut BodyParts) {
+    let (mime, params) =
+        parse_content_params(header(headers, "content-type").unwrap_or("text/plain"));
+
+    if mime.starts_with("multipart/") {
+        if let Some(boundary) = params.get("boundary") {
+            for part in split_multipart(body, boundary) {
+                let (part_headers, part_body) = split_header_body(part);
+                collect_parts(&parse_headers(part_headers), part_body, parts);
+            }
+        }
+        return;
+    }
+
+    let encoding = header(headers, "content-transfer-encoding").unwrap_or("7bit");
+    let data = decode_transfer_encoding(body, encoding);
+    let (disposition, disposition_params) =
+        parse_content_params(header(headers, "content-disposition").unwrap_or(""));
+    let filename = disposition_params
+        .get("filename")
+        .or_else(|| params.get("name"))
+        .cloned();
+
+    let is_attachment = disposition == "attachment"
+        || (filename.is_some() && !mime.starts_with("text/"))
+        || !(mime.starts_with("text/") || mime.starts_with("message/"));
+    if is_attachment {
+        let media_type = match mime.split('/').next() {
+            Some("image") => MediaType::Image,
+            Some("audio") => MediaType::Audio,
+            Some("video") => MediaType::Video,
+            _ => MediaType::Document,
+        };
+        let mut attachment = MediaAttachment::new(media_type).with_data(data);
+        if let Some(ref filename) = filename {
+            attachment = attachment.with_filename(filename);
+        }
+        parts.attachments.push(attachment);
+        return;
+    }
+
+    let charset = params.get("charset").map(String::as_str).unwrap_or("utf-8");
+    let text = decode_charset(&data, charset);
+    match mime.as_str() {
+        "text/html" if parts.html.is_none() => parts.html = Some(text),
+        "text/plain" if parts.plain.is_none() => parts.plain = Some(text),
+        _ => {}
+    }
+}
+
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let delimiter = delimiter.as_bytes();
+    let mut parts = Vec::new();
+    let mut current: Option<usize> = None;
+    let mut offset = 0;
+
+    while offset < body.len() {
+        let line_end = body[offset..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map(|p| offset + p + 1)
+            .unwrap_or(body.len());
+        let line = trim_line_ending(&body[offset..line_end]);
+
+        if line.starts_with(delimiter) {
+            if let Some(start) = current {
+                // The CRLF before a delimiter belongs to the delimiter.
+                parts.push(trim_line_ending(&body[start..offset]));
+            }
+            if line[delimiter.len()..].starts_with(b"--") {
+                return parts;
+            }
+            current = Some(line_end);
+        }
+        offset = line_end;
+    }
+    if let Some(start) = current {
+        parts.push(&body[start..]);
+    }
+    parts
+}
+
+fn trim_line_ending(bytes: &[u8]) -> &[u8] {
+    let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+    bytes.strip_suffix(b"\r").unwrap_or(bytes)
+}
+
+fn decode_transfer_encoding(body: &[u8], encoding: &str) -> Vec<u8> {
+    match encoding.trim().to_ascii_lowercase().as_str() {
+        "base64" => {
+            let compact: Vec<u8> = body
+                .iter()
+                .copied()
+                .filter(|b| !b.is_ascii_whitespace())
+                .collect();
+            base64::engine::general_purpose::STANDARD
+                .decode(&compact)
+                .unwrap_or_default()
+        }
+        "quoted-printable" => decode_quoted_printable(body),
+        _ => body.to_vec(),
+    }
+}
+
+fn decode_quoted_printable(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        if input[i] != b'=' {
+            out.push(input[i]);
+            i += 1;
+            continue;
+        }
+        match (input.get(i + 1), input.get(i + 2)) {
+            // Soft line break.
+            (Some(b'\r'), Some(b'\n')) => i += 3,
+            (Some(b'\n'), _) => i += 2,
+            (Some(&hi), Some(&lo)) => match hex_pair(hi, lo) {
+                Some(byte) => {
+                    out.push(byte);
+                    i += 3;
+                }
+                None => {
+                    out.push(b'=');
+                    i += 1;
+                }
+            },
+            _ => {
+                out.push(b'=');
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+fn hex_pair(hi: u8, lo: u8) -> Option<u8> {
+    let hex = [hi, lo];
+    u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() + 1 {
+            if let Some(byte) = bytes
+                .get(i + 1..i + 3)
+                .and_then(|pair| hex_pair(pair[0], pair[1]))
+            {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// Decodes text in UTF-8 or Latin-1; other charsets are decoded as lossy UTF-8.
+fn decode_charset(bytes: &[u8], charset: &str) -> String {
+    match charset.trim().to_ascii_lowercase().as_str() {
+        "iso-8859-1" | "latin1" | "windows-1252" => bytes.iter().map(|&b| b as char).collect(),
+        _ => String::from_utf8_lossy(bytes).to_string(),
+    }
+}
+
+fn html_to_text(html: &str) -> String {
+    let without_blocks = HTML_DROP_RE.replace_all(html, "");
+    let with_breaks = HTML_BREAK_RE.replace_all(&without_blocks, "\n");
+    let text = HTML_TAG_RE.replace_all(&with_breaks, "");
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_simple_message() {
+        let raw = b"From: \"Alice Example\" <Alice@Example.com>\r\n\
+To: bot@example.com\r\n\
+Subject: Quarterly\r\n numbers\r\n\
+Message-ID: <abc@example.com>\r\n\
+\r\n\
+Hi there,\r\nplease help.\r\n";
+        let email = parse_email(raw);
+
+        assert_eq!(email.from, "alice@example.com");
+        assert_eq!(email.from_name.as_deref(), Some("Alice Example"));
+        assert_eq!(email.subject, "Quarterly numbers");
+        assert_eq!(email.message_id.as_deref(), Some("<abc@example.com>"));
+        assert_eq!(email.thread_root(), Some("<abc@example.com>"));
+        assert_eq!(email.text, "Hi there,\r\nplease help.\r\n");
+        assert!(!email.automated);
+    }
+
+    #[test]
+    fn test_thread_root_prefers_first_reference() {
+        let raw = b"From: a@example.com\r\n\
+Message-ID: <3@x>\r\n\
+In-Reply-To: <2@x>\r\n\
+References: <1@x>\r\n <2@x>\r\n\
+\r\nbody";
+        let email = parse_email(raw);
+        assert_eq!(email.references, vec!["<1@x>", "<2@x>"]);
+        assert_eq!(email.in_reply_to.as_deref(), Some("<2@x>"));
+        assert_eq!(email.thread_root(), Some("<1@x>"));
+    }
+
+    #[test]
+    fn test_encoded_words_and_quoted_printable() {
+        let raw = b"From: =?UTF-8?B?SsO8cmdlbg==?= <j@example.com>\r\n\
+Subject: =?utf-8?Q?Caf=C3=A9?= =?utf-8?Q?_menu?=\r\n\
+Content-Type: text/plain; charset=utf-8\r\n\
+Content-Transfer-Encoding: quoted-printable\r\n\
+\r\n\
+Cr=C3=A8me br=C3=BBl=\r\n\
+=C3=A9e";
+        let email = parse_email(raw);
+        assert_eq!(email.from_name.as_deref(), Some("Jürgen"));
+        assert_eq!(email.subject, "Café menu");
+        assert_eq!(email.text, "Crème brûlée");
+    }
+
+    #[test]
+    fn test_encoded_words_cannot_smuggle_line_breaks() {
+        let raw = b"From: =?utf-8?Q?Eve=0D=0A?= <eve@example.com>\r\n\
+Subject: =?utf-8?Q?hi=0D=0ABcc:_x@evil?=\r\n\r\nbody";
+        let parsed = parse_email(raw);
+        assert_eq!(parsed.subject, "hi  Bcc: x@evil");
+        assert_eq!(parsed.from_name.as_deref(), Some("Eve"));
+        assert_eq!(parsed.from, "eve@example.com");
+    }
+
+    #[test]
+    fn test_multipart_with_attachment() {
+        let raw = b"From: a@example.com\r\n\
+Content-Type: multipart/mixed; boundary=\"outer\"\r\n\
+\r\n\
+preamble\r\n\
+--outer\r\n\
+Content-Type: multipart/alternative; boundary=inner\r\n\
+\r\n\
+--inner\r\n\
+Content-Type: text/plain\r\n\
+\r\n\
+Plain version\r\n\
+--inner\r\n\
+Content-Type: text/html\r\n\
+\r\n\
+<p>HTML version</p>\r\n\
+--inner--\r\n\
+--outer\r\n\
+Content-Type: image/png; name=\"chart.png\"\r\n\
+Content-Disposition: attachment; filename=\"chart.png\"\r\n\
+Content-Transfer-Encoding: base64\r\n\
+\r\n\
+AQID\r\n\
+BA==\r\n\
+--outer--\r\n";
+        let email = parse_email(raw);
+
+        assert_eq!(email.text, "Plain version");
+        assert_eq!(email.attachments.len(), 1);
+        let attachment = &email.attachments[0];
+        assert_eq!(attachment.media_type, MediaType::Image);
+        assert_eq!(attachment.filename.as_deref(), Some("chart.png"));
+        assert_eq!(attachment.data.as_deref(), Some(&[1u8, 2, 3, 4][..]));
+    }
+
+    #[test]
+    fn test_html_only_body() {
+        let raw = b"From: a@example.com\r\n\
+Content-Type: text/html; charset=utf-8\r\n\
+\r\n\
+<html><head><style>p{}</style></head><body><p>Hello &amp; welcome</p>Line two<br>end</body></html>";
+        let email = parse_email(raw);
+        assert_eq!(email.text, "Hello & welcome\nLine two\nend");
+    }
+
+    #[test]
+    fn test_rfc2231_filename() {
+        let (_, params) = parse_content_params("attachment; filename*=UTF-8''r%C3%A9sum%C3%A9.pdf");
+        assert_eq!(
+            params.get("filename").map(String::as_str),
+            Some("résumé.pdf")
+        );
+    }
+
+    #[test]
+    fn test_automated_messages_are_flagged() {
+        for header in [
+            "Auto-Submitted: auto-replied",
+            "Precedence: bulk",
+            "List-Id: <dev.lists.example.com>",
+        ] {
+            let raw = format!("From: a@example.com\r\n{}\r\n\r\nbody", header);
+            assert!(parse_email(raw.as_bytes()).automated, "{}", header);
+        }
+        let bounce = parse_email(b"From: MAILER-DAEMON@example.com\r\n\r\nfailed");
+        assert!(bounce.automated);
+        let human = parse_email(b"From: a@example.com\r\nAuto-Submitted: no\r\n\r\nhi");
+        assert!(!human.automated);
+    }
+
+    #[test]
+    fn test_sender_authenticated() {
+        let trusted = vec!["mx.example.net".to_string()];
+        let parse = |auth: &str| {
+            parse_email(
+                format!(
+                    "Authentication-Results: {}\r\nFrom: alice@example.com\r\n\r\nhi",
+                    auth
+                )
+                .as_bytes(),
+            )
+        };
+
+        assert!(
+            parse("mx.example.net; dmarc=pass (p=none) header.from=example.com")
+                .sender_authenticated(&trusted)
+        );
+        assert!(
+            parse("MX.example.net 1; spf=fail; dkim=pass header.d=example.com")
+                .sender_authenticated(&trusted)
+        );
+        // Untrusted server, failed checks, or a signature from another domain.
+        assert!(!parse("evil.example; dmarc=pass header.from=example.com")
+            .sender_authenticated(&trusted));
+        assert!(!parse("mx.example.net; dmarc=fail header.from=example.com")
+            .sender_authenticated(&trusted));
+        assert!(
+            !parse("mx.example.net; dkim=pass header.d=attacker.example")
+                .sender_authenticated(&trusted)
+        );
+        assert!(
+            !parse("mx.example.net; dkim=pass header.d=ample.com").sender_authenticated(&trusted)
+        );
+        assert!(!parse_email(b"From: alice@example.com\r\n\r\nhi").sender_authenticated(&trusted));
+    }
+
+    #[test]
+    fn test_strip_quoted_reply_gmail_style() {
+        let text = "Sounds good, go ahead.\n\nOn Mon, 3 Jun 2024 at 10:00, Bot <bot@example.com> wrote:\n> Shall I proceed?\n";
+        assert_eq!(strip_quoted_reply(text), "Sounds good, go ahead.");
+    }
+
+    #[test]
+    fn test_strip_quoted_reply_wrapped_header_and_signature() {
+        let text = "Yes please.\n\n-- \nAlice\nACME Corp";
+        assert_eq!(strip_quoted_reply(text), "Yes please.");
+
+        let wrapped = "Thanks!\nOn Mon, 3 Jun 2024 at 10:00, Bot\n<bot@example.com> wrote:\n> old";
+        assert_eq!(strip_quoted_reply(wrapped), "Thanks!");
+    }
+
+    #[test]
+    fn test_strip_quoted_reply_outlook_and_mobile() {
+        let outlook = "Approved.\n\nFrom: Bot <bot@example.com>\nSent: Monday\nSubject: Re: x";
+        assert_eq!(strip_quoted_reply(outlook), "Approved.");
+
+        let mobile = "On my way\n\nSent from my iPhone";
+        assert_eq!(strip_quoted_reply(mobile), "On my way");
+
+        let interleaved = "> question one\nanswer one\n> question two\nanswer two";
+        assert_eq!(strip_quoted_reply(interleaved), "answer one\nanswer two");
+    }
+}
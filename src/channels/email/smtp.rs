@@ -0,0 +1,370 @@
+//! Message composition and a minimal SMTP submission client
+//! (EHLO, STARTTLS, AUTH PLAIN, MAIL/RCPT/DATA).
+
+use base64::Engine;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::bus::MediaAttachment;
+use crate::channels::matrix::guess_content_type;
+use crate::config::{EmailConfig, SmtpSecurity};
+use crate::error::{Result, ZeptoError};
+
+use super::transport::{self, BoxedStream};
+
+/// Line length for base64 bodies (RFC 2045 caps lines at 76 characters).
+const BASE64_LINE_LEN: usize = 76;
+
+/// An outgoing message before serialization.
+#[derive(Debug, Default)]
+pub(super) struct OutgoingEmail {
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+    /// `Message-ID` for this message, including angle brackets.
+    pub message_id: String,
+    pub in_reply_to: Option<String>,
+    pub references: Vec<String>,
+    pub attachments: Vec<MediaAttachment>,
+}
+
+/// Serializes `email` as an RFC 5322 message with CRLF line endings.
+///
+/// Fails if a header value contains a line break, which would otherwise
+/// let it inject extra headers (e.g. additional recipients).
+pub(super) fn build_message(email: &OutgoingEmail) -> Result<String> {
+    let mut headers = vec![
+        ("From", email.from.clone()),
+        ("To", email.to.clone()),
+        ("Subject", encode_header_value(&email.subject)),
+        ("Date", chrono::Utc::now().to_rfc2822()),
+        ("Message-ID", email.message_id.clone()),
+    ];
+    if let Some(ref in_reply_to) = email.in_reply_to {
+        headers.push(("In-Reply-To", in_reply_to.clone()));
+    }
+    if !email.references.is_empty() {
+        headers.push(("References", email.references.join(" ")));
+    }
+    headers.push(("Auto-Submitted", "auto-replied".to_string()));
+    headers.push(("MIME-Version", "1.0".to_string()));
+
+    let mut out = String::new();
+    for (name, value) in headers {
+        if value.contains(['\r', '\n']) {
+            return Err(ZeptoError::Channel(format!(
+                "Refusing to send email: line break in {} header",
+                name
+            )));
+        }
+        out.push_str(name);
+        out.push_str(": ");
+        out.push_str(&value);
+        out.push_str("\r\n");
+    }
+
+    let attachments: Vec<&MediaAttachment> =
+        email.attachments.iter().filter(|a| a.has_data()).collect();
+    if attachments.is_empty() {
+        out.push_str(&text_part(&email.body));
+        return Ok(out);
+    }
+
+    let boundary = format!("zeptoclaw-{}", uuid::Uuid::new_v4().simple());
+    out.push_str(&format!(
+        "Content-Type: multipart/mixed; boundary=\"{}\"\r\n\r\n",
+        boundary
+    ));
+    out.push_str(&format!("--{}\r\n", boundary));
+    out.push_str(&text_part(&email.body));
+    for attachment in attachments {
+        let filename: String = attachment
+            .filename
+            .as_deref()
+            .unwrap_or("attachment")
+            .chars()
+            .map(|c| if c.is_control() || c == '"' { '_' } else { c })
+            .collect();
+        out.push_str(&format!("--{}\r\n", boundary));
+        out.push_str(&format!(
+            "Content-Type: {}; name=\"{}\"\r\n",
+            guess_content_type(&attachment.media_type, &filename),
+            filename
+        ));
+        out.push_str(&format!(
+            "Content-Disposition: attachment; filename=\"{}\"\r\n",
+            filename
+        ));
+        out.push_str("Content-Transfer-Encoding: base64\r\n\r\n");
+        out.push_str(&base64_lines(
+            attachment.data.as_deref().unwrap_or_default(),
+        ));
+    }
+    out.push_str(&format!("--{}--\r\n", boundary));
+    Ok(out)
+}
+
+/// Delivers `email` through the configured SMTP server.
+pub(super) async fn send_mail(config: &EmailConfig, email: &OutgoingEmail) -> Result<()> {
+    // Addresses end up inside SMTP commands, so check them before connecting.
+    for address in [&email.from, &email.to] {
+        if !is_valid_address(address) {
+            return Err(ZeptoError::Channel(format!(
+                "Invalid email address: {:?}",
+                address
+            )));
+        }
+    }
+    let message = dot_stuff(&build_message(email)?);
+
+    let implicit_tls = config.smtp_security == SmtpSecurity::Tls;
+    let stream = transport::connect(&config.smtp_host, config.smtp_port, implicit_tls).await?;
+    let mut client = SmtpClient {
+        stream: BufReader::new(stream),
+    };
+
+    client.expect(220).await?;
+    client
+        .command(&format!("EHLO {}", ehlo_domain(&email.from)), 250)
+        .await?;
+
+    if config.smtp_security == SmtpSecurity::StartTls {
+        client.command("STARTTLS", 220).await?;
+        let stream = transport::start_tls(client.stream.into_inner(), &config.smtp_host).await?;
+        client = SmtpClient {
+            stream: BufReader::new(stream),
+        };
+        client
+            .command(&format!("EHLO {}", ehlo_domain(&email.from)), 250)
+            .await?;
+    }
+
+    if !config.username.is_empty() {
+        let credentials = format!("\0{}\0{}", config.username, config.password);
+        let encoded = base64::engine::general_purpose::STANDARD.encode(credentials);
+        client
+            .command(&format!("AUTH PLAIN {}", encoded), 235)
+            .await?;
+    }
+
+    client
+        .command(&format!("MAIL FROM:<{}>", email.from), 250)
+        .await?;
+    client
+        .command(&format!("RCPT TO:<{}>", email.to), 250)
+        .await?;
+    client.command("DATA", 354).await?;
+    client.write_raw(&message).await?;
+    client.command(".", 250).await?;
+    let _ = client.command("QUIT", 221).await;
+    Ok(())
+}
+
+struct SmtpClient {
+    stream: BufReader<BoxedStream>,
+}
+
+impl SmtpClient {
+    async fn command(&mut self, command: &str, expected: u16) -> Result<String> {
+        self.write_raw(&format!("{}\r\n", command)).await?;
+        self.expect(expected).await
+    }
+
+    async fn write_raw(&mut self, data: &str) -> Result<()> {
+        self.stream.write_all(data.as_bytes()).await?;
+        self.stream.flush().await?;
+        Ok(())
+    }
+
+    /// Reads a (possibly multi-line) reply and checks its status code.
+    async fn expect(&mut self, expected: u16) -> Result<String> {
+        let mut reply = String::new();
+        loop {
+            let mut line = String::new();
+            if self.stream.read_line(&mut line).await? == 0 {
+                return Err(ZeptoError::Channel(
+                    "SMTP server closed the connection".to_string(),
+                ));
+            }
+            reply.push_str(&line);
+            // `250-...` continues a multi-line reply, `250 ...` ends it.
+            if line.as_bytes().get(3) != Some(&b'-') {
+                break;
+            }
+        }
+
+        let code: u16 = reply.get(..3).and_then(|c| c.parse().ok()).unwrap_or(0);
+        if code != expected {
+            return Err(ZeptoError::Channel(format!(
+                "SMTP server replied: {}",
+                reply.trim_end()
+            )));
+        }
+        Ok(reply)
+    }
+}
+
+fn text_part(body: &str) -> String {
+    format!(
+        "Content-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: base64\r\n\r\n{}",
+        base64_lines(body.as_bytes())
+    )
+}
+
+fn base64_lines(data: &[u8]) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(data);
+    let mut out = String::with_capacity(encoded.len() + encoded.len() / BASE64_LINE_LEN * 2 + 2);
+    for chunk in encoded.as_bytes().chunks(BASE64_LINE_LEN) {
+        // base64 output is ASCII, so every chunk is valid UTF-8.
+        out.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Checks that `address` is a bare `local@domain` mailbox that is safe to
+/// put in a header or an SMTP command.
+pub(super) fn is_valid_address(address: &str) -> bool {
+    let Some((local, domain)) = address.rsplit_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.is_empty()
+        && address
+            .chars()
+            .all(|c| !c.is_control() && !c.is_whitespace() && !"<>()[],;:\\\"".contains(c))
+        && !local.contains('@')
+}
+
+/// RFC 2047-encodes a header value that is not plain printable ASCII.
+fn encode_header_value(value: &str) -> String {
+    if value.is_ascii() && !value.chars().any(|c| c.is_ascii_control()) {
+        value.to_string()
+    } else {
+        format!(
+            "=?utf-8?B?{}?=",
+            base64::engine::general_purpose::STANDARD.encode(value)
+        )
+    }
+}
+
+/// Escapes lines starting with `.` and terminates the DATA section's last line.
+fn dot_stuff(message: &str) -> String {
+    let mut out = String::with_capacity(message.len() + 8);
+    for line in message.split_inclusive("\r\n") {
+        if line.starts_with('.') {
+            out.push('.');
+        }
+        out.push_str(line);
+    }
+    if !out.ends_with("\r\n") {
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn ehlo_domain(from: &str) -> &str {
+    from.rsplit_once('@')
+        .map(|(_, domain)| domain)
+        .filter(|d| !d.is_empty())
+        .unwrap_or("localhost")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::MediaType;
+    use crate::channels::email::mime::parse_email;
+
+    fn sample() -> OutgoingEmail {
+        OutgoingEmail {
+            from: "bot@example.com".to_string(),
+            to: "alice@example.com".to_string(),
+            subject: "Re: Café".to_string(),
+            body: "Here you go.\n.hidden line".to_string(),
+            message_id: "<reply@example.com>".to_string(),
+            in_reply_to: Some("<2@example.com>".to_string()),
+            references: vec!["<1@example.com>".to_string(), "<2@example.com>".to_string()],
+            attachments: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_build_message_round_trips() {
+        let raw = build_message(&sample()).unwrap();
+        assert!(raw.contains("In-Reply-To: <2@example.com>\r\n"));
+        assert!(raw.contains("References: <1@example.com> <2@example.com>\r\n"));
+        assert!(raw.contains("Auto-Submitted: auto-replied\r\n"));
+
+        let parsed = parse_email(raw.as_bytes());
+        assert_eq!(parsed.from, "bot@example.com");
+        assert_eq!(parsed.subject, "Re: Café");
+        assert_eq!(parsed.message_id.as_deref(), Some("<reply@example.com>"));
+        assert_eq!(parsed.text, "Here you go.\n.hidden line");
+    }
+
+    #[test]
+    fn test_build_message_with_attachment() {
+        let mut email = sample();
+        email.attachments.push(
+            MediaAttachment::new(MediaType::Document)
+                .with_data(b"%PDF-1.4".to_vec())
+                .with_filename("report.pdf"),
+        );
+        let raw = build_message(&email).unwrap();
+        assert!(raw.contains("Content-Type: multipart/mixed; boundary="));
+        assert!(raw.contains("Content-Type: application/pdf; name=\"report.pdf\""));
+
+        let parsed = parse_email(raw.as_bytes());
+        assert_eq!(parsed.text, "Here you go.\n.hidden line");
+        assert_eq!(parsed.attachments.len(), 1);
+        assert_eq!(
+            parsed.attachments[0].filename.as_deref(),
+            Some("report.pdf")
+        );
+        assert_eq!(
+            parsed.attachments[0].data.as_deref(),
+            Some(&b"%PDF-1.4"[..])
+        );
+    }
+
+    #[test]
+    fn test_build_message_rejects_header_injection() {
+        let mut email = sample();
+        email.subject = "hi\r\nBcc: x@evil.example".to_string();
+        let raw = build_message(&email).unwrap();
+        assert!(!raw.contains("\r\nBcc:"));
+        assert_eq!(
+            parse_email(raw.as_bytes()).subject,
+            "hi  Bcc: x@evil.example"
+        );
+
+        let mut email = sample();
+        email.in_reply_to = Some("<2@example.com>\r\nBcc: x@evil.example".to_string());
+        assert!(build_message(&email).is_err());
+    }
+
+    #[test]
+    fn test_is_valid_address() {
+        assert!(is_valid_address("alice@example.com"));
+        assert!(is_valid_address("a.b+tag@mail.example.co"));
+        assert!(!is_valid_address("alice"));
+        assert!(!is_valid_address("@example.com"));
+        assert!(!is_valid_address("alice@"));
+        assert!(!is_valid_address("alice@example.com>\r\nRCPT TO:<x@evil"));
+        assert!(!is_valid_address("alice@example.com bcc"));
+        assert!(!is_valid_address("a@b@c"));
+    }
+
+    #[test]
+    fn test_dot_stuffing() {
+        assert_eq!(dot_stuff("a\r\n.b\r\n..c"), "a\r\n..b\r\n...c\r\n");
+        assert_eq!(dot_stuff("plain\r\n"), "plain\r\n");
+    }
+
+    #[test]
+    fn test_ehlo_domain() {
+        assert_eq!(ehlo_domain("bot@example.com"), "example.com");
+        assert_eq!(ehlo_domain("bot"), "localhost");
+    }
+}
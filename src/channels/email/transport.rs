@@ -0,0 +1,60 @@
+//! TCP/TLS connection helpers shared by the IMAP and SMTP clients.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+use crate::error::{Result, ZeptoError};
+
+/// Timeout for establishing a TCP connection or TLS handshake.
+const CONNECT_TIMEOUT_SECS: u64 = 30;
+
+/// A bidirectional byte stream, plain or TLS.
+pub(super) trait MailStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> MailStream for T {}
+
+pub(super) type BoxedStream = Box<dyn MailStream>;
+
+/// Opens a connection to `host:port`, wrapped in TLS when `tls` is set.
+pub(super) async fn connect(host: &str, port: u16, tls: bool) -> Result<BoxedStream> {
+    let tcp = tokio::time::timeout(
+        Duration::from_secs(CONNECT_TIMEOUT_SECS),
+        TcpStream::connect((host, port)),
+    )
+    .await
+    .map_err(|_| ZeptoError::Channel(format!("Timed out connecting to {}:{}", host, port)))?
+    .map_err(|e| ZeptoError::Channel(format!("Failed to connect to {}:{}: {}", host, port, e)))?;
+
+    if tls {
+        start_tls(Box::new(tcp), host).await
+    } else {
+        Ok(Box::new(tcp))
+    }
+}
+
+/// Performs a TLS handshake over an existing stream (implicit TLS or STARTTLS).
+pub(super) async fn start_tls(stream: BoxedStream, host: &str) -> Result<BoxedStream> {
+    let mut roots = RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let config = ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let server_name = ServerName::try_from(host.to_string())
+        .map_err(|_| ZeptoError::Channel(format!("Invalid TLS server name: {}", host)))?;
+
+    let tls = tokio::time::timeout(
+        Duration::from_secs(CONNECT_TIMEOUT_SECS),
+        TlsConnector::from(Arc::new(config)).connect(server_name, stream),
+    )
+    .await
+    .map_err(|_| ZeptoError::Channel(format!("TLS handshake with {} timed out", host)))?
+    .map_err(|e| ZeptoError::Channel(format!("TLS handshake with {} failed: {}", host, e)))?;
+
+    Ok(Box::new(tls))
+}
@@ -12,7 +12,10 @@ use super::plugin::{default_channel_plugins_dir, discover_channel_plugins, Chann
 use super::webhook::{WebhookChannel, WebhookChannelConfig};
 use super::WhatsAppChannel;
 use super::WhatsAppCloudChannel;
-use super::{BaseChannelConfig, ChannelManager, DiscordChannel, SlackChannel, TelegramChannel};
+use super::{
+    BaseChannelConfig, ChannelManager, DiscordChannel, EmailChannel, MatrixChannel, SlackChannel,
+    TelegramChannel,
+};
 
 /// Register all configured channels that currently have implementations.
 ///
@@ -80,11 +83,24 @@ pub async fn register_configured_channels(
                 port: webhook_config.port,
                 path: webhook_config.path.clone(),
                 auth_token: webhook_config.auth_token.clone(),
+                callback_url: webhook_config.callback_url.clone(),
+                callback_allowed_hosts: webhook_config.callback_allowed_hosts.clone(),
+                callback_secret: webhook_config.callback_secret.clone(),
+                callback_max_retries: webhook_config.callback_max_retries,
+                dead_letter_path: webhook_config
+                    .dead_letter_path
+                    .as_deref()
+                    .map(crate::config::expand_home),
+                sync_response: webhook_config.sync_response,
+                sync_timeout: std::time::Duration::from_secs(webhook_config.sync_timeout_secs),
+                sources: webhook_config.sources.clone(),
+                ..Default::default()
             };
             let base_config = BaseChannelConfig {
                 name: "webhook".to_string(),
                 allowlist: webhook_config.allow_from.clone(),
                 deny_by_default: webhook_config.deny_by_default,
+                ..Default::default()
             };
             manager
                 .register(Box::new(WebhookChannel::new(
@@ -138,6 +154,47 @@ pub async fn register_configured_channels(
             }
         }
     }
+
+    // Matrix
+    if let Some(ref matrix_config) = config.channels.matrix {
+        if matrix_config.enabled {
+            if matrix_config.homeserver_url.is_empty() || matrix_config.access_token.is_empty() {
+                warn!("Matrix channel enabled but homeserver_url or access_token is empty");
+            } else {
+                manager
+                    .register(Box::new(MatrixChannel::new(
+                        matrix_config.clone(),
+                        bus.clone(),
+                    )))
+                    .await;
+                info!(
+                    "Registered Matrix channel ({})",
+                    matrix_config.homeserver_url
+                );
+            }
+        }
+    }
+
+    // Email
+    if let Some(ref email_config) = config.channels.email {
+        if email_config.enabled {
+            if email_config.imap_host.is_empty()
+                || email_config.smtp_host.is_empty()
+                || email_config.username.is_empty()
+            {
+                warn!("Email channel enabled but imap_host, smtp_host or username is empty");
+            } else {
+                manager
+                    .register(Box::new(EmailChannel::new(
+                        email_config.clone(),
+                        bus.clone(),
+                    )))
+                    .await;
+                info!("Registered Email channel ({})", email_config.username);
+            }
+        }
+    }
+
     if config
         .channels
         .feishu
@@ -201,7 +258,10 @@ pub async fn register_configured_channels(
 mod tests {
     use super::*;
     use crate::bus::MessageBus;
-    use crate::config::{Config, SlackConfig, TelegramConfig, WhatsAppCloudConfig, WhatsAppConfig};
+    use crate::config::{
+        Config, EmailConfig, MatrixConfig, SlackConfig, TelegramConfig, WhatsAppCloudConfig,
+        WhatsAppConfig,
+    };
 
     #[tokio::test]
     async fn test_register_configured_channels_registers_telegram() {
@@ -278,4 +338,58 @@ mod tests {
         assert_eq!(count, 1);
         assert!(manager.has_channel("whatsapp_cloud").await);
     }
+
+    #[tokio::test]
+    async fn test_register_configured_channels_registers_matrix() {
+        let bus = Arc::new(MessageBus::new());
+        let mut config = Config::default();
+        config.channels.matrix = Some(MatrixConfig {
+            enabled: true,
+            homeserver_url: "https://matrix.example.org".to_string(),
+            access_token: "syt_test".to_string(),
+            ..Default::default()
+        });
+
+        let manager = ChannelManager::new(bus.clone(), config.clone());
+        let count = register_configured_channels(&manager, bus, &config).await;
+
+        assert_eq!(count, 1);
+        assert!(manager.has_channel("matrix").await);
+    }
+
+    #[tokio::test]
+    async fn test_register_configured_channels_skips_matrix_without_token() {
+        let bus = Arc::new(MessageBus::new());
+        let mut config = Config::default();
+        config.channels.matrix = Some(MatrixConfig {
+            enabled: true,
+            homeserver_url: "https://matrix.example.org".to_string(),
+            ..Default::default()
+        });
+
+        let manager = ChannelManager::new(bus.clone(), config.clone());
+        let count = register_configured_channels(&manager, bus, &config).await;
+
+        assert_eq!(count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_register_configured_channels_registers_email() {
+        let bus = Arc::new(MessageBus::new());
+        let mut config = Config::default();
+        config.channels.email = Some(EmailConfig {
+            enabled: true,
+            imap_host: "imap.example.com".to_string(),
+            smtp_host: "smtp.example.com".to_string(),
+            username: "bot@example.com".to_string(),
+            password: "secret".to_string(),
+            ..Default::default()
+        });
+
+        let manager = ChannelManager::new(bus.clone(), config.clone());
+        let count = register_configured_channels(&manager, bus, &config).await;
+
+        assert_eq!(count, 1);
+        assert!(manager.has_channel("email").await);
+    }
 }
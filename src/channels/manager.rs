@@ -14,6 +14,7 @@ use tracing::{error, info, warn};
 use crate::bus::{MessageBus, OutboundMessage};
 use crate::config::Config;
 use crate::error::Result;
+use crate::safety::{MessageDirection, SafetyLayer};
 
 use super::Channel;
 
@@ -79,6 +80,8 @@ pub struct ChannelManager {
     shutdown_rx: watch::Receiver<bool>,
     /// Handle to the dispatcher task (if running)
     dispatcher_handle: Arc<RwLock<Option<JoinHandle<()>>>>,
+    /// Outbound safety checkpoint, `None` when disabled
+    outbound: Option<Arc<OutboundCheckpoint>>,
 }
 
 impl ChannelManager {
@@ -103,6 +106,9 @@ impl ChannelManager {
     /// ```
     pub fn new(bus: Arc<MessageBus>, config: Config) -> Self {
         let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let outbound = Some(SafetyLayer::new(config.safety.clone()))
+            .filter(|layer| layer.checks_messages(MessageDirection::Outbound))
+            .map(|layer| Arc::new(OutboundCheckpoint::new(layer)));
         Self {
             channels: Arc::new(RwLock::new(HashMap::new())),
             bus,
@@ -110,6 +116,7 @@ impl ChannelManager {
             shutdown_tx,
             shutdown_rx,
             dispatcher_handle: Arc::new(RwLock::new(None)),
+            outbound,
         }
     }
 
@@ -230,8 +237,9 @@ impl ChannelManager {
         let bus = self.bus.clone();
         let channels_ref = self.channels.clone();
         let shutdown_rx = self.shutdown_rx.clone();
+        let outbound = self.outbound.clone();
         let handle = tokio::spawn(async move {
-            dispatch_outbound(bus, channels_ref, shutdown_rx).await;
+            dispatch_outbound(bus, channels_ref, outbound, shutdown_rx).await;
         });
 
         // Store the handle so we can wait for it to stop
@@ -308,6 +316,9 @@ impl ChannelManager {
     /// manager.send("telegram", msg).await?;
     /// ```
     pub async fn send(&self, channel_name: &str, msg: OutboundMessage) -> Result<()> {
+        let Some(msg) = check_outbound(self.outbound.as_deref(), msg) else {
+            return Ok(());
+        };
         let channel = {
             let channels = self.channels.read().await;
             channels.get(channel_name).cloned()
@@ -328,6 +339,122 @@ impl ChannelManager {
     }
 }
 
+/// Notice sent in place of a reply blocked by the outbound checkpoint.
+const WITHHELD_NOTICE: &str = "[Message withheld: it contained sensitive information]";
+
+/// Characters at the end of a streamed reply held back from partials, so a
+/// secret split across chunks is scanned whole before any of it is sent.
+/// Longer than the shortest match of every built-in leak pattern.
+const STREAM_HOLDBACK_CHARS: usize = 128;
+
+/// The outbound safety layer plus the streamed replies it is scanning.
+struct OutboundCheckpoint {
+    safety: SafetyLayer,
+    /// Streams in progress, keyed by (channel, chat_id)
+    streams: std::sync::Mutex<HashMap<(String, String), StreamState>>,
+}
+
+/// Text of a streamed reply still needed for scanning.
+#[derive(Default)]
+struct StreamState {
+    /// Recently sent text followed by the held-back text
+    buf: String,
+    /// Byte offset in `buf` where the held-back text starts
+    sent: usize,
+    /// Set once the checkpoint objects; later chunks are dropped
+    withheld: bool,
+}
+
+impl OutboundCheckpoint {
+    fn new(safety: SafetyLayer) -> Self {
+        Self {
+            safety,
+            streams: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Check a streaming chunk against the reply so far.
+    ///
+    /// The last [`STREAM_HOLDBACK_CHARS`] characters are held back until
+    /// more text arrives. Once a secret shows up the rest of the stream is
+    /// dropped; the final reply still goes through [`check_outbound`] whole.
+    fn check_partial(&self, mut msg: OutboundMessage) -> Option<OutboundMessage> {
+        let mut streams = self.streams.lock().unwrap_or_else(|e| e.into_inner());
+        let stream = streams
+            .entry((msg.channel.clone(), msg.chat_id.clone()))
+            .or_default();
+        if stream.withheld {
+            return None;
+        }
+        stream.buf.push_str(&msg.content);
+        let result = self.safety.check_outbound(&stream.buf);
+        if result.blocked || result.was_modified {
+            warn!(
+                channel = %msg.channel,
+                "Streamed reply withheld by safety checkpoint"
+            );
+            stream.withheld = true;
+            return None;
+        }
+
+        let end = char_offset_from_end(&stream.buf, STREAM_HOLDBACK_CHARS);
+        if end <= stream.sent {
+            return None;
+        }
+        msg.content = stream.buf[stream.sent..end].to_string();
+        // Keep some sent text as context for the next scan
+        let keep = char_offset_from_end(&stream.buf[..end], STREAM_HOLDBACK_CHARS);
+        stream.buf.drain(..keep);
+        stream.sent = end - keep;
+        Some(msg)
+    }
+
+    /// Forget the stream for a chat once its final reply is sent.
+    fn end_stream(&self, msg: &OutboundMessage) {
+        let mut streams = self.streams.lock().unwrap_or_else(|e| e.into_inner());
+        streams.remove(&(msg.channel.clone(), msg.chat_id.clone()));
+    }
+}
+
+/// Byte offset of the `n`th character from the end of `s`, or 0 when `s`
+/// is shorter.
+fn char_offset_from_end(s: &str, n: usize) -> usize {
+    if n == 0 {
+        return s.len();
+    }
+    s.char_indices().rev().nth(n - 1).map_or(0, |(i, _)| i)
+}
+
+/// Apply the outbound safety checkpoint to a message.
+///
+/// Returns `None` when nothing should be sent. A blocked reply is replaced
+/// with a short notice; streaming chunks go through
+/// [`OutboundCheckpoint::check_partial`].
+fn check_outbound(
+    checkpoint: Option<&OutboundCheckpoint>,
+    mut msg: OutboundMessage,
+) -> Option<OutboundMessage> {
+    let Some(checkpoint) = checkpoint else {
+        return Some(msg);
+    };
+    if msg.partial {
+        return checkpoint.check_partial(msg);
+    }
+    checkpoint.end_stream(&msg);
+    let result = checkpoint.safety.check_outbound(&msg.content);
+    if result.blocked {
+        warn!(
+            channel = %msg.channel,
+            reason = %result.block_reason.unwrap_or_default(),
+            "Outbound message blocked by safety checkpoint"
+        );
+        msg.content = WITHHELD_NOTICE.to_string();
+    } else if result.was_modified {
+        msg.content = result.content;
+    }
+    Some(msg)
+}
+
 /// Background task that dispatches outbound messages from the bus to channels.
 ///
 /// This function runs in a loop, consuming outbound messages from the bus
@@ -338,10 +465,12 @@ impl ChannelManager {
 ///
 /// * `bus` - The message bus to consume from
 /// * `channels` - The shared map of channels
+/// * `outbound` - Outbound safety checkpoint, if enabled
 /// * `shutdown_rx` - Receiver for shutdown signals
 async fn dispatch_outbound(
     bus: Arc<MessageBus>,
     channels: Arc<RwLock<HashMap<String, SharedChannel>>>,
+    outbound: Option<Arc<OutboundCheckpoint>>,
     mut shutdown_rx: watch::Receiver<bool>,
 ) {
     info!("Outbound dispatcher started");
@@ -357,6 +486,9 @@ async fn dispatch_outbound(
             // Wait for outbound messages
             msg = bus.consume_outbound() => {
                 if let Some(msg) = msg {
+                    let Some(msg) = check_outbound(outbound.as_deref(), msg) else {
+                        continue;
+                    };
                     let channel_name = msg.channel.clone();
                     let channel = {
                         let channels = channels.read().await;
@@ -568,6 +700,94 @@ mod tests {
         assert!(channel.is_allowed("anyone"));
     }
 
+    fn outbound_safety(action: crate::safety::leak_detector::LeakAction) -> OutboundCheckpoint {
+        let mut config = Config::default();
+        config.safety.outbound.enabled = true;
+        config.safety.outbound.action = action;
+        OutboundCheckpoint::new(SafetyLayer::new(config.safety))
+    }
+
+    #[test]
+    fn test_check_outbound_redacts() {
+        use crate::safety::leak_detector::LeakAction;
+
+        let safety = outbound_safety(LeakAction::Redact);
+        let msg = OutboundMessage::new("telegram", "chat1", "key: sk-abc12345678901234567890");
+        let checked = check_outbound(Some(&safety), msg).unwrap();
+        assert_eq!(checked.content, "key: sk-a***7890");
+
+        // Without a safety layer the message is untouched
+        let msg = OutboundMessage::new("telegram", "chat1", "key: sk-abc12345678901234567890");
+        let checked = check_outbound(None, msg).unwrap();
+        assert!(checked.content.contains("sk-abc12345678901234567890"));
+    }
+
+    #[test]
+    fn test_check_outbound_block() {
+        use crate::safety::leak_detector::LeakAction;
+
+        let safety = outbound_safety(LeakAction::Block);
+        let msg = OutboundMessage::new("telegram", "chat1", "key: sk-abc12345678901234567890");
+        let checked = check_outbound(Some(&safety), msg.clone()).unwrap();
+        assert_eq!(checked.content, WITHHELD_NOTICE);
+
+        assert!(check_outbound(Some(&safety), msg.into_partial()).is_none());
+    }
+
+    fn stream(safety: &OutboundCheckpoint, chunks: &[&str]) -> String {
+        chunks
+            .iter()
+            .filter_map(|chunk| {
+                let msg = OutboundMessage::new("telegram", "chat1", chunk).into_partial();
+                check_outbound(Some(safety), msg)
+            })
+            .map(|msg| msg.content)
+            .collect()
+    }
+
+    #[test]
+    fn test_partials_catch_secret_split_across_chunks() {
+        use crate::safety::leak_detector::LeakAction;
+
+        let safety = outbound_safety(LeakAction::Redact);
+        let filler = "word ".repeat(40);
+        let sent = stream(
+            &safety,
+            &[&filler, "key: sk-abc1234567", "8901234567890 ", &filler],
+        );
+        assert!(sent.starts_with("word "));
+        assert!(!sent.contains("sk-abc"), "secret prefix leaked: {}", sent);
+
+        // The final reply is checked whole and ends the stream
+        let reply = format!("{}key: sk-abc12345678901234567890 {}", filler, filler);
+        let checked = check_outbound(
+            Some(&safety),
+            OutboundMessage::new("telegram", "chat1", &reply),
+        )
+        .unwrap();
+        assert!(checked.content.contains("sk-a***7890"));
+        let both = filler.repeat(2);
+        let sent = stream(&safety, &[&filler, &filler]);
+        assert_eq!(sent, both[..both.len() - STREAM_HOLDBACK_CHARS]);
+    }
+
+    #[test]
+    fn test_partials_hold_back_tail() {
+        use crate::safety::leak_detector::LeakAction;
+
+        let safety = outbound_safety(LeakAction::Redact);
+        let text = "é".repeat(STREAM_HOLDBACK_CHARS + 10);
+        let chunks: Vec<&str> = vec![&text[..40], &text[40..]];
+        let sent = stream(&safety, &chunks);
+        assert_eq!(sent, "é".repeat(10));
+    }
+
+    #[test]
+    fn test_outbound_checkpoint_disabled_by_default() {
+        let manager = ChannelManager::new(Arc::new(MessageBus::new()), Config::default());
+        assert!(manager.outbound.is_none());
+    }
+
     #[tokio::test]
     async fn test_bus_reference() {
         let bus = Arc::new(MessageBus::new());
@@ -0,0 +1,1588 @@
+//! Matrix channel implementation.
+//!
+//! Talks to a homeserver over the Matrix client-server API using plain
+//! `reqwest` -- no Matrix SDK crate required.
+//!
+//! # Sync flow
+//!
+//! 1. Resolve the bot's user ID via `GET /account/whoami` (unless configured).
+//! 2. Long-poll `GET /sync?since=<next_batch>&timeout=<ms>`.
+//! 3. Join rooms from `rooms.invite` when the inviter passes the allowlist
+//!    (`auto_join`), rejecting the invite otherwise.
+//! 4. Publish `m.room.message` events from `rooms.join.*.timeline` to the bus.
+//! 5. Persist `next_batch` to disk so a restart resumes where it left off.
+//!
+//! The very first sync (no stored token) only records `next_batch`, so the
+//! bot does not answer a room's backlog when it first connects.
+//!
+//! # Outbound
+//!
+//! Replies are sent as `m.text` (or `m.notice`) with an HTML `formatted_body`
+//! rendered from Markdown. Media attachments are uploaded to the content
+//! repository and sent as `m.image` / `m.audio` / `m.video` / `m.file` events.
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{debug, error, info, warn};
+
+use crate::bus::{InboundMessage, MediaAttachment, MediaType, MessageBus, OutboundMessage};
+use crate::config::{Config, MatrixConfig};
+use crate::error::{Result, ZeptoError};
+
+use super::{BaseChannelConfig, Channel};
+
+// ---------------------------------------------------------------------------
+// Constants
+// ---------------------------------------------------------------------------
+
+const CLIENT_API_PREFIX: &str = "/_matrix/client/v3";
+const MEDIA_UPLOAD_PATH: &str = "/_matrix/media/v3/upload";
+const MEDIA_DOWNLOAD_PREFIX: &str = "/_matrix/client/v1/media/download";
+
+/// `format` value for HTML `formatted_body` content.
+const HTML_FORMAT: &str = "org.matrix.custom.html";
+
+/// Maximum timeline events requested per room and sync.
+const SYNC_TIMELINE_LIMIT: u32 = 50;
+/// Extra time allowed on top of the long-poll timeout before giving up on a sync.
+const SYNC_REQUEST_GRACE_SECS: u64 = 30;
+
+/// Maximum reconnect delay (in seconds) for exponential backoff.
+const MAX_RECONNECT_DELAY_SECS: u64 = 120;
+/// Base reconnect delay (in seconds).
+const BASE_RECONNECT_DELAY_SECS: u64 = 2;
+/// Maximum number of consecutive failures counted towards the backoff.
+const MAX_RECONNECT_ATTEMPTS: u32 = 10;
+
+static BOLD_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\*\*(.+?)\*\*").unwrap());
+static LINK_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[([^\]]+)\]\((https?://[^\s)]+)\)").unwrap());
+
+// ---------------------------------------------------------------------------
+// Sync response types (deserialization)
+// ---------------------------------------------------------------------------
+
+/// Response of `GET /sync`, reduced to the parts the channel uses.
+#[derive(Debug, Default, Deserialize)]
+struct SyncResponse {
+    /// Token to pass as `since` in the next sync.
+    next_batch: String,
+    #[serde(default)]
+    rooms: SyncRooms,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SyncRooms {
+    /// Rooms the bot has joined, keyed by room ID.
+    #[serde(default)]
+    join: HashMap<String, JoinedRoom>,
+    /// Rooms the bot has been invited to, keyed by room ID.
+    #[serde(default)]
+    invite: HashMap<String, InvitedRoom>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct JoinedRoom {
+    #[serde(default)]
+    timeline: Timeline,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Timeline {
+    #[serde(default)]
+    events: Vec<RoomEvent>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct InvitedRoom {
+    #[serde(default)]
+    invite_state: InviteState,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct InviteState {
+    #[serde(default)]
+    events: Vec<RoomEvent>,
+}
+
+/// A timeline or stripped state event.
+#[derive(Debug, Deserialize)]
+struct RoomEvent {
+    #[serde(rename = "type")]
+    event_type: String,
+    #[serde(default)]
+    event_id: String,
+    #[serde(default)]
+    sender: String,
+    #[serde(default)]
+    state_key: Option<String>,
+    #[serde(default)]
+    content: Value,
+}
+
+/// Content of an `m.room.message` event.
+#[derive(Debug, Deserialize)]
+struct MessageContent {
+    #[serde(default)]
+    msgtype: String,
+    #[serde(default)]
+    body: String,
+    /// `mxc://` URI for media messages.
+    #[serde(default)]
+    url: Option<String>,
+    /// Original filename when `body` is used as a caption.
+    #[serde(default)]
+    filename: Option<String>,
+    #[serde(default, rename = "m.relates_to")]
+    relates_to: Option<RelatesTo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RelatesTo {
+    #[serde(default)]
+    rel_type: Option<String>,
+    #[serde(default)]
+    event_id: Option<String>,
+}
+
+/// What to do with the rooms and events of one sync response.
+#[derive(Debug, Default)]
+struct SyncOutcome {
+    inbound: Vec<InboundMessage>,
+    joins: Vec<String>,
+    rejects: Vec<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Client-server API
+// ---------------------------------------------------------------------------
+
+/// Thin wrapper around the homeserver endpoints used by the channel.
+#[derive(Clone)]
+struct MatrixApi {
+    client: reqwest::Client,
+    homeserver: String,
+    access_token: String,
+}
+
+impl MatrixApi {
+    fn new(client: reqwest::Client, homeserver: &str, access_token: &str) -> Self {
+        Self {
+            client,
+            homeserver: homeserver.trim().trim_end_matches('/').to_string(),
+            access_token: access_token.trim().to_string(),
+        }
+    }
+
+    fn client_url(&self, path: &str) -> String {
+        format!("{}{}{}", self.homeserver, CLIENT_API_PREFIX, path)
+    }
+
+    /// Sends an authenticated request and returns the JSON body of a
+    /// successful response.
+    async fn execute(&self, request: reqwest::RequestBuilder, what: &str) -> Result<Value> {
+        let response = request
+            .bearer_auth(&self.access_token)
+            .send()
+            .await
+            .map_err(|e| ZeptoError::Channel(format!("Matrix {} request failed: {}", what, e)))?;
+
+        let status = response.status();
+        let body = response.text().await.map_err(|e| {
+            ZeptoError::Channel(format!("Failed to read Matrix {} response: {}", what, e))
+        })?;
+
+        if !status.is_success() {
+            return Err(ZeptoError::Channel(format!(
+                "Matrix {} returned HTTP {}: {}",
+                what, status, body
+            )));
+        }
+
+        if body.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&body)
+            .map_err(|e| ZeptoError::Channel(format!("Invalid Matrix {} response: {}", what, e)))
+    }
+
+    /// Returns the user ID the access token belongs to.
+    async fn whoami(&self) -> Result<String> {
+        let body = self
+            .execute(
+                self.client.get(self.client_url("/account/whoami")),
+                "whoami",
+            )
+            .await?;
+        body.get("user_id")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| ZeptoError::Channel("Matrix whoami response has no user_id".into()))
+    }
+
+    async fn sync(&self, since: Option<&str>, timeout_ms: u64) -> Result<SyncResponse> {
+        let filter = json!({ "room": { "timeline": { "limit": SYNC_TIMELINE_LIMIT } } });
+        let mut query = vec![
+            ("timeout", timeout_ms.to_string()),
+            ("filter", filter.to_string()),
+        ];
+        if let Some(since) = since {
+            query.push(("since", since.to_string()));
+        }
+
+        let request = self
+            .client
+            .get(self.client_url("/sync"))
+            .query(&query)
+            .timeout(
+                Duration::from_millis(timeout_ms) + Duration::from_secs(SYNC_REQUEST_GRACE_SECS),
+            );
+        let body = self.execute(request, "sync").await?;
+        serde_json::from_value(body)
+            .map_err(|e| ZeptoError::Channel(format!("Invalid Matrix sync response: {}", e)))
+    }
+
+    async fn join(&self, room_id: &str) -> Result<()> {
+        let url = self.client_url(&format!("/join/{}", encode_path_segment(room_id)));
+        self.execute(self.client.post(url).json(&json!({})), "join")
+            .await
+            .map(|_| ())
+    }
+
+    async fn leave(&self, room_id: &str) -> Result<()> {
+        let url = self.client_url(&format!("/rooms/{}/leave", encode_path_segment(room_id)));
+        self.execute(self.client.post(url).json(&json!({})), "leave")
+            .await
+            .map(|_| ())
+    }
+
+    /// Sends an `m.room.message` event and returns its event ID.
+    async fn send_message(&self, room_id: &str, content: &Value) -> Result<String> {
+        let txn_id = uuid::Uuid::new_v4().simple().to_string();
+        let url = self.client_url(&format!(
+            "/rooms/{}/send/m.room.message/{}",
+            encode_path_segment(room_id),
+            txn_id
+        ));
+        let body = self
+            .execute(self.client.put(url).json(content), "send")
+            .await?;
+        Ok(body
+            .get("event_id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    /// Uploads media to the content repository and returns its `mxc://` URI.
+    async fn upload(&self, data: Vec<u8>, content_type: &str, filename: &str) -> Result<String> {
+        let url = format!("{}{}", self.homeserver, MEDIA_UPLOAD_PATH);
+        let request = self
+            .client
+            .post(url)
+            .query(&[("filename", filename)])
+            .header("Content-Type", content_type)
+            .body(data);
+        let body = self.execute(request, "upload").await?;
+        body.get("content_uri")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| ZeptoError::Channel("Matrix upload response has no content_uri".into()))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// MatrixChannel
+// ---------------------------------------------------------------------------
+
+/// Matrix channel backed by the client-server API (`/sync` long polling for
+/// inbound, `PUT /rooms/{room}/send` for outbound).
+pub struct MatrixChannel {
+    config: MatrixConfig,
+    base_config: BaseChannelConfig,
+    bus: Arc<MessageBus>,
+    api: MatrixApi,
+    running: Arc<AtomicBool>,
+    shutdown_tx: Option<watch::Sender<bool>>,
+}
+
+impl MatrixChannel {
+    /// Creates a new Matrix channel.
+    pub fn new(config: MatrixConfig, bus: Arc<MessageBus>) -> Self {
+        let base_config = BaseChannelConfig {
+            name: "matrix".to_string(),
+            allowlist: config.allow_from.clone(),
+            deny_by_default: config.deny_by_default,
+            ..Default::default()
+        };
+        let api = MatrixApi::new(
+            reqwest::Client::new(),
+            &config.homeserver_url,
+            &config.access_token,
+        );
+
+        Self {
+            config,
+            base_config,
+            bus,
+            api,
+            running: Arc::new(AtomicBool::new(false)),
+            shutdown_tx: None,
+        }
+    }
+
+    /// Returns a reference to the Matrix configuration.
+    pub fn matrix_config(&self) -> &MatrixConfig {
+        &self.config
+    }
+
+    /// Returns whether the channel is enabled in configuration.
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    // -----------------------------------------------------------------------
+    // Inbound
+    // -----------------------------------------------------------------------
+
+    /// Splits a sync response into messages to publish and invites to act on.
+    ///
+    /// Timeline events are only turned into messages when `include_timeline`
+    /// is set; the initial sync skips them to avoid replying to old history.
+    fn process_sync(
+        response: &SyncResponse,
+        bot_user_id: &str,
+        base_config: &BaseChannelConfig,
+        homeserver: &str,
+        auto_join: bool,
+        include_timeline: bool,
+    ) -> SyncOutcome {
+        let mut outcome = SyncOutcome::default();
+
+        if auto_join {
+            for (room_id, room) in &response.rooms.invite {
+                let inviter = room.invite_state.events.iter().find_map(|event| {
+                    let is_own_invite = event.event_type == "m.room.member"
+                        && event.state_key.as_deref() == Some(bot_user_id)
+                        && event.content.get("membership").and_then(Value::as_str)
+                            == Some("invite");
+                    is_own_invite.then_some(event.sender.as_str())
+                });
+                match inviter {
+                    Some(inviter) if base_config.is_allowed(inviter) => {
+                        outcome.joins.push(room_id.clone())
+                    }
+                    Some(inviter) => {
+                        info!(
+                            "Matrix: rejecting invite to {} from {} (not in allowlist)",
+                            room_id, inviter
+                        );
+                        outcome.rejects.push(room_id.clone());
+                    }
+                    None => debug!("Matrix: invite to {} has no member event", room_id),
+                }
+            }
+        }
+
+        if include_timeline {
+            for (room_id, room) in &response.rooms.join {
+                outcome
+                    .inbound
+                    .extend(room.timeline.events.iter().filter_map(|event| {
+                        Self::parse_room_event(room_id, event, bot_user_id, base_config, homeserver)
+                    }));
+            }
+        }
+
+        outcome
+    }
+
+    /// Parses an `m.room.message` timeline event into an `InboundMessage`,
+    /// returning `None` if it should be ignored (own message, notice, edit,
+    /// disallowed sender, etc.).
+    fn parse_room_event(
+        room_id: &str,
+        event: &RoomEvent,
+        bot_user_id: &str,
+        base_config: &BaseChannelConfig,
+        homeserver: &str,
+    ) -> Option<InboundMessage> {
+        if event.event_type != "m.room.message" || event.sender == bot_user_id {
+            return None;
+        }
+        let content: MessageContent = serde_json::from_value(event.content.clone()).ok()?;
+
+        // Edits re-send the whole message; only the original is processed.
+        let relation = content.relates_to.as_ref();
+        if relation.and_then(|r| r.rel_type.as_deref()) == Some("m.replace") {
+            return None;
+        }
+
+        let media_type = match content.msgtype.as_str() {
+            "m.text" | "m.emote" => None,
+            "m.image" => Some(MediaType::Image),
+            "m.audio" => Some(MediaType::Audio),
+            "m.video" => Some(MediaType::Video),
+            "m.file" => Some(MediaType::Document),
+            // Notices are bot output by convention; never answer them.
+            other => {
+                debug!("Matrix: ignoring {} message in {}", other, room_id);
+                return None;
+            }
+        };
+
+        let sender_id = event.sender.trim();
+        if sender_id.is_empty() {
+            return None;
+        }
+        if !base_config.is_allowed(sender_id) {
+            info!(
+                "Matrix: user {} not in allowlist, ignoring message",
+                sender_id
+            );
+            return None;
+        }
+
+        let body = strip_reply_fallback(&content.body);
+        if body.is_empty() {
+            return None;
+        }
+
+        let mut inbound = InboundMessage::new("matrix", sender_id, room_id, &body);
+        if !event.event_id.is_empty() {
+            inbound = inbound.with_metadata("matrix_event_id", &event.event_id);
+        }
+        if let Some(relation) = relation {
+            if relation.rel_type.as_deref() == Some("m.thread") {
+                if let Some(thread_root) = relation.event_id.as_deref() {
+                    inbound = inbound.with_thread(thread_root);
+                }
+            }
+        }
+
+        if let Some(media_type) = media_type {
+            let mxc = content.url.as_deref()?;
+            let mut media = MediaAttachment::new(media_type)
+                .with_filename(content.filename.as_deref().unwrap_or(&content.body));
+            if let Some(url) = mxc_to_download_url(homeserver, mxc) {
+                media = media.with_url(&url);
+            }
+            inbound = inbound
+                .with_media(media)
+                .with_metadata("matrix_mxc_uri", mxc);
+        }
+
+        Some(inbound)
+    }
+
+    /// Main sync loop: long-polls `/sync`, handles invites, publishes
+    /// messages and persists `next_batch`. Backs off exponentially on errors.
+    async fn run_sync_loop(
+        api: MatrixApi,
+        config: MatrixConfig,
+        base_config: BaseChannelConfig,
+        bus: Arc<MessageBus>,
+        mut shutdown_rx: watch::Receiver<bool>,
+    ) {
+        let token_path = Self::sync_token_path(&config);
+        let mut since = load_sync_token(&token_path);
+        let mut user_id = config.user_id.trim().to_string();
+        let timeout_ms = config.sync_timeout_secs.saturating_mul(1000);
+        let mut attempt: u32 = 0;
+
+        loop {
+            if *shutdown_rx.borrow() {
+                info!("Matrix sync shutdown requested");
+                return;
+            }
+
+            let result = if user_id.is_empty() {
+                tokio::select! {
+                    _ = shutdown_rx.changed() => return,
+                    result = api.whoami() => result.map(|id| {
+                        info!("Matrix: logged in as {}", id);
+                        user_id = id;
+                        None
+                    }),
+                }
+            } else {
+                // The initial sync returns immediately; it only fetches a token.
+                let timeout = if since.is_some() { timeout_ms } else { 0 };
+                tokio::select! {
+                    _ = shutdown_rx.changed() => return,
+                    result = api.sync(since.as_deref(), timeout) => result.map(Some),
+                }
+            };
+
+            let response = match result {
+                Ok(Some(response)) => response,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!("Matrix: {}", e);
+                    let delay = backoff_delay(attempt);
+                    attempt = (attempt + 1).min(MAX_RECONNECT_ATTEMPTS);
+                    tokio::select! {
+                        _ = shutdown_rx.changed() => return,
+                        _ = tokio::time::sleep(delay) => continue,
+                    }
+                }
+            };
+            attempt = 0;
+
+            let outcome = Self::process_sync(
+                &response,
+                &user_id,
+                &base_config,
+                &api.homeserver,
+                config.auto_join,
+                since.is_some(),
+            );
+            for room_id in &outcome.joins {
+                match api.join(room_id).await {
+                    Ok(()) => info!("Matrix: joined {}", room_id),
+                    Err(e) => warn!("Matrix: failed to join {}: {}", room_id, e),
+                }
+            }
+            for room_id in &outcome.rejects {
+                if let Err(e) = api.leave(room_id).await {
+                    warn!("Matrix: failed to reject invite to {}: {}", room_id, e);
+                }
+            }
+            for inbound in outcome.inbound {
+                if let Err(e) = bus.publish_inbound(inbound).await {
+                    error!("Failed to publish Matrix inbound message: {}", e);
+                }
+            }
+
+            if let Err(e) = save_sync_token(&token_path, &response.next_batch) {
+                warn!("Matrix: failed to persist sync token: {}", e);
+            }
+            since = Some(response.next_batch);
+        }
+    }
+
+    fn sync_token_path(config: &MatrixConfig) -> PathBuf {
+        config
+            .sync_token_path
+            .as_deref()
+            .map(crate::config::expand_home)
+            .unwrap_or_else(|| Config::dir().join("matrix").join("sync_token"))
+    }
+
+    // -----------------------------------------------------------------------
+    // Outbound
+    // -----------------------------------------------------------------------
+
+    /// Builds the content of a text message, adding an HTML `formatted_body`
+    /// when the Markdown contains formatting.
+    fn build_text_content(text: &str, msgtype: &str, thread_id: Option<&str>) -> Value {
+        let mut content = json!({ "msgtype": msgtype, "body": text });
+        if let Some(html) = markdown_to_html(text) {
+            content["format"] = json!(HTML_FORMAT);
+            content["formatted_body"] = json!(html);
+        }
+        add_thread_relation(&mut content, thread_id);
+        content
+    }
+
+    /// Builds the content of a media message pointing at an uploaded `mxc://` URI.
+    fn build_media_content(media: &MediaAttachment, mxc: &str, thread_id: Option<&str>) -> Value {
+        let msgtype = match media.media_type {
+            MediaType::Image => "m.image",
+            MediaType::Audio => "m.audio",
+            MediaType::Video => "m.video",
+            MediaType::Document => "m.file",
+        };
+        let filename = media.filename.as_deref().unwrap_or("attachment");
+        let mut content = json!({
+            "msgtype": msgtype,
+            "body": filename,
+            "filename": filename,
+            "url": mxc,
+        });
+        if let Some(ref data) = media.data {
+            content["info"] = json!({
+                "mimetype": guess_content_type(&media.media_type, filename),
+                "size": data.len(),
+            });
+        }
+        add_thread_relation(&mut content, thread_id);
+        content
+    }
+
+    /// Returns the `mxc://` URI for an outbound attachment, uploading inline
+    /// data first. Returns `None` for attachments that only have a remote
+    /// HTTP URL; those are linked in the text instead.
+    async fn resolve_media_uri(&self, media: &MediaAttachment) -> Result<Option<String>> {
+        if let Some(ref data) = media.data {
+            let filename = media.filename.as_deref().unwrap_or("attachment");
+            let content_type = guess_content_type(&media.media_type, filename);
+            return self
+                .api
+                .upload(data.clone(), content_type, filename)
+                .await
+                .map(Some);
+        }
+        Ok(media
+            .url
+            .as_deref()
+            .filter(|url| url.starts_with("mxc://"))
+            .map(str::to_string))
+    }
+}
+
+#[async_trait]
+impl Channel for MatrixChannel {
+    fn name(&self) -> &str {
+        "matrix"
+    }
+
+    async fn start(&mut self) -> Result<()> {
+        if self.running.swap(true, Ordering::SeqCst) {
+            info!("Matrix channel already running");
+            return Ok(());
+        }
+
+        if !self.config.enabled {
+            warn!("Matrix channel is disabled in configuration");
+            self.running.store(false, Ordering::SeqCst);
+            return Ok(());
+        }
+
+        if self.api.homeserver.is_empty() || self.api.access_token.is_empty() {
+            self.running.store(false, Ordering::SeqCst);
+            return Err(ZeptoError::Config(
+                "Matrix requires homeserver_url and access_token".to_string(),
+            ));
+        }
+
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        self.shutdown_tx = Some(shutdown_tx);
+
+        info!("Starting Matrix channel for {}", self.api.homeserver);
+        tokio::spawn(Self::run_sync_loop(
+            self.api.clone(),
+            self.config.clone(),
+            self.base_config.clone(),
+            Arc::clone(&self.bus),
+            shutdown_rx,
+        ));
+
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        if !self.running.swap(false, Ordering::SeqCst) {
+            info!("Matrix channel already stopped");
+            return Ok(());
+        }
+
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(true);
+        }
+
+        info!("Matrix channel stopped");
+        Ok(())
+    }
+
+    async fn send(&self, msg: OutboundMessage) -> Result<()> {
+        if !self.running.load(Ordering::SeqCst) {
+            return Err(ZeptoError::Channel(
+                "Matrix channel not running".to_string(),
+            ));
+        }
+
+        let room_id = msg.chat_id.trim();
+        if room_id.is_empty() {
+            return Err(ZeptoError::Channel(
+                "Matrix room ID cannot be empty".to_string(),
+            ));
+        }
+        let thread_id = msg.thread_id.as_deref();
+        let mut text = msg.content.trim().to_string();
+
+        if let Some(ref media) = msg.media {
+            match self.resolve_media_uri(media).await? {
+                Some(mxc) => {
+                    let content = Self::build_media_content(media, &mxc, thread_id);
+                    self.api.send_message(room_id, &content).await?;
+                }
+                None => {
+                    if let Some(ref url) = media.url {
+                        if !text.is_empty() {
+                            text.push('\n');
+                        }
+                        text.push_str(url);
+                    }
+                }
+            }
+        }
+
+        if !text.is_empty() {
+            let msgtype = if self.config.use_notice {
+                "m.notice"
+            } else {
+                "m.text"
+            };
+            let content = Self::build_text_content(&text, msgtype, thread_id);
+            self.api.send_message(room_id, &content).await?;
+        }
+
+        info!("Matrix: message sent to {}", room_id);
+        Ok(())
+    }
+
+    fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    fn is_allowed(&self, user_id: &str) -> bool {
+        self.base_config.is_allowed(user_id)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Exponential backoff: 2s, 4s, 8s, ... capped at `MAX_RECONNECT_DELAY_SECS`.
+fn backoff_delay(attempt: u32) -> Duration {
+    let delay_secs = BASE_RECONNECT_DELAY_SECS
+        .saturating_mul(2u64.saturating_pow(attempt))
+        .min(MAX_RECONNECT_DELAY_SECS);
+    Duration::from_secs(delay_secs)
+}
+
+fn load_sync_token(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+fn save_sync_token(path: &Path, token: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, token)
+}
+
+/// Percent-encodes a path segment (room IDs contain `!`, `:` and `#`).
+fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Converts `mxc://server/media_id` into an authenticated download URL.
+fn mxc_to_download_url(homeserver: &str, mxc: &str) -> Option<String> {
+    let (server, media_id) = mxc.strip_prefix("mxc://")?.split_once('/')?;
+    if server.is_empty() || media_id.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{}{}/{}/{}",
+        homeserver,
+        MEDIA_DOWNLOAD_PREFIX,
+        encode_path_segment(server),
+        encode_path_segment(media_id)
+    ))
+}
+
+/// Removes the quoted `> ...` fallback that clients prepend to reply bodies.
+fn strip_reply_fallback(body: &str) -> String {
+    if !body.starts_with("> ") {
+        return body.trim().to_string();
+    }
+    body.lines()
+        .skip_while(|line| line.starts_with('>'))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+fn add_thread_relation(content: &mut Value, thread_id: Option<&str>) {
+    if let Some(thread_id) = thread_id {
+        content["m.relates_to"] = json!({ "rel_type": "m.thread", "event_id": thread_id });
+    }
+}
+
+/// Best-effort MIME type for an outgoing attachment, from its file extension.
+pub(crate) fn guess_content_type(media_type: &MediaType, filename: &str) -> &'static str {
+    let extension = filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "mp3" => "audio/mpeg",
+        "ogg" | "oga" => "audio/ogg",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "pdf" => "application/pdf",
+        "txt" => "text/plain",
+        _ => match media_type {
+            MediaType::Image => "image/png",
+            _ => "application/octet-stream",
+        },
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders inline Markdown (code spans, bold, links) of a single line.
+fn render_inline(line: &str) -> String {
+    let segments: Vec<&str> = line.split('`').collect();
+    // An even number of segments means the last backtick is unmatched.
+    let unmatched_tail = segments.len().is_multiple_of(2);
+    let mut html = String::new();
+    for (i, segment) in segments.iter().enumerate() {
+        let is_tail = i == segments.len() - 1;
+        if i % 2 == 1 && !(unmatched_tail && is_tail) {
+            html.push_str("<code>");
+            html.push_str(&escape_html(segment));
+            html.push_str("</code>");
+        } else {
+            if i % 2 == 1 {
+                html.push('`');
+            }
+            let escaped = escape_html(segment);
+            let bold = BOLD_RE.replace_all(&escaped, "<strong>$1</strong>");
+            html.push_str(&LINK_RE.replace_all(&bold, r#"<a href="$2">$1</a>"#));
+        }
+    }
+    html
+}
+
+/// Converts a Markdown reply into Matrix HTML.
+///
+/// Supports fenced code blocks, inline code, `**bold**` and `[text](url)`
+/// links. Returns `None` when the text has no formatting, in which case the
+/// plain `body` is sufficient.
+fn markdown_to_html(text: &str) -> Option<String> {
+    let mut html = String::new();
+    let mut formatted = false;
+    let mut in_code_block = false;
+    let mut after_text_line = false;
+
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            formatted = true;
+            if in_code_block {
+                html.push_str("</code></pre>");
+            } else {
+                html.push_str("<pre><code>");
+            }
+            in_code_block = !in_code_block;
+            after_text_line = false;
+            continue;
+        }
+        if in_code_block {
+            html.push_str(&escape_html(line));
+            html.push('\n');
+            continue;
+        }
+
+        let rendered = render_inline(line);
+        if rendered != escape_html(line) {
+            formatted = true;
+        }
+        if after_text_line {
+            html.push_str("<br/>");
+        }
+        html.push_str(&rendered);
+        after_text_line = true;
+    }
+    if in_code_block {
+        html.push_str("</code></pre>");
+    }
+
+    formatted.then_some(html)
+}
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const BOT: &str = "@bot:localhost";
+
+    fn test_base() -> BaseChannelConfig {
+        BaseChannelConfig::new("matrix")
+    }
+
+    fn message_event(sender: &str, content: Value) -> RoomEvent {
+        RoomEvent {
+            event_type: "m.room.message".to_string(),
+            event_id: "$ev1".to_string(),
+            sender: sender.to_string(),
+            state_key: None,
+            content,
+        }
+    }
+
+    fn invite_sync(inviter: &str) -> SyncResponse {
+        serde_json::from_value(json!({
+            "next_batch": "s2",
+            "rooms": {
+                "invite": {
+                    "!room:localhost": {
+                        "invite_state": {
+                            "events": [{
+                                "type": "m.room.member",
+                                "sender": inviter,
+                                "state_key": BOT,
+                                "content": { "membership": "invite" }
+                            }]
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    // -----------------------------------------------------------------------
+    // Mock homeserver
+    // -----------------------------------------------------------------------
+
+    #[derive(Debug, Clone)]
+    struct RecordedRequest {
+        method: String,
+        path: String,
+        query: String,
+        authorization: String,
+        body: String,
+    }
+
+    type Responder = Arc<dyn Fn(&RecordedRequest) -> (u16, Value) + Send + Sync>;
+
+    /// Minimal HTTP/1.1 server answering each request via `responder` and
+    /// recording everything it receives.
+    async fn spawn_mock_homeserver(
+        responder: Responder,
+    ) -> (String, Arc<Mutex<Vec<RecordedRequest>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&requests);
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let responder = Arc::clone(&responder);
+                let recorded = Arc::clone(&recorded);
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 4096];
+                    let header_end = loop {
+                        let n = stream.read(&mut chunk).await.unwrap_or(0);
+                        if n == 0 {
+                            return;
+                        }
+                        buf.extend_from_slice(&chunk[..n]);
+                        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                            break pos;
+                        }
+                    };
+                    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+                    let content_length = head
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    while buf.len() < header_end + 4 + content_length {
+                        let n = stream.read(&mut chunk).await.unwrap_or(0);
+                        if n == 0 {
+                            break;
+                        }
+                        buf.extend_from_slice(&chunk[..n]);
+                    }
+
+                    let mut request_line = head.lines().next().unwrap_or("").split(' ');
+                    let method = request_line.next().unwrap_or("").to_string();
+                    let target = request_line.next().unwrap_or("");
+                    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+                    let authorization = head
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("authorization")
+                                .then(|| value.trim().to_string())
+                        })
+                        .unwrap_or_default();
+                    let request = RecordedRequest {
+                        method,
+                        path: path.to_string(),
+                        query: query.to_string(),
+                        authorization,
+                        body: String::from_utf8_lossy(&buf[header_end + 4..]).to_string(),
+                    };
+                    let (status, body) = responder(&request);
+                    recorded.lock().unwrap().push(request);
+
+                    let body = body.to_string();
+                    let response = format!(
+                        "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        (base_url, requests)
+    }
+
+    fn running_channel(homeserver_url: &str, use_notice: bool) -> MatrixChannel {
+        let config = MatrixConfig {
+            enabled: true,
+            homeserver_url: homeserver_url.to_string(),
+            access_token: "syt_test".to_string(),
+            use_notice,
+            ..Default::default()
+        };
+        let channel = MatrixChannel::new(config, Arc::new(MessageBus::new()));
+        channel.running.store(true, Ordering::SeqCst);
+        channel
+    }
+
+    // -----------------------------------------------------------------------
+    // Channel basics
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_matrix_channel_creation() {
+        let config = MatrixConfig {
+            enabled: true,
+            homeserver_url: "https://matrix.example.org/".to_string(),
+            access_token: "syt_abc".to_string(),
+            allow_from: vec!["@alice:example.org".to_string()],
+            ..Default::default()
+        };
+        let channel = MatrixChannel::new(config, Arc::new(MessageBus::new()));
+
+        assert_eq!(channel.name(), "matrix");
+        assert!(channel.is_enabled());
+        assert!(!channel.is_running());
+        assert_eq!(channel.api.homeserver, "https://matrix.example.org");
+        assert!(channel.is_allowed("@alice:example.org"));
+        assert!(!channel.is_allowed("@mallory:example.org"));
+    }
+
+    #[tokio::test]
+    async fn test_matrix_start_requires_credentials() {
+        let config = MatrixConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let mut channel = MatrixChannel::new(config, Arc::new(MessageBus::new()));
+
+        let result = channel.start().await;
+        assert!(matches!(result, Err(ZeptoError::Config(_))));
+        assert!(!channel.is_running());
+    }
+
+    #[tokio::test]
+    async fn test_matrix_start_disabled() {
+        let mut channel = MatrixChannel::new(MatrixConfig::default(), Arc::new(MessageBus::new()));
+        assert!(channel.start().await.is_ok());
+        assert!(!channel.is_running());
+    }
+
+    #[tokio::test]
+    async fn test_matrix_send_not_running() {
+        let channel = MatrixChannel::new(MatrixConfig::default(), Arc::new(MessageBus::new()));
+        let result = channel
+            .send(OutboundMessage::new("matrix", "!room:localhost", "hi"))
+            .await;
+        assert!(result.is_err());
+    }
+
+    // -----------------------------------------------------------------------
+    // Inbound parsing
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_parse_text_message() {
+        let event = message_event(
+            "@alice:localhost",
+            json!({ "msgtype": "m.text", "body": "  hello bot  " }),
+        );
+        let inbound = MatrixChannel::parse_room_event(
+            "!room:localhost",
+            &event,
+            BOT,
+            &test_base(),
+            "https://hs",
+        )
+        .expect("inbound expected");
+
+        assert_eq!(inbound.channel, "matrix");
+        assert_eq!(inbound.sender_id, "@alice:localhost");
+        assert_eq!(inbound.chat_id, "!room:localhost");
+        assert_eq!(inbound.content, "hello bot");
+        assert_eq!(inbound.session_key, "matrix:!room:localhost");
+        assert_eq!(
+            inbound.metadata.get("matrix_event_id").map(String::as_str),
+            Some("$ev1")
+        );
+        assert!(inbound.thread_id.is_none());
+    }
+
+    #[test]
+    fn test_parse_ignores_own_notices_and_edits() {
+        let own = message_event(BOT, json!({ "msgtype": "m.text", "body": "echo" }));
+        let notice = message_event(
+            "@other-bot:localhost",
+            json!({ "msgtype": "m.notice", "body": "beep" }),
+        );
+        let edit = message_event(
+            "@alice:localhost",
+            json!({
+                "msgtype": "m.text",
+                "body": "* fixed",
+                "m.relates_to": { "rel_type": "m.replace", "event_id": "$orig" }
+            }),
+        );
+
+        for event in [own, notice, edit] {
+            assert!(MatrixChannel::parse_room_event(
+                "!room:localhost",
+                &event,
+                BOT,
+                &test_base(),
+                "https://hs",
+            )
+            .is_none());
+        }
+    }
+
+    #[test]
+    fn test_parse_respects_allowlist() {
+        let base =
+            BaseChannelConfig::with_allowlist("matrix", vec!["@alice:localhost".to_string()]);
+        let allowed = message_event(
+            "@alice:localhost",
+            json!({ "msgtype": "m.text", "body": "a" }),
+        );
+        let denied = message_event(
+            "@eve:localhost",
+            json!({ "msgtype": "m.text", "body": "b" }),
+        );
+
+        assert!(MatrixChannel::parse_room_event(
+            "!r:localhost",
+            &allowed,
+            BOT,
+            &base,
+            "https://hs"
+        )
+        .is_some());
+        assert!(
+            MatrixChannel::parse_room_event("!r:localhost", &denied, BOT, &base, "https://hs")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_parse_thread_reply_and_reply_fallback() {
+        let event = message_event(
+            "@alice:localhost",
+            json!({
+                "msgtype": "m.text",
+                "body": "> <@bot:localhost> earlier answer\n\nfollow-up question",
+                "m.relates_to": { "rel_type": "m.thread", "event_id": "$root" }
+            }),
+        );
+        let inbound = MatrixChannel::parse_room_event(
+            "!room:localhost",
+            &event,
+            BOT,
+            &test_base(),
+            "https://hs",
+        )
+        .unwrap();
+
+        assert_eq!(inbound.content, "follow-up question");
+        assert_eq!(inbound.thread_id.as_deref(), Some("$root"));
+    }
+
+    #[test]
+    fn test_parse_image_message() {
+        let event = message_event(
+            "@alice:localhost",
+            json!({
+                "msgtype": "m.image",
+                "body": "cat.png",
+                "url": "mxc://localhost/abc123"
+            }),
+        );
+        let inbound = MatrixChannel::parse_room_event(
+            "!room:localhost",
+            &event,
+            BOT,
+            &test_base(),
+            "https://hs",
+        )
+        .unwrap();
+
+        let media = inbound.media.expect("media expected");
+        assert_eq!(media.media_type, MediaType::Image);
+        assert_eq!(media.filename.as_deref(), Some("cat.png"));
+        assert_eq!(
+            media.url.as_deref(),
+            Some("https://hs/_matrix/client/v1/media/download/localhost/abc123")
+        );
+        assert_eq!(
+            inbound.metadata.get("matrix_mxc_uri").map(String::as_str),
+            Some("mxc://localhost/abc123")
+        );
+    }
+
+    // -----------------------------------------------------------------------
+    // Sync processing
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_process_sync_joins_allowed_invite() {
+        let base =
+            BaseChannelConfig::with_allowlist("matrix", vec!["@alice:localhost".to_string()]);
+        let outcome = MatrixChannel::process_sync(
+            &invite_sync("@alice:localhost"),
+            BOT,
+            &base,
+            "https://hs",
+            true,
+            true,
+        );
+        assert_eq!(outcome.joins, vec!["!room:localhost"]);
+        assert!(outcome.rejects.is_empty());
+    }
+
+    #[test]
+    fn test_process_sync_rejects_disallowed_invite() {
+        let base =
+            BaseChannelConfig::with_allowlist("matrix", vec!["@alice:localhost".to_string()]);
+        let outcome = MatrixChannel::process_sync(
+            &invite_sync("@eve:localhost"),
+            BOT,
+            &base,
+            "https://hs",
+            true,
+            true,
+        );
+        assert!(outcome.joins.is_empty());
+        assert_eq!(outcome.rejects, vec!["!room:localhost"]);
+    }
+
+    #[test]
+    fn test_process_sync_auto_join_disabled_leaves_invites_pending() {
+        let outcome = MatrixChannel::process_sync(
+            &invite_sync("@alice:localhost"),
+            BOT,
+            &test_base(),
+            "https://hs",
+            false,
+            true,
+        );
+        assert!(outcome.joins.is_empty());
+        assert!(outcome.rejects.is_empty());
+    }
+
+    #[test]
+    fn test_process_sync_skips_timeline_on_initial_sync() {
+        let response: SyncResponse = serde_json::from_value(json!({
+            "next_batch": "s1",
+            "rooms": { "join": { "!room:localhost": { "timeline": { "events": [{
+                "type": "m.room.message",
+                "event_id": "$old",
+                "sender": "@alice:localhost",
+                "content": { "msgtype": "m.text", "body": "old message" }
+            }] } } } }
+        }))
+        .unwrap();
+
+        let initial =
+            MatrixChannel::process_sync(&response, BOT, &test_base(), "https://hs", true, false);
+        let incremental =
+            MatrixChannel::process_sync(&response, BOT, &test_base(), "https://hs", true, true);
+        assert!(initial.inbound.is_empty());
+        assert_eq!(incremental.inbound.len(), 1);
+    }
+
+    // -----------------------------------------------------------------------
+    // Outbound formatting
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_markdown_to_html_plain_text_is_none() {
+        assert!(markdown_to_html("just words, a < b").is_none());
+    }
+
+    #[test]
+    fn test_markdown_to_html_inline_formatting() {
+        let html = markdown_to_html("Run **now**: `ls <dir>`\nsee [docs](https://x.io/a?b=1&c=2)")
+            .unwrap();
+        assert_eq!(
+            html,
+            "Run <strong>now</strong>: <code>ls &lt;dir&gt;</code><br/>see <a href=\"https://x.io/a?b=1&amp;c=2\">docs</a>"
+        );
+    }
+
+    #[test]
+    fn test_markdown_to_html_code_block() {
+        let html = markdown_to_html("Example:\n```rust\nlet x = 1 < 2;\n```\nDone").unwrap();
+        assert_eq!(
+            html,
+            "Example:<pre><code>let x = 1 &lt; 2;\n</code></pre>Done"
+        );
+    }
+
+    #[test]
+    fn test_markdown_to_html_unmatched_backtick() {
+        assert!(markdown_to_html("it's a ` tick").is_none());
+    }
+
+    #[test]
+    fn test_build_text_content_notice_in_thread() {
+        let content = MatrixChannel::build_text_content("**hi**", "m.notice", Some("$root"));
+        assert_eq!(content["msgtype"], "m.notice");
+        assert_eq!(content["body"], "**hi**");
+        assert_eq!(content["format"], HTML_FORMAT);
+        assert_eq!(content["formatted_body"], "<strong>hi</strong>");
+        assert_eq!(content["m.relates_to"]["rel_type"], "m.thread");
+        assert_eq!(content["m.relates_to"]["event_id"], "$root");
+    }
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(
+            encode_path_segment("!abc:example.org"),
+            "%21abc%3Aexample.org"
+        );
+        assert!(mxc_to_download_url("https://hs", "https://not-mxc").is_none());
+        assert_eq!(
+            guess_content_type(&MediaType::Document, "report.PDF"),
+            "application/pdf"
+        );
+        assert_eq!(strip_reply_fallback("plain"), "plain");
+        assert_eq!(backoff_delay(0), Duration::from_secs(2));
+        assert_eq!(
+            backoff_delay(20),
+            Duration::from_secs(MAX_RECONNECT_DELAY_SECS)
+        );
+    }
+
+    #[test]
+    fn test_sync_token_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("sync_token");
+        assert!(load_sync_token(&path).is_none());
+        save_sync_token(&path, "s42_1").unwrap();
+        assert_eq!(load_sync_token(&path).as_deref(), Some("s42_1"));
+    }
+
+    // -----------------------------------------------------------------------
+    // Mock homeserver
+    // -----------------------------------------------------------------------
+
+    #[tokio::test]
+    async fn test_send_formatted_text_to_mock_homeserver() {
+        let (base_url, requests) =
+            spawn_mock_homeserver(Arc::new(|_| (200, json!({ "event_id": "$sent" })))).await;
+        let channel = running_channel(&base_url, true);
+
+        channel
+            .send(OutboundMessage::new(
+                "matrix",
+                "!room:localhost",
+                "**done**",
+            ))
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "PUT");
+        assert!(requests[0]
+            .path
+            .starts_with("/_matrix/client/v3/rooms/%21room%3Alocalhost/send/m.room.message/"));
+        let body: Value = serde_json::from_str(&requests[0].body).unwrap();
+        assert_eq!(body["msgtype"], "m.notice");
+        assert_eq!(body["formatted_body"], "<strong>done</strong>");
+    }
+
+    #[tokio::test]
+    async fn test_send_media_uploads_then_sends() {
+        let (base_url, requests) = spawn_mock_homeserver(Arc::new(|req| {
+            if req.path == MEDIA_UPLOAD_PATH {
+                (200, json!({ "content_uri": "mxc://localhost/up1" }))
+            } else {
+                (200, json!({ "event_id": "$sent" }))
+            }
+        }))
+        .await;
+        let channel = running_channel(&base_url, false);
+        let media = MediaAttachment::new(MediaType::Image)
+            .with_data(vec![1, 2, 3])
+            .with_filename("chart.png");
+
+        channel
+            .send(OutboundMessage::new("matrix", "!room:localhost", "Here").with_media(media))
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].path, MEDIA_UPLOAD_PATH);
+        assert_eq!(requests[0].query, "filename=chart.png");
+        let image: Value = serde_json::from_str(&requests[1].body).unwrap();
+        assert_eq!(image["msgtype"], "m.image");
+        assert_eq!(image["url"], "mxc://localhost/up1");
+        assert_eq!(image["info"]["mimetype"], "image/png");
+        let text: Value = serde_json::from_str(&requests[2].body).unwrap();
+        assert_eq!(text["msgtype"], "m.text");
+        assert_eq!(text["body"], "Here");
+    }
+
+    #[tokio::test]
+    async fn test_send_reports_homeserver_error() {
+        let (base_url, _) = spawn_mock_homeserver(Arc::new(|_| {
+            (
+                403,
+                json!({ "errcode": "M_FORBIDDEN", "error": "not in room" }),
+            )
+        }))
+        .await;
+        let channel = running_channel(&base_url, false);
+
+        let err = channel
+            .send(OutboundMessage::new("matrix", "!room:localhost", "hi"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("M_FORBIDDEN"));
+    }
+
+    #[tokio::test]
+    async fn test_sync_loop_against_mock_homeserver() {
+        let (base_url, requests) = spawn_mock_homeserver(Arc::new(|req| {
+            let since = req
+                .query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("since="))
+                .unwrap_or("");
+            match (req.path.as_str(), since) {
+                ("/_matrix/client/v3/account/whoami", _) => (200, json!({ "user_id": BOT })),
+                ("/_matrix/client/v3/sync", "") => (
+                    200,
+                    json!({
+                        "next_batch": "s1",
+                        "rooms": {
+                            "invite": { "!new:localhost": { "invite_state": { "events": [{
+                                "type": "m.room.member",
+                                "sender": "@alice:localhost",
+                                "state_key": BOT,
+                                "content": { "membership": "invite" }
+                            }] } } },
+                            "join": { "!room:localhost": { "timeline": { "events": [{
+                                "type": "m.room.message",
+                                "event_id": "$old",
+                                "sender": "@alice:localhost",
+                                "content": { "msgtype": "m.text", "body": "backlog" }
+                            }] } } }
+                        }
+                    }),
+                ),
+                ("/_matrix/client/v3/sync", "s1") => (
+                    200,
+                    json!({
+                        "next_batch": "s2",
+                        "rooms": { "join": { "!room:localhost": { "timeline": { "events": [{
+                            "type": "m.room.message",
+                            "event_id": "$new",
+                            "sender": "@alice:localhost",
+                            "content": { "msgtype": "m.text", "body": "hello" }
+                        }] } } } }
+                    }),
+                ),
+                ("/_matrix/client/v3/sync", _) => (200, json!({ "next_batch": "s2" })),
+                _ => (200, json!({})),
+            }
+        }))
+        .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let token_path = dir.path().join("sync_token");
+        let config = MatrixConfig {
+            enabled: true,
+            homeserver_url: base_url,
+            access_token: "syt_test".to_string(),
+            sync_timeout_secs: 0,
+            sync_token_path: Some(token_path.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let bus = Arc::new(MessageBus::new());
+        let mut channel = MatrixChannel::new(config, Arc::clone(&bus));
+        channel.start().await.unwrap();
+
+        let inbound = tokio::time::timeout(Duration::from_secs(5), bus.consume_inbound())
+            .await
+            .expect("inbound message within timeout")
+            .expect("inbound message");
+        // The token is persisted right after the batch is published.
+        for _ in 0..50 {
+            if load_sync_token(&token_path).as_deref() == Some("s2") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        channel.stop().await.unwrap();
+
+        assert_eq!(inbound.content, "hello");
+        assert_eq!(inbound.chat_id, "!room:localhost");
+        assert_eq!(load_sync_token(&token_path).as_deref(), Some("s2"));
+        let requests = requests.lock().unwrap();
+        assert!(requests
+            .iter()
+            .any(|r| r.method == "POST" && r.path == "/_matrix/client/v3/join/%21new%3Alocalhost"));
+        assert!(requests
+            .iter()
+            .all(|r| r.authorization == "Bearer syt_test"));
+    }
+}
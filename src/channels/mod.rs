@@ -109,8 +109,10 @@
 //! ```
 
 pub mod discord;
+pub mod email;
 mod factory;
 mod manager;
+pub mod matrix;
 pub mod plugin;
 pub mod slack;
 pub mod telegram;
@@ -120,12 +122,14 @@ pub mod whatsapp;
 pub mod whatsapp_cloud;
 
 pub use discord::DiscordChannel;
+pub use email::EmailChannel;
 pub use factory::register_configured_channels;
 pub use manager::ChannelManager;
+pub use matrix::MatrixChannel;
 pub use plugin::ChannelPluginAdapter;
 pub use slack::SlackChannel;
 pub use telegram::TelegramChannel;
-pub use types::{BaseChannelConfig, Channel};
+pub use types::{BaseChannelConfig, Channel, GroupDecision};
 pub use webhook::{WebhookChannel, WebhookChannelConfig};
 pub use whatsapp::WhatsAppChannel;
 pub use whatsapp_cloud::WhatsAppCloudChannel;
@@ -630,6 +630,7 @@ mod tests {
             name: "test".to_string(),
             allowlist: vec![],
             deny_by_default: true,
+            ..Default::default()
         };
         let adapter = ChannelPluginAdapter::new(test_manifest(), PathBuf::from("/tmp"), base);
         assert!(!adapter.is_allowed("anyone"));
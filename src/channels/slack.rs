@@ -3,6 +3,8 @@
 //! Supports:
 //! - outbound messaging via Slack Web API (`chat.postMessage`)
 //! - inbound messaging via Slack Socket Mode (`apps.connections.open`)
+//! - thread-aware replies and per-thread/per-user sessions via `session_scope`
+//! - mention gating and other group policies in channels (not DMs)
 
 use async_trait::async_trait;
 use futures::{SinkExt, StreamExt};
@@ -17,7 +19,7 @@ use tokio_tungstenite::tungstenite::Message as WsMessage;
 use tracing::{debug, error, info, warn};
 
 use crate::bus::{InboundMessage, MessageBus, OutboundMessage};
-use crate::config::SlackConfig;
+use crate::config::{SessionScope, SlackConfig};
 use crate::error::{Result, ZeptoError};
 
 use super::{BaseChannelConfig, Channel};
@@ -47,6 +49,15 @@ struct SlackSocketEnvelope {
 struct SlackSocketPayload {
     #[serde(default)]
     event: Option<SlackEvent>,
+    /// Installations the event was delivered for; carries the bot's user ID.
+    #[serde(default)]
+    authorizations: Vec<SlackAuthorization>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlackAuthorization {
+    #[serde(default)]
+    user_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -67,6 +78,12 @@ struct SlackEvent {
     ts: Option<String>,
     #[serde(default)]
     thread_ts: Option<String>,
+    /// `im` for direct messages; `channel`, `group` or `mpim` otherwise.
+    #[serde(default)]
+    channel_type: Option<String>,
+    /// Author of the thread's parent message (thread replies only).
+    #[serde(default)]
+    parent_user_id: Option<String>,
 }
 
 struct ParsedSocketMessage {
@@ -91,6 +108,8 @@ impl SlackChannel {
             name: "slack".to_string(),
             allowlist: config.allow_from.clone(),
             deny_by_default: config.deny_by_default,
+            session_scope: config.session_scope,
+            group_policy: config.group_policy.clone(),
         };
 
         Self {
@@ -126,9 +145,11 @@ impl SlackChannel {
             "text": msg.content,
         });
 
-        if let Some(ref reply_to) = msg.reply_to {
+        // Slack threads are addressed by the parent message `ts`; an explicit
+        // thread takes precedence over a reply target.
+        if let Some(thread_ts) = msg.thread_id.as_ref().or(msg.reply_to.as_ref()) {
             if let Some(map) = payload.as_object_mut() {
-                map.insert("thread_ts".to_string(), Value::String(reply_to.clone()));
+                map.insert("thread_ts".to_string(), Value::String(thread_ts.clone()));
             }
         }
 
@@ -178,8 +199,7 @@ impl SlackChannel {
 
     fn parse_socket_message(
         raw: &str,
-        allowlist: &[String],
-        deny_by_default: bool,
+        base_config: &BaseChannelConfig,
     ) -> Result<ParsedSocketMessage> {
         let envelope: SlackSocketEnvelope = serde_json::from_str(raw)
             .map_err(|e| ZeptoError::Channel(format!("Invalid Slack socket payload: {}", e)))?;
@@ -188,7 +208,7 @@ impl SlackChannel {
             .envelope_id
             .as_deref()
             .map(|envelope_id| json!({ "envelope_id": envelope_id }).to_string());
-        let inbound_message = Self::extract_inbound_message(&envelope, allowlist, deny_by_default);
+        let inbound_message = Self::extract_inbound_message(&envelope, base_config);
 
         Ok(ParsedSocketMessage {
             ack_message,
@@ -198,8 +218,7 @@ impl SlackChannel {
 
     fn extract_inbound_message(
         envelope: &SlackSocketEnvelope,
-        allowlist: &[String],
-        deny_by_default: bool,
+        base_config: &BaseChannelConfig,
     ) -> Option<InboundMessage> {
         if envelope.envelope_type != "events_api" {
             return None;
@@ -217,17 +236,25 @@ impl SlackChannel {
 
         let sender_id = event.user.as_deref()?.trim().to_string();
         let chat_id = event.channel.as_deref()?.trim().to_string();
-        let content = event.text.as_deref()?.trim().to_string();
+        let bot_user_id = payload
+            .authorizations
+            .iter()
+            .find_map(|auth| auth.user_id.as_deref())
+            .map(str::trim)
+            .filter(|id| !id.is_empty());
+        let raw_text = event.text.as_deref()?;
+        let mentioned = bot_user_id.is_some_and(|id| raw_text.contains(&format!("<@{}>", id)));
+        let content = match bot_user_id {
+            Some(id) if mentioned => raw_text.replace(&format!("<@{}>", id), ""),
+            _ => raw_text.to_string(),
+        }
+        .trim()
+        .to_string();
         if sender_id.is_empty() || chat_id.is_empty() || content.is_empty() {
             return None;
         }
 
-        let allowed = if allowlist.is_empty() {
-            !deny_by_default
-        } else {
-            allowlist.contains(&sender_id)
-        };
-        if !allowed {
+        if !base_config.is_allowed(&sender_id) {
             info!(
                 "Slack: user {} not in allowlist, ignoring inbound message",
                 sender_id
@@ -235,16 +262,48 @@ impl SlackChannel {
             return None;
         }
 
-        let mut inbound = InboundMessage::new("slack", &sender_id, &chat_id, &content);
-        if let Some(ts) = event.ts.as_deref() {
-            if !ts.trim().is_empty() {
-                inbound = inbound.with_metadata("slack_ts", ts);
-            }
+        let ts = event
+            .ts
+            .as_deref()
+            .map(str::trim)
+            .filter(|ts| !ts.is_empty());
+        let thread_ts = event
+            .thread_ts
+            .as_deref()
+            .map(str::trim)
+            .filter(|ts| !ts.is_empty());
+
+        // Messages already in a thread always reply there. In thread scope a
+        // top-level message starts a new thread rooted at its own `ts`, so the
+        // follow-ups in that thread land in the same session.
+        let thread_id = match base_config.session_scope {
+            SessionScope::Thread => thread_ts.or(ts),
+            _ => thread_ts,
+        };
+        let session_key = base_config.session_key(&chat_id, thread_id, &sender_id);
+
+        let mut inbound = InboundMessage::new("slack", &sender_id, &chat_id, &content)
+            .with_session_key(&session_key);
+        if let Some(thread_id) = thread_id {
+            inbound = inbound.with_thread(thread_id);
         }
-        if let Some(thread_ts) = event.thread_ts.as_deref() {
-            if !thread_ts.trim().is_empty() {
-                inbound = inbound.with_metadata("slack_thread_ts", thread_ts);
-            }
+        if let Some(ts) = ts {
+            inbound = inbound.with_metadata("slack_ts", ts);
+        }
+        if let Some(thread_ts) = thread_ts {
+            inbound = inbound.with_metadata("slack_thread_ts", thread_ts);
+        }
+
+        let is_dm = match event.channel_type.as_deref() {
+            Some(channel_type) => channel_type == "im",
+            // DM channel IDs start with "D" when the type is not reported.
+            None => chat_id.starts_with('D'),
+        };
+        if !is_dm {
+            let replied_to_bot = thread_ts.is_some()
+                && bot_user_id.is_some()
+                && event.parent_user_id.as_deref().map(str::trim) == bot_user_id;
+            return base_config.filter_group_message(inbound, mentioned || replied_to_bot);
         }
 
         Some(inbound)
@@ -261,8 +320,7 @@ impl SlackChannel {
         client: reqwest::Client,
         app_token: String,
         bus: Arc<MessageBus>,
-        allowlist: Vec<String>,
-        deny_by_default: bool,
+        base_config: BaseChannelConfig,
         mut shutdown_rx: mpsc::Receiver<()>,
     ) {
         loop {
@@ -318,7 +376,7 @@ impl SlackChannel {
 
                 match next {
                     Some(Ok(WsMessage::Text(raw))) => {
-                        match Self::parse_socket_message(&raw, &allowlist, deny_by_default) {
+                        match Self::parse_socket_message(&raw, &base_config) {
                             Ok(parsed) => {
                                 if let Some(ack_message) = parsed.ack_message {
                                     if let Err(e) =
@@ -407,8 +465,7 @@ impl Channel for SlackChannel {
             self.client.clone(),
             app_token,
             Arc::clone(&self.bus),
-            self.config.allow_from.clone(),
-            self.config.deny_by_default,
+            self.base_config.clone(),
             shutdown_rx,
         ));
 
@@ -495,11 +552,24 @@ impl Channel for SlackChannel {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::GroupPolicy;
 
     fn test_bus() -> Arc<MessageBus> {
         Arc::new(MessageBus::new())
     }
 
+    fn slack_base() -> BaseChannelConfig {
+        BaseChannelConfig::new("slack")
+    }
+
+    fn slack_scoped(session_scope: SessionScope) -> BaseChannelConfig {
+        BaseChannelConfig {
+            name: "slack".to_string(),
+            session_scope,
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn test_slack_channel_creation() {
         let config = SlackConfig {
@@ -655,7 +725,7 @@ mod tests {
         }"#;
 
         let parsed =
-            SlackChannel::parse_socket_message(raw, &[], false).expect("parse should succeed");
+            SlackChannel::parse_socket_message(raw, &slack_base()).expect("parse should succeed");
         assert_eq!(
             parsed.ack_message,
             Some(r#"{"envelope_id":"envelope-123"}"#.to_string())
@@ -675,6 +745,210 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_slack_payload_with_thread_prefers_thread_over_reply() {
+        let msg = OutboundMessage::new("slack", "C123", "hello")
+            .with_reply("173401.000200")
+            .with_thread("173401.000100");
+        let payload = SlackChannel::build_payload(&msg).expect("payload should build");
+
+        assert_eq!(payload["thread_ts"], "173401.000100");
+    }
+
+    #[test]
+    fn test_slack_payload_without_thread_or_reply() {
+        let msg = OutboundMessage::new("slack", "C123", "hello");
+        let payload = SlackChannel::build_payload(&msg).expect("payload should build");
+
+        assert!(payload.get("thread_ts").is_none());
+    }
+
+    fn message_envelope(ts: &str, thread_ts: Option<&str>, user: &str) -> String {
+        let mut event = json!({
+            "type": "message",
+            "user": user,
+            "channel": "C999",
+            "text": "hi",
+            "ts": ts,
+        });
+        if let Some(thread_ts) = thread_ts {
+            event["thread_ts"] = json!(thread_ts);
+        }
+        json!({
+            "envelope_id": "e",
+            "type": "events_api",
+            "payload": { "event": event }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_parse_socket_message_chat_scope_replies_in_existing_thread() {
+        let raw = message_envelope("2.0", Some("1.0"), "U1");
+        let inbound = SlackChannel::parse_socket_message(&raw, &slack_base())
+            .expect("parse should succeed")
+            .inbound_message
+            .expect("inbound expected");
+
+        assert_eq!(inbound.session_key, "slack:C999");
+        assert_eq!(inbound.thread_id.as_deref(), Some("1.0"));
+    }
+
+    #[test]
+    fn test_parse_socket_message_chat_scope_top_level_has_no_thread() {
+        let raw = message_envelope("2.0", None, "U1");
+        let inbound = SlackChannel::parse_socket_message(&raw, &slack_base())
+            .expect("parse should succeed")
+            .inbound_message
+            .expect("inbound expected");
+
+        assert_eq!(inbound.session_key, "slack:C999");
+        assert!(inbound.thread_id.is_none());
+    }
+
+    #[test]
+    fn test_parse_socket_message_thread_scope_separates_threads() {
+        let base = slack_scoped(SessionScope::Thread);
+        let first =
+            SlackChannel::parse_socket_message(&message_envelope("2.0", Some("1.0"), "U1"), &base)
+                .expect("parse should succeed")
+                .inbound_message
+                .expect("inbound expected");
+        let second =
+            SlackChannel::parse_socket_message(&message_envelope("4.0", Some("3.0"), "U1"), &base)
+                .expect("parse should succeed")
+                .inbound_message
+                .expect("inbound expected");
+
+        assert_eq!(first.session_key, "slack:C999:1.0");
+        assert_eq!(second.session_key, "slack:C999:3.0");
+    }
+
+    #[test]
+    fn test_parse_socket_message_thread_scope_top_level_starts_thread() {
+        let base = slack_scoped(SessionScope::Thread);
+        let top_level =
+            SlackChannel::parse_socket_message(&message_envelope("5.0", None, "U1"), &base)
+                .expect("parse should succeed")
+                .inbound_message
+                .expect("inbound expected");
+        let follow_up =
+            SlackChannel::parse_socket_message(&message_envelope("6.0", Some("5.0"), "U2"), &base)
+                .expect("parse should succeed")
+                .inbound_message
+                .expect("inbound expected");
+
+        assert_eq!(top_level.thread_id.as_deref(), Some("5.0"));
+        assert_eq!(top_level.session_key, "slack:C999:5.0");
+        assert_eq!(follow_up.session_key, top_level.session_key);
+    }
+
+    #[test]
+    fn test_parse_socket_message_user_scope() {
+        let base = slack_scoped(SessionScope::User);
+        let inbound =
+            SlackChannel::parse_socket_message(&message_envelope("2.0", None, "U7"), &base)
+                .expect("parse should succeed")
+                .inbound_message
+                .expect("inbound expected");
+
+        assert_eq!(inbound.session_key, "slack:C999:U7");
+    }
+
+    fn group_envelope(text: &str, thread_ts: Option<&str>, parent_user_id: Option<&str>) -> String {
+        let mut event = json!({
+            "type": "message",
+            "user": "U1",
+            "channel": "C999",
+            "channel_type": "channel",
+            "text": text,
+            "ts": "9.0",
+        });
+        if let Some(thread_ts) = thread_ts {
+            event["thread_ts"] = json!(thread_ts);
+        }
+        if let Some(parent_user_id) = parent_user_id {
+            event["parent_user_id"] = json!(parent_user_id);
+        }
+        json!({
+            "envelope_id": "e",
+            "type": "events_api",
+            "payload": {
+                "event": event,
+                "authorizations": [{ "user_id": "UBOT" }]
+            }
+        })
+        .to_string()
+    }
+
+    fn mention_gated() -> BaseChannelConfig {
+        BaseChannelConfig {
+            name: "slack".to_string(),
+            group_policy: GroupPolicy {
+                require_mention: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_socket_message_mention_gate_ignores_unaddressed() {
+        let raw = group_envelope("just chatting", None, None);
+        let parsed = SlackChannel::parse_socket_message(&raw, &mention_gated())
+            .expect("parse should succeed");
+
+        assert!(parsed.inbound_message.is_none());
+    }
+
+    #[test]
+    fn test_parse_socket_message_mention_gate_strips_mention() {
+        let raw = group_envelope("<@UBOT> what's up?", None, None);
+        let inbound = SlackChannel::parse_socket_message(&raw, &mention_gated())
+            .expect("parse should succeed")
+            .inbound_message
+            .expect("inbound expected");
+
+        assert_eq!(inbound.content, "what's up?");
+        assert!(!inbound.is_passive());
+    }
+
+    #[test]
+    fn test_parse_socket_message_mention_gate_accepts_thread_reply_to_bot() {
+        let raw = group_envelope("and then?", Some("8.0"), Some("UBOT"));
+        let inbound = SlackChannel::parse_socket_message(&raw, &mention_gated())
+            .expect("parse should succeed")
+            .inbound_message
+            .expect("inbound expected");
+
+        assert_eq!(inbound.content, "and then?");
+    }
+
+    #[test]
+    fn test_parse_socket_message_mention_gate_skips_direct_messages() {
+        let raw = message_envelope("2.0", None, "U1").replace("C999", "D123");
+        let inbound = SlackChannel::parse_socket_message(&raw, &mention_gated())
+            .expect("parse should succeed")
+            .inbound_message;
+
+        assert!(inbound.is_some());
+    }
+
+    #[test]
+    fn test_parse_socket_message_passive_capture() {
+        let mut base = mention_gated();
+        base.group_policy.passive_capture = true;
+        let inbound = SlackChannel::parse_socket_message(
+            &group_envelope("background chatter", None, None),
+            &base,
+        )
+        .expect("parse should succeed")
+        .inbound_message
+        .expect("passive inbound expected");
+
+        assert!(inbound.is_passive());
+    }
+
     #[test]
     fn test_parse_socket_message_ignores_non_message_event() {
         let raw = r#"{
@@ -684,7 +958,7 @@ mod tests {
         }"#;
 
         let parsed =
-            SlackChannel::parse_socket_message(raw, &[], false).expect("parse should succeed");
+            SlackChannel::parse_socket_message(raw, &slack_base()).expect("parse should succeed");
         assert!(parsed.ack_message.is_some());
         assert!(parsed.inbound_message.is_none());
     }
@@ -699,8 +973,11 @@ mod tests {
             }
         }"#;
 
-        let parsed = SlackChannel::parse_socket_message(raw, &["U123".to_string()], false)
-            .expect("parse should succeed");
+        let parsed = SlackChannel::parse_socket_message(
+            raw,
+            &BaseChannelConfig::with_allowlist("slack", vec!["U123".to_string()]),
+        )
+        .expect("parse should succeed");
         assert!(parsed.ack_message.is_some());
         assert!(parsed.inbound_message.is_none());
     }
@@ -718,9 +995,9 @@ mod tests {
             "payload":{"event":{"type":"message","subtype":"message_changed","channel":"C1","text":"edit"}}
         }"#;
 
-        let bot_parsed = SlackChannel::parse_socket_message(bot_message, &[], false)
+        let bot_parsed = SlackChannel::parse_socket_message(bot_message, &slack_base())
             .expect("parse should succeed");
-        let subtype_parsed = SlackChannel::parse_socket_message(subtype_message, &[], false)
+        let subtype_parsed = SlackChannel::parse_socket_message(subtype_message, &slack_base())
             .expect("parse should succeed");
 
         assert!(bot_parsed.inbound_message.is_none());
@@ -117,6 +117,8 @@ impl TelegramChannel {
             name: "telegram".to_string(),
             allowlist: config.allow_from.clone(),
             deny_by_default: config.deny_by_default,
+            group_policy: config.group_policy.clone(),
+            ..Default::default()
         };
         Self {
             config,
@@ -146,6 +148,29 @@ impl TelegramChannel {
         Duration::from_secs(delay_secs)
     }
 
+    /// Returns whether `text` contains an `@username` mention of the bot.
+    fn mentions_bot(text: &str, bot_username: &str) -> bool {
+        if bot_username.is_empty() {
+            return false;
+        }
+        let mention = format!("@{}", bot_username.to_ascii_lowercase());
+        text.to_ascii_lowercase().contains(&mention)
+    }
+
+    /// Removes `@username` mentions of the bot and trims the result.
+    fn strip_mention(text: &str, bot_username: &str) -> String {
+        if bot_username.is_empty() {
+            return text.trim().to_string();
+        }
+        // Usernames are ASCII, so ASCII lowercasing keeps byte offsets aligned.
+        let mention = format!("@{}", bot_username.to_ascii_lowercase());
+        let mut stripped = text.to_string();
+        while let Some(pos) = stripped.to_ascii_lowercase().find(&mention) {
+            stripped.replace_range(pos..pos + mention.len(), "");
+        }
+        stripped.trim().to_string()
+    }
+
     /// Build a Telegram bot client with explicit proxy behavior.
     ///
     /// We disable automatic system proxy detection to avoid macOS dynamic-store
@@ -208,8 +233,7 @@ impl Channel for TelegramChannel {
         // Clone values for the spawned task
         let token = self.config.token.clone();
         let bus = self.bus.clone();
-        let allowlist = self.config.allow_from.clone();
-        let deny_by_default = self.config.deny_by_default;
+        let base_config = self.base_config.clone();
         // Share the same running flag with the spawned task so state stays in sync
         let running_clone = Arc::clone(&self.running);
 
@@ -234,9 +258,9 @@ impl Channel for TelegramChannel {
                 // kill the channel.  Permanent errors (invalid token, API errors)
                 // bail immediately on the first attempt.
                 let mut attempt: u32 = 0;
-                loop {
+                let me = loop {
                     match bot.get_me().await {
-                        Ok(_) => break,
+                        Ok(me) => break me,
                         Err(e) => {
                             use teloxide::RequestError;
 
@@ -278,7 +302,7 @@ impl Channel for TelegramChannel {
                             attempt += 1;
                         }
                     }
-                }
+                };
 
                 // Create the handler for incoming messages
                 // Note: dptree injects dependencies separately, not as tuples
@@ -287,8 +311,8 @@ impl Channel for TelegramChannel {
                         |_bot: Bot,
                          msg: Message,
                          bus: Arc<MessageBus>,
-                         allowlist: Vec<String>,
-                         deny_by_default: bool| async move {
+                         base_config: BaseChannelConfig,
+                         me: teloxide::types::Me| async move {
                             // Extract user ID
                             let user_id = msg
                                 .from()
@@ -296,12 +320,7 @@ impl Channel for TelegramChannel {
                                 .unwrap_or_else(|| "unknown".to_string());
 
                             // Check allowlist with deny_by_default support
-                            let allowed = if allowlist.is_empty() {
-                                !deny_by_default
-                            } else {
-                                allowlist.contains(&user_id)
-                            };
-                            if !allowed {
+                            if !base_config.is_allowed(&user_id) {
                                 info!(
                                     "Telegram: User {} not in allowlist, ignoring message",
                                     user_id
@@ -324,9 +343,30 @@ impl Channel for TelegramChannel {
                                     }
                                 );
 
+                                let is_group = msg.chat.is_group() || msg.chat.is_supergroup();
+                                let mentioned = TelegramChannel::mentions_bot(text, me.username());
+                                let content = if mentioned {
+                                    TelegramChannel::strip_mention(text, me.username())
+                                } else {
+                                    text.to_string()
+                                };
+
                                 // Create and publish the inbound message
-                                let inbound =
-                                    InboundMessage::new("telegram", &user_id, &chat_id, text);
+                                let mut inbound =
+                                    InboundMessage::new("telegram", &user_id, &chat_id, &content);
+
+                                if is_group {
+                                    let replied_to_bot = msg
+                                        .reply_to_message()
+                                        .and_then(|reply| reply.from())
+                                        .is_some_and(|from| from.id == me.id);
+                                    match base_config
+                                        .filter_group_message(inbound, mentioned || replied_to_bot)
+                                    {
+                                        Some(filtered) => inbound = filtered,
+                                        None => return Ok(()),
+                                    }
+                                }
 
                                 if let Err(e) = bus.publish_inbound(inbound).await {
                                     error!("Failed to publish inbound message to bus: {}", e);
@@ -340,7 +380,7 @@ impl Channel for TelegramChannel {
 
                 // Build the dispatcher with dependencies
                 let mut dispatcher = Dispatcher::builder(bot, handler)
-                    .dependencies(dptree::deps![bus, allowlist, deny_by_default])
+                    .dependencies(dptree::deps![bus, base_config, me])
                     .build();
 
                 info!("Telegram bot dispatcher started, waiting for messages...");
@@ -624,6 +664,53 @@ mod tests {
         assert_eq!(channel.base_config.allowlist, vec!["allowed_user"]);
     }
 
+    #[test]
+    fn test_telegram_base_config_carries_group_policy() {
+        let config = TelegramConfig {
+            enabled: true,
+            token: "test-token".to_string(),
+            group_policy: crate::config::GroupPolicy {
+                require_mention: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let channel = TelegramChannel::new(config, Arc::new(MessageBus::new()));
+
+        assert!(channel.base_config.group_policy.require_mention);
+    }
+
+    // -----------------------------------------------------------------------
+    // Mention handling
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn test_mentions_bot_case_insensitive() {
+        assert!(TelegramChannel::mentions_bot(
+            "hey @ZeptoBot help",
+            "zeptobot"
+        ));
+        assert!(TelegramChannel::mentions_bot("@zeptobot", "ZeptoBot"));
+        assert!(!TelegramChannel::mentions_bot("hey everyone", "zeptobot"));
+        assert!(!TelegramChannel::mentions_bot("@zeptobot", ""));
+    }
+
+    #[test]
+    fn test_strip_mention() {
+        assert_eq!(
+            TelegramChannel::strip_mention("@ZeptoBot what's the weather?", "zeptobot"),
+            "what's the weather?"
+        );
+        assert_eq!(
+            TelegramChannel::strip_mention("ping @zeptobot and @zeptobot", "zeptobot"),
+            "ping  and"
+        );
+        assert_eq!(
+            TelegramChannel::strip_mention("héllo @zeptobot", "zeptobot"),
+            "héllo"
+        );
+    }
+
     // -----------------------------------------------------------------------
     // Startup retry backoff
     // -----------------------------------------------------------------------
@@ -4,8 +4,10 @@
 //! (Telegram, Discord, Slack, etc.) must implement, along with supporting types.
 
 use async_trait::async_trait;
+use tracing::debug;
 
-use crate::bus::OutboundMessage;
+use crate::bus::{InboundMessage, OutboundMessage};
+use crate::config::{GroupPolicy, SessionScope};
 use crate::error::Result;
 
 /// The `Channel` trait defines the interface for all communication channels.
@@ -144,6 +146,22 @@ pub struct BaseChannelConfig {
     /// When `true`, an empty allowlist rejects all senders (strict mode).
     /// When `false` (the default), an empty allowlist allows all senders.
     pub deny_by_default: bool,
+    /// How inbound messages are grouped into sessions.
+    pub session_scope: SessionScope,
+    /// Behaviour in group chats (mention gating, group allowlist, quiet hours).
+    pub group_policy: GroupPolicy,
+}
+
+/// What a channel should do with a group chat message, as decided by
+/// [`BaseChannelConfig::group_decision`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupDecision {
+    /// Publish the message for a normal agent run.
+    Respond,
+    /// Publish the message as passive context only (no LLM call).
+    Capture,
+    /// Drop the message.
+    Ignore,
 }
 
 impl BaseChannelConfig {
@@ -168,6 +186,8 @@ impl BaseChannelConfig {
             name: name.to_string(),
             allowlist: Vec::new(),
             deny_by_default: false,
+            session_scope: SessionScope::default(),
+            group_policy: GroupPolicy::default(),
         }
     }
 
@@ -192,6 +212,8 @@ impl BaseChannelConfig {
             name: name.to_string(),
             allowlist,
             deny_by_default: false,
+            session_scope: SessionScope::default(),
+            group_policy: GroupPolicy::default(),
         }
     }
 
@@ -242,11 +264,149 @@ impl BaseChannelConfig {
             self.allowlist.contains(&user_id.to_string())
         }
     }
+
+    /// Builds the session key for an inbound message according to `session_scope`.
+    ///
+    /// | `session_scope` | Key                                                  |
+    /// |-----------------|------------------------------------------------------|
+    /// | `Chat`          | `name:chat_id`                                       |
+    /// | `Thread`        | `name:chat_id:thread_id` (`name:chat_id` if no thread) |
+    /// | `User`          | `name:chat_id:sender_id`                             |
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use zeptoclaw::channels::BaseChannelConfig;
+    /// use zeptoclaw::config::SessionScope;
+    ///
+    /// let config = BaseChannelConfig {
+    ///     name: "slack".to_string(),
+    ///     session_scope: SessionScope::Thread,
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(config.session_key("C1", Some("171.2"), "U1"), "slack:C1:171.2");
+    /// assert_eq!(config.session_key("C1", None, "U1"), "slack:C1");
+    /// ```
+    pub fn session_key(&self, chat_id: &str, thread_id: Option<&str>, sender_id: &str) -> String {
+        match (self.session_scope, thread_id) {
+            (SessionScope::Thread, Some(thread_id)) => {
+                format!("{}:{}:{}", self.name, chat_id, thread_id)
+            }
+            (SessionScope::User, _) => format!("{}:{}:{}", self.name, chat_id, sender_id),
+            _ => format!("{}:{}", self.name, chat_id),
+        }
+    }
+
+    /// Applies the group policy to a message received in a group chat.
+    ///
+    /// `addressed` is whether the bot was mentioned or replied to; keyword
+    /// triggers are checked here against `content`. Channels should only call
+    /// this for group chats — direct messages always get a response.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use zeptoclaw::channels::{BaseChannelConfig, GroupDecision};
+    /// use zeptoclaw::config::GroupPolicy;
+    ///
+    /// let config = BaseChannelConfig {
+    ///     name: "telegram".to_string(),
+    ///     group_policy: GroupPolicy {
+    ///         require_mention: true,
+    ///         keywords: vec!["deploy".to_string()],
+    ///         ..Default::default()
+    ///     },
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(config.group_decision("g1", "hello all", false), GroupDecision::Ignore);
+    /// assert_eq!(config.group_decision("g1", "hello all", true), GroupDecision::Respond);
+    /// assert_eq!(config.group_decision("g1", "Deploy now?", false), GroupDecision::Respond);
+    /// ```
+    pub fn group_decision(&self, chat_id: &str, content: &str, addressed: bool) -> GroupDecision {
+        self.group_decision_at(chat_id, content, addressed, chrono::Local::now().time())
+    }
+
+    /// Applies [`group_decision`](Self::group_decision) to a group chat
+    /// message, returning the message to publish (marked passive when it
+    /// should only be captured as context) or `None` to drop it.
+    pub fn filter_group_message(
+        &self,
+        msg: InboundMessage,
+        addressed: bool,
+    ) -> Option<InboundMessage> {
+        let decision = self.group_decision(&msg.chat_id, &msg.content, addressed);
+        if decision == GroupDecision::Ignore {
+            debug!(
+                "{}: group policy ignored message in chat {}",
+                self.name, msg.chat_id
+            );
+        }
+        decision.apply(msg)
+    }
+
+    fn group_decision_at(
+        &self,
+        chat_id: &str,
+        content: &str,
+        addressed: bool,
+        now: chrono::NaiveTime,
+    ) -> GroupDecision {
+        let policy = &self.group_policy;
+        if !policy.allowed_groups.is_empty() && !policy.allowed_groups.iter().any(|g| g == chat_id)
+        {
+            return GroupDecision::Ignore;
+        }
+
+        let silent = GroupDecision::from_passive(policy.passive_capture);
+        if policy
+            .quiet_hours
+            .as_ref()
+            .is_some_and(|quiet| quiet.contains(now))
+        {
+            return silent;
+        }
+
+        if !policy.require_mention || addressed {
+            return GroupDecision::Respond;
+        }
+        let lowered = content.to_lowercase();
+        let keyword_hit = policy
+            .keywords
+            .iter()
+            .map(|k| k.trim().to_lowercase())
+            .any(|k| !k.is_empty() && lowered.contains(&k));
+        if keyword_hit {
+            GroupDecision::Respond
+        } else {
+            silent
+        }
+    }
+}
+
+impl GroupDecision {
+    fn from_passive(passive_capture: bool) -> Self {
+        if passive_capture {
+            GroupDecision::Capture
+        } else {
+            GroupDecision::Ignore
+        }
+    }
+
+    /// Applies the decision to an inbound message, returning what should be
+    /// published to the bus (if anything).
+    pub fn apply(self, msg: InboundMessage) -> Option<InboundMessage> {
+        match self {
+            GroupDecision::Respond => Some(msg),
+            GroupDecision::Capture => Some(msg.into_passive()),
+            GroupDecision::Ignore => None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::QuietHours;
 
     #[test]
     fn test_base_channel_config_new() {
@@ -332,6 +492,7 @@ mod tests {
             name: "test".to_string(),
             allowlist: vec![],
             deny_by_default: true,
+            ..Default::default()
         };
         assert!(!config.is_allowed("anyone"));
     }
@@ -342,6 +503,7 @@ mod tests {
             name: "test".to_string(),
             allowlist: vec!["user1".to_string()],
             deny_by_default: true,
+            ..Default::default()
         };
         assert!(config.is_allowed("user1"));
         assert!(!config.is_allowed("user2"));
@@ -353,6 +515,7 @@ mod tests {
             name: "test".to_string(),
             allowlist: vec![],
             deny_by_default: false,
+            ..Default::default()
         };
         assert!(config.is_allowed("anyone"));
     }
@@ -363,4 +526,158 @@ mod tests {
         assert!(!config.deny_by_default);
         assert!(config.is_allowed("anyone"));
     }
+
+    // ---- session_scope tests ----
+
+    #[test]
+    fn test_session_key_chat_scope_ignores_thread_and_sender() {
+        let config = BaseChannelConfig::new("slack");
+        assert_eq!(config.session_key("C1", Some("171.2"), "U1"), "slack:C1");
+    }
+
+    #[test]
+    fn test_session_key_thread_scope() {
+        let config = BaseChannelConfig {
+            name: "discord".to_string(),
+            session_scope: SessionScope::Thread,
+            ..Default::default()
+        };
+        assert_eq!(config.session_key("ch", Some("th"), "u"), "discord:ch:th");
+        assert_eq!(config.session_key("ch", None, "u"), "discord:ch");
+    }
+
+    #[test]
+    fn test_session_key_user_scope() {
+        let config = BaseChannelConfig {
+            name: "slack".to_string(),
+            session_scope: SessionScope::User,
+            ..Default::default()
+        };
+        assert_eq!(config.session_key("C1", Some("171.2"), "U1"), "slack:C1:U1");
+        assert_eq!(config.session_key("C1", None, "U2"), "slack:C1:U2");
+    }
+
+    fn at(hh_mm: &str) -> chrono::NaiveTime {
+        chrono::NaiveTime::parse_from_str(hh_mm, "%H:%M").unwrap()
+    }
+
+    fn policy_config(group_policy: GroupPolicy) -> BaseChannelConfig {
+        BaseChannelConfig {
+            name: "telegram".to_string(),
+            group_policy,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_group_decision_default_policy_responds() {
+        let config = BaseChannelConfig::new("telegram");
+        assert_eq!(
+            config.group_decision_at("g1", "hello", false, at("12:00")),
+            GroupDecision::Respond
+        );
+    }
+
+    #[test]
+    fn test_group_decision_allowed_groups() {
+        let config = policy_config(GroupPolicy {
+            allowed_groups: vec!["g1".to_string()],
+            ..Default::default()
+        });
+        assert_eq!(
+            config.group_decision_at("g1", "hi", true, at("12:00")),
+            GroupDecision::Respond
+        );
+        assert_eq!(
+            config.group_decision_at("g2", "hi", true, at("12:00")),
+            GroupDecision::Ignore
+        );
+    }
+
+    #[test]
+    fn test_group_decision_require_mention_and_keywords() {
+        let config = policy_config(GroupPolicy {
+            require_mention: true,
+            keywords: vec!["  Incident ".to_string(), String::new()],
+            ..Default::default()
+        });
+        assert_eq!(
+            config.group_decision_at("g1", "lunch?", false, at("12:00")),
+            GroupDecision::Ignore
+        );
+        assert_eq!(
+            config.group_decision_at("g1", "lunch?", true, at("12:00")),
+            GroupDecision::Respond
+        );
+        assert_eq!(
+            config.group_decision_at("g1", "new INCIDENT opened", false, at("12:00")),
+            GroupDecision::Respond
+        );
+    }
+
+    #[test]
+    fn test_group_decision_quiet_hours_wrap_midnight() {
+        let config = policy_config(GroupPolicy {
+            quiet_hours: Some(QuietHours {
+                start: "22:00".to_string(),
+                end: "07:00".to_string(),
+            }),
+            ..Default::default()
+        });
+        assert_eq!(
+            config.group_decision_at("g1", "hi", true, at("23:30")),
+            GroupDecision::Ignore
+        );
+        assert_eq!(
+            config.group_decision_at("g1", "hi", true, at("06:59")),
+            GroupDecision::Ignore
+        );
+        assert_eq!(
+            config.group_decision_at("g1", "hi", true, at("07:00")),
+            GroupDecision::Respond
+        );
+    }
+
+    #[test]
+    fn test_group_decision_passive_capture() {
+        let config = policy_config(GroupPolicy {
+            require_mention: true,
+            passive_capture: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            config.group_decision_at("g1", "chatter", false, at("12:00")),
+            GroupDecision::Capture
+        );
+
+        let msg = InboundMessage::new("telegram", "u1", "g1", "chatter");
+        let captured = config
+            .filter_group_message(msg, false)
+            .expect("passive message expected");
+        assert!(captured.is_passive());
+    }
+
+    #[test]
+    fn test_quiet_hours_contains() {
+        let day = QuietHours {
+            start: "09:00".to_string(),
+            end: "17:00".to_string(),
+        };
+        assert!(day.contains(at("09:00")));
+        assert!(day.contains(at("16:59")));
+        assert!(!day.contains(at("17:00")));
+        assert!(!day.contains(at("08:59")));
+
+        let invalid = QuietHours {
+            start: "late".to_string(),
+            end: "17:00".to_string(),
+        };
+        assert!(!invalid.contains(at("12:00")));
+
+        let empty = QuietHours {
+            start: "10:00".to_string(),
+            end: "10:00".to_string(),
+        };
+        assert!(!empty.contains(at("10:00")));
+    }
 }
@@ -33,6 +33,64 @@
 //! }
 //! ```
 //!
+//! The payload may also carry a `"callback_url"` to receive replies for that
+//! chat; otherwise the configured default callback URL (if any) is used.
+//! Payload callback URLs must point at one of the configured
+//! `callback_allowed_hosts` (requests naming any other host get `403`), and
+//! the first callback URL registered for a chat sticks: a later payload
+//! naming a different one gets `409`.
+//!
+//! # Callbacks
+//!
+//! When a callback URL is known for a chat, the agent's replies are POSTed to
+//! it as JSON:
+//!
+//! ```json
+//! POST <callback_url> HTTP/1.1
+//! Content-Type: application/json
+//! X-ZeptoClaw-Delivery: 0b6c...
+//! X-ZeptoClaw-Timestamp: 1718000000
+//! X-ZeptoClaw-Signature: sha256=<hex HMAC-SHA256 of "<timestamp>.<body>">
+//!
+//! {
+//!     "id": "0b6c...",
+//!     "channel": "webhook",
+//!     "chat_id": "webhook-chat-123",
+//!     "content": "Hello from ZeptoClaw",
+//!     "thread_id": null,
+//!     "reply_to": null,
+//!     "timestamp": 1718000000
+//! }
+//! ```
+//!
+//! The signature header is only sent when a callback secret is configured.
+//! Network errors, `408`, `429` and `5xx` responses are retried with
+//! exponential backoff; deliveries that still fail (or get any other `4xx`)
+//! are appended to a JSON Lines dead-letter file.
+//!
+//! # Synchronous mode
+//!
+//! With `sync_response` enabled the HTTP connection is held open until the
+//! agent replies to that chat (or `sync_timeout` elapses, answering `504`),
+//! and the reply is returned in the response body:
+//!
+//! ```json
+//! {"status": "ok", "chat_id": "webhook-chat-123", "reply": "Hello!"}
+//! ```
+//!
+//! Requests with `Accept: text/event-stream` (or `"stream": true` in the
+//! payload) get a Server-Sent Events response instead: `delta` events carry
+//! chunks of the reply as the model produces them, and a final `reply` event
+//! carries the complete text before the connection closes.
+//!
+//! # Signed requests
+//!
+//! When `sources` are configured, every request must name its source in
+//! `X-ZeptoClaw-Source` and carry `X-ZeptoClaw-Timestamp` and
+//! `X-ZeptoClaw-Signature` headers computed with that source's secret, the
+//! same scheme used for callbacks. This is checked in addition to the Bearer
+//! token.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -49,13 +107,21 @@
 
 use async_trait::async_trait;
 use serde::Deserialize;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpListener;
+use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
 use crate::bus::{InboundMessage, MessageBus, OutboundMessage};
+use crate::config::{Config, WebhookSourceConfig};
 use crate::error::{Result, ZeptoError};
 
 use super::{BaseChannelConfig, Channel};
@@ -78,6 +144,50 @@ fn constant_time_eq(a: &str, b: &str) -> bool {
     result == 0
 }
 
+/// HMAC-SHA256 (RFC 2104) over `message` with `key`.
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+/// `sha256=<hex>` signature over `"<timestamp>.<body>"`.
+fn sign_payload(secret: &str, timestamp: i64, body: &str) -> String {
+    let signed = format!("{}.{}", timestamp, body);
+    format!(
+        "sha256={}",
+        hex::encode(hmac_sha256(secret.as_bytes(), signed.as_bytes()))
+    )
+}
+
+/// Builds a complete JSON response with the given status line.
+fn json_response(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+/// Formats one Server-Sent Events frame.
+fn sse_event(event: &str, data: &serde_json::Value) -> String {
+    format!("event: {}\ndata: {}\n\n", event, data)
+}
+
 /// Maximum allowed request body size (1 MB).
 const MAX_BODY_SIZE: usize = 1_048_576;
 
@@ -90,10 +200,31 @@ const HTTP_200_OK: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n
 const HTTP_400_BAD_REQUEST: &str =
     "HTTP/1.1 400 Bad Request\r\nContent-Type: application/json\r\nConnection: close\r\n\r\n";
 const HTTP_401_UNAUTHORIZED: &str = "HTTP/1.1 401 Unauthorized\r\nContent-Type: application/json\r\nContent-Length: 26\r\nConnection: close\r\n\r\n{\"error\":\"unauthorized\"}";
+const HTTP_403_CALLBACK_FORBIDDEN: &str = "HTTP/1.1 403 Forbidden\r\nContent-Type: application/json\r\nContent-Length: 37\r\nConnection: close\r\n\r\n{\"error\":\"callback host not allowed\"}";
 const HTTP_404_NOT_FOUND: &str = "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: 23\r\nConnection: close\r\n\r\n{\"error\":\"not found\"}";
 const HTTP_405_METHOD_NOT_ALLOWED: &str = "HTTP/1.1 405 Method Not Allowed\r\nContent-Type: application/json\r\nContent-Length: 32\r\nConnection: close\r\n\r\n{\"error\":\"method not allowed\"}";
+const HTTP_409_CALLBACK_CONFLICT: &str = "HTTP/1.1 409 Conflict\r\nContent-Type: application/json\r\nContent-Length: 48\r\nConnection: close\r\n\r\n{\"error\":\"chat_id has a different callback_url\"}";
 const HTTP_413_PAYLOAD_TOO_LARGE: &str = "HTTP/1.1 413 Payload Too Large\r\nContent-Type: application/json\r\nContent-Length: 31\r\nConnection: close\r\n\r\n{\"error\":\"payload too large\"}";
 
+// --- Callback delivery ---
+
+/// Header carrying the unique ID of a callback delivery.
+const DELIVERY_HEADER: &str = "X-ZeptoClaw-Delivery";
+/// Header carrying the Unix timestamp the signature covers.
+const TIMESTAMP_HEADER: &str = "X-ZeptoClaw-Timestamp";
+/// Header carrying `sha256=<hex HMAC>` of `"<timestamp>.<body>"`.
+const SIGNATURE_HEADER: &str = "X-ZeptoClaw-Signature";
+/// Header naming the configured source that signed an inbound request.
+const SOURCE_HEADER: &str = "X-ZeptoClaw-Source";
+
+/// Maximum age (either direction) of a signed request's timestamp.
+const SIGNATURE_TOLERANCE_SECS: i64 = 300;
+
+/// Timeout for a single callback request.
+const CALLBACK_TIMEOUT_SECS: u64 = 30;
+/// Upper bound for the delay between callback retries.
+const MAX_CALLBACK_RETRY_DELAY: Duration = Duration::from_secs(300);
+
 /// Runtime configuration for the webhook HTTP server.
 ///
 /// This is the internal runtime configuration, not the serde config struct
@@ -109,6 +240,27 @@ pub struct WebhookChannelConfig {
     /// Optional Bearer token for request authentication.
     /// When set, all requests must include a matching `Authorization: Bearer <token>` header.
     pub auth_token: Option<String>,
+    /// Default URL replies are POSTed to when the inbound payload has no `callback_url`.
+    pub callback_url: Option<String>,
+    /// Hosts a payload `callback_url` may point to (a host also covers its
+    /// subdomains). Empty rejects all payload callback URLs.
+    pub callback_allowed_hosts: Vec<String>,
+    /// Secret used to sign callback bodies with HMAC-SHA256.
+    pub callback_secret: Option<String>,
+    /// Retries after the first failed callback attempt.
+    pub callback_max_retries: u32,
+    /// Delay before the first retry; doubles on each further attempt.
+    pub callback_retry_delay: Duration,
+    /// JSON Lines file for permanently failed deliveries
+    /// (default: `~/.zeptoclaw/webhook/dead_letters.jsonl`).
+    pub dead_letter_path: Option<PathBuf>,
+    /// Hold each request open and return the agent's reply in the response.
+    pub sync_response: bool,
+    /// How long a synchronous request waits for the reply.
+    pub sync_timeout: Duration,
+    /// Sources allowed to sign requests. When non-empty, every request must
+    /// carry a valid signature from one of them.
+    pub sources: Vec<WebhookSourceConfig>,
 }
 
 impl Default for WebhookChannelConfig {
@@ -118,10 +270,28 @@ impl Default for WebhookChannelConfig {
             port: 9876,
             path: "/webhook".to_string(),
             auth_token: None,
+            callback_url: None,
+            callback_allowed_hosts: Vec::new(),
+            callback_secret: None,
+            callback_max_retries: 5,
+            callback_retry_delay: Duration::from_secs(2),
+            dead_letter_path: None,
+            sync_response: false,
+            sync_timeout: Duration::from_secs(120),
+            sources: Vec::new(),
         }
     }
 }
 
+impl WebhookChannelConfig {
+    /// Resolved path of the dead-letter file.
+    pub fn dead_letter_path(&self) -> PathBuf {
+        self.dead_letter_path
+            .clone()
+            .unwrap_or_else(|| Config::dir().join("webhook").join("dead_letters.jsonl"))
+    }
+}
+
 /// JSON body expected from webhook POST requests.
 #[derive(Debug, Deserialize)]
 struct WebhookPayload {
@@ -131,6 +301,282 @@ struct WebhookPayload {
     sender: String,
     /// Chat/conversation identifier for session routing.
     chat_id: String,
+    /// URL that replies for this chat should be POSTed to.
+    #[serde(default)]
+    callback_url: Option<String>,
+    /// In synchronous mode, stream the reply as Server-Sent Events.
+    #[serde(default)]
+    stream: bool,
+}
+
+/// Where replies for a chat go: requests waiting synchronously, or a
+/// callback URL learned from an inbound payload.
+#[derive(Clone, Default)]
+struct ReplyRoutes {
+    callbacks: Arc<Mutex<HashMap<String, String>>>,
+    /// Synchronous requests per chat, oldest first. The agent answers a
+    /// chat's messages in order, so the oldest waiter gets the next reply.
+    waiters: Arc<Mutex<HashMap<String, VecDeque<ReplyWaiter>>>>,
+    next_waiter_id: Arc<AtomicU64>,
+}
+
+struct ReplyWaiter {
+    id: u64,
+    tx: mpsc::UnboundedSender<OutboundMessage>,
+}
+
+impl ReplyRoutes {
+    /// Registers `url` as the callback for `chat_id`.
+    ///
+    /// The first registration wins; returns `false` if the chat already
+    /// has a different callback URL.
+    fn claim_callback(&self, chat_id: &str, url: &str) -> bool {
+        self.callbacks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(chat_id.to_string())
+            .or_insert_with(|| url.to_string())
+            == url
+    }
+
+    fn callback(&self, chat_id: &str) -> Option<String> {
+        self.callbacks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(chat_id)
+            .cloned()
+    }
+
+    /// Queues a synchronous request for the next reply to `chat_id`.
+    fn wait_for_reply(&self, chat_id: &str) -> (u64, mpsc::UnboundedReceiver<OutboundMessage>) {
+        let id = self.next_waiter_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.waiters
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(chat_id.to_string())
+            .or_default()
+            .push_back(ReplyWaiter { id, tx });
+        (id, rx)
+    }
+
+    /// Removes a waiter whose message never reached the agent.
+    fn cancel(&self, chat_id: &str, id: u64) {
+        let mut waiters = self.waiters.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(queue) = waiters.get_mut(chat_id) {
+            queue.retain(|waiter| waiter.id != id);
+            if queue.is_empty() {
+                waiters.remove(chat_id);
+            }
+        }
+    }
+
+    /// Hands `msg` to the oldest request waiting on its chat.
+    ///
+    /// Partial messages go to that request without completing it; a final
+    /// message completes it. Returns the message if no request took it
+    /// (including one that already timed out, which keeps later replies
+    /// matched to the right requests).
+    fn dispatch(&self, msg: OutboundMessage) -> Option<OutboundMessage> {
+        let mut waiters = self.waiters.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(queue) = waiters.get_mut(&msg.chat_id) else {
+            return Some(msg);
+        };
+        let chat_id = msg.chat_id.clone();
+        let unclaimed = if msg.partial {
+            queue
+                .front()
+                .and_then(|waiter| waiter.tx.send(msg).err().map(|e| e.0))
+        } else {
+            match queue.pop_front() {
+                Some(waiter) => waiter.tx.send(msg).err().map(|e| e.0),
+                None => Some(msg),
+            }
+        };
+        if queue.is_empty() {
+            waiters.remove(&chat_id);
+        }
+        unclaimed
+    }
+}
+
+/// Outcome of a single callback attempt.
+enum AttemptError {
+    /// Worth retrying (network error, timeout, `408`, `429`, `5xx`).
+    Transient(String),
+    /// Retrying will not help (any other non-success status).
+    Permanent(String),
+}
+
+/// Signs and POSTs callback payloads, retrying and dead-lettering failures.
+#[derive(Clone)]
+struct CallbackSender {
+    client: reqwest::Client,
+    secret: Option<String>,
+    max_retries: u32,
+    retry_delay: Duration,
+    dead_letter_path: PathBuf,
+}
+
+impl CallbackSender {
+    fn new(config: &WebhookChannelConfig) -> Self {
+        // Redirects are not followed: an allowed callback host must not be
+        // able to bounce deliveries to an internal address.
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(CALLBACK_TIMEOUT_SECS))
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            secret: config
+                .callback_secret
+                .clone()
+                .filter(|secret| !secret.is_empty()),
+            max_retries: config.callback_max_retries,
+            retry_delay: config.callback_retry_delay,
+            dead_letter_path: config.dead_letter_path(),
+        }
+    }
+
+    /// Builds the JSON body for an outbound message.
+    fn payload(delivery_id: &str, msg: &OutboundMessage, timestamp: i64) -> String {
+        json!({
+            "id": delivery_id,
+            "channel": "webhook",
+            "chat_id": msg.chat_id,
+            "content": msg.content,
+            "thread_id": msg.thread_id,
+            "reply_to": msg.reply_to,
+            "timestamp": timestamp,
+        })
+        .to_string()
+    }
+
+    /// Delivers `msg` to `url`. Returns `true` on success; permanent failures
+    /// are written to the dead-letter file.
+    async fn deliver(&self, url: &str, msg: &OutboundMessage) -> bool {
+        let delivery_id = uuid::Uuid::new_v4().to_string();
+        let timestamp = chrono::Utc::now().timestamp();
+        let body = Self::payload(&delivery_id, msg, timestamp);
+
+        let mut attempts = 0;
+        let error = loop {
+            attempts += 1;
+            match self.attempt(url, &delivery_id, timestamp, &body).await {
+                Ok(()) => {
+                    debug!("Webhook: callback {} delivered to {}", delivery_id, url);
+                    return true;
+                }
+                Err(AttemptError::Permanent(e)) => break e,
+                Err(AttemptError::Transient(e)) if attempts > self.max_retries => break e,
+                Err(AttemptError::Transient(e)) => {
+                    let delay = self
+                        .retry_delay
+                        .saturating_mul(2u32.saturating_pow(attempts - 1))
+                        .min(MAX_CALLBACK_RETRY_DELAY);
+                    warn!(
+                        "Webhook: callback to {} failed ({}), retrying in {:?}",
+                        url, e, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        };
+
+        error!(
+            "Webhook: callback {} to {} failed after {} attempt(s): {}",
+            delivery_id, url, attempts, error
+        );
+        let record = json!({
+            "id": delivery_id,
+            "url": url,
+            "attempts": attempts,
+            "error": error,
+            "failed_at": chrono::Utc::now().to_rfc3339(),
+            "payload": serde_json::from_str::<serde_json::Value>(&body).unwrap_or_default(),
+        });
+        if let Err(e) = append_dead_letter(&self.dead_letter_path, &record) {
+            error!(
+                "Webhook: failed to write dead letter to {}: {}",
+                self.dead_letter_path.display(),
+                e
+            );
+        }
+        false
+    }
+
+    async fn attempt(
+        &self,
+        url: &str,
+        delivery_id: &str,
+        timestamp: i64,
+        body: &str,
+    ) -> std::result::Result<(), AttemptError> {
+        let mut request = self
+            .client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .header(DELIVERY_HEADER, delivery_id)
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .body(body.to_string());
+        if let Some(ref secret) = self.secret {
+            request = request.header(SIGNATURE_HEADER, sign_payload(secret, timestamp, body));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AttemptError::Transient(e.to_string()))?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else if status.is_server_error()
+            || status == reqwest::StatusCode::REQUEST_TIMEOUT
+            || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        {
+            Err(AttemptError::Transient(format!("HTTP {}", status)))
+        } else {
+            Err(AttemptError::Permanent(format!("HTTP {}", status)))
+        }
+    }
+}
+
+/// Appends one JSON record to a JSON Lines file, creating parent directories.
+fn append_dead_letter(path: &Path, record: &serde_json::Value) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", record)
+}
+
+/// Returns whether `url` is an absolute `http`/`https` URL.
+fn is_valid_callback_url(url: &str) -> bool {
+    reqwest::Url::parse(url)
+        .map(|u| matches!(u.scheme(), "http" | "https") && u.host_str().is_some())
+        .unwrap_or(false)
+}
+
+/// Returns whether `url`'s host is one of `allowed_hosts` or a subdomain of one.
+fn is_allowed_callback_host(url: &str, allowed_hosts: &[String]) -> bool {
+    let Some(host) = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_ascii_lowercase()))
+    else {
+        return false;
+    };
+    allowed_hosts.iter().any(|allowed| {
+        let allowed = allowed.trim().trim_end_matches('.').to_ascii_lowercase();
+        !allowed.is_empty()
+            && (host == allowed
+                || host
+                    .strip_suffix(allowed.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.')))
+    })
 }
 
 /// Parsed representation of an incoming HTTP request (first line + headers + body).
@@ -147,9 +593,8 @@ struct ParsedHttpRequest {
 /// token, parses the JSON body, and publishes an `InboundMessage` to the
 /// message bus.
 ///
-/// The channel is primarily inbound-only. The `send()` method logs the
-/// outbound message but does not deliver it anywhere because there is no
-/// persistent connection back to the caller.
+/// Replies are POSTed to the chat's callback URL (from the inbound payload or
+/// the configured default). Without one, `send()` only logs the message.
 pub struct WebhookChannel {
     /// Webhook-specific configuration (bind address, port, path, auth).
     config: WebhookChannelConfig,
@@ -161,6 +606,10 @@ pub struct WebhookChannel {
     running: Arc<AtomicBool>,
     /// One-shot sender to signal the TCP listener loop to shut down.
     shutdown_tx: Option<tokio::sync::oneshot::Sender<()>>,
+    /// Waiting synchronous requests and callback URLs, keyed by chat ID.
+    routes: ReplyRoutes,
+    /// Delivers replies to callback URLs.
+    callback_sender: CallbackSender,
 }
 
 impl WebhookChannel {
@@ -193,12 +642,15 @@ impl WebhookChannel {
         base_config: BaseChannelConfig,
         bus: Arc<MessageBus>,
     ) -> Self {
+        let callback_sender = CallbackSender::new(&config);
         Self {
             config,
             base_config,
             bus,
             running: Arc::new(AtomicBool::new(false)),
             shutdown_tx: None,
+            routes: ReplyRoutes::default(),
+            callback_sender,
         }
     }
 
@@ -294,6 +746,7 @@ impl WebhookChannel {
         config: &WebhookChannelConfig,
         base_config: &BaseChannelConfig,
         bus: &MessageBus,
+        routes: &ReplyRoutes,
     ) {
         // Read request data with size limits
         let mut buf = vec![0u8; MAX_HEADER_SIZE + MAX_BODY_SIZE];
@@ -382,6 +835,19 @@ impl WebhookChannel {
             let _ = stream.write_all(HTTP_401_UNAUTHORIZED.as_bytes()).await;
             return;
         }
+        let source = match Self::verify_signature(
+            &request.headers,
+            &request.body,
+            &config.sources,
+            chrono::Utc::now().timestamp(),
+        ) {
+            Ok(source) => source,
+            Err(reason) => {
+                info!("Webhook: rejecting request: {}", reason);
+                let _ = stream.write_all(HTTP_401_UNAUTHORIZED.as_bytes()).await;
+                return;
+            }
+        };
 
         // Parse JSON body
         let payload: WebhookPayload = match serde_json::from_str(&request.body) {
@@ -416,6 +882,32 @@ impl WebhookChannel {
             return;
         }
 
+        let callback_url = payload
+            .callback_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty());
+        if let Some(url) = callback_url {
+            if !is_valid_callback_url(url) {
+                let body = r#"{"error":"callback_url must be an http(s) URL"}"#;
+                let response = format!(
+                    "{}Content-Length: {}\r\n\r\n{}",
+                    HTTP_400_BAD_REQUEST,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+                return;
+            }
+            if !is_allowed_callback_host(url, &config.callback_allowed_hosts) {
+                warn!("Webhook: rejecting callback_url {} (host not allowed)", url);
+                let _ = stream
+                    .write_all(HTTP_403_CALLBACK_FORBIDDEN.as_bytes())
+                    .await;
+                return;
+            }
+        }
+
         // Check allowlist
         if !base_config.is_allowed(&payload.sender) {
             info!(
@@ -427,21 +919,45 @@ impl WebhookChannel {
         }
 
         // Build and publish inbound message
-        let inbound = InboundMessage::new(
+        let mut inbound = InboundMessage::new(
             "webhook",
             payload.sender.trim(),
             payload.chat_id.trim(),
             payload.message.trim(),
         );
+        let chat_id = payload.chat_id.trim();
+        if let Some(url) = callback_url {
+            if !routes.claim_callback(chat_id, url) {
+                warn!(
+                    "Webhook: rejecting callback_url for chat {} (already routed elsewhere)",
+                    chat_id
+                );
+                let _ = stream
+                    .write_all(HTTP_409_CALLBACK_CONFLICT.as_bytes())
+                    .await;
+                return;
+            }
+            inbound = inbound.with_metadata("callback_url", url);
+        }
+        if let Some(ref source) = source {
+            inbound = inbound.with_metadata("webhook_source", source);
+        }
+
+        let use_sse = config.sync_response
+            && (payload.stream || Self::accepts_event_stream(&request.headers));
+        if use_sse {
+            inbound = inbound.into_streaming();
+        }
+        // Register before publishing so the reply cannot arrive first.
+        let waiter = config.sync_response.then(|| routes.wait_for_reply(chat_id));
 
         if let Err(e) = bus.publish_inbound(inbound).await {
             error!("Webhook: failed to publish inbound message: {}", e);
+            if let Some((id, _)) = waiter {
+                routes.cancel(chat_id, id);
+            }
             let body = r#"{"error":"internal server error"}"#;
-            let response = format!(
-                "HTTP/1.1 500 Internal Server Error\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
-                body.len(),
-                body
-            );
+            let response = json_response("500 Internal Server Error", body);
             let _ = stream.write_all(response.as_bytes()).await;
             return;
         }
@@ -450,7 +966,129 @@ impl WebhookChannel {
             "Webhook: received message from {} in chat {}",
             payload.sender, payload.chat_id
         );
-        let _ = stream.write_all(HTTP_200_OK.as_bytes()).await;
+        match waiter {
+            Some((_, rx)) if use_sse => {
+                Self::stream_reply(&mut stream, rx, config.sync_timeout).await;
+            }
+            Some((_, rx)) => {
+                Self::respond_with_reply(&mut stream, rx, config.sync_timeout, chat_id).await;
+            }
+            None => {
+                let _ = stream.write_all(HTTP_200_OK.as_bytes()).await;
+            }
+        }
+    }
+
+    /// Verifies the request signature against the configured sources.
+    ///
+    /// Returns the name of the signing source, `None` when no sources are
+    /// configured, or the reason the request must be rejected.
+    fn verify_signature(
+        headers: &[(String, String)],
+        body: &str,
+        sources: &[WebhookSourceConfig],
+        now: i64,
+    ) -> std::result::Result<Option<String>, &'static str> {
+        if sources.is_empty() {
+            return Ok(None);
+        }
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.trim())
+        };
+
+        let source_name = header(SOURCE_HEADER).ok_or("missing source header")?;
+        let source = sources
+            .iter()
+            .find(|s| s.name == source_name)
+            .ok_or("unknown source")?;
+        let timestamp: i64 = header(TIMESTAMP_HEADER)
+            .and_then(|v| v.parse().ok())
+            .ok_or("missing or invalid timestamp")?;
+        if (now - timestamp).abs() > SIGNATURE_TOLERANCE_SECS {
+            return Err("stale timestamp");
+        }
+        let signature = header(SIGNATURE_HEADER).ok_or("missing signature")?;
+        if !constant_time_eq(signature, &sign_payload(&source.secret, timestamp, body)) {
+            return Err("invalid signature");
+        }
+        Ok(Some(source.name.clone()))
+    }
+
+    /// Whether the client asked for a Server-Sent Events response.
+    fn accepts_event_stream(headers: &[(String, String)]) -> bool {
+        headers.iter().any(|(name, value)| {
+            name.eq_ignore_ascii_case("accept") && value.contains("text/event-stream")
+        })
+    }
+
+    /// Waits for the final reply and returns it as a JSON response.
+    async fn respond_with_reply(
+        stream: &mut tokio::net::TcpStream,
+        mut rx: mpsc::UnboundedReceiver<OutboundMessage>,
+        timeout: Duration,
+        chat_id: &str,
+    ) {
+        let deadline = tokio::time::Instant::now() + timeout;
+        let response = loop {
+            match tokio::time::timeout_at(deadline, rx.recv()).await {
+                Ok(Some(msg)) if msg.partial => continue,
+                Ok(Some(msg)) => {
+                    let body = json!({
+                        "status": "ok",
+                        "chat_id": chat_id,
+                        "reply": msg.content,
+                        "thread_id": msg.thread_id,
+                    });
+                    break json_response("200 OK", &body.to_string());
+                }
+                Ok(None) | Err(_) => {
+                    warn!("Webhook: timed out waiting for reply in chat {}", chat_id);
+                    let body = r#"{"error":"timed out waiting for reply"}"#;
+                    break json_response("504 Gateway Timeout", body);
+                }
+            }
+        };
+        let _ = stream.write_all(response.as_bytes()).await;
+    }
+
+    /// Streams partial replies as SSE `delta` events, then the final `reply`.
+    async fn stream_reply(
+        stream: &mut tokio::net::TcpStream,
+        mut rx: mpsc::UnboundedReceiver<OutboundMessage>,
+        timeout: Duration,
+    ) {
+        let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n";
+        if stream.write_all(head.as_bytes()).await.is_err() {
+            return;
+        }
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let (frame, done) = match tokio::time::timeout_at(deadline, rx.recv()).await {
+                Ok(Some(msg)) if msg.partial => (
+                    sse_event("delta", &json!({ "content": msg.content })),
+                    false,
+                ),
+                Ok(Some(msg)) => (
+                    sse_event(
+                        "reply",
+                        &json!({ "content": msg.content, "thread_id": msg.thread_id }),
+                    ),
+                    true,
+                ),
+                Ok(None) | Err(_) => (
+                    sse_event("error", &json!({ "error": "timed out waiting for reply" })),
+                    true,
+                ),
+            };
+            // Stop once the client has gone away.
+            if stream.write_all(frame.as_bytes()).await.is_err() || done {
+                return;
+            }
+        }
     }
 
     /// Find the byte offset of the `\r\n\r\n` header/body separator.
@@ -504,6 +1142,7 @@ impl Channel for WebhookChannel {
         let base_config = self.base_config.clone();
         let bus = Arc::clone(&self.bus);
         let running = Arc::clone(&self.running);
+        let routes = self.routes.clone();
 
         tokio::spawn(async move {
             // Convert the oneshot into a future we can select against
@@ -518,8 +1157,10 @@ impl Channel for WebhookChannel {
                                 let cfg = config.clone();
                                 let bc = base_config.clone();
                                 let bus_ref = Arc::clone(&bus);
+                                let routes = routes.clone();
                                 tokio::spawn(async move {
-                                    Self::handle_connection(stream, &cfg, &bc, &bus_ref).await;
+                                    Self::handle_connection(stream, &cfg, &bc, &bus_ref, &routes)
+                                        .await;
                                 });
                             }
                             Err(e) => {
@@ -560,9 +1201,12 @@ impl Channel for WebhookChannel {
         Ok(())
     }
 
-    /// Webhook is primarily inbound-only; outbound messages are logged but
-    /// not delivered because there is no persistent return channel to the
-    /// original HTTP caller.
+    /// Delivers the message to a request waiting on its chat (synchronous
+    /// mode), or else POSTs it to the chat's callback URL in the background.
+    ///
+    /// Retries and dead-lettering happen in a spawned task so a slow callback
+    /// endpoint never blocks other outbound messages. Without either, the
+    /// message is only logged.
     async fn send(&self, msg: OutboundMessage) -> Result<()> {
         if !self.running.load(Ordering::SeqCst) {
             return Err(ZeptoError::Channel(
@@ -570,6 +1214,27 @@ impl Channel for WebhookChannel {
             ));
         }
 
+        let Some(msg) = self.routes.dispatch(msg) else {
+            return Ok(());
+        };
+        // Streamed chunks are only useful to a waiting SSE client.
+        if msg.partial {
+            return Ok(());
+        }
+
+        let callback_url = self
+            .routes
+            .callback(&msg.chat_id)
+            .or_else(|| self.config.callback_url.clone())
+            .filter(|url| !url.trim().is_empty());
+        if let Some(url) = callback_url {
+            let sender = self.callback_sender.clone();
+            tokio::spawn(async move {
+                sender.deliver(&url, &msg).await;
+            });
+            return Ok(());
+        }
+
         info!(
             "Webhook: outbound message to chat {} (logged only, no delivery): {}",
             msg.chat_id,
@@ -624,6 +1289,7 @@ mod tests {
             port: 8080,
             path: "/api/hook".to_string(),
             auth_token: Some("secret-token".to_string()),
+            ..Default::default()
         };
         assert_eq!(config.bind_address, "0.0.0.0");
         assert_eq!(config.port, 8080);
@@ -915,6 +1581,7 @@ mod tests {
             port: 3000,
             path: "/hooks/inbound".to_string(),
             auth_token: Some("abc".to_string()),
+            ..Default::default()
         };
         let channel = WebhookChannel::new(config, BaseChannelConfig::new("webhook"), test_bus());
         let cfg = channel.webhook_config();
@@ -938,6 +1605,7 @@ mod tests {
             port: 0,
             path: "/webhook".to_string(),
             auth_token: None,
+            ..Default::default()
         };
 
         // We need to bind ourselves first to discover the actual port, then
@@ -1014,6 +1682,7 @@ mod tests {
             port,
             path: "/webhook".to_string(),
             auth_token: None,
+            ..Default::default()
         };
 
         let mut channel =
@@ -1052,6 +1721,7 @@ mod tests {
             port,
             path: "/webhook".to_string(),
             auth_token: None,
+            ..Default::default()
         };
 
         let mut channel =
@@ -1085,6 +1755,7 @@ mod tests {
             port,
             path: "/webhook".to_string(),
             auth_token: Some("test-token".to_string()),
+            ..Default::default()
         };
 
         let mut channel =
@@ -1146,6 +1817,7 @@ mod tests {
             port,
             path: "/webhook".to_string(),
             auth_token: Some("correct-token".to_string()),
+            ..Default::default()
         };
 
         let mut channel =
@@ -1181,4 +1853,623 @@ mod tests {
 
         channel.stop().await.unwrap();
     }
+
+    // -----------------------------------------------------------------------
+    // 15. Callback delivery
+    // -----------------------------------------------------------------------
+
+    /// A received callback request: lowercased headers and body.
+    type Recorded = Arc<Mutex<Vec<(HashMap<String, String>, String)>>>;
+
+    /// Local callback receiver answering with `statuses` in order (the last
+    /// one repeats).
+    async fn spawn_callback_server(statuses: Vec<u16>) -> (String, Recorded) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let recorded: Recorded = Arc::new(Mutex::new(Vec::new()));
+        let requests = Arc::clone(&recorded);
+
+        tokio::spawn(async move {
+            let mut count = 0;
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                let (header_end, content_length) = loop {
+                    let n = stream.read(&mut chunk).await.unwrap_or(0);
+                    if n == 0 {
+                        break (0, 0);
+                    }
+                    buf.extend_from_slice(&chunk[..n]);
+                    if let Some(end) = WebhookChannel::find_header_end(&buf) {
+                        let req = WebhookChannel::parse_http_request(&buf).unwrap();
+                        break (end, WebhookChannel::content_length(&req.headers));
+                    }
+                };
+                while buf.len() < header_end + 4 + content_length {
+                    let n = stream.read(&mut chunk).await.unwrap_or(0);
+                    if n == 0 {
+                        break;
+                    }
+                    buf.extend_from_slice(&chunk[..n]);
+                }
+                let req = WebhookChannel::parse_http_request(&buf).unwrap();
+                let headers = req
+                    .headers
+                    .into_iter()
+                    .map(|(k, v)| (k.to_ascii_lowercase(), v))
+                    .collect();
+                requests.lock().unwrap().push((headers, req.body));
+
+                let status = statuses[count.min(statuses.len() - 1)];
+                count += 1;
+                let response = format!(
+                    "HTTP/1.1 {} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        (url, recorded)
+    }
+
+    fn callback_config(dead_letters: &Path) -> WebhookChannelConfig {
+        WebhookChannelConfig {
+            callback_secret: Some("shh".to_string()),
+            callback_max_retries: 2,
+            callback_retry_delay: Duration::from_millis(10),
+            dead_letter_path: Some(dead_letters.to_path_buf()),
+            ..Default::default()
+        }
+    }
+
+    async fn wait_for_requests(recorded: &Recorded, count: usize) {
+        for _ in 0..200 {
+            if recorded.lock().unwrap().len() >= count {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("expected {} callback requests", count);
+    }
+
+    #[test]
+    fn test_hmac_sha256_rfc4231_vectors() {
+        assert_eq!(
+            hex::encode(hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Keys longer than the block size are hashed first.
+        assert_eq!(
+            hex::encode(hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn test_is_valid_callback_url() {
+        assert!(is_valid_callback_url("https://example.com/hook"));
+        assert!(is_valid_callback_url("http://127.0.0.1:8080/cb"));
+        assert!(!is_valid_callback_url("ftp://example.com/hook"));
+        assert!(!is_valid_callback_url("file:///etc/passwd"));
+        assert!(!is_valid_callback_url("not a url"));
+    }
+
+    #[test]
+    fn test_webhook_payload_with_callback_url() {
+        let json = r#"{"message":"hi","sender":"s","chat_id":"c","callback_url":"https://example.com/cb"}"#;
+        let payload: WebhookPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            payload.callback_url.as_deref(),
+            Some("https://example.com/cb")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_delivers_signed_callback_after_retry() {
+        let dir = tempfile::tempdir().unwrap();
+        let dead_letters = dir.path().join("dead.jsonl");
+        let (url, recorded) = spawn_callback_server(vec![503, 200]).await;
+        let config = WebhookChannelConfig {
+            callback_url: Some(url),
+            ..callback_config(&dead_letters)
+        };
+        let channel = WebhookChannel::new(config, BaseChannelConfig::new("webhook"), test_bus());
+        channel.running.store(true, Ordering::SeqCst);
+
+        channel
+            .send(OutboundMessage::new("webhook", "chat-1", "All done").with_thread("t1"))
+            .await
+            .unwrap();
+        wait_for_requests(&recorded, 2).await;
+
+        let requests = recorded.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        let (headers, body) = &requests[1];
+        let payload: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(payload["chat_id"], "chat-1");
+        assert_eq!(payload["content"], "All done");
+        assert_eq!(payload["thread_id"], "t1");
+        assert_eq!(headers["x-zeptoclaw-delivery"], payload["id"]);
+
+        let timestamp: i64 = headers["x-zeptoclaw-timestamp"].parse().unwrap();
+        assert_eq!(
+            headers["x-zeptoclaw-signature"],
+            sign_payload("shh", timestamp, body)
+        );
+        // Retries reuse the same delivery ID and body.
+        assert_eq!(&requests[0].1, body);
+        assert!(!dead_letters.exists());
+    }
+
+    #[tokio::test]
+    async fn test_callback_exhausted_retries_dead_letter() {
+        let dir = tempfile::tempdir().unwrap();
+        let dead_letters = dir.path().join("nested").join("dead.jsonl");
+        let (url, recorded) = spawn_callback_server(vec![500]).await;
+        let sender = CallbackSender::new(&callback_config(&dead_letters));
+
+        let delivered = sender
+            .deliver(&url, &OutboundMessage::new("webhook", "chat-1", "lost"))
+            .await;
+
+        assert!(!delivered);
+        assert_eq!(recorded.lock().unwrap().len(), 3);
+        let contents = std::fs::read_to_string(&dead_letters).unwrap();
+        let record: serde_json::Value = serde_json::from_str(contents.trim()).unwrap();
+        assert_eq!(record["url"], url);
+        assert_eq!(record["attempts"], 3);
+        assert_eq!(record["error"], "HTTP 500 Internal Server Error");
+        assert_eq!(record["payload"]["content"], "lost");
+    }
+
+    #[tokio::test]
+    async fn test_callback_client_error_is_not_retried() {
+        let dir = tempfile::tempdir().unwrap();
+        let dead_letters = dir.path().join("dead.jsonl");
+        let (url, recorded) = spawn_callback_server(vec![404]).await;
+        let sender = CallbackSender::new(&callback_config(&dead_letters));
+
+        assert!(
+            !sender
+                .deliver(&url, &OutboundMessage::new("webhook", "c", "x"))
+                .await
+        );
+        assert_eq!(recorded.lock().unwrap().len(), 1);
+        assert!(dead_letters.exists());
+    }
+
+    #[tokio::test]
+    async fn test_payload_callback_url_routes_replies() {
+        let dir = tempfile::tempdir().unwrap();
+        let (callback_url, recorded) = spawn_callback_server(vec![200]).await;
+        let temp_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = temp_listener.local_addr().unwrap().port();
+        drop(temp_listener);
+
+        let bus = test_bus();
+        let config = WebhookChannelConfig {
+            port,
+            callback_secret: None,
+            callback_allowed_hosts: vec!["127.0.0.1".to_string()],
+            ..callback_config(&dir.path().join("dead.jsonl"))
+        };
+        let mut channel =
+            WebhookChannel::new(config, BaseChannelConfig::new("webhook"), Arc::clone(&bus));
+        channel.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let body = format!(
+            r#"{{"message":"ping","sender":"svc","chat_id":"job-7","callback_url":"{}"}}"#,
+            callback_url
+        );
+        let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .unwrap();
+        let request = format!(
+            "POST /webhook HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = vec![0u8; 1024];
+        let n = stream.read(&mut response).await.unwrap();
+        assert!(std::str::from_utf8(&response[..n])
+            .unwrap()
+            .starts_with("HTTP/1.1 200 OK"));
+
+        let inbound = tokio::time::timeout(Duration::from_secs(2), bus.consume_inbound())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(inbound.metadata.get("callback_url"), Some(&callback_url));
+
+        channel
+            .send(OutboundMessage::reply_to(&inbound, "pong"))
+            .await
+            .unwrap();
+        wait_for_requests(&recorded, 1).await;
+        channel.stop().await.unwrap();
+
+        let requests = recorded.lock().unwrap();
+        let (headers, body) = &requests[0];
+        assert!(!headers.contains_key("x-zeptoclaw-signature"));
+        assert!(body.contains(r#""content":"pong""#));
+    }
+
+    // -----------------------------------------------------------------------
+    // 16. Synchronous replies and signed requests
+    // -----------------------------------------------------------------------
+
+    fn sync_config(port: u16) -> WebhookChannelConfig {
+        WebhookChannelConfig {
+            port,
+            sync_response: true,
+            sync_timeout: Duration::from_secs(5),
+            ..Default::default()
+        }
+    }
+
+    async fn free_port() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap().port()
+    }
+
+    /// POSTs `body` with extra headers and returns the whole response.
+    async fn post_raw(port: u16, extra_headers: &str, body: &str) -> String {
+        let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .unwrap();
+        let request = format!(
+            "POST /webhook HTTP/1.1\r\nHost: localhost\r\n{}Content-Length: {}\r\n\r\n{}",
+            extra_headers,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = Vec::new();
+        tokio::time::timeout(Duration::from_secs(10), stream.read_to_end(&mut response))
+            .await
+            .unwrap()
+            .unwrap();
+        String::from_utf8(response).unwrap()
+    }
+
+    #[test]
+    fn test_is_allowed_callback_host() {
+        let allowed = vec!["example.com".to_string(), "10.0.0.5".to_string()];
+        assert!(is_allowed_callback_host("https://example.com/cb", &allowed));
+        assert!(is_allowed_callback_host(
+            "https://hooks.EXAMPLE.com/cb",
+            &allowed
+        ));
+        assert!(is_allowed_callback_host(
+            "http://10.0.0.5:8080/cb",
+            &allowed
+        ));
+        assert!(!is_allowed_callback_host(
+            "https://badexample.com/cb",
+            &allowed
+        ));
+        assert!(!is_allowed_callback_host(
+            "http://169.254.169.254/latest",
+            &allowed
+        ));
+        assert!(!is_allowed_callback_host("https://example.com/cb", &[]));
+    }
+
+    #[test]
+    fn test_reply_routes_first_callback_wins() {
+        let routes = ReplyRoutes::default();
+        assert!(routes.claim_callback("c1", "https://a.example/cb"));
+        assert!(routes.claim_callback("c1", "https://a.example/cb"));
+        assert!(!routes.claim_callback("c1", "https://evil.example/cb"));
+        assert_eq!(
+            routes.callback("c1").as_deref(),
+            Some("https://a.example/cb")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_payload_callback_url_host_and_route_checks() {
+        let port = free_port().await;
+        let bus = test_bus();
+        let config = WebhookChannelConfig {
+            port,
+            callback_allowed_hosts: vec!["example.com".to_string()],
+            ..Default::default()
+        };
+        let mut channel =
+            WebhookChannel::new(config, BaseChannelConfig::new("webhook"), Arc::clone(&bus));
+        channel.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let payload = |url: &str| {
+            format!(
+                r#"{{"message":"hi","sender":"svc","chat_id":"job-1","callback_url":"{}"}}"#,
+                url
+            )
+        };
+        let response = post_raw(port, "", &payload("http://169.254.169.254/latest")).await;
+        assert!(response.starts_with("HTTP/1.1 403"), "{response}");
+
+        let response = post_raw(port, "", &payload("https://example.com/cb")).await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        let response = post_raw(port, "", &payload("https://hooks.example.com/other")).await;
+        assert!(response.starts_with("HTTP/1.1 409"), "{response}");
+
+        channel.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reply_routes_dispatch_in_order() {
+        let routes = ReplyRoutes::default();
+        let (_, mut first) = routes.wait_for_reply("c1");
+        let (_, mut second) = routes.wait_for_reply("c1");
+
+        let partial = OutboundMessage::new("webhook", "c1", "Hel").into_partial();
+        assert!(routes.dispatch(partial).is_none());
+        assert!(routes
+            .dispatch(OutboundMessage::new("webhook", "c1", "Hello"))
+            .is_none());
+        assert!(routes
+            .dispatch(OutboundMessage::new("webhook", "c1", "Second"))
+            .is_none());
+
+        assert!(first.recv().await.unwrap().partial);
+        assert_eq!(first.recv().await.unwrap().content, "Hello");
+        assert_eq!(second.recv().await.unwrap().content, "Second");
+
+        // Nobody waiting: the message is handed back.
+        let unclaimed = routes.dispatch(OutboundMessage::new("webhook", "c1", "late"));
+        assert_eq!(unclaimed.unwrap().content, "late");
+    }
+
+    #[test]
+    fn test_reply_routes_timed_out_waiter_keeps_order() {
+        let routes = ReplyRoutes::default();
+        let (_, timed_out) = routes.wait_for_reply("c1");
+        let (_, mut next) = routes.wait_for_reply("c1");
+        drop(timed_out);
+
+        // The late reply belongs to the timed-out request, not the next one.
+        let late = routes.dispatch(OutboundMessage::new("webhook", "c1", "late"));
+        assert_eq!(late.unwrap().content, "late");
+        assert!(routes
+            .dispatch(OutboundMessage::new("webhook", "c1", "fresh"))
+            .is_none());
+        assert_eq!(next.try_recv().unwrap().content, "fresh");
+    }
+
+    #[test]
+    fn test_reply_routes_cancel() {
+        let routes = ReplyRoutes::default();
+        let (id, _rx) = routes.wait_for_reply("c1");
+        routes.cancel("c1", id);
+        assert!(routes
+            .dispatch(OutboundMessage::new("webhook", "c1", "x"))
+            .is_some());
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let sources = vec![WebhookSourceConfig {
+            name: "ci".to_string(),
+            secret: "ci-secret".to_string(),
+        }];
+        let body = r#"{"message":"hi","sender":"s","chat_id":"c"}"#;
+        let now = 1_700_000_000;
+        let signed = |source: &str, timestamp: i64, secret: &str| {
+            vec![
+                (SOURCE_HEADER.to_string(), source.to_string()),
+                (TIMESTAMP_HEADER.to_string(), timestamp.to_string()),
+                (
+                    SIGNATURE_HEADER.to_string(),
+                    sign_payload(secret, timestamp, body),
+                ),
+            ]
+        };
+
+        assert_eq!(
+            WebhookChannel::verify_signature(&[], body, &[], now),
+            Ok(None)
+        );
+        assert_eq!(
+            WebhookChannel::verify_signature(&signed("ci", now, "ci-secret"), body, &sources, now),
+            Ok(Some("ci".to_string()))
+        );
+        assert_eq!(
+            WebhookChannel::verify_signature(&signed("ci", now, "wrong"), body, &sources, now),
+            Err("invalid signature")
+        );
+        assert_eq!(
+            WebhookChannel::verify_signature(
+                &signed("ci", now - 600, "ci-secret"),
+                body,
+                &sources,
+                now
+            ),
+            Err("stale timestamp")
+        );
+        assert_eq!(
+            WebhookChannel::verify_signature(
+                &signed("other", now, "ci-secret"),
+                body,
+                &sources,
+                now
+            ),
+            Err("unknown source")
+        );
+        assert_eq!(
+            WebhookChannel::verify_signature(&[], body, &sources, now),
+            Err("missing source header")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_response_returns_reply() {
+        let bus = test_bus();
+        let port = free_port().await;
+        let mut channel = WebhookChannel::new(
+            sync_config(port),
+            BaseChannelConfig::new("webhook"),
+            Arc::clone(&bus),
+        );
+        channel.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let request = tokio::spawn(async move {
+            post_raw(
+                port,
+                "",
+                r#"{"message":"2+2?","sender":"script","chat_id":"calc"}"#,
+            )
+            .await
+        });
+        let inbound = tokio::time::timeout(Duration::from_secs(2), bus.consume_inbound())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!inbound.wants_streaming());
+        channel
+            .send(OutboundMessage::reply_to(&inbound, "4"))
+            .await
+            .unwrap();
+
+        let response = request.await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        let body: serde_json::Value =
+            serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(body["reply"], "4");
+        assert_eq!(body["chat_id"], "calc");
+
+        channel.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sync_response_streams_sse() {
+        let bus = test_bus();
+        let port = free_port().await;
+        let mut channel = WebhookChannel::new(
+            sync_config(port),
+            BaseChannelConfig::new("webhook"),
+            Arc::clone(&bus),
+        );
+        channel.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let request = tokio::spawn(async move {
+            post_raw(
+                port,
+                "Accept: text/event-stream\r\n",
+                r#"{"message":"story","sender":"script","chat_id":"sse"}"#,
+            )
+            .await
+        });
+        let inbound = tokio::time::timeout(Duration::from_secs(2), bus.consume_inbound())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(inbound.wants_streaming());
+        for chunk in ["Once ", "upon"] {
+            channel
+                .send(OutboundMessage::reply_to(&inbound, chunk).into_partial())
+                .await
+                .unwrap();
+        }
+        channel
+            .send(OutboundMessage::reply_to(&inbound, "Once upon"))
+            .await
+            .unwrap();
+
+        let response = request.await.unwrap();
+        assert!(response.contains("Content-Type: text/event-stream"));
+        let events: Vec<&str> = response
+            .split("\r\n\r\n")
+            .nth(1)
+            .unwrap()
+            .split("\n\n")
+            .filter(|e| !e.is_empty())
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                "event: delta\ndata: {\"content\":\"Once \"}",
+                "event: delta\ndata: {\"content\":\"upon\"}",
+                "event: reply\ndata: {\"content\":\"Once upon\",\"thread_id\":null}",
+            ]
+        );
+
+        channel.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sync_response_times_out() {
+        let port = free_port().await;
+        let config = WebhookChannelConfig {
+            sync_timeout: Duration::from_millis(100),
+            ..sync_config(port)
+        };
+        let mut channel =
+            WebhookChannel::new(config, BaseChannelConfig::new("webhook"), test_bus());
+        channel.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let response = post_raw(
+            port,
+            "",
+            r#"{"message":"hello","sender":"script","chat_id":"slow"}"#,
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 504 Gateway Timeout"));
+
+        channel.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_signed_request_end_to_end() {
+        let bus = test_bus();
+        let port = free_port().await;
+        let config = WebhookChannelConfig {
+            port,
+            sources: vec![WebhookSourceConfig {
+                name: "ci".to_string(),
+                secret: "ci-secret".to_string(),
+            }],
+            ..Default::default()
+        };
+        let mut channel =
+            WebhookChannel::new(config, BaseChannelConfig::new("webhook"), Arc::clone(&bus));
+        channel.start().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let body = r#"{"message":"build failed","sender":"ci","chat_id":"builds"}"#;
+        let unsigned = post_raw(port, "", body).await;
+        assert!(unsigned.starts_with("HTTP/1.1 401"));
+
+        let timestamp = chrono::Utc::now().timestamp();
+        let headers = format!(
+            "{}: ci\r\n{}: {}\r\n{}: {}\r\n",
+            SOURCE_HEADER,
+            TIMESTAMP_HEADER,
+            timestamp,
+            SIGNATURE_HEADER,
+            sign_payload("ci-secret", timestamp, body)
+        );
+        let signed = post_raw(port, &headers, body).await;
+        assert!(signed.starts_with("HTTP/1.1 200 OK"));
+
+        let inbound = tokio::time::timeout(Duration::from_secs(2), bus.consume_inbound())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            inbound.metadata.get("webhook_source").map(String::as_str),
+            Some("ci")
+        );
+
+        channel.stop().await.unwrap();
+    }
 }
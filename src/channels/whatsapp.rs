@@ -121,6 +121,7 @@ impl WhatsAppChannel {
             name: "whatsapp".to_string(),
             allowlist: config.allow_from.clone(),
             deny_by_default: config.deny_by_default,
+            ..Default::default()
         };
 
         Self {
@@ -330,6 +330,7 @@ impl WhatsAppCloudChannel {
             name: "whatsapp_cloud".to_string(),
             allowlist: config.allow_from.clone(),
             deny_by_default: config.deny_by_default,
+            ..Default::default()
         };
 
         Self {
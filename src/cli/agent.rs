@@ -1,6 +1,7 @@
 //! Agent command handlers (interactive + stdin mode).
 
 use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
@@ -13,12 +14,19 @@ use zeptoclaw::providers::{
 
 use super::common::{create_agent, create_agent_with_template, resolve_template};
 
+/// Session key of messages sent from the interactive CLI.
+const CLI_SESSION_KEY: &str = "cli:cli";
+
+/// Note the agent appends to an answer stopped mid-stream.
+const STOPPED_NOTE: &str = "[Stopped by the user.]";
+
 /// Interactive or single-message agent mode.
 pub(crate) async fn cmd_agent(
     message: Option<String>,
     template_name: Option<String>,
     stream: bool,
     dry_run: bool,
+    show_reasoning: bool,
 ) -> Result<()> {
     // Load configuration
     let config = Config::load().with_context(|| "Failed to load configuration")?;
@@ -91,7 +99,7 @@ pub(crate) async fn cmd_agent(
     if let Some(msg) = message {
         // Single message mode
         let inbound = InboundMessage::new("cli", "user", "cli", &msg);
-        let streaming = stream || config.agents.defaults.streaming;
+        let streaming = stream || show_reasoning || config.agents.defaults.streaming;
 
         if streaming {
             use zeptoclaw::providers::StreamEvent;
@@ -108,6 +116,9 @@ pub(crate) async fn cmd_agent(
                                 eprintln!("{}", format_cli_error(&e));
                                 std::process::exit(1);
                             }
+                            StreamEvent::ReasoningDelta(text) => {
+                                print_reasoning(&text, show_reasoning);
+                            }
                             StreamEvent::ToolCalls(_) => {}
                         }
                     }
@@ -133,8 +144,27 @@ pub(crate) async fn cmd_agent(
         // Interactive mode
         println!("ZeptoClaw Interactive Agent");
         println!("Type your message and press Enter. Type 'quit' or 'exit' to stop.");
+        println!("Press Ctrl-C to stop a response in progress.");
         println!();
 
+        // Ctrl-C stops the current run; at the prompt it exits as usual.
+        let busy = Arc::new(AtomicBool::new(false));
+        {
+            let agent = Arc::clone(&agent);
+            let busy = Arc::clone(&busy);
+            tokio::spawn(async move {
+                while tokio::signal::ctrl_c().await.is_ok() {
+                    if !busy.load(Ordering::SeqCst) {
+                        println!();
+                        std::process::exit(130);
+                    }
+                    if agent.cancel_run(CLI_SESSION_KEY) {
+                        eprintln!("\nStopping...");
+                    }
+                }
+            });
+        }
+
         let stdin = io::stdin();
         let mut stdout = io::stdout();
 
@@ -161,23 +191,37 @@ pub(crate) async fn cmd_agent(
 
                     // Process message
                     let inbound = InboundMessage::new("cli", "user", "cli", input);
-                    let streaming = stream || config.agents.defaults.streaming;
+                    let streaming = stream || show_reasoning || config.agents.defaults.streaming;
+                    busy.store(true, Ordering::SeqCst);
 
                     if streaming {
                         use zeptoclaw::providers::StreamEvent;
                         match agent.process_message_streaming(&inbound).await {
                             Ok(mut rx) => {
                                 println!();
+                                let mut printed = false;
                                 while let Some(event) = rx.recv().await {
                                     match event {
                                         StreamEvent::Delta(text) => {
+                                            printed = true;
                                             print!("{}", text);
                                             let _ = io::stdout().flush();
                                         }
-                                        StreamEvent::Done { .. } => break,
+                                        StreamEvent::Done { content, .. } => {
+                                            // A stopped run ends with a summary that was not streamed.
+                                            if !printed {
+                                                print!("{}", content);
+                                            } else if content.ends_with(STOPPED_NOTE) {
+                                                print!("\n\n{}", STOPPED_NOTE);
+                                            }
+                                            break;
+                                        }
                                         StreamEvent::Error(e) => {
                                             eprintln!("{}", format_cli_error(&e));
                                         }
+                                        StreamEvent::ReasoningDelta(text) => {
+                                            print_reasoning(&text, show_reasoning);
+                                        }
                                         StreamEvent::ToolCalls(_) => {}
                                     }
                                 }
@@ -202,6 +246,7 @@ pub(crate) async fn cmd_agent(
                             }
                         }
                     }
+                    busy.store(false, Ordering::SeqCst);
                 }
                 Err(e) => {
                     eprintln!("Error reading input: {}", e);
@@ -214,6 +259,14 @@ pub(crate) async fn cmd_agent(
     Ok(())
 }
 
+/// Print a streamed reasoning chunk to stderr so stdout stays the answer.
+fn print_reasoning(text: &str, show: bool) {
+    if show {
+        eprint!("{}", text);
+        let _ = io::stderr().flush();
+    }
+}
+
 /// Run agent in stdin/stdout mode for containerized execution.
 pub(crate) async fn cmd_agent_stdin() -> Result<()> {
     let mut config = Config::load().with_context(|| "Failed to load configuration")?;
@@ -1,6 +1,6 @@
 //! Batch command handler.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -9,7 +9,7 @@ use anyhow::{Context, Result};
 use zeptoclaw::batch::{format_results, load_prompts, BatchOutputFormat, BatchResult};
 use zeptoclaw::bus::{InboundMessage, MessageBus};
 use zeptoclaw::config::Config;
-use zeptoclaw::providers::StreamEvent;
+use zeptoclaw::providers::{OutputFormat, StreamEvent};
 
 use super::common::{create_agent, create_agent_with_template, resolve_template};
 use super::BatchFormat;
@@ -22,6 +22,7 @@ pub(crate) async fn cmd_batch(
     stop_on_error: bool,
     stream: bool,
     template: Option<String>,
+    schema: Option<PathBuf>,
 ) -> Result<()> {
     let prompts = load_prompts(&input).with_context(|| {
         format!(
@@ -30,13 +31,16 @@ pub(crate) async fn cmd_batch(
         )
     })?;
 
+    let output_format = schema.as_deref().map(load_schema).transpose()?;
+
     let config = Config::load().with_context(|| "Failed to load configuration")?;
     let use_streaming = stream || config.agents.defaults.streaming;
 
+    let tpl = template.as_deref().map(resolve_template).transpose()?;
+
     let bus = Arc::new(MessageBus::new());
-    let agent = if let Some(name) = template.as_deref() {
-        let tpl = resolve_template(name)?;
-        create_agent_with_template(config, bus, Some(tpl)).await?
+    let agent = if tpl.is_some() {
+        create_agent_with_template(config, bus, tpl).await?
     } else {
         create_agent(config, bus).await?
     };
@@ -48,7 +52,13 @@ pub(crate) async fn cmd_batch(
         let start = Instant::now();
         let inbound = InboundMessage::new("cli", "batch", &format!("batch-{}", index), &prompt);
 
-        let response = if use_streaming {
+        let response = if let Some(format) = &output_format {
+            agent
+                .process_message_structured(&inbound, format.clone())
+                .await
+                .map(|output| output.value.to_string())
+                .map_err(anyhow::Error::from)
+        } else if use_streaming {
             process_streaming(&agent, &inbound).await
         } else {
             agent
@@ -61,6 +71,9 @@ pub(crate) async fn cmd_batch(
         match response {
             Ok(content) => results.push(BatchResult {
                 index,
+                output: output_format
+                    .as_ref()
+                    .and_then(|_| serde_json::from_str(&content).ok()),
                 prompt,
                 response: Some(content),
                 error: None,
@@ -72,6 +85,7 @@ pub(crate) async fn cmd_batch(
                     index,
                     prompt,
                     response: None,
+                    output: None,
                     error: Some(err.to_string()),
                     duration_ms,
                 });
@@ -82,11 +96,11 @@ pub(crate) async fn cmd_batch(
         }
     }
 
-    let output_format = match format {
+    let results_format = match format {
         BatchFormat::Text => BatchOutputFormat::Text,
         BatchFormat::Jsonl => BatchOutputFormat::Jsonl,
     };
-    let rendered = format_results(&results, &output_format);
+    let rendered = format_results(&results, &results_format);
 
     if let Some(path) = output {
         std::fs::write(&path, rendered)
@@ -107,6 +121,23 @@ pub(crate) async fn cmd_batch(
     Ok(())
 }
 
+/// Load a JSON Schema file as a structured output format named after the file.
+fn load_schema(path: &Path) -> Result<OutputFormat> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read schema file {}", path.display()))?;
+    let schema: serde_json::Value = serde_json::from_str(&content)
+        .with_context(|| format!("Schema file {} is not valid JSON", path.display()))?;
+    let name = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("output")
+        .replace(
+            |c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '-',
+            "_",
+        );
+    Ok(OutputFormat::json_schema(&name, schema))
+}
+
 async fn process_streaming(
     agent: &Arc<zeptoclaw::agent::AgentLoop>,
     inbound: &InboundMessage,
@@ -118,7 +149,7 @@ async fn process_streaming(
             StreamEvent::Delta(chunk) => response.push_str(&chunk),
             StreamEvent::Done { .. } => break,
             StreamEvent::Error(err) => anyhow::bail!("stream error: {}", err),
-            StreamEvent::ToolCalls(_) => {}
+            StreamEvent::ToolCalls(_) | StreamEvent::ReasoningDelta(_) => {}
         }
     }
     Ok(response)
@@ -79,6 +79,34 @@ async fn cmd_channel_list() -> Result<()> {
     };
     println!("  {:<12} {:<10} {}", "whatsapp", wa_status, wa_detail);
 
+    // Matrix
+    let (mx_status, mx_detail) = match config.channels.matrix {
+        Some(ref c) if c.enabled => (
+            "enabled",
+            if c.access_token.is_empty() {
+                "token missing".to_string()
+            } else {
+                format!("homeserver: {}", c.homeserver_url)
+            },
+        ),
+        _ => ("disabled", "-".to_string()),
+    };
+    println!("  {:<12} {:<10} {}", "matrix", mx_status, mx_detail);
+
+    // Email
+    let (em_status, em_detail) = match config.channels.email {
+        Some(ref c) if c.enabled => (
+            "enabled",
+            if c.username.is_empty() || c.imap_host.is_empty() {
+                "credentials missing".to_string()
+            } else {
+                format!("{} via {}", c.username, c.imap_host)
+            },
+        ),
+        _ => ("disabled", "-".to_string()),
+    };
+    println!("  {:<12} {:<10} {}", "email", em_status, em_detail);
+
     // Webhook
     let (wh_status, wh_detail) = match config.channels.webhook {
         Some(ref c) if c.enabled => (
@@ -9,17 +9,20 @@ use anyhow::{Context, Result};
 use tracing::{info, warn};
 
 use zeptoclaw::agent::{AgentLoop, ContextBuilder, RuntimeContext};
-use zeptoclaw::auth::{self, AuthMethod};
+use zeptoclaw::auth::{self, AuthMethod, ResolvedCredential};
 use zeptoclaw::bus::MessageBus;
 use zeptoclaw::config::templates::{AgentTemplate, TemplateRegistry};
-use zeptoclaw::config::{Config, MemoryBackend, MemoryCitationsMode};
+use zeptoclaw::config::{Config, MemoryBackend, MemoryCitationsMode, ShellPolicyMode};
 use zeptoclaw::cron::CronService;
 use zeptoclaw::memory::factory::create_searcher;
+use zeptoclaw::providers::keypool::{key_pool_state_path, mask_api_key};
 use zeptoclaw::providers::{
     provider_config_by_name, resolve_runtime_providers, ClaudeProvider, FallbackProvider,
-    LLMProvider, OpenAIProvider, RetryProvider, RuntimeProviderSelection,
+    GeminiProvider, KeyPoolProvider, LLMProvider, OpenAIProvider, RateLimiter, RetryProvider,
+    RouterProvider, RuntimeProviderSelection,
 };
-use zeptoclaw::runtime::{create_runtime, NativeRuntime};
+use zeptoclaw::runtime::{create_runtime, ContainerRuntime, NativeRuntime, SessionRuntime};
+use zeptoclaw::security::{EgressPolicy, ShellPolicy, ShellSecurityConfig, StoreEncryption};
 use zeptoclaw::session::SessionManager;
 use zeptoclaw::skills::SkillsLoader;
 use zeptoclaw::tools::cron::CronTool;
@@ -123,30 +126,119 @@ pub(crate) fn resolve_template(name: &str) -> Result<AgentTemplate> {
 
 fn provider_from_runtime_selection(
     selection: &RuntimeProviderSelection,
+    config: &Config,
+) -> Option<Box<dyn LLMProvider>> {
+    if selection.extra_api_keys.is_empty() {
+        return provider_for_credential(selection, config);
+    }
+
+    // Several keys for one provider: one inner provider (and rate limiter)
+    // per key, load-balanced by a key pool.
+    let mut members = Vec::with_capacity(1 + selection.extra_api_keys.len());
+    let mut limiters = Vec::with_capacity(members.capacity());
+    for api_key in std::iter::once(&selection.api_key).chain(&selection.extra_api_keys) {
+        let keyed = RuntimeProviderSelection {
+            api_key: api_key.clone(),
+            credential: ResolvedCredential::ApiKey(api_key.clone()),
+            extra_api_keys: Vec::new(),
+            ..selection.clone()
+        };
+        members.push((
+            mask_api_key(api_key),
+            provider_for_credential(&keyed, config)?,
+        ));
+        limiters.push(rate_limiter_for(&keyed, config));
+    }
+
+    let pool = &config.providers.key_pool;
+    info!(
+        provider = selection.name,
+        keys = members.len(),
+        strategy = pool.strategy.as_str(),
+        "Using API key pool"
+    );
+    Some(Box::new(
+        KeyPoolProvider::new(
+            selection.name,
+            members,
+            pool.strategy,
+            pool.failure_threshold,
+            pool.cooldown_secs,
+        )
+        .with_bench_secs(pool.bench_secs)
+        .with_rate_limiters(limiters)
+        .with_state_path(key_pool_state_path(selection.name)),
+    ))
+}
+
+fn provider_for_credential(
+    selection: &RuntimeProviderSelection,
+    config: &Config,
 ) -> Option<Box<dyn LLMProvider>> {
+    let limiter = rate_limiter_for(selection, config);
     match selection.backend {
         "anthropic" => {
             // Use credential-aware constructor when OAuth token is available
-            if selection.credential.is_bearer() {
-                Some(Box::new(ClaudeProvider::with_credential(
-                    selection.credential.clone(),
-                )))
+            let mut provider = if selection.credential.is_bearer() {
+                ClaudeProvider::with_credential(selection.credential.clone())
             } else {
-                Some(Box::new(ClaudeProvider::new(&selection.api_key)))
+                ClaudeProvider::new(&selection.api_key)
+            };
+            if let Some(limiter) = limiter {
+                provider = provider.with_rate_limiter(limiter);
             }
+            Some(Box::new(provider))
         }
         "openai" => {
-            let provider = if let Some(base_url) = selection.api_base.as_deref() {
+            let mut provider = if let Some(base_url) = selection.api_base.as_deref() {
                 OpenAIProvider::with_base_url(&selection.api_key, base_url)
             } else {
                 OpenAIProvider::new(&selection.api_key)
             };
+            if let Some(limiter) = limiter {
+                provider = provider.with_rate_limiter(limiter);
+            }
+            Some(Box::new(provider))
+        }
+        "gemini" => {
+            let mut provider = if let Some(base_url) = selection.api_base.as_deref() {
+                GeminiProvider::with_base_url(&selection.api_key, base_url)
+            } else {
+                GeminiProvider::new(&selection.api_key)
+            };
+            if let Some(p) = provider_config_by_name(config, selection.name) {
+                provider = provider.with_safety_settings(p.safety_settings.clone());
+            }
+            if let Some(limiter) = limiter {
+                provider = provider.with_rate_limiter(limiter);
+            }
             Some(Box::new(provider))
         }
         _ => None,
     }
 }
 
+/// Shared rate limiter for a provider selection, unless rate limiting is off.
+fn rate_limiter_for(
+    selection: &RuntimeProviderSelection,
+    config: &Config,
+) -> Option<Arc<RateLimiter>> {
+    if !config.providers.rate_limit.enabled {
+        return None;
+    }
+    let limiter = RateLimiter::shared(selection.name, &selection.api_key);
+    limiter.set_max_wait(std::time::Duration::from_millis(
+        config.providers.retry.max_retry_after_ms,
+    ));
+    if let Some(rpm) = provider_config_by_name(config, selection.name)
+        .and_then(|p| p.requests_per_minute)
+        .filter(|rpm| *rpm > 0)
+    {
+        limiter.set_requests_per_minute(rpm);
+    }
+    Some(limiter)
+}
+
 struct RuntimeProviderCandidate {
     name: &'static str,
     provider: Box<dyn LLMProvider>,
@@ -201,7 +293,7 @@ fn build_runtime_provider_chain(
     let mut candidates: Vec<RuntimeProviderCandidate> = Vec::new();
 
     for selection in resolve_runtime_providers(config) {
-        if let Some(provider) = provider_from_runtime_selection(&selection) {
+        if let Some(provider) = provider_from_runtime_selection(&selection, config) {
             candidates.push(RuntimeProviderCandidate {
                 name: selection.name,
                 provider,
@@ -259,10 +351,30 @@ fn apply_retry_wrapper(provider: Box<dyn LLMProvider>, config: &Config) -> Box<d
         RetryProvider::new(provider)
             .with_max_retries(config.providers.retry.max_retries)
             .with_base_delay_ms(config.providers.retry.base_delay_ms)
-            .with_max_delay_ms(config.providers.retry.max_delay_ms),
+            .with_max_delay_ms(config.providers.retry.max_delay_ms)
+            .with_max_retry_after_ms(config.providers.retry.max_retry_after_ms),
     )
 }
 
+fn apply_router_wrapper(
+    provider: Box<dyn LLMProvider>,
+    config: &Config,
+    agent: &AgentLoop,
+) -> Box<dyn LLMProvider> {
+    let router = &config.providers.router;
+    if !router.enabled {
+        return provider;
+    }
+
+    info!(
+        simple_model = router.simple_model.as_deref().unwrap_or("-"),
+        complex_model = router.complex_model.as_deref().unwrap_or("-"),
+        classifier_model = router.classifier_model.as_deref().unwrap_or("-"),
+        "Configured model router"
+    );
+    Box::new(RouterProvider::new(provider, router.clone()).with_metrics(agent.metrics_collector()))
+}
+
 fn provider_auth_method(config: &Config, name: &str) -> AuthMethod {
     provider_config_by_name(config, name)
         .map(|p| p.resolved_auth_method())
@@ -399,6 +511,12 @@ pub(crate) async fn create_agent_with_template(
         if let Some(max_tool_iterations) = tpl.max_tool_iterations {
             config.agents.defaults.max_tool_iterations = max_tool_iterations;
         }
+        if let Some(tool_choice) = &tpl.tool_choice {
+            config.agents.defaults.tool_choice = Some(tool_choice.clone());
+        }
+        if let Some(parallel) = tpl.parallel_tool_calls {
+            config.agents.defaults.parallel_tool_calls = Some(parallel);
+        }
     }
 
     let allowed_tools = template
@@ -457,11 +575,20 @@ pub(crate) async fn create_agent_with_template(
         !blocked_tools.contains(&key)
     };
 
+    // At-rest encryption for sessions, memory, reminders and cron jobs
+    let store_encryption = StoreEncryption::from_config(&config)
+        .with_context(|| "Failed to set up data store encryption")?;
+    if store_encryption.is_enabled() {
+        info!("Data store encryption enabled");
+    }
+
     // Create session manager
-    let session_manager = SessionManager::new().unwrap_or_else(|_| {
-        warn!("Failed to create persistent session manager, using in-memory");
-        SessionManager::new_memory()
-    });
+    let session_manager = SessionManager::new()
+        .map(|m| m.with_encryption(store_encryption.clone()))
+        .unwrap_or_else(|_| {
+            warn!("Failed to create persistent session manager, using in-memory");
+            SessionManager::new_memory()
+        });
 
     let skills_prompt = build_skills_prompt(&config);
     let mut context_builder = ContextBuilder::new();
@@ -496,9 +623,10 @@ pub(crate) async fn create_agent_with_template(
         let ltm_path = zeptoclaw::config::Config::dir()
             .join("memory")
             .join("longterm.json");
-        match zeptoclaw::memory::longterm::LongTermMemory::with_path_and_searcher(
+        match zeptoclaw::memory::longterm::LongTermMemory::open(
             ltm_path,
             memory_searcher.clone(),
+            store_encryption.clone(),
         ) {
             Ok(ltm) => {
                 let memory_ctx = zeptoclaw::memory::build_memory_injection(
@@ -531,11 +659,14 @@ pub(crate) async fn create_agent_with_template(
 
     // Create and start cron service for scheduled tasks.
     let cron_store_path = Config::dir().join("cron").join("jobs.json");
-    let cron_service = Arc::new(CronService::with_jitter(
-        cron_store_path,
-        agent.bus().clone(),
-        config.routines.jitter_ms,
-    ));
+    let cron_service = Arc::new(
+        CronService::with_jitter(
+            cron_store_path,
+            agent.bus().clone(),
+            config.routines.jitter_ms,
+        )
+        .with_encryption(store_encryption.clone()),
+    );
     cron_service.start(&config.routines.on_miss).await?;
 
     // Create runtime from config
@@ -562,6 +693,20 @@ Enable runtime.allow_fallback_to_native to opt in to native fallback.",
         }
     };
 
+    // Keep one shell per agent session if configured
+    let runtime: Arc<dyn ContainerRuntime> = if config.runtime.shell_sessions.enabled {
+        info!(
+            "Persistent shell sessions enabled (idle TTL {}s)",
+            config.runtime.shell_sessions.idle_ttl_secs
+        );
+        Arc::new(SessionRuntime::new(
+            runtime,
+            std::time::Duration::from_secs(config.runtime.shell_sessions.idle_ttl_secs),
+        ))
+    } else {
+        runtime
+    };
+
     // Register all tools
     if tool_enabled("echo") {
         agent.register_tool(Box::new(EchoTool)).await;
@@ -579,11 +724,23 @@ Enable runtime.allow_fallback_to_native to opt in to native fallback.",
         agent.register_tool(Box::new(EditFileTool)).await;
     }
     if tool_enabled("shell") {
+        let mut shell_security = ShellSecurityConfig::new();
+        if config.tools.shell.mode == ShellPolicyMode::Allowlist {
+            let policy = ShellPolicy::from_config(&config.tools.shell)
+                .with_context(|| "Invalid tools.shell allowlist")?;
+            shell_security = shell_security.with_policy(policy);
+        }
         agent
-            .register_tool(Box::new(ShellTool::with_runtime(runtime)))
+            .register_tool(Box::new(ShellTool::with_security_and_runtime(
+                shell_security,
+                runtime,
+            )))
             .await;
     }
 
+    // Outbound requests from HTTP-calling tools go through the egress policy.
+    let egress = EgressPolicy::from_config(&config.tools.egress);
+
     // Register web tools.
     if tool_enabled("web_search") {
         if let Some(web_search_key) = config.tools.web.search.api_key.as_deref() {
@@ -600,7 +757,11 @@ Enable runtime.allow_fallback_to_native to opt in to native fallback.",
         }
     }
     if tool_enabled("web_fetch") {
-        agent.register_tool(Box::new(WebFetchTool::new())).await;
+        agent
+            .register_tool(Box::new(
+                WebFetchTool::new().with_egress(egress.for_tool("web_fetch")),
+            ))
+            .await;
         info!("Registered web_fetch tool");
     }
 
@@ -620,11 +781,14 @@ Enable runtime.allow_fallback_to_native to opt in to native fallback.",
         ) {
             if !phone_number_id.trim().is_empty() && !access_token.trim().is_empty() {
                 agent
-                    .register_tool(Box::new(WhatsAppTool::with_default_language(
-                        phone_number_id.trim(),
-                        access_token.trim(),
-                        config.tools.whatsapp.default_language.trim(),
-                    )))
+                    .register_tool(Box::new(
+                        WhatsAppTool::with_default_language(
+                            phone_number_id.trim(),
+                            access_token.trim(),
+                            config.tools.whatsapp.default_language.trim(),
+                        )
+                        .with_egress(egress.for_tool("whatsapp_send")),
+                    ))
                     .await;
                 info!("Registered whatsapp_send tool");
             }
@@ -637,14 +801,18 @@ Enable runtime.allow_fallback_to_native to opt in to native fallback.",
             let token = access_token.trim();
             if !token.is_empty() {
                 agent
-                    .register_tool(Box::new(GoogleSheetsTool::new(token)))
+                    .register_tool(Box::new(
+                        GoogleSheetsTool::new(token).with_egress(egress.for_tool("google_sheets")),
+                    ))
                     .await;
                 info!("Registered google_sheets tool");
             }
         } else if let Some(encoded) = config.tools.google_sheets.service_account_base64.as_deref() {
             match GoogleSheetsTool::from_service_account(encoded.trim()) {
                 Ok(tool) => {
-                    agent.register_tool(Box::new(tool)).await;
+                    agent
+                        .register_tool(Box::new(tool.with_egress(egress.for_tool("google_sheets"))))
+                        .await;
                     info!("Registered google_sheets tool from base64 payload");
                 }
                 Err(e) => warn!("Failed to initialize google_sheets tool: {}", e),
@@ -671,9 +839,10 @@ Enable runtime.allow_fallback_to_native to opt in to native fallback.",
             let ltm_path = zeptoclaw::config::Config::dir()
                 .join("memory")
                 .join("longterm.json");
-            match zeptoclaw::memory::longterm::LongTermMemory::with_path_and_searcher(
+            match zeptoclaw::memory::longterm::LongTermMemory::open(
                 ltm_path,
                 memory_searcher.clone(),
+                store_encryption.clone(),
             ) {
                 Ok(ltm) => {
                     let tool = zeptoclaw::tools::longterm_memory::LongTermMemoryTool::with_memory(
@@ -710,11 +879,33 @@ Enable runtime.allow_fallback_to_native to opt in to native fallback.",
             .await;
     }
     if tool_enabled("r8r") {
-        agent.register_tool(Box::new(R8rTool::default())).await;
+        let tool = R8rTool::default();
+        // r8r only talks to its configured endpoint, so that server is
+        // allowed (and nothing else) unless tools.egress.tools.r8r says otherwise.
+        let mut r8r_policy = egress.clone();
+        r8r_policy.pin_tool_endpoint("r8r", tool.endpoint())?;
+        let r8r_egress = r8r_policy.for_tool("r8r");
+        r8r_egress.check_url_str(tool.endpoint()).with_context(|| {
+            format!(
+                "r8r endpoint {} is blocked by tools.egress; allow it in tools.egress.tools.r8r or disable the r8r tool",
+                tool.endpoint()
+            )
+        })?;
+        agent
+            .register_tool(Box::new(tool.with_egress(r8r_egress)))
+            .await;
     }
     if tool_enabled("reminder") {
-        match zeptoclaw::tools::reminder::ReminderTool::new(Some(cron_service.clone())) {
-            Ok(tool) => {
+        let store = zeptoclaw::tools::reminder::ReminderStore::open(
+            zeptoclaw::tools::reminder::ReminderStore::default_path(),
+            store_encryption.clone(),
+        );
+        match store {
+            Ok(store) => {
+                let tool = zeptoclaw::tools::reminder::ReminderTool::with_store(
+                    Arc::new(tokio::sync::Mutex::new(store)),
+                    Some(cron_service.clone()),
+                );
                 agent.register_tool(Box::new(tool)).await;
                 info!("Registered reminder tool");
             }
@@ -776,10 +967,13 @@ Enable runtime.allow_fallback_to_native to opt in to native fallback.",
                             }
                         } else {
                             agent
-                                .register_tool(Box::new(zeptoclaw::tools::plugin::PluginTool::new(
-                                    tool_def.clone(),
-                                    plugin.name(),
-                                )))
+                                .register_tool(Box::new(
+                                    zeptoclaw::tools::plugin::PluginTool::new(
+                                        tool_def.clone(),
+                                        plugin.name(),
+                                    )
+                                    .with_egress(egress.for_tool(&tool_def.name)),
+                                ))
                                 .await;
                             info!(
                                 plugin = %plugin.name(),
@@ -808,7 +1002,8 @@ Enable runtime.allow_fallback_to_native to opt in to native fallback.",
             warn!(tool = %tool_def.name, "Skipping custom tool with empty command");
             continue;
         }
-        let tool = zeptoclaw::tools::custom::CustomTool::new(tool_def.clone());
+        let tool = zeptoclaw::tools::custom::CustomTool::new(tool_def.clone())
+            .with_egress(egress.for_tool(&tool_def.name));
         agent.register_tool(Box::new(tool)).await;
         info!(tool = %tool_def.name, "Registered custom CLI tool");
     }
@@ -827,6 +1022,7 @@ Enable runtime.allow_fallback_to_native to opt in to native fallback.",
         let retry_max_delay_ms = config.providers.retry.max_delay_ms;
 
         let provider_chain = apply_retry_wrapper(provider_chain, &config);
+        let provider_chain = apply_router_wrapper(provider_chain, &config, &agent);
 
         agent.set_provider(provider_chain).await;
 
@@ -1038,7 +1234,7 @@ mod tests {
         ) -> zeptoclaw::error::Result<LLMResponse> {
             let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
             if call <= self.fail_until {
-                Err(ProviderError::RateLimit("simulated rate limit".to_string()).into())
+                Err(ProviderError::RateLimit("simulated rate limit".into()).into())
             } else {
                 Ok(LLMResponse::text("ok"))
             }
@@ -34,7 +34,7 @@ pub(crate) async fn cmd_config(action: ConfigAction) -> Result<()> {
                 println!("{}", diag);
             }
 
-            let errors = diagnostics
+            let mut errors = diagnostics
                 .iter()
                 .filter(|d| d.level == zeptoclaw::config::validate::DiagnosticLevel::Error)
                 .count();
@@ -51,6 +51,13 @@ pub(crate) async fn cmd_config(action: ConfigAction) -> Result<()> {
             }
             warnings += tool_warnings.len();
 
+            // Validate custom safety rules
+            let rule_errors = zeptoclaw::safety::validate_rules(&config.safety);
+            for e in &rule_errors {
+                println!("[ERROR] {}", e);
+            }
+            errors += rule_errors.len();
+
             if errors == 0 && warnings == 0 {
                 println!("\nConfiguration looks good!");
             } else {
@@ -318,6 +318,13 @@ pub(crate) async fn cmd_gateway(
         let _ = tokio::time::timeout(std::time::Duration::from_secs(5), handle).await;
     }
 
+    // Remove persistent shell sessions (and their containers)
+    let _ = tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        zeptoclaw::runtime::shutdown_sessions(),
+    )
+    .await;
+
     // Stop health server
     if let Some(handle) = health_handle {
         handle.abort();
@@ -2,14 +2,19 @@
 
 use anyhow::{Context, Result};
 
+use zeptoclaw::config::Config;
+use zeptoclaw::security::StoreEncryption;
 use zeptoclaw::session::{ConversationHistory, Role, SessionManager};
 
 use super::HistoryAction;
 
 /// Manage CLI conversation history.
 pub(crate) async fn cmd_history(action: HistoryAction) -> Result<()> {
-    let history =
-        ConversationHistory::new().with_context(|| "Failed to initialize history store")?;
+    let config = Config::load().with_context(|| "Failed to load configuration")?;
+    let encryption = StoreEncryption::from_config(&config)?;
+    let history = ConversationHistory::new()
+        .with_context(|| "Failed to initialize history store")?
+        .with_encryption(encryption.clone());
 
     match action {
         HistoryAction::List { limit } => {
@@ -33,7 +38,9 @@ pub(crate) async fn cmd_history(action: HistoryAction) -> Result<()> {
                 anyhow::bail!("No conversation found for query '{}'", query);
             };
 
-            let manager = SessionManager::new().with_context(|| "Failed to open session store")?;
+            let manager = SessionManager::new()
+                .with_context(|| "Failed to open session store")?
+                .with_encryption(encryption.clone());
             let Some(session) = manager.get(&entry.session_key).await? else {
                 anyhow::bail!(
                     "Conversation '{}' exists in index but could not be loaded",
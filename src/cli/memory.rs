@@ -1,7 +1,10 @@
 //! Memory CLI command handlers.
 
 use anyhow::{Context, Result};
+use zeptoclaw::config::Config;
+use zeptoclaw::memory::builtin_searcher::BuiltinSearcher;
 use zeptoclaw::memory::longterm::LongTermMemory;
+use zeptoclaw::security::StoreEncryption;
 
 use super::MemoryAction;
 
@@ -21,8 +24,17 @@ pub(crate) async fn cmd_memory(action: MemoryAction) -> Result<()> {
     }
 }
 
+/// Open the long-term memory store, decrypting it when at-rest encryption is on.
+fn open_memory() -> Result<LongTermMemory> {
+    let config = Config::load().with_context(|| "Failed to load configuration")?;
+    let encryption = StoreEncryption::from_config(&config)?;
+    let path = Config::dir().join("memory").join("longterm.json");
+    LongTermMemory::open(path, std::sync::Arc::new(BuiltinSearcher), encryption)
+        .with_context(|| "Failed to open long-term memory")
+}
+
 async fn cmd_memory_list(category: Option<String>) -> Result<()> {
-    let mem = LongTermMemory::new().with_context(|| "Failed to open long-term memory")?;
+    let mem = open_memory()?;
     let entries = if let Some(ref cat) = category {
         mem.list_by_category(cat)
     } else {
@@ -54,7 +66,7 @@ async fn cmd_memory_list(category: Option<String>) -> Result<()> {
 }
 
 async fn cmd_memory_search(query: String) -> Result<()> {
-    let mem = LongTermMemory::new().with_context(|| "Failed to open long-term memory")?;
+    let mem = open_memory()?;
     let results = mem.search(&query);
 
     if results.is_empty() {
@@ -77,7 +89,7 @@ async fn cmd_memory_set(
     category: String,
     tags: Option<String>,
 ) -> Result<()> {
-    let mut mem = LongTermMemory::new().with_context(|| "Failed to open long-term memory")?;
+    let mut mem = open_memory()?;
     let tag_vec: Vec<String> = tags
         .map(|t| {
             t.split(',')
@@ -98,7 +110,7 @@ async fn cmd_memory_set(
 }
 
 async fn cmd_memory_delete(key: String) -> Result<()> {
-    let mut mem = LongTermMemory::new().with_context(|| "Failed to open long-term memory")?;
+    let mut mem = open_memory()?;
     if mem.delete(&key).await? {
         println!("Deleted: {}", key);
     } else {
@@ -108,7 +120,7 @@ async fn cmd_memory_delete(key: String) -> Result<()> {
 }
 
 async fn cmd_memory_stats() -> Result<()> {
-    let mem = LongTermMemory::new().with_context(|| "Failed to open long-term memory")?;
+    let mem = open_memory()?;
     let count = mem.count();
     let categories = mem.categories();
 
@@ -150,7 +162,7 @@ async fn cmd_memory_cleanup(threshold: f32) -> Result<()> {
     if !(0.0..=1.0).contains(&threshold) || !threshold.is_finite() {
         anyhow::bail!("Threshold must be between 0.0 and 1.0");
     }
-    let mut mem = LongTermMemory::new().with_context(|| "Failed to open long-term memory")?;
+    let mut mem = open_memory()?;
     let before = mem.count();
     let removed = mem.cleanup_expired(threshold)?;
     println!(
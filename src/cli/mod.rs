@@ -13,6 +13,7 @@ pub mod history;
 pub mod memory;
 pub mod migrate;
 pub mod onboard;
+pub mod safety;
 pub mod secrets;
 pub mod skills;
 pub mod status;
@@ -55,6 +56,9 @@ enum Commands {
         /// Show what tools would be called without executing them
         #[arg(long)]
         dry_run: bool,
+        /// Stream the model's reasoning to stderr (implies --stream)
+        #[arg(long)]
+        show_reasoning: bool,
     },
     /// Process prompts from a file
     Batch {
@@ -71,11 +75,14 @@ enum Commands {
         #[arg(long)]
         stop_on_error: bool,
         /// Stream LLM output internally while collecting final result text
-        #[arg(long)]
+        #[arg(long, conflicts_with = "schema")]
         stream: bool,
         /// Apply an agent template to all prompts
         #[arg(long)]
         template: Option<String>,
+        /// JSON Schema file; final answers are validated and repaired to match it
+        #[arg(long, value_name = "FILE")]
+        schema: Option<std::path::PathBuf>,
     },
     /// Start multi-channel gateway
     Gateway {
@@ -146,6 +153,11 @@ enum Commands {
         #[command(subcommand)]
         action: SecretsAction,
     },
+    /// Inspect the safety layer
+    Safety {
+        #[command(subcommand)]
+        action: SafetyAction,
+    },
     /// Watch a URL for changes and notify
     Watch {
         /// URL to monitor
@@ -324,8 +336,19 @@ pub enum SecretsAction {
     Encrypt,
     /// Decrypt all secrets for editing
     Decrypt,
-    /// Re-encrypt with a new key
+    /// Re-encrypt secrets and encrypted data stores with a new key
     Rotate,
+    /// Encrypt data store files that are still plaintext
+    EncryptStores,
+}
+
+#[derive(Subcommand)]
+pub enum SafetyAction {
+    /// Dry-run leak and policy rules against sample text
+    Test {
+        /// File with sample text ("-" reads stdin)
+        file: String,
+    },
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
@@ -371,8 +394,9 @@ pub async fn run() -> Result<()> {
             template,
             stream,
             dry_run,
+            show_reasoning,
         }) => {
-            agent::cmd_agent(message, template, stream, dry_run).await?;
+            agent::cmd_agent(message, template, stream, dry_run, show_reasoning).await?;
         }
         Some(Commands::Batch {
             input,
@@ -381,8 +405,18 @@ pub async fn run() -> Result<()> {
             stop_on_error,
             stream,
             template,
+            schema,
         }) => {
-            batch::cmd_batch(input, output, format, stop_on_error, stream, template).await?;
+            batch::cmd_batch(
+                input,
+                output,
+                format,
+                stop_on_error,
+                stream,
+                template,
+                schema,
+            )
+            .await?;
         }
         Some(Commands::Gateway {
             containerized,
@@ -426,6 +460,9 @@ pub async fn run() -> Result<()> {
         Some(Commands::Secrets { action }) => {
             secrets::cmd_secrets(action).await?;
         }
+        Some(Commands::Safety { action }) => {
+            safety::cmd_safety(action).await?;
+        }
         Some(Commands::Watch {
             url,
             interval,
@@ -702,6 +702,11 @@ fn configure_runtime(config: &mut Config) -> Result<()> {
     println!("  2. Docker (requires Docker installed)");
     #[cfg(target_os = "macos")]
     println!("  3. Apple Container (macOS 15+ only)");
+    #[cfg(target_os = "linux")]
+    println!("  3. Sandbox (Landlock + seccomp, no daemon needed)");
+    println!("  4. Podman (rootless, requires Podman installed)");
+    #[cfg(target_os = "linux")]
+    println!("  5. Bubblewrap (requires bwrap installed)");
     println!();
 
     loop {
@@ -764,6 +769,35 @@ fn configure_runtime(config: &mut Config) -> Result<()> {
                 }
                 break;
             }
+            #[cfg(target_os = "linux")]
+            "3" => {
+                config.runtime.runtime_type = RuntimeType::Sandbox;
+                config.runtime.allow_fallback_to_native = false;
+                println!("Configured: Sandbox runtime (requires Linux 5.13+ with Landlock)");
+                break;
+            }
+            "4" => {
+                config.runtime.runtime_type = RuntimeType::Podman;
+                print!("Podman image [{}]: ", config.runtime.podman.image);
+                io::stdout().flush()?;
+                let image = read_line()?.trim().to_string();
+                if !image.is_empty() {
+                    config.runtime.podman.image = image;
+                }
+                config.runtime.allow_fallback_to_native = false;
+                println!(
+                    "Configured: Podman runtime with image {}",
+                    config.runtime.podman.image
+                );
+                break;
+            }
+            #[cfg(target_os = "linux")]
+            "5" => {
+                config.runtime.runtime_type = RuntimeType::Bubblewrap;
+                config.runtime.allow_fallback_to_native = false;
+                println!("Configured: Bubblewrap runtime");
+                break;
+            }
             _ => {
                 println!("Invalid choice. Please try again.");
             }
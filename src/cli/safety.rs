@@ -0,0 +1,139 @@
+//! Safety CLI commands.
+//!
+//! Provides `zeptoclaw safety test <file>` to dry-run the configured leak
+//! and policy rules against sample text without emitting audit events.
+
+use std::io::Read;
+
+use anyhow::{Context, Result};
+
+use super::SafetyAction;
+use zeptoclaw::config::Config;
+use zeptoclaw::safety::leak_detector::LeakAction;
+use zeptoclaw::safety::{validate_rules, SafetyLayer};
+
+/// Dispatch safety subcommands.
+pub(crate) async fn cmd_safety(action: SafetyAction) -> Result<()> {
+    match action {
+        SafetyAction::Test { file } => cmd_test(&file),
+    }
+}
+
+/// Run leak detection and policy checks on a file and print the findings.
+fn cmd_test(file: &str) -> Result<()> {
+    let text = if file == "-" {
+        let mut buf = String::new();
+        std::io::stdin()
+            .read_to_string(&mut buf)
+            .context("Failed to read stdin")?;
+        buf
+    } else {
+        std::fs::read_to_string(file).with_context(|| format!("Failed to read {}", file))?
+    };
+
+    let config = Config::load().with_context(|| "Failed to load configuration")?;
+    let errors = validate_rules(&config.safety);
+    for e in &errors {
+        println!("[ERROR] {}", e);
+    }
+    if !errors.is_empty() {
+        anyhow::bail!("{} invalid safety rule(s)", errors.len());
+    }
+
+    let layer = SafetyLayer::new(config.safety);
+    print!("{}", render_report(&layer, &text));
+    Ok(())
+}
+
+/// Format leak detections, policy violations and the redacted text.
+fn render_report(layer: &SafetyLayer, text: &str) -> String {
+    let mut out = String::new();
+
+    let (redacted, detections) = layer.leak_detector().redact(text);
+    out.push_str(&format!(
+        "Leak detection ({} match(es))\n",
+        detections.len()
+    ));
+    for d in &detections {
+        let action = match d.action {
+            LeakAction::Block => "BLOCK",
+            LeakAction::Redact => "REDACT",
+            LeakAction::Warn => "WARN",
+        };
+        out.push_str(&format!(
+            "  [{}] {}: {}\n",
+            action, d.pattern_name, d.matched_text
+        ));
+    }
+
+    let violations = layer.policy_engine().check(text);
+    out.push_str(&format!(
+        "\nPolicy rules ({} violation(s))\n",
+        violations.len()
+    ));
+    for v in &violations {
+        out.push_str(&format!(
+            "  [{:?}] {} ({:?}): {}",
+            v.action, v.rule_name, v.severity, v.description
+        ));
+        if let Some(ref matched) = v.matched_text {
+            out.push_str(&format!(" -- {}", matched));
+        }
+        out.push('\n');
+    }
+
+    if detections.iter().any(|d| d.action == LeakAction::Redact) {
+        out.push_str("\nRedacted text:\n");
+        out.push_str(&redacted);
+        if !redacted.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zeptoclaw::safety::leak_detector::LeakRuleConfig;
+    use zeptoclaw::safety::policy::PolicyRuleConfig;
+    use zeptoclaw::safety::SafetyConfig;
+
+    #[test]
+    fn test_render_report_includes_custom_rules() {
+        let layer = SafetyLayer::new(SafetyConfig {
+            leak_rules: vec![LeakRuleConfig {
+                name: "acme_token".to_string(),
+                pattern: r"acme_[a-z0-9]{12}".to_string(),
+                literal: false,
+                case_insensitive: false,
+                action: LeakAction::Redact,
+            }],
+            policy_rules: vec![PolicyRuleConfig {
+                name: "codename".to_string(),
+                pattern: "Project Bluebird".to_string(),
+                literal: true,
+                case_insensitive: true,
+                severity: zeptoclaw::safety::policy::PolicySeverity::High,
+                action: zeptoclaw::safety::policy::PolicyAction::Block,
+                description: "Internal codename".to_string(),
+            }],
+            ..Default::default()
+        });
+
+        let report = render_report(&layer, "token acme_abc123def456 for project bluebird");
+        assert!(report.contains("[REDACT] acme_token: acme_abc123def456"));
+        assert!(report.contains("[Block] codename (High): Internal codename"));
+        assert!(report.contains("token acme***f456 for project bluebird"));
+    }
+
+    #[test]
+    fn test_render_report_clean_text() {
+        let layer = SafetyLayer::new(SafetyConfig::default());
+        let report = render_report(&layer, "nothing to see here");
+        assert!(report.contains("Leak detection (0 match(es))"));
+        assert!(report.contains("Policy rules (0 violation(s))"));
+        assert!(!report.contains("Redacted text"));
+    }
+}
@@ -1,14 +1,20 @@
 //! Secret encryption CLI commands.
 //!
 //! Provides `zeptoclaw secrets encrypt|decrypt|rotate` to manage
-//! encrypted secrets in `~/.zeptoclaw/config.json`.
+//! encrypted secrets in `~/.zeptoclaw/config.json`. `rotate` also
+//! re-encrypts the data stores covered by `storage.encrypt_at_rest`, and
+//! `encrypt-stores` encrypts store files that are still plaintext.
 
-use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
 use serde_json::Value;
 
 use super::SecretsAction;
 use zeptoclaw::config::Config;
-use zeptoclaw::security::encryption::{is_secret_field, resolve_master_key, SecretEncryption};
+use zeptoclaw::security::encryption::{
+    is_secret_field, resolve_master_key, resolve_new_master_key, SecretEncryption, StoreEncryption,
+};
 
 /// Dispatch secrets subcommands.
 pub(crate) async fn cmd_secrets(action: SecretsAction) -> Result<()> {
@@ -16,6 +22,7 @@ pub(crate) async fn cmd_secrets(action: SecretsAction) -> Result<()> {
         SecretsAction::Encrypt => cmd_encrypt().await,
         SecretsAction::Decrypt => cmd_decrypt().await,
         SecretsAction::Rotate => cmd_rotate().await,
+        SecretsAction::EncryptStores => cmd_encrypt_stores().await,
     }
 }
 
@@ -142,13 +149,96 @@ async fn cmd_rotate() -> Result<()> {
     let mut root: Value = serde_json::from_str(&content)?;
 
     // Step 1: Decrypt with current key
-    println!("Step 1/2: Decrypt with current key");
+    println!("Step 1/4: Decrypt with current key");
     let old_enc = resolve_master_key(true).map_err(|e| anyhow::anyhow!("{e}"))?;
     let dec_count = decrypt_value(&old_enc, &mut root)?;
     println!("  Decrypted {dec_count} secret(s)");
 
-    // Step 2: Prompt for new passphrase and re-encrypt
-    println!("Step 2/2: Re-encrypt with new key");
+    // Same view of the config as the running agent, env overrides included
+    let encrypt_at_rest = Config::from_value(root.clone())
+        .with_context(|| format!("Failed to parse {}", path.display()))?
+        .storage
+        .encrypt_at_rest;
+
+    let old_store = StoreEncryption::new(old_enc);
+    let stores = read_data_stores(&data_store_paths(&Config::dir()), &old_store)?;
+    let reseal = encrypt_at_rest || stores.iter().any(|s| s.encrypted);
+    println!("  Read {} data store file(s)", stores.len());
+
+    // Step 2: Obtain the new key and re-encrypt config secrets in memory
+    println!("Step 2/4: Re-encrypt secrets with new key");
+    let new_enc = match resolve_new_master_key().map_err(|e| anyhow::anyhow!("{e}"))? {
+        Some(enc) => enc,
+        None if reseal => anyhow::bail!(
+            "data stores are encrypted at rest: set ZEPTOCLAW_NEW_MASTER_KEY to the new \
+             64-char hex key (passphrases cannot be used for data stores)"
+        ),
+        None => prompt_new_passphrase()?,
+    };
+    let enc_count = encrypt_value(&new_enc, &mut root)?;
+    println!("  Re-encrypted {enc_count} secret(s)");
+
+    // Step 3: Re-encrypt data stores in memory
+    println!("Step 3/4: Re-encrypt data stores");
+    let mut files = if reseal {
+        seal_data_stores(&stores, &StoreEncryption::new(new_enc))?
+    } else {
+        println!("  Data store encryption is off; nothing to do");
+        Vec::new()
+    };
+
+    // Step 4: Write everything. Every file is staged before any is replaced,
+    // and config goes last so it holds the old key's secrets until every
+    // store has been replaced.
+    println!("Step 4/4: Write data stores and config");
+    files.push((path.clone(), serde_json::to_string_pretty(&root)?));
+    replace_files(&files)?;
+    if reseal {
+        println!("  Re-encrypted {} data store file(s)", stores.len());
+    }
+    println!("  Wrote {}", path.display());
+    if reseal {
+        println!("  Set ZEPTOCLAW_MASTER_KEY to the new key before restarting.");
+    }
+
+    println!("Key rotated successfully.");
+    Ok(())
+}
+
+// ============================================================================
+// encrypt-stores
+// ============================================================================
+
+/// Encrypt data store files that are still plaintext with the current key.
+///
+/// Stores otherwise only become encrypted when they are next written, so
+/// sessions, memory or reminders that never change again would stay
+/// plaintext after `storage.encrypt_at_rest` is turned on.
+async fn cmd_encrypt_stores() -> Result<()> {
+    let enc = resolve_master_key(false).map_err(|e| {
+        anyhow::anyhow!("{e} (data stores need ZEPTOCLAW_MASTER_KEY set to a 64-char hex key)")
+    })?;
+    let encryption = StoreEncryption::new(enc);
+
+    let stores = read_data_stores(&data_store_paths(&Config::dir()), &encryption)?;
+    let total = stores.len();
+    let plaintext: Vec<DataStore> = stores.into_iter().filter(|s| !s.encrypted).collect();
+    write_data_stores(&plaintext, &encryption)?;
+    println!(
+        "Encrypted {} data store file(s); {} already encrypted",
+        plaintext.len(),
+        total - plaintext.len()
+    );
+
+    let enabled = Config::load().is_ok_and(|config| config.storage.encrypt_at_rest);
+    if !enabled {
+        println!("Enable storage.encrypt_at_rest so these files stay readable and new writes are encrypted.");
+    }
+    Ok(())
+}
+
+/// Prompt twice for a new master passphrase.
+fn prompt_new_passphrase() -> Result<SecretEncryption> {
     let new_passphrase = rpassword::prompt_password("Enter NEW master passphrase: ")
         .map_err(|e| anyhow::anyhow!("failed to read passphrase: {e}"))?;
     if new_passphrase.is_empty() {
@@ -160,18 +250,119 @@ async fn cmd_rotate() -> Result<()> {
         anyhow::bail!("passphrases do not match");
     }
 
-    let new_enc =
-        SecretEncryption::from_passphrase(&new_passphrase).map_err(|e| anyhow::anyhow!("{e}"))?;
-    let enc_count = encrypt_value(&new_enc, &mut root)?;
+    SecretEncryption::from_passphrase(&new_passphrase).map_err(|e| anyhow::anyhow!("{e}"))
+}
 
-    let pretty = serde_json::to_string_pretty(&root)?;
-    std::fs::write(&path, pretty)?;
+/// A data store file decrypted in memory ahead of re-encryption.
+struct DataStore {
+    path: PathBuf,
+    plaintext: String,
+    encrypted: bool,
+}
 
-    println!("  Re-encrypted {enc_count} secret(s) in {}", path.display());
-    println!("Key rotated successfully.");
+/// Files covered by `storage.encrypt_at_rest` under the data directory.
+fn data_store_paths(dir: &Path) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(dir.join("sessions")) {
+        let mut sessions: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        sessions.sort();
+        paths.extend(sessions);
+    }
+    for file in [
+        dir.join("memory").join("longterm.json"),
+        dir.join("reminders.json"),
+        dir.join("cron").join("jobs.json"),
+    ] {
+        if file.is_file() {
+            paths.push(file);
+        }
+    }
+    paths
+}
+
+/// Seal `stores` with `encryption`, replacing all of the files or none.
+///
+/// Sealed contents are staged in temporary files next to the originals and
+/// only renamed over them once every file has been staged.
+fn write_data_stores(stores: &[DataStore], encryption: &StoreEncryption) -> Result<()> {
+    replace_files(&seal_data_stores(stores, encryption)?)
+}
+
+/// Seal every store with `encryption`, returning the new file contents.
+fn seal_data_stores(
+    stores: &[DataStore],
+    encryption: &StoreEncryption,
+) -> Result<Vec<(PathBuf, String)>> {
+    stores
+        .iter()
+        .map(|store| Ok((store.path.clone(), encryption.seal(&store.plaintext)?)))
+        .collect()
+}
+
+/// Replace each file with its new contents, in order.
+///
+/// Every temp file is written and synced before the first rename, so a
+/// failure while staging leaves all the originals untouched.
+fn replace_files(files: &[(PathBuf, String)]) -> Result<()> {
+    let mut staged = Vec::with_capacity(files.len());
+    let staging = files.iter().try_for_each(|(path, contents)| {
+        let tmp = temp_path(path);
+        staged.push(tmp.clone());
+        write_synced(&tmp, contents)
+    });
+    if let Err(e) = staging {
+        for tmp in &staged {
+            let _ = std::fs::remove_file(tmp);
+        }
+        return Err(e);
+    }
+
+    for ((path, _), tmp) in files.iter().zip(&staged) {
+        std::fs::rename(tmp, path)
+            .with_context(|| format!("Failed to replace {}", path.display()))?;
+    }
     Ok(())
 }
 
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+fn write_synced(path: &Path, contents: &str) -> Result<()> {
+    use std::io::Write;
+
+    let mut file = std::fs::File::create(path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    file.write_all(contents.as_bytes())
+        .and_then(|()| file.sync_all())
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Read and decrypt every store up front so a wrong key fails before any
+/// file is rewritten.
+fn read_data_stores(paths: &[PathBuf], encryption: &StoreEncryption) -> Result<Vec<DataStore>> {
+    paths
+        .iter()
+        .map(|path| {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let plaintext = encryption
+                .open(&content)
+                .with_context(|| format!("Failed to decrypt {}", path.display()))?;
+            Ok(DataStore {
+                path: path.clone(),
+                encrypted: SecretEncryption::is_encrypted(content.trim()),
+                plaintext,
+            })
+        })
+        .collect()
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -415,4 +606,113 @@ mod tests {
         let count = encrypt_value(&enc, &mut val).unwrap();
         assert_eq!(count, 5);
     }
+
+    #[test]
+    fn test_data_store_paths_finds_store_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("sessions")).unwrap();
+        std::fs::write(dir.path().join("sessions").join("cli%3Aa.json"), "{}").unwrap();
+        std::fs::write(dir.path().join("sessions").join("notes.txt"), "x").unwrap();
+        std::fs::create_dir_all(dir.path().join("memory")).unwrap();
+        std::fs::write(dir.path().join("memory").join("longterm.json"), "{}").unwrap();
+        std::fs::write(dir.path().join("reminders.json"), "{}").unwrap();
+
+        let paths = data_store_paths(dir.path());
+        assert_eq!(paths.len(), 3);
+        assert!(paths.iter().all(|p| p.extension().unwrap() == "json"));
+    }
+
+    #[test]
+    fn test_read_data_stores_decrypts_and_reseals() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = StoreEncryption::new(test_enc());
+        let sealed_path = dir.path().join("sealed.json");
+        let plain_path = dir.path().join("plain.json");
+        std::fs::write(&sealed_path, old.seal(r#"{"a":1}"#).unwrap()).unwrap();
+        std::fs::write(&plain_path, r#"{"b":2}"#).unwrap();
+
+        let stores = read_data_stores(&[sealed_path, plain_path], &old).unwrap();
+        assert!(stores[0].encrypted);
+        assert_eq!(stores[0].plaintext, r#"{"a":1}"#);
+        assert!(!stores[1].encrypted);
+
+        let new = StoreEncryption::new(SecretEncryption::from_raw_key(&[0x11u8; 32]));
+        let resealed = new.seal(&stores[0].plaintext).unwrap();
+        assert!(old.open(&resealed).is_err());
+        assert_eq!(new.open(&resealed).unwrap(), r#"{"a":1}"#);
+    }
+
+    #[test]
+    fn test_write_data_stores_replaces_all_or_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("a.json");
+        let second = dir.path().join("b.json");
+        std::fs::write(&first, r#"{"a":1}"#).unwrap();
+        std::fs::write(&second, r#"{"b":2}"#).unwrap();
+        let stores = read_data_stores(
+            &[first.clone(), second.clone()],
+            &StoreEncryption::disabled(),
+        )
+        .unwrap();
+        let new = StoreEncryption::new(test_enc());
+
+        // A store that cannot be staged leaves every file untouched
+        let mut failing =
+            read_data_stores(std::slice::from_ref(&first), &StoreEncryption::disabled()).unwrap();
+        failing.push(DataStore {
+            path: dir.path().join("missing").join("c.json"),
+            plaintext: "{}".to_string(),
+            encrypted: false,
+        });
+        assert!(write_data_stores(&failing, &new).is_err());
+        assert_eq!(std::fs::read_to_string(&first).unwrap(), r#"{"a":1}"#);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+
+        write_data_stores(&stores, &new).unwrap();
+        let reread = read_data_stores(&[first, second], &new).unwrap();
+        assert!(reread.iter().all(|s| s.encrypted));
+        assert_eq!(reread[1].plaintext, r#"{"b":2}"#);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_replace_files_stages_config_with_stores() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = dir.path().join("a.json");
+        let config = dir.path().join("config.json");
+        std::fs::write(&store, "old store").unwrap();
+        std::fs::write(&config, "old config").unwrap();
+
+        // A config that cannot be staged leaves the store untouched too
+        let failing = vec![
+            (store.clone(), "new store".to_string()),
+            (
+                dir.path().join("missing").join("config.json"),
+                "new".to_string(),
+            ),
+        ];
+        assert!(replace_files(&failing).is_err());
+        assert_eq!(std::fs::read_to_string(&store).unwrap(), "old store");
+        assert!(!temp_path(&store).exists());
+
+        let files = vec![
+            (store.clone(), "new store".to_string()),
+            (config.clone(), "new config".to_string()),
+        ];
+        replace_files(&files).unwrap();
+        assert_eq!(std::fs::read_to_string(&store).unwrap(), "new store");
+        assert_eq!(std::fs::read_to_string(&config).unwrap(), "new config");
+        assert!(!temp_path(&config).exists());
+    }
+
+    #[test]
+    fn test_read_data_stores_wrong_key_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sealed.json");
+        let sealed = StoreEncryption::new(test_enc()).seal("{}").unwrap();
+        std::fs::write(&path, sealed).unwrap();
+
+        let wrong = StoreEncryption::new(SecretEncryption::from_raw_key(&[0x22u8; 32]));
+        assert!(read_data_stores(&[path], &wrong).is_err());
+    }
 }
@@ -4,8 +4,10 @@ use anyhow::{Context, Result};
 
 use zeptoclaw::auth;
 use zeptoclaw::config::{Config, ContainerAgentBackend, ProviderConfig};
+use zeptoclaw::providers::keypool::{key_pool_state_path, mask_api_key, KeyPoolSnapshot};
 use zeptoclaw::providers::{
-    configured_unsupported_provider_names, resolve_runtime_provider, RUNTIME_SUPPORTED_PROVIDERS,
+    configured_provider_names, configured_unsupported_provider_names, provider_config_by_name,
+    resolve_runtime_provider, RUNTIME_SUPPORTED_PROVIDERS,
 };
 use zeptoclaw::runtime::available_runtimes;
 
@@ -346,17 +348,87 @@ async fn cmd_auth_status() -> Result<()> {
 }
 
 fn provider_status(provider: &Option<ProviderConfig>) -> &'static str {
-    provider
-        .as_ref()
-        .and_then(|p| p.api_key.as_ref())
-        .map(|k| {
-            if k.is_empty() {
-                "not set"
-            } else {
-                "configured"
-            }
+    match provider.as_ref().map(|p| p.pooled_api_keys().len()) {
+        Some(0) | None => "not set",
+        Some(1) => "configured",
+        Some(_) => "configured (key pool)",
+    }
+}
+
+/// Print configured API key pools and, when a gateway has written one, the
+/// latest health snapshot of each key.
+fn print_key_pools(config: &Config) {
+    let pools: Vec<(&str, Vec<&str>)> = configured_provider_names(config)
+        .into_iter()
+        .filter_map(|name| {
+            let keys = provider_config_by_name(config, name)?.pooled_api_keys();
+            (keys.len() > 1).then_some((name, keys))
         })
-        .unwrap_or("not set")
+        .collect();
+    if pools.is_empty() {
+        return;
+    }
+
+    println!("API Key Pools");
+    println!("-------------");
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    for (name, keys) in pools {
+        println!(
+            "  {}: {} keys ({})",
+            name,
+            keys.len(),
+            config.providers.key_pool.strategy.as_str()
+        );
+        // Ignore snapshots written for a different set of keys.
+        let snapshot = KeyPoolSnapshot::load(&key_pool_state_path(name)).filter(|snapshot| {
+            snapshot
+                .keys
+                .iter()
+                .map(|k| k.label.clone())
+                .eq(keys.iter().map(|k| mask_api_key(k)))
+        });
+        let Some(snapshot) = snapshot else {
+            for key in keys {
+                println!("    {}  (no health data yet)", mask_api_key(key));
+            }
+            continue;
+        };
+        for key in &snapshot.keys {
+            let mut line = format!("    {}  {}", key.label, key.state.as_str());
+            if key.failures > 0 {
+                line.push_str(&format!(", {} consecutive failures", key.failures));
+            }
+            if let Some(at) = key.last_rate_limited {
+                line.push_str(&format!(
+                    ", rate limited {} ago",
+                    human_secs(now.saturating_sub(at))
+                ));
+            }
+            if let Some(until) = key.benched_until {
+                line.push_str(&format!(
+                    ", benched for {} more",
+                    human_secs(until.saturating_sub(now))
+                ));
+            }
+            if let Some(reason) = &key.bench_reason {
+                line.push_str(&format!(" ({})", reason));
+            }
+            println!("{}", line);
+        }
+        println!(
+            "    (as of {} ago)",
+            human_secs(now.saturating_sub(snapshot.updated_at))
+        );
+    }
+    println!();
+}
+
+fn human_secs(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        _ => format!("{}h{}m", secs / 3600, (secs % 3600) / 60),
+    }
 }
 
 /// Show system status.
@@ -490,7 +562,14 @@ pub(crate) async fn cmd_status() -> Result<()> {
     // Long-term memory stats
     let ltm_path = Config::dir().join("memory").join("longterm.json");
     if ltm_path.exists() {
-        match zeptoclaw::memory::longterm::LongTermMemory::new() {
+        let mem = zeptoclaw::security::StoreEncryption::from_config(&config).and_then(|enc| {
+            zeptoclaw::memory::longterm::LongTermMemory::open(
+                ltm_path,
+                std::sync::Arc::new(zeptoclaw::memory::builtin_searcher::BuiltinSearcher),
+                enc,
+            )
+        });
+        match mem {
             Ok(mem) => {
                 let count = mem.count();
                 let categories = mem.categories();
@@ -558,6 +637,8 @@ pub(crate) async fn cmd_status() -> Result<()> {
     );
     println!();
 
+    print_key_pools(&config);
+
     // Available tools (dynamic)
     println!("Available Tools");
     println!("---------------");
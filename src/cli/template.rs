@@ -56,6 +56,12 @@ pub(crate) async fn cmd_template(action: TemplateAction) -> Result<()> {
             if let Some(max_tool_iterations) = tpl.max_tool_iterations {
                 println!("Max tool iterations override: {}", max_tool_iterations);
             }
+            if let Some(tool_choice) = &tpl.tool_choice {
+                println!("Tool choice: {}", tool_choice);
+            }
+            if let Some(parallel) = tpl.parallel_tool_calls {
+                println!("Parallel tool calls: {}", parallel);
+            }
             if let Some(allowed) = &tpl.allowed_tools {
                 println!("Allowed tools: {}", allowed.join(", "));
             }
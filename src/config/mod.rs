@@ -47,7 +47,7 @@ impl Config {
     /// The master key is resolved via `ZEPTOCLAW_MASTER_KEY` env var or, when
     /// running in an interactive terminal, an interactive passphrase prompt.
     pub fn load_from_path(path: &PathBuf) -> Result<Self> {
-        let mut config = if path.exists() {
+        if path.exists() {
             let content = std::fs::read_to_string(path)?;
             let mut raw: serde_json::Value = serde_json::from_str(&content)?;
 
@@ -58,14 +58,19 @@ impl Config {
                 decrypt_config_values(&mut raw, &enc)?;
             }
 
-            serde_json::from_value(raw)?
+            Self::from_value(raw)
         } else {
-            Config::default()
-        };
+            let mut config = Config::default();
+            config.apply_env_overrides();
+            Ok(config)
+        }
+    }
 
-        // Apply environment variable overrides
+    /// Build configuration from already-decrypted config JSON, with
+    /// environment overrides applied as in [`load`](Self::load).
+    pub fn from_value(raw: serde_json::Value) -> Result<Self> {
+        let mut config: Config = serde_json::from_value(raw)?;
         config.apply_env_overrides();
-
         Ok(config)
     }
 
@@ -112,6 +117,11 @@ impl Config {
                 _ => {}
             }
         }
+        if let Ok(val) = std::env::var("ZEPTOCLAW_AGENTS_DEFAULTS_MAX_CONCURRENT_RUNS") {
+            if let Ok(v) = val.parse::<usize>() {
+                self.agents.defaults.max_concurrent_runs = v.clamp(1, 100);
+            }
+        }
         if let Ok(val) = std::env::var("ZEPTOCLAW_AGENTS_DEFAULTS_COMPACT_TOOLS") {
             self.agents.defaults.compact_tools = val == "true" || val == "1";
         }
@@ -121,6 +131,19 @@ impl Config {
         if let Ok(val) = std::env::var("ZEPTOCLAW_AGENTS_DEFAULTS_TIMEZONE") {
             self.agents.defaults.timezone = val;
         }
+        if let Ok(val) = std::env::var("ZEPTOCLAW_AGENTS_DEFAULTS_REASONING_EFFORT") {
+            self.agents.defaults.reasoning_effort = val.parse().ok();
+        }
+        if let Ok(val) = std::env::var("ZEPTOCLAW_AGENTS_DEFAULTS_THINKING_BUDGET") {
+            if let Ok(v) = val.parse() {
+                self.agents.defaults.thinking_budget = Some(v);
+            }
+        }
+        if let Ok(val) = std::env::var("ZEPTOCLAW_AGENTS_DEFAULTS_PARALLEL_TOOL_CALLS") {
+            if let Ok(v) = val.parse() {
+                self.agents.defaults.parallel_tool_calls = Some(v);
+            }
+        }
 
         // Gateway
         if let Ok(val) = std::env::var("ZEPTOCLAW_GATEWAY_HOST") {
@@ -158,6 +181,11 @@ impl Config {
         // Safety layer
         self.apply_safety_env_overrides();
 
+        // Data store encryption
+        if let Ok(val) = std::env::var("ZEPTOCLAW_STORAGE_ENCRYPT_AT_REST") {
+            self.storage.encrypt_at_rest = val.eq_ignore_ascii_case("true") || val == "1";
+        }
+
         // Context compaction
         self.apply_compaction_env_overrides();
 
@@ -264,6 +292,13 @@ impl Config {
                 .get_or_insert_with(ProviderConfig::default);
             provider.api_base = Some(val);
         }
+        if let Ok(val) = std::env::var("ZEPTOCLAW_PROVIDERS_GEMINI_BACKEND") {
+            let provider = self
+                .providers
+                .gemini
+                .get_or_insert_with(ProviderConfig::default);
+            provider.backend = Some(val);
+        }
 
         // vLLM
         if let Ok(val) = std::env::var("ZEPTOCLAW_PROVIDERS_VLLM_API_KEY") {
@@ -334,6 +369,18 @@ impl Config {
                 self.providers.retry.max_delay_ms = v;
             }
         }
+        if let Ok(val) = std::env::var("ZEPTOCLAW_PROVIDERS_RETRY_MAX_RETRY_AFTER_MS") {
+            if let Ok(v) = val.parse() {
+                self.providers.retry.max_retry_after_ms = v;
+            }
+        }
+
+        // Client-side provider rate limiting
+        if let Ok(val) = std::env::var("ZEPTOCLAW_PROVIDERS_RATE_LIMIT_ENABLED") {
+            if let Ok(enabled) = val.parse() {
+                self.providers.rate_limit.enabled = enabled;
+            }
+        }
 
         // Provider fallback behavior
         if let Ok(val) = std::env::var("ZEPTOCLAW_PROVIDERS_FALLBACK_ENABLED") {
@@ -363,6 +410,99 @@ impl Config {
                 _ => {}
             }
         }
+
+        // Pooled API keys (comma-separated) and key pool behavior
+        for (var, provider) in [
+            (
+                "ZEPTOCLAW_PROVIDERS_ANTHROPIC_API_KEYS",
+                &mut self.providers.anthropic,
+            ),
+            (
+                "ZEPTOCLAW_PROVIDERS_OPENAI_API_KEYS",
+                &mut self.providers.openai,
+            ),
+            (
+                "ZEPTOCLAW_PROVIDERS_OPENROUTER_API_KEYS",
+                &mut self.providers.openrouter,
+            ),
+            (
+                "ZEPTOCLAW_PROVIDERS_GROQ_API_KEYS",
+                &mut self.providers.groq,
+            ),
+            (
+                "ZEPTOCLAW_PROVIDERS_ZHIPU_API_KEYS",
+                &mut self.providers.zhipu,
+            ),
+            (
+                "ZEPTOCLAW_PROVIDERS_VLLM_API_KEYS",
+                &mut self.providers.vllm,
+            ),
+            (
+                "ZEPTOCLAW_PROVIDERS_GEMINI_API_KEYS",
+                &mut self.providers.gemini,
+            ),
+            (
+                "ZEPTOCLAW_PROVIDERS_OLLAMA_API_KEYS",
+                &mut self.providers.ollama,
+            ),
+            (
+                "ZEPTOCLAW_PROVIDERS_NVIDIA_API_KEYS",
+                &mut self.providers.nvidia,
+            ),
+        ] {
+            if let Ok(val) = std::env::var(var) {
+                let provider = provider.get_or_insert_with(ProviderConfig::default);
+                provider.api_keys = val
+                    .split(',')
+                    .map(|k| k.trim().to_string())
+                    .filter(|k| !k.is_empty())
+                    .collect();
+            }
+        }
+        if let Ok(val) = std::env::var("ZEPTOCLAW_PROVIDERS_KEY_POOL_STRATEGY") {
+            match val.trim().to_ascii_lowercase().as_str() {
+                "round_robin" | "roundrobin" => {
+                    self.providers.key_pool.strategy =
+                        crate::providers::keypool::KeyStrategy::RoundRobin
+                }
+                "least_rate_limited" => {
+                    self.providers.key_pool.strategy =
+                        crate::providers::keypool::KeyStrategy::LeastRateLimited
+                }
+                _ => {}
+            }
+        }
+        if let Ok(val) = std::env::var("ZEPTOCLAW_PROVIDERS_KEY_POOL_BENCH_SECS") {
+            if let Ok(v) = val.parse() {
+                self.providers.key_pool.bench_secs = v;
+            }
+        }
+
+        // Model routing
+        if let Ok(val) = std::env::var("ZEPTOCLAW_PROVIDERS_ROUTER_ENABLED") {
+            if let Ok(enabled) = val.parse() {
+                self.providers.router.enabled = enabled;
+            }
+        }
+        for (var, slot) in [
+            (
+                "ZEPTOCLAW_PROVIDERS_ROUTER_SIMPLE_MODEL",
+                &mut self.providers.router.simple_model,
+            ),
+            (
+                "ZEPTOCLAW_PROVIDERS_ROUTER_COMPLEX_MODEL",
+                &mut self.providers.router.complex_model,
+            ),
+            (
+                "ZEPTOCLAW_PROVIDERS_ROUTER_CLASSIFIER_MODEL",
+                &mut self.providers.router.classifier_model,
+            ),
+        ] {
+            if let Ok(val) = std::env::var(var) {
+                let value = val.trim().to_string();
+                *slot = if value.is_empty() { None } else { Some(value) };
+            }
+        }
     }
 
     /// Apply channel-specific environment variable overrides
@@ -446,6 +586,55 @@ impl Config {
             }
         }
 
+        // Matrix
+        if let Ok(val) = std::env::var("ZEPTOCLAW_CHANNELS_MATRIX_HOMESERVER_URL") {
+            let channel = self
+                .channels
+                .matrix
+                .get_or_insert_with(MatrixConfig::default);
+            channel.homeserver_url = val;
+        }
+        if let Ok(val) = std::env::var("ZEPTOCLAW_CHANNELS_MATRIX_ACCESS_TOKEN") {
+            let channel = self
+                .channels
+                .matrix
+                .get_or_insert_with(MatrixConfig::default);
+            channel.access_token = val;
+        }
+        if let Ok(val) = std::env::var("ZEPTOCLAW_CHANNELS_MATRIX_ENABLED") {
+            if let Ok(enabled) = val.parse() {
+                let channel = self
+                    .channels
+                    .matrix
+                    .get_or_insert_with(MatrixConfig::default);
+                channel.enabled = enabled;
+            }
+        }
+
+        // Email
+        if let Ok(val) = std::env::var("ZEPTOCLAW_CHANNELS_EMAIL_IMAP_HOST") {
+            let channel = self.channels.email.get_or_insert_with(EmailConfig::default);
+            channel.imap_host = val;
+        }
+        if let Ok(val) = std::env::var("ZEPTOCLAW_CHANNELS_EMAIL_SMTP_HOST") {
+            let channel = self.channels.email.get_or_insert_with(EmailConfig::default);
+            channel.smtp_host = val;
+        }
+        if let Ok(val) = std::env::var("ZEPTOCLAW_CHANNELS_EMAIL_USERNAME") {
+            let channel = self.channels.email.get_or_insert_with(EmailConfig::default);
+            channel.username = val;
+        }
+        if let Ok(val) = std::env::var("ZEPTOCLAW_CHANNELS_EMAIL_PASSWORD") {
+            let channel = self.channels.email.get_or_insert_with(EmailConfig::default);
+            channel.password = val;
+        }
+        if let Ok(val) = std::env::var("ZEPTOCLAW_CHANNELS_EMAIL_ENABLED") {
+            if let Ok(enabled) = val.parse() {
+                let channel = self.channels.email.get_or_insert_with(EmailConfig::default);
+                channel.enabled = enabled;
+            }
+        }
+
         // Runtime: Apple Container
         if let Ok(val) = std::env::var("ZEPTOCLAW_RUNTIME_APPLE_ALLOW_EXPERIMENTAL") {
             if let Ok(v) = val.parse() {
@@ -876,7 +1065,7 @@ fn decrypt_config_values(
 }
 
 /// Expand ~ to home directory in a path string
-fn expand_home(path: &str) -> PathBuf {
+pub(crate) fn expand_home(path: &str) -> PathBuf {
     if path.is_empty() {
         return PathBuf::from(path);
     }
@@ -1310,6 +1499,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_session_scope_from_json() {
+        let json = r#"{
+            "channels": {
+                "slack": {"bot_token": "xoxb", "app_token": "xapp", "session_scope": "thread"},
+                "discord": {"token": "tok", "session_scope": "user"},
+                "telegram": {"token": "tg"}
+            }
+        }"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            config.channels.slack.unwrap().session_scope,
+            SessionScope::Thread
+        );
+        assert_eq!(
+            config.channels.discord.unwrap().session_scope,
+            SessionScope::User
+        );
+    }
+
+    #[test]
+    fn test_session_scope_default_is_chat() {
+        let slack: SlackConfig =
+            serde_json::from_str(r#"{"bot_token": "xoxb", "app_token": "xapp"}"#).unwrap();
+        assert_eq!(slack.session_scope, SessionScope::Chat);
+    }
+
+    #[test]
+    fn test_group_policy_from_json() {
+        let json = r#"{
+            "token": "t",
+            "group_policy": {
+                "require_mention": true,
+                "keywords": ["deploy"],
+                "allowed_groups": ["-100123"],
+                "quiet_hours": {"start": "22:00", "end": "07:00"},
+                "passive_capture": true
+            }
+        }"#;
+        let telegram: TelegramConfig = serde_json::from_str(json).unwrap();
+        let policy = telegram.group_policy;
+        assert!(policy.require_mention);
+        assert_eq!(policy.keywords, vec!["deploy"]);
+        assert_eq!(policy.allowed_groups, vec!["-100123"]);
+        assert_eq!(policy.quiet_hours.unwrap().start, "22:00");
+        assert!(policy.passive_capture);
+
+        let discord: DiscordConfig = serde_json::from_str(r#"{"token": "t"}"#).unwrap();
+        assert_eq!(discord.group_policy, GroupPolicy::default());
+    }
+
     #[test]
     fn test_env_override_compact_tools() {
         std::env::set_var("ZEPTOCLAW_AGENTS_DEFAULTS_COMPACT_TOOLS", "true");
@@ -10,6 +10,7 @@ use std::collections::HashMap;
 use std::path::Path;
 
 use crate::error::{Result, ZeptoError};
+use crate::providers::ToolChoice;
 
 // ============================================================================
 // AgentTemplate
@@ -58,6 +59,15 @@ pub struct AgentTemplate {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_tool_iterations: Option<u32>,
 
+    /// Optional tool choice for the first request of each turn
+    /// ("auto", "none", "required" or `{"tool": "<name>"}`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+
+    /// Optional switch for several tool calls in one response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
+
     /// Metadata tags for categorization and filtering.
     #[serde(default)]
     pub tags: Vec<String>,
@@ -88,6 +98,8 @@ fn builtin_coder() -> AgentTemplate {
         allowed_tools: None, // all tools
         blocked_tools: None,
         max_tool_iterations: None,
+        tool_choice: None,
+        parallel_tool_calls: None,
         tags: vec!["development".to_string(), "coding".to_string()],
     }
 }
@@ -119,6 +131,8 @@ fn builtin_researcher() -> AgentTemplate {
         ]),
         blocked_tools: None,
         max_tool_iterations: None,
+        tool_choice: None,
+        parallel_tool_calls: None,
         tags: vec!["research".to_string(), "information".to_string()],
     }
 }
@@ -150,6 +164,8 @@ fn builtin_writer() -> AgentTemplate {
         ]),
         blocked_tools: None,
         max_tool_iterations: None,
+        tool_choice: None,
+        parallel_tool_calls: None,
         tags: vec!["writing".to_string(), "content".to_string()],
     }
 }
@@ -174,6 +190,8 @@ fn builtin_assistant() -> AgentTemplate {
         allowed_tools: None, // all tools
         blocked_tools: None,
         max_tool_iterations: None,
+        tool_choice: None,
+        parallel_tool_calls: None,
         tags: vec!["general".to_string()],
     }
 }
@@ -212,6 +230,8 @@ fn builtin_task_manager() -> AgentTemplate {
         ]),
         blocked_tools: None,
         max_tool_iterations: None,
+        tool_choice: None,
+        parallel_tool_calls: None,
         tags: vec![
             "productivity".to_string(),
             "tasks".to_string(),
@@ -503,6 +523,8 @@ mod tests {
             allowed_tools: Some(vec!["shell".to_string()]),
             blocked_tools: None,
             max_tool_iterations: Some(10),
+            tool_choice: None,
+            parallel_tool_calls: None,
             tags: vec!["devops".to_string(), "infrastructure".to_string()],
         };
 
@@ -535,6 +557,8 @@ mod tests {
             allowed_tools: Some(vec!["shell".to_string(), "read_file".to_string()]),
             blocked_tools: None,
             max_tool_iterations: None,
+            tool_choice: None,
+            parallel_tool_calls: None,
             tags: vec!["development".to_string(), "rust".to_string()],
         };
         registry.register(custom_coder);
@@ -664,6 +688,8 @@ mod tests {
             allowed_tools: Some(vec!["shell".to_string(), "read_file".to_string()]),
             blocked_tools: Some(vec!["web_search".to_string()]),
             max_tool_iterations: Some(15),
+            tool_choice: None,
+            parallel_tool_calls: None,
             tags: vec!["test".to_string()],
         };
 
@@ -808,6 +834,8 @@ mod tests {
             allowed_tools: None,
             blocked_tools: None,
             max_tool_iterations: None,
+            tool_choice: None,
+            parallel_tool_calls: None,
             tags: vec![],
         };
 
@@ -818,6 +846,25 @@ mod tests {
         assert!(!json.contains("allowed_tools"));
         assert!(!json.contains("blocked_tools"));
         assert!(!json.contains("max_tool_iterations"));
+        assert!(!json.contains("tool_choice"));
+        assert!(!json.contains("parallel_tool_calls"));
+    }
+
+    #[test]
+    fn test_template_tool_choice_from_json() {
+        let json = r#"{
+            "name": "extractor",
+            "description": "Always extracts",
+            "system_prompt": "Extract the fields.",
+            "tool_choice": {"tool": "save_record"},
+            "parallel_tool_calls": false
+        }"#;
+        let template: AgentTemplate = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            template.tool_choice,
+            Some(ToolChoice::Tool("save_record".to_string()))
+        );
+        assert_eq!(template.parallel_tool_calls, Some(false));
     }
 
     #[test]
@@ -4,7 +4,7 @@
 //! All types implement serde traits for JSON serialization and have sensible defaults.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Main configuration struct for ZeptoClaw
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,6 +47,8 @@ pub struct Config {
     pub hooks: crate::hooks::HooksConfig,
     /// Safety layer configuration
     pub safety: crate::safety::SafetyConfig,
+    /// At-rest encryption of persisted data stores
+    pub storage: StorageConfig,
     /// Context compaction configuration
     pub compaction: CompactionConfig,
     /// MCP (Model Context Protocol) server configuration
@@ -64,6 +66,20 @@ pub struct Config {
     pub tool_profiles: HashMap<String, Option<Vec<String>>>,
 }
 
+// ============================================================================
+// Storage Configuration
+// ============================================================================
+
+/// Persisted data store configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    /// Encrypt sessions, long-term memory, reminders and cron jobs with the
+    /// master key from `ZEPTOCLAW_MASTER_KEY`. Existing plaintext files are
+    /// read as-is and encrypted the next time they are written.
+    pub encrypt_at_rest: bool,
+}
+
 // ============================================================================
 // Compaction Configuration
 // ============================================================================
@@ -234,6 +250,8 @@ pub struct AgentDefaults {
     pub agent_timeout_secs: u64,
     /// How to handle messages arriving during an active run.
     pub message_queue_mode: MessageQueueMode,
+    /// Maximum agent runs in progress at once across sessions.
+    pub max_concurrent_runs: usize,
     /// Whether to stream the final LLM response token-by-token in CLI mode.
     pub streaming: bool,
     /// Per-session token budget (input + output). 0 = unlimited.
@@ -249,6 +267,21 @@ pub struct AgentDefaults {
     /// Defaults to system local timezone, falls back to "UTC".
     #[serde(default = "default_timezone")]
     pub timezone: String,
+    /// Reasoning effort for models that support it (low, medium, high).
+    #[serde(default)]
+    pub reasoning_effort: Option<crate::providers::ReasoningEffort>,
+    /// Explicit thinking token budget for Anthropic models; overrides the
+    /// budget implied by `reasoning_effort`.
+    #[serde(default)]
+    pub thinking_budget: Option<u32>,
+    /// Tool choice for the first request of each turn: "auto", "none",
+    /// "required" or `{"tool": "<name>"}`. Forced choices relax to auto
+    /// once tool results are sent back.
+    #[serde(default)]
+    pub tool_choice: Option<crate::providers::ToolChoice>,
+    /// Allow several tool calls in one response (provider default when unset).
+    #[serde(default)]
+    pub parallel_tool_calls: Option<bool>,
 }
 
 /// Detect the system's IANA timezone.
@@ -289,11 +322,16 @@ impl Default for AgentDefaults {
             max_tool_iterations: 20,
             agent_timeout_secs: 300,
             message_queue_mode: MessageQueueMode::default(),
+            max_concurrent_runs: 4,
             streaming: false,
             token_budget: 0,
             compact_tools: false,
             tool_profile: None,
             timezone: default_timezone(),
+            reasoning_effort: None,
+            thinking_budget: None,
+            tool_choice: None,
+            parallel_tool_calls: None,
         }
     }
 }
@@ -313,6 +351,67 @@ pub enum MessageQueueMode {
 // Channel Configurations
 // ============================================================================
 
+/// How inbound messages on a channel are grouped into conversation sessions.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionScope {
+    /// One session per chat/channel (`channel:chat_id`).
+    #[default]
+    Chat,
+    /// One session per thread within a chat (`channel:chat_id:thread_id`).
+    /// Top-level messages start a new thread for the reply.
+    Thread,
+    /// One session per user within a chat (`channel:chat_id:sender_id`).
+    User,
+}
+
+/// Behaviour in group chats (Telegram groups, Discord servers, Slack channels).
+///
+/// Direct messages are not affected by this policy.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct GroupPolicy {
+    /// Only respond when the bot is mentioned, replied to, or a keyword matches.
+    pub require_mention: bool,
+    /// Case-insensitive keywords that trigger a response without a mention.
+    pub keywords: Vec<String>,
+    /// Group/channel IDs the bot may respond in (empty = all groups).
+    /// Applied in addition to the user allowlist.
+    pub allowed_groups: Vec<String>,
+    /// Local-time window during which the bot stays silent in groups.
+    pub quiet_hours: Option<QuietHours>,
+    /// Store unaddressed group messages in the session without calling the
+    /// LLM, so the bot has context when it is eventually addressed.
+    pub passive_capture: bool,
+}
+
+/// A daily local-time window, e.g. `22:00`–`07:00` (may wrap past midnight).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct QuietHours {
+    /// Start of the window (`HH:MM`, inclusive).
+    pub start: String,
+    /// End of the window (`HH:MM`, exclusive).
+    pub end: String,
+}
+
+impl QuietHours {
+    /// Returns whether `time` falls inside the window.
+    ///
+    /// Unparseable bounds or an empty window (`start == end`) never match.
+    pub fn contains(&self, time: chrono::NaiveTime) -> bool {
+        let parse = |s: &str| chrono::NaiveTime::parse_from_str(s.trim(), "%H:%M").ok();
+        let (Some(start), Some(end)) = (parse(&self.start), parse(&self.end)) else {
+            return false;
+        };
+        if start <= end {
+            start <= time && time < end
+        } else {
+            // Window wraps past midnight, e.g. 22:00-07:00.
+            time >= start || time < end
+        }
+    }
+}
+
 /// All channel configurations
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
@@ -337,6 +436,10 @@ pub struct ChannelsConfig {
     pub dingtalk: Option<DingTalkConfig>,
     /// Webhook inbound channel configuration
     pub webhook: Option<WebhookConfig>,
+    /// Matrix client-server API configuration
+    pub matrix: Option<MatrixConfig>,
+    /// Email (IMAP/SMTP) configuration
+    pub email: Option<EmailConfig>,
     /// Directory for channel plugins (default: ~/.zeptoclaw/channels/)
     #[serde(default)]
     pub channel_plugins_dir: Option<String>,
@@ -366,6 +469,51 @@ pub struct WebhookConfig {
     /// When true, empty `allow_from` rejects all senders (strict mode).
     #[serde(default)]
     pub deny_by_default: bool,
+    /// Default URL replies are POSTed to (payloads may override it per chat
+    /// with `callback_url`)
+    #[serde(default)]
+    pub callback_url: Option<String>,
+    /// Hosts a payload `callback_url` may point to (subdomains included;
+    /// empty = payload callback URLs are rejected)
+    #[serde(default)]
+    pub callback_allowed_hosts: Vec<String>,
+    /// Secret for the `X-ZeptoClaw-Signature` HMAC-SHA256 header on callbacks
+    #[serde(default)]
+    pub callback_secret: Option<String>,
+    /// Retries after a failed callback delivery
+    #[serde(default = "default_webhook_callback_max_retries")]
+    pub callback_max_retries: u32,
+    /// File that permanently failed callbacks are appended to
+    /// (default: ~/.zeptoclaw/webhook/dead_letters.jsonl)
+    #[serde(default)]
+    pub dead_letter_path: Option<String>,
+    /// Hold requests open and return the agent's reply in the response body
+    #[serde(default)]
+    pub sync_response: bool,
+    /// Seconds a synchronous request waits for the reply
+    #[serde(default = "default_webhook_sync_timeout_secs")]
+    pub sync_timeout_secs: u64,
+    /// Sources allowed to sign requests with HMAC-SHA256 (when non-empty,
+    /// unsigned requests are rejected)
+    #[serde(default)]
+    pub sources: Vec<WebhookSourceConfig>,
+}
+
+/// A caller that signs its webhook requests with its own secret
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct WebhookSourceConfig {
+    /// Name sent in the `X-ZeptoClaw-Source` header
+    pub name: String,
+    /// HMAC-SHA256 signing secret
+    pub secret: String,
+}
+
+fn default_webhook_callback_max_retries() -> u32 {
+    5
+}
+
+fn default_webhook_sync_timeout_secs() -> u64 {
+    120
 }
 
 fn default_webhook_bind_address() -> String {
@@ -390,6 +538,14 @@ impl Default for WebhookConfig {
             auth_token: None,
             allow_from: Vec::new(),
             deny_by_default: false,
+            callback_url: None,
+            callback_allowed_hosts: Vec::new(),
+            callback_secret: None,
+            callback_max_retries: default_webhook_callback_max_retries(),
+            dead_letter_path: None,
+            sync_response: false,
+            sync_timeout_secs: default_webhook_sync_timeout_secs(),
+            sources: Vec::new(),
         }
     }
 }
@@ -408,6 +564,9 @@ pub struct TelegramConfig {
     /// When true, empty `allow_from` rejects all senders (strict mode).
     #[serde(default)]
     pub deny_by_default: bool,
+    /// Group chat behaviour (mention gating, group allowlist, quiet hours).
+    #[serde(default)]
+    pub group_policy: GroupPolicy,
 }
 
 /// Discord channel configuration
@@ -424,6 +583,12 @@ pub struct DiscordConfig {
     /// When true, empty `allow_from` rejects all senders (strict mode).
     #[serde(default)]
     pub deny_by_default: bool,
+    /// How messages are grouped into sessions (`chat`, `thread` or `user`).
+    #[serde(default)]
+    pub session_scope: SessionScope,
+    /// Group chat behaviour (mention gating, group allowlist, quiet hours).
+    #[serde(default)]
+    pub group_policy: GroupPolicy,
 }
 
 /// Slack channel configuration
@@ -442,6 +607,12 @@ pub struct SlackConfig {
     /// When true, empty `allow_from` rejects all senders (strict mode).
     #[serde(default)]
     pub deny_by_default: bool,
+    /// How messages are grouped into sessions (`chat`, `thread` or `user`).
+    #[serde(default)]
+    pub session_scope: SessionScope,
+    /// Group chat behaviour (mention gating, group allowlist, quiet hours).
+    #[serde(default)]
+    pub group_policy: GroupPolicy,
 }
 
 /// WhatsApp channel configuration (via bridge)
@@ -553,6 +724,152 @@ impl Default for WhatsAppCloudConfig {
     }
 }
 
+/// Matrix channel configuration (client-server API)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatrixConfig {
+    /// Whether the channel is enabled
+    #[serde(default)]
+    pub enabled: bool,
+    /// Homeserver base URL (e.g. "https://matrix.example.org")
+    #[serde(default)]
+    pub homeserver_url: String,
+    /// Access token of the bot account
+    #[serde(default)]
+    pub access_token: String,
+    /// Fully-qualified bot user ID (e.g. "@zepto:example.org").
+    /// Looked up via `/account/whoami` when empty.
+    #[serde(default)]
+    pub user_id: String,
+    /// Allowlist of Matrix user IDs (empty = allow all unless `deny_by_default` is set)
+    #[serde(default)]
+    pub allow_from: Vec<String>,
+    /// When true, empty `allow_from` rejects all senders (strict mode).
+    #[serde(default)]
+    pub deny_by_default: bool,
+    /// Join rooms the bot is invited to when the inviter is allowed.
+    #[serde(default = "default_true")]
+    pub auto_join: bool,
+    /// Send replies as `m.notice` (the convention for bots) instead of `m.text`.
+    #[serde(default)]
+    pub use_notice: bool,
+    /// Long-poll timeout for `/sync` in seconds.
+    #[serde(default = "default_matrix_sync_timeout_secs")]
+    pub sync_timeout_secs: u64,
+    /// File where the `/sync` `next_batch` token is persisted
+    /// (default: ~/.zeptoclaw/matrix/sync_token).
+    #[serde(default)]
+    pub sync_token_path: Option<String>,
+}
+
+fn default_matrix_sync_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for MatrixConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            homeserver_url: String::new(),
+            access_token: String::new(),
+            user_id: String::new(),
+            allow_from: Vec::new(),
+            deny_by_default: false,
+            auto_join: true,
+            use_notice: false,
+            sync_timeout_secs: default_matrix_sync_timeout_secs(),
+            sync_token_path: None,
+        }
+    }
+}
+
+/// Transport security for an SMTP connection.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// TLS from the first byte (usually port 465).
+    #[default]
+    Tls,
+    /// Plain connection upgraded with `STARTTLS` (usually port 587).
+    StartTls,
+    /// No encryption. Only for local relays and testing.
+    None,
+}
+
+/// Email channel configuration (IMAP for inbound, SMTP for replies)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailConfig {
+    /// Whether the channel is enabled
+    pub enabled: bool,
+    /// IMAP server hostname
+    pub imap_host: String,
+    /// IMAP server port
+    pub imap_port: u16,
+    /// Connect to IMAP over TLS (disable only for local testing)
+    pub imap_tls: bool,
+    /// Mailbox to watch
+    pub mailbox: String,
+    /// SMTP server hostname
+    pub smtp_host: String,
+    /// SMTP server port
+    pub smtp_port: u16,
+    /// SMTP transport security (`tls`, `start_tls` or `none`)
+    pub smtp_security: SmtpSecurity,
+    /// Login for both IMAP and SMTP
+    pub username: String,
+    /// Password (or app password) for both IMAP and SMTP
+    pub password: String,
+    /// Address replies are sent from (default: `username`)
+    pub from_address: String,
+    /// Seconds between mailbox checks when IMAP IDLE is unavailable or disabled
+    pub poll_interval_secs: u64,
+    /// Use IMAP IDLE for push delivery when the server supports it
+    pub idle: bool,
+    /// Allowlist of sender addresses (empty = allow all unless `deny_by_default` is set)
+    pub allow_from: Vec<String>,
+    /// When true, empty `allow_from` rejects all senders (strict mode).
+    pub deny_by_default: bool,
+    /// Only accept mail whose sender passed DKIM or DMARC according to a
+    /// trusted `Authentication-Results` header
+    pub require_sender_auth: bool,
+    /// authserv-ids of the receiving mail servers whose
+    /// `Authentication-Results` headers are trusted (e.g. `mx.google.com`)
+    pub trusted_authserv_ids: Vec<String>,
+    /// Largest message fetched over IMAP, in bytes; bigger ones are skipped
+    pub max_message_bytes: usize,
+    /// Most attachments passed on per message
+    pub max_attachments: usize,
+    /// Largest attachment passed on, in bytes
+    pub max_attachment_bytes: usize,
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            imap_host: String::new(),
+            imap_port: 993,
+            imap_tls: true,
+            mailbox: "INBOX".to_string(),
+            smtp_host: String::new(),
+            smtp_port: 465,
+            smtp_security: SmtpSecurity::Tls,
+            username: String::new(),
+            password: String::new(),
+            from_address: String::new(),
+            poll_interval_secs: 60,
+            idle: true,
+            allow_from: Vec::new(),
+            deny_by_default: false,
+            require_sender_auth: true,
+            trusted_authserv_ids: Vec::new(),
+            max_message_bytes: 25 * 1024 * 1024,
+            max_attachments: 10,
+            max_attachment_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
 /// Feishu (Lark) channel configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct FeishuConfig {
@@ -681,10 +998,16 @@ pub struct ProvidersConfig {
     pub nvidia: Option<ProviderConfig>,
     /// Retry behavior for runtime provider calls
     pub retry: RetryConfig,
+    /// Client-side request pacing shared per provider and API key
+    pub rate_limit: RateLimitConfig,
     /// Fallback behavior across multiple configured runtime providers
     pub fallback: FallbackConfig,
     /// Provider rotation configuration for 3+ health-aware providers
     pub rotation: RotationConfig,
+    /// Load balancing across a provider's pooled API keys
+    pub key_pool: KeyPoolConfig,
+    /// Per-request model routing by task complexity
+    pub router: RouterConfig,
 }
 
 /// Generic provider configuration
@@ -699,9 +1022,34 @@ pub struct ProviderConfig {
     /// Authentication method: "api_key" (default), "oauth", or "auto"
     #[serde(default)]
     pub auth_method: Option<String>,
+    /// Client-side request limit; learned from rate-limit headers when unset
+    #[serde(default)]
+    pub requests_per_minute: Option<u32>,
+    /// Additional API keys pooled with `api_key` and load-balanced per request
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+    /// Override the provider's API backend: "anthropic", "openai" or "gemini"
+    #[serde(default)]
+    pub backend: Option<String>,
+    /// Gemini safety thresholds by harm category (native Gemini backend only)
+    #[serde(default)]
+    pub safety_settings: BTreeMap<String, String>,
 }
 
 impl ProviderConfig {
+    /// All configured API keys: `api_key` first, then `api_keys`, skipping
+    /// blanks and duplicates.
+    pub fn pooled_api_keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = Vec::new();
+        for key in self.api_key.iter().chain(self.api_keys.iter()) {
+            let key = key.as_str();
+            if !key.is_empty() && !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        keys
+    }
+
     /// Resolve the authentication method for this provider.
     pub fn resolved_auth_method(&self) -> crate::auth::AuthMethod {
         crate::auth::AuthMethod::from_option(self.auth_method.as_deref())
@@ -720,6 +1068,8 @@ pub struct RetryConfig {
     pub base_delay_ms: u64,
     /// Maximum delay cap in milliseconds for exponential backoff.
     pub max_delay_ms: u64,
+    /// Longest provider-requested (`retry-after`) wait to honor, in milliseconds.
+    pub max_retry_after_ms: u64,
 }
 
 impl Default for RetryConfig {
@@ -729,10 +1079,26 @@ impl Default for RetryConfig {
             max_retries: 3,
             base_delay_ms: 1_000,
             max_delay_ms: 30_000,
+            max_retry_after_ms: 60_000,
         }
     }
 }
 
+/// Client-side rate limiting for runtime provider calls.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// Pace requests using provider rate-limit headers and any configured
+    /// `requests_per_minute`.
+    pub enabled: bool,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
 /// Fallback behavior across multiple configured runtime providers.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
@@ -784,6 +1150,76 @@ impl Default for RotationConfig {
     }
 }
 
+/// Load balancing across multiple API keys for the same provider.
+///
+/// Applies to any provider with more than one key in `api_key` + `api_keys`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyPoolConfig {
+    /// Key selection strategy (round_robin or least_rate_limited).
+    pub strategy: crate::providers::keypool::KeyStrategy,
+    /// Consecutive rate limits before a key is marked unhealthy (default: 3).
+    pub failure_threshold: u32,
+    /// Seconds before an unhealthy key is probed again (default: 30).
+    pub cooldown_secs: u64,
+    /// Seconds a key is benched after an Auth or Billing error (default: 3600).
+    pub bench_secs: u64,
+}
+
+impl Default for KeyPoolConfig {
+    fn default() -> Self {
+        Self {
+            strategy: crate::providers::keypool::KeyStrategy::default(),
+            failure_threshold: 3,
+            cooldown_secs: 30,
+            bench_secs: 3600,
+        }
+    }
+}
+
+/// Per-request model routing by task complexity.
+///
+/// Simple turns go to `simple_model`, complex ones to `complex_model`, and
+/// standard ones keep `agents.defaults.model`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RouterConfig {
+    /// Enable model routing.
+    pub enabled: bool,
+    /// Model for simple turns (None = keep the requested model).
+    pub simple_model: Option<String>,
+    /// Model for complex turns (None = keep the requested model).
+    pub complex_model: Option<String>,
+    /// Messages up to this many characters are simple (default: 200).
+    pub simple_max_chars: usize,
+    /// Messages of at least this many characters are complex (default: 2000).
+    pub complex_min_chars: usize,
+    /// Tool iterations in one turn that make it complex; 0 disables (default: 4).
+    pub complex_tool_iterations: usize,
+    /// Lowest tier for messages with attachments (default: standard).
+    pub attachment_tier: crate::providers::router::ComplexityTier,
+    /// Keyword rules, checked first; the first match wins.
+    pub keyword_rules: Vec<crate::providers::router::KeywordRule>,
+    /// Cheap model asked to classify turns no rule decides (None = standard).
+    pub classifier_model: Option<String>,
+}
+
+impl Default for RouterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            simple_model: None,
+            complex_model: None,
+            simple_max_chars: 200,
+            complex_min_chars: 2000,
+            complex_tool_iterations: 4,
+            attachment_tier: crate::providers::router::ComplexityTier::Standard,
+            keyword_rules: Vec::new(),
+            classifier_model: None,
+        }
+    }
+}
+
 // ============================================================================
 // Gateway Configuration
 // ============================================================================
@@ -821,6 +1257,10 @@ pub struct ToolsConfig {
     pub whatsapp: WhatsAppToolConfig,
     /// Google Sheets tool configuration
     pub google_sheets: GoogleSheetsToolConfig,
+    /// Shell tool command policy
+    pub shell: ShellToolConfig,
+    /// Network egress policy for HTTP-calling tools
+    pub egress: EgressConfig,
 }
 
 /// Web tools configuration
@@ -895,6 +1335,89 @@ pub struct GoogleSheetsToolConfig {
     pub service_account_base64: Option<String>,
 }
 
+/// How the shell tool decides which commands may run.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ShellPolicyMode {
+    /// Run anything not matched by the built-in blocklist (default).
+    #[default]
+    Blocklist,
+    /// Parse commands and run only allowlisted binaries and arguments.
+    Allowlist,
+}
+
+/// A binary the shell tool may run in allowlist mode.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct ShellAllowRule {
+    /// Command name exactly as written (e.g. "git" or "/usr/bin/git").
+    pub binary: String,
+    /// Regexes every argument must match (whole argument). Empty allows any.
+    pub args: Vec<String>,
+    /// Regexes no argument may match (whole argument).
+    pub deny_args: Vec<String>,
+}
+
+/// Shell tool configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ShellToolConfig {
+    /// Policy mode: "blocklist" (default) or "allowlist".
+    pub mode: ShellPolicyMode,
+    /// Allowed binaries in allowlist mode.
+    pub allow: Vec<ShellAllowRule>,
+    /// Per-channel rules that replace `allow` for that channel.
+    pub channels: HashMap<String, Vec<ShellAllowRule>>,
+    /// Allow redirects to and from files (fd duplication and /dev/null are always allowed).
+    pub allow_redirects: bool,
+}
+
+/// Network egress policy applied to tools that make outbound requests.
+///
+/// Domain entries match the domain itself and all of its subdomains; a
+/// leading `*.` is accepted and ignored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EgressConfig {
+    /// If non-empty, only these domains may be contacted.
+    pub allow_domains: Vec<String>,
+    /// Domains that may never be contacted.
+    pub deny_domains: Vec<String>,
+    /// Block private, link-local and loopback addresses, checked after DNS
+    /// resolution and on every redirect hop (default: true).
+    pub block_private: bool,
+    /// If non-empty, only these ports may be contacted.
+    pub allowed_ports: Vec<u16>,
+    /// Per-tool overrides keyed by tool name.
+    pub tools: HashMap<String, EgressToolOverride>,
+}
+
+impl Default for EgressConfig {
+    fn default() -> Self {
+        Self {
+            allow_domains: Vec::new(),
+            deny_domains: Vec::new(),
+            block_private: true,
+            allowed_ports: Vec::new(),
+            tools: HashMap::new(),
+        }
+    }
+}
+
+/// Per-tool egress overrides. Unset fields inherit the global policy.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct EgressToolOverride {
+    /// Replaces the global `allow_domains` for this tool.
+    pub allow_domains: Option<Vec<String>>,
+    /// Added to the global `deny_domains` for this tool.
+    pub deny_domains: Vec<String>,
+    /// Replaces the global `block_private` for this tool.
+    pub block_private: Option<bool>,
+    /// Replaces the global `allowed_ports` for this tool.
+    pub allowed_ports: Option<Vec<u16>>,
+}
+
 // ============================================================================
 // Memory Configuration
 // ============================================================================
@@ -1086,6 +1609,10 @@ pub struct SwarmRole {
     pub system_prompt: String,
     /// Allowed tool names (empty = all minus delegate/spawn).
     pub tools: Vec<String>,
+    /// Tool choice for the sub-agent's first request (see `AgentDefaults`).
+    pub tool_choice: Option<crate::providers::ToolChoice>,
+    /// Allow several tool calls in one response.
+    pub parallel_tool_calls: Option<bool>,
 }
 
 // ============================================================================
@@ -1104,6 +1631,13 @@ pub enum RuntimeType {
     /// Apple Container isolation (macOS only)
     #[serde(rename = "apple")]
     AppleContainer,
+    /// Landlock + seccomp sandboxed native execution (Linux only)
+    Sandbox,
+    /// Rootless Podman container isolation
+    Podman,
+    /// Bubblewrap namespace isolation (Linux only)
+    #[serde(rename = "bwrap")]
+    Bubblewrap,
 }
 
 /// Runtime configuration for shell execution
@@ -1121,6 +1655,14 @@ pub struct RuntimeConfig {
     pub docker: DockerConfig,
     /// Apple Container-specific configuration (macOS)
     pub apple: AppleContainerConfig,
+    /// Landlock/seccomp sandbox configuration (Linux)
+    pub sandbox: SandboxConfig,
+    /// Podman-specific configuration
+    pub podman: PodmanConfig,
+    /// Bubblewrap-specific configuration (Linux)
+    pub bwrap: BubblewrapConfig,
+    /// Persistent per-session shells
+    pub shell_sessions: ShellSessionConfig,
 }
 
 fn default_mount_allowlist_path() -> String {
@@ -1135,6 +1677,10 @@ impl Default for RuntimeConfig {
             mount_allowlist_path: default_mount_allowlist_path(),
             docker: DockerConfig::default(),
             apple: AppleContainerConfig::default(),
+            sandbox: SandboxConfig::default(),
+            podman: PodmanConfig::default(),
+            bwrap: BubblewrapConfig::default(),
+            shell_sessions: ShellSessionConfig::default(),
         }
     }
 }
@@ -1180,6 +1726,118 @@ pub struct AppleContainerConfig {
     pub allow_experimental: bool,
 }
 
+/// Persistent shell session configuration.
+///
+/// When enabled, each agent session gets one long-lived shell (a session
+/// container for Docker/Podman) so `cd`, env vars and installed packages
+/// persist between shell tool calls.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ShellSessionConfig {
+    /// Keep a shell alive per session (default: false)
+    pub enabled: bool,
+    /// Close a session's shell after this many idle seconds
+    pub idle_ttl_secs: u64,
+}
+
+impl Default for ShellSessionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_ttl_secs: 1800,
+        }
+    }
+}
+
+/// Sandbox runtime configuration (Linux only)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct SandboxConfig {
+    /// Paths commands may read and execute. The workspace is always writable;
+    /// everything else is inaccessible.
+    pub read_only_paths: Vec<String>,
+    /// Allow commands to open network sockets (default: false)
+    pub allow_network: bool,
+    /// Syscalls to permit from the built-in deny list (e.g. "ptrace")
+    pub allowed_syscalls: Vec<String>,
+    /// CPU time limit in seconds (RLIMIT_CPU)
+    pub cpu_time_secs: Option<u64>,
+    /// Address space limit in megabytes (RLIMIT_AS)
+    pub memory_limit_mb: Option<u64>,
+    /// Processes a command may start on top of those the user already
+    /// runs (RLIMIT_NPROC)
+    pub max_processes: Option<u64>,
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            read_only_paths: ["/usr", "/bin", "/sbin", "/lib", "/lib64", "/etc"]
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            allow_network: false,
+            allowed_syscalls: Vec::new(),
+            cpu_time_secs: Some(60),
+            memory_limit_mb: Some(1024),
+            max_processes: Some(128),
+        }
+    }
+}
+
+/// Podman runtime configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PodmanConfig {
+    /// Container image to use for shell execution
+    pub image: String,
+    /// Additional volume mounts (host:container format)
+    pub extra_mounts: Vec<String>,
+    /// Memory limit (e.g., "512m")
+    pub memory_limit: Option<String>,
+    /// CPU limit (e.g., "1.0")
+    pub cpu_limit: Option<String>,
+    /// Network mode (default: none for security)
+    pub network: String,
+}
+
+impl Default for PodmanConfig {
+    fn default() -> Self {
+        Self {
+            image: "docker.io/library/alpine:latest".to_string(),
+            extra_mounts: Vec::new(),
+            memory_limit: Some("512m".to_string()),
+            cpu_limit: Some("1.0".to_string()),
+            network: "none".to_string(),
+        }
+    }
+}
+
+/// Bubblewrap runtime configuration (Linux only)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct BubblewrapConfig {
+    /// Host paths bound read-only at the same location
+    pub read_only_paths: Vec<String>,
+    /// Additional bind mounts (host:container format)
+    pub extra_mounts: Vec<String>,
+    /// Share the host network (default: false)
+    pub network: bool,
+}
+
+impl Default for BubblewrapConfig {
+    fn default() -> Self {
+        Self {
+            read_only_paths: ["/usr", "/bin", "/sbin", "/lib", "/lib64", "/etc"]
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            extra_mounts: Vec::new(),
+            network: false,
+        }
+    }
+}
+
 // ============================================================================
 // Containerized Agent Configuration
 // ============================================================================
@@ -1566,6 +2224,54 @@ mod tests {
         assert_eq!(wac.phone_number_id, "999");
     }
 
+    #[test]
+    fn test_channels_config_with_matrix() {
+        let json = r#"{
+            "channels": {
+                "matrix": {
+                    "enabled": true,
+                    "homeserver_url": "https://matrix.example.org",
+                    "access_token": "syt_abc",
+                    "allow_from": ["@alice:example.org"]
+                }
+            }
+        }"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let matrix = config.channels.matrix.unwrap();
+        assert!(matrix.enabled);
+        assert_eq!(matrix.homeserver_url, "https://matrix.example.org");
+        assert!(matrix.auto_join);
+        assert!(!matrix.use_notice);
+        assert_eq!(matrix.sync_timeout_secs, 30);
+        assert!(matrix.sync_token_path.is_none());
+    }
+
+    #[test]
+    fn test_channels_config_with_email() {
+        let json = r#"{
+            "channels": {
+                "email": {
+                    "enabled": true,
+                    "imap_host": "imap.example.com",
+                    "smtp_host": "smtp.example.com",
+                    "smtp_port": 587,
+                    "smtp_security": "start_tls",
+                    "username": "bot@example.com",
+                    "password": "secret"
+                }
+            }
+        }"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let email = config.channels.email.unwrap();
+        assert!(email.enabled);
+        assert_eq!(email.imap_port, 993);
+        assert!(email.imap_tls);
+        assert_eq!(email.mailbox, "INBOX");
+        assert_eq!(email.smtp_port, 587);
+        assert_eq!(email.smtp_security, SmtpSecurity::StartTls);
+        assert!(email.idle);
+    }
+
     #[test]
     fn test_memory_backend_bm25_deserialize() {
         let json = r#"{"memory": {"backend": "bm25"}}"#;
@@ -43,6 +43,11 @@ const KNOWN_AGENTS_DEFAULTS: &[&str] = &[
     "token_budget",
     "compact_tools",
     "tool_profile",
+    "timezone",
+    "reasoning_effort",
+    "thinking_budget",
+    "tool_choice",
+    "parallel_tool_calls",
 ];
 
 #[allow(dead_code)]
@@ -13,6 +13,7 @@ use uuid::Uuid;
 
 use crate::bus::{InboundMessage, MessageBus};
 use crate::error::{Result, ZeptoError};
+use crate::security::encryption::StoreEncryption;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
@@ -191,6 +192,7 @@ pub struct CronService {
     running: Arc<AtomicBool>,
     handle: Arc<RwLock<Option<JoinHandle<()>>>>,
     jitter_ms: u64,
+    encryption: StoreEncryption,
 }
 
 impl CronService {
@@ -208,9 +210,17 @@ impl CronService {
             running: Arc::new(AtomicBool::new(false)),
             handle: Arc::new(RwLock::new(None)),
             jitter_ms,
+            encryption: StoreEncryption::disabled(),
         }
     }
 
+    /// Encrypt the job store at rest. A plaintext store is read as-is and
+    /// encrypted on the next save.
+    pub fn with_encryption(mut self, encryption: StoreEncryption) -> Self {
+        self.encryption = encryption;
+        self
+    }
+
     /// Start scheduler loop (idempotent).
     pub async fn start(&self, on_miss: &OnMiss) -> Result<()> {
         if self.running.swap(true, Ordering::SeqCst) {
@@ -266,11 +276,12 @@ impl CronService {
         let bus = Arc::clone(&self.bus);
         let running = Arc::clone(&self.running);
         let jitter_ms = self.jitter_ms;
+        let encryption = self.encryption.clone();
 
         let handle = tokio::spawn(async move {
             info!("Cron service started");
             while running.load(Ordering::SeqCst) {
-                if let Err(err) = tick(&store, &store_path, &bus, jitter_ms).await {
+                if let Err(err) = tick(&store, &store_path, &bus, jitter_ms, &encryption).await {
                     error!("Cron tick failed: {}", err);
                 }
                 tokio::time::sleep(std::time::Duration::from_secs(1)).await;
@@ -356,6 +367,7 @@ impl CronService {
             return Ok(CronStore::default());
         }
         let content = tokio::fs::read_to_string(&self.store_path).await?;
+        let content = self.encryption.open(&content)?;
         let store = serde_json::from_str::<CronStore>(&content)?;
         Ok(store)
     }
@@ -368,6 +380,7 @@ impl CronService {
             let store = self.store.read().await;
             serde_json::to_string_pretty(&*store)?
         };
+        let json = self.encryption.seal(&json)?;
         tokio::fs::write(&self.store_path, json).await?;
         Ok(())
     }
@@ -384,6 +397,7 @@ async fn tick(
     store_path: &PathBuf,
     bus: &Arc<MessageBus>,
     jitter_ms: u64,
+    encryption: &StoreEncryption,
 ) -> Result<()> {
     let now = now_ms();
     let due_jobs: Vec<CronJob> = {
@@ -455,6 +469,7 @@ async fn tick(
         let store_guard = store.read().await;
         serde_json::to_string_pretty(&*store_guard)?
     };
+    let json = encryption.seal(&json)?;
     if let Some(parent) = store_path.parent() {
         tokio::fs::create_dir_all(parent).await?;
     }
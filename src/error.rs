@@ -5,8 +5,11 @@
 //! `Error` trait implementations.
 
 use std::fmt;
+use std::time::Duration;
 use thiserror::Error;
 
+use crate::providers::RateLimitInfo;
+
 // ============================================================================
 // Provider Error Classification
 // ============================================================================
@@ -20,7 +23,7 @@ pub enum ProviderError {
     /// 401 — Invalid API key or authentication failure
     Auth(String),
     /// 429 — Rate limit or quota exceeded
-    RateLimit(String),
+    RateLimit(RateLimitError),
     /// 402 — Payment required or billing issue
     Billing(String),
     /// 500/502/503/504 — Server-side errors
@@ -39,7 +42,7 @@ impl fmt::Display for ProviderError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ProviderError::Auth(msg) => write!(f, "Authentication error: {}", msg),
-            ProviderError::RateLimit(msg) => write!(f, "Rate limit error: {}", msg),
+            ProviderError::RateLimit(err) => write!(f, "Rate limit error: {}", err),
             ProviderError::Billing(msg) => write!(f, "Billing error: {}", msg),
             ProviderError::ServerError(msg) => write!(f, "Server error: {}", msg),
             ProviderError::InvalidRequest(msg) => write!(f, "Invalid request: {}", msg),
@@ -72,6 +75,24 @@ impl ProviderError {
         )
     }
 
+    /// Attach parsed rate-limit headers to a rate-limit error.
+    ///
+    /// Other variants are returned unchanged.
+    pub fn with_rate_limit_info(self, info: RateLimitInfo) -> Self {
+        match self {
+            ProviderError::RateLimit(err) => ProviderError::RateLimit(err.with_info(info)),
+            other => other,
+        }
+    }
+
+    /// How long the provider asked us to wait before retrying, if it said.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ProviderError::RateLimit(err) => err.retry_after(),
+            _ => None,
+        }
+    }
+
     /// Returns the HTTP status code associated with this error, if applicable.
     pub fn status_code(&self) -> Option<u16> {
         match self {
@@ -87,6 +108,71 @@ impl ProviderError {
     }
 }
 
+/// Details of a 429 response: the provider's message plus any rate-limit
+/// headers it sent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateLimitError {
+    /// Error body returned by the provider
+    pub message: String,
+    /// Parsed rate-limit headers (boxed to keep `ZeptoError` small)
+    info: Box<RateLimitInfo>,
+}
+
+impl RateLimitError {
+    /// Create a rate-limit error with no header information.
+    pub fn new(message: &str) -> Self {
+        Self {
+            message: message.to_string(),
+            info: Box::default(),
+        }
+    }
+
+    /// Attach parsed rate-limit headers.
+    pub fn with_info(mut self, info: RateLimitInfo) -> Self {
+        self.info = Box::new(info);
+        self
+    }
+
+    /// Parsed rate-limit headers.
+    pub fn info(&self) -> &RateLimitInfo {
+        &self.info
+    }
+
+    /// How long the provider asked us to wait before retrying, if it said.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.info.wait_hint()
+    }
+}
+
+impl fmt::Display for RateLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.retry_after() {
+            Some(wait) => write!(
+                f,
+                "{} (retry after {:.1}s)",
+                self.message,
+                wait.as_secs_f64()
+            ),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl From<&str> for RateLimitError {
+    fn from(message: &str) -> Self {
+        Self::new(message)
+    }
+}
+
+impl From<String> for RateLimitError {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            info: Box::default(),
+        }
+    }
+}
+
 impl From<ProviderError> for ZeptoError {
     fn from(err: ProviderError) -> Self {
         ZeptoError::ProviderTyped(err)
@@ -121,6 +207,10 @@ pub enum ZeptoError {
     #[error("Tool error: {0}")]
     Tool(String),
 
+    /// Tool arguments that do not match the tool's parameter schema
+    #[error("Invalid arguments: {0}")]
+    InvalidArguments(String),
+
     /// Session management errors (invalid state, persistence failures, etc.)
     #[error("Session error: {0}")]
     Session(String),
@@ -198,6 +288,7 @@ mod tests {
         let _ = ZeptoError::ProviderTyped(ProviderError::Auth("test".into()));
         let _ = ZeptoError::Channel("test".into());
         let _ = ZeptoError::Tool("test".into());
+        let _ = ZeptoError::InvalidArguments("test".into());
         let _ = ZeptoError::Session("test".into());
         let _ = ZeptoError::BusClosed;
         let _ = ZeptoError::NotFound("test".into());
@@ -310,6 +401,24 @@ mod tests {
         assert!(ze.to_string().contains("Rate limit error"));
     }
 
+    #[test]
+    fn test_rate_limit_info_attaches_only_to_rate_limits() {
+        let info = RateLimitInfo {
+            retry_after: Some(Duration::from_secs(3)),
+            ..Default::default()
+        };
+
+        let err = ProviderError::RateLimit("too fast".into()).with_rate_limit_info(info.clone());
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(3)));
+        assert_eq!(
+            err.to_string(),
+            "Rate limit error: too fast (retry after 3.0s)"
+        );
+
+        let err = ProviderError::ServerError("boom".into()).with_rate_limit_info(info);
+        assert_eq!(err.retry_after(), None);
+    }
+
     #[test]
     fn test_provider_typed_display() {
         let err = ZeptoError::ProviderTyped(ProviderError::Auth("invalid key".into()));
@@ -20,6 +20,7 @@ use crate::bus::{InboundMessage, MessageBus, OutboundMessage};
 use crate::config::{Config, ContainerAgentBackend, ContainerAgentConfig};
 use crate::error::{Result, ZeptoError};
 use crate::health::UsageMetrics;
+use crate::security::encryption::StoreEncryption;
 use crate::security::mount::validate_mount_not_blocked;
 use crate::session::SessionManager;
 
@@ -83,7 +84,9 @@ impl ContainerAgentProxy {
         let (shutdown_tx, shutdown_rx) = watch::channel(false);
         let container_config = config.container_agent.clone();
         let max_concurrent = container_config.max_concurrent.max(1);
-        let session_manager = match SessionManager::new() {
+        let session_manager = match StoreEncryption::from_config(&config)
+            .and_then(|enc| SessionManager::new().map(|m| m.with_encryption(enc)))
+        {
             Ok(manager) => Some(manager),
             Err(e) => {
                 warn!(
@@ -150,6 +153,9 @@ impl ContainerAgentProxy {
                 }
                 msg = self.bus.consume_inbound() => {
                     match msg {
+                        Some(inbound) if inbound.is_passive() => {
+                            self.capture_passive(&inbound).await;
+                        }
                         Some(inbound) => {
                             let permit = self.semaphore.clone().acquire_owned().await;
                             match permit {
@@ -218,17 +224,13 @@ impl ContainerAgentProxy {
                 AgentResult::Success { content, session } => {
                     self.persist_session_snapshot(&message.session_key, session)
                         .await;
-                    OutboundMessage::new(&message.channel, &message.chat_id, &content)
+                    OutboundMessage::reply_to(message, &content)
                 }
                 AgentResult::Error { message: err, .. } => {
                     if let Some(metrics) = usage_metrics.as_ref() {
                         metrics.record_error();
                     }
-                    OutboundMessage::new(
-                        &message.channel,
-                        &message.chat_id,
-                        &format!("Error: {}", err),
-                    )
+                    OutboundMessage::reply_to(message, &format!("Error: {}", err))
                 }
             },
             Err(e) => {
@@ -236,11 +238,7 @@ impl ContainerAgentProxy {
                 if let Some(metrics) = usage_metrics.as_ref() {
                     metrics.record_error();
                 }
-                OutboundMessage::new(
-                    &message.channel,
-                    &message.chat_id,
-                    &format!("Container error: {}", e),
-                )
+                OutboundMessage::reply_to(message, &format!("Container error: {}", e))
             }
         }
     }
@@ -257,6 +255,26 @@ impl ContainerAgentProxy {
         }
     }
 
+    /// Record a passive group message in its session without spawning a container.
+    async fn capture_passive(&self, message: &InboundMessage) {
+        let Some(manager) = self.session_manager.as_ref() else {
+            return;
+        };
+        let result = async {
+            let mut session = manager.get_or_create(&message.session_key).await?;
+            session.add_message(crate::session::Message::user(&message.passive_context()));
+            manager.save(&session).await
+        }
+        .await;
+        if let Err(e) = result {
+            warn!(
+                session = %message.session_key,
+                "Failed to capture passive message: {}",
+                e
+            );
+        }
+    }
+
     async fn persist_session_snapshot(
         &self,
         expected_session_key: &str,
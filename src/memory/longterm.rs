@@ -13,6 +13,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::config::Config;
 use crate::error::{Result, ZeptoError};
+use crate::security::encryption::StoreEncryption;
 
 use super::builtin_searcher::BuiltinSearcher;
 use super::traits::MemorySearcher;
@@ -72,6 +73,7 @@ pub struct LongTermMemory {
     entries: HashMap<String, MemoryEntry>,
     storage_path: PathBuf,
     searcher: Arc<dyn MemorySearcher>,
+    encryption: StoreEncryption,
 }
 
 impl std::fmt::Debug for LongTermMemory {
@@ -80,6 +82,7 @@ impl std::fmt::Debug for LongTermMemory {
             .field("entries", &self.entries)
             .field("storage_path", &self.storage_path)
             .field("searcher", &self.searcher.name())
+            .field("encryption", &self.encryption)
             .finish()
     }
 }
@@ -103,11 +106,23 @@ impl LongTermMemory {
         path: PathBuf,
         searcher: Arc<dyn MemorySearcher>,
     ) -> Result<Self> {
-        let entries = Self::load(&path)?;
+        Self::open(path, searcher, StoreEncryption::disabled())
+    }
+
+    /// Open a long-term memory store with a custom searcher and at-rest
+    /// encryption. A plaintext file is read as-is and encrypted on the next
+    /// save.
+    pub fn open(
+        path: PathBuf,
+        searcher: Arc<dyn MemorySearcher>,
+        encryption: StoreEncryption,
+    ) -> Result<Self> {
+        let entries = Self::load(&path, &encryption)?;
         Ok(Self {
             entries,
             storage_path: path,
             searcher,
+            encryption,
         })
     }
 
@@ -341,6 +356,7 @@ impl LongTermMemory {
         let json = serde_json::to_string_pretty(&self.entries).map_err(|e| {
             ZeptoError::Config(format!("Failed to serialize long-term memory: {}", e))
         })?;
+        let json = self.encryption.seal(&json)?;
 
         std::fs::write(&self.storage_path, json).map_err(|e| {
             ZeptoError::Config(format!(
@@ -355,7 +371,7 @@ impl LongTermMemory {
 
     /// Load memory entries from a JSON file on disk. Returns an empty map if
     /// the file does not exist.
-    fn load(path: &PathBuf) -> Result<HashMap<String, MemoryEntry>> {
+    fn load(path: &PathBuf, encryption: &StoreEncryption) -> Result<HashMap<String, MemoryEntry>> {
         if !path.exists() {
             return Ok(HashMap::new());
         }
@@ -371,6 +387,7 @@ impl LongTermMemory {
         if content.trim().is_empty() {
             return Ok(HashMap::new());
         }
+        let content = encryption.open(&content)?;
 
         let entries: HashMap<String, MemoryEntry> =
             serde_json::from_str(&content).map_err(|e| {
@@ -698,6 +715,32 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_encrypted_persistence_roundtrip() {
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let path = dir.path().join("longterm.json");
+        let key = crate::security::SecretEncryption::from_raw_key(&[7u8; 32]);
+        let encryption = StoreEncryption::new(key);
+
+        {
+            let mut mem =
+                LongTermMemory::open(path.clone(), Arc::new(BuiltinSearcher), encryption.clone())
+                    .unwrap();
+            mem.set("user:name", "Alice", "user", vec![], 1.0)
+                .await
+                .unwrap();
+        }
+
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(raw.starts_with("ENC["));
+        assert!(!raw.contains("Alice"));
+
+        let mem =
+            LongTermMemory::open(path.clone(), Arc::new(BuiltinSearcher), encryption).unwrap();
+        assert_eq!(mem.get_readonly("user:name").unwrap().value, "Alice");
+        assert!(LongTermMemory::with_path(path).is_err());
+    }
+
     #[tokio::test]
     async fn test_summary() {
         let (mut mem, _dir) = temp_memory();
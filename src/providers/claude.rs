@@ -26,16 +26,21 @@
 //! }
 //! ```
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use tracing::warn;
+use tracing::{debug, warn};
 
 use crate::error::{Result, ZeptoError};
-use crate::session::{Message, Role, ToolCall};
+use crate::session::{Message, Role, ThinkingBlock, ToolCall};
 
+use super::ratelimit::{RateLimitInfo, RateLimiter};
+use super::reasoning::{reasoning_support, ReasoningSupport, MIN_THINKING_BUDGET};
 use super::{
-    parse_provider_error, ChatOptions, LLMProvider, LLMResponse, LLMToolCall, ToolDefinition, Usage,
+    parse_provider_error, ChatOptions, LLMProvider, LLMResponse, LLMToolCall, ToolChoice,
+    ToolDefinition, Usage,
 };
 
 /// The Claude API endpoint URL.
@@ -60,6 +65,10 @@ pub struct ClaudeProvider {
     credential: crate::auth::ResolvedCredential,
     /// HTTP client for making requests
     client: Client,
+    /// Whether to add `cache_control` breakpoints to requests
+    prompt_caching: bool,
+    /// Client-side request pacing, shared with other clients of the same key
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl ClaudeProvider {
@@ -83,6 +92,8 @@ impl ClaudeProvider {
                 .timeout(std::time::Duration::from_secs(120))
                 .build()
                 .unwrap_or_else(|_| Client::new()),
+            prompt_caching: true,
+            rate_limiter: None,
         }
     }
 
@@ -96,6 +107,8 @@ impl ClaudeProvider {
                 .timeout(std::time::Duration::from_secs(120))
                 .build()
                 .unwrap_or_else(|_| Client::new()),
+            prompt_caching: true,
+            rate_limiter: None,
         }
     }
 
@@ -111,7 +124,152 @@ impl ClaudeProvider {
         Self {
             credential: crate::auth::ResolvedCredential::ApiKey(api_key.to_string()),
             client,
+            prompt_caching: true,
+            rate_limiter: None,
+        }
+    }
+
+    /// Enable or disable automatic prompt caching (enabled by default).
+    ///
+    /// When enabled, requests mark the system prompt, the tool list and the
+    /// latest conversation turn as cache breakpoints so repeated tool-loop
+    /// iterations read the shared prefix from Anthropic's prompt cache.
+    pub fn with_prompt_caching(mut self, enabled: bool) -> Self {
+        self.prompt_caching = enabled;
+        self
+    }
+
+    /// Pace requests through a rate limiter.
+    ///
+    /// The limiter is updated from Anthropic's `anthropic-ratelimit-*` and
+    /// `retry-after` headers on every response.
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Send a request, pacing it through the rate limiter and mapping error
+    /// responses to typed provider errors.
+    async fn send(&self, request: &ClaudeRequest) -> Result<reqwest::Response> {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await?;
+        }
+
+        let response = self
+            .client
+            .post(CLAUDE_API_URL)
+            .headers(self.auth_headers())
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .header("content-type", "application/json")
+            .json(request)
+            .send()
+            .await?;
+
+        let rate_limit = RateLimitInfo::from_headers(response.headers());
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.observe(&rate_limit);
+        }
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let error_text = response.text().await.unwrap_or_default();
+
+            // Build a human-readable body for the typed error
+            let body = if let Ok(error_response) =
+                serde_json::from_str::<ClaudeErrorResponse>(&error_text)
+            {
+                format!(
+                    "Claude API error: {} - {}",
+                    error_response.error.r#type, error_response.error.message
+                )
+            } else {
+                format!("Claude API error: {}", error_text)
+            };
+
+            return Err(ZeptoError::from(
+                parse_provider_error(status, &body).with_rate_limit_info(rate_limit),
+            ));
         }
+
+        Ok(response)
+    }
+
+    /// Build a request body, applying cache breakpoints when enabled.
+    ///
+    /// Extended thinking is enabled only when reasoning is requested and the
+    /// model supports it. Stored thinking blocks are replayed only then, since
+    /// the API rejects them otherwise.
+    fn build_request(
+        &self,
+        mut messages: Vec<Message>,
+        tools: Vec<ToolDefinition>,
+        model: &str,
+        options: ChatOptions,
+        stream: Option<bool>,
+    ) -> Result<ClaudeRequest> {
+        let mut thinking = thinking_config(model, &options);
+        let tool_choice = if tools.is_empty() {
+            None
+        } else {
+            claude_tool_choice(options.tool_choice.as_ref(), options.parallel_tool_calls)
+        };
+        // Extended thinking only allows the "auto" and "none" tool choices.
+        if thinking.is_some()
+            && options
+                .tool_choice
+                .as_ref()
+                .is_some_and(ToolChoice::forces_call)
+        {
+            debug!("Disabling extended thinking for a forced tool call");
+            thinking = None;
+        }
+        if thinking.is_none() {
+            for msg in &mut messages {
+                msg.thinking.clear();
+            }
+        }
+
+        let (mut system, claude_messages) = convert_messages(messages)?;
+
+        // Append structured output instructions to system prompt if needed
+        if let Some(suffix) = options.output_format.to_claude_system_suffix() {
+            let base = system.unwrap_or_default();
+            system = Some(format!("{}{}", base, suffix));
+        }
+
+        let mut max_tokens = options.max_tokens.unwrap_or(8192);
+        let (mut temperature, mut top_p) = (options.temperature, options.top_p);
+        if let Some(config) = &thinking {
+            // The budget counts against max_tokens, so keep room for the answer.
+            if max_tokens <= config.budget_tokens {
+                max_tokens += config.budget_tokens;
+            }
+            // Sampling overrides are not allowed with thinking enabled.
+            temperature = None;
+            top_p = None;
+        }
+
+        let mut request = ClaudeRequest {
+            model: model.to_string(),
+            max_tokens,
+            messages: claude_messages,
+            system: system.map(ClaudeSystem::Text),
+            tools: if tools.is_empty() {
+                None
+            } else {
+                Some(convert_tools(tools))
+            },
+            temperature,
+            top_p,
+            stop_sequences: options.stop,
+            stream,
+            thinking,
+            tool_choice,
+        };
+        if self.prompt_caching {
+            apply_cache_breakpoints(&mut request);
+        }
+        Ok(request)
     }
 
     /// Build auth headers based on the resolved credential type.
@@ -158,61 +316,9 @@ impl LLMProvider for ClaudeProvider {
     ) -> Result<LLMResponse> {
         let model = model.unwrap_or(DEFAULT_MODEL);
 
-        // Convert messages to Claude format, extracting system message
-        let (mut system, claude_messages) = convert_messages(messages)?;
-
-        // Append structured output instructions to system prompt if needed
-        if let Some(suffix) = options.output_format.to_claude_system_suffix() {
-            let base = system.unwrap_or_default();
-            system = Some(format!("{}{}", base, suffix));
-        }
-
-        // Build request
-        let request = ClaudeRequest {
-            model: model.to_string(),
-            max_tokens: options.max_tokens.unwrap_or(8192),
-            messages: claude_messages,
-            system,
-            tools: if tools.is_empty() {
-                None
-            } else {
-                Some(convert_tools(tools))
-            },
-            temperature: options.temperature,
-            top_p: options.top_p,
-            stop_sequences: options.stop,
-            stream: None,
-        };
-
-        // Send request
-        let response = self
-            .client
-            .post(CLAUDE_API_URL)
-            .headers(self.auth_headers())
-            .header("anthropic-version", ANTHROPIC_VERSION)
-            .header("content-type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let error_text = response.text().await.unwrap_or_default();
-
-            // Build a human-readable body for the typed error
-            let body = if let Ok(error_response) =
-                serde_json::from_str::<ClaudeErrorResponse>(&error_text)
-            {
-                format!(
-                    "Claude API error: {} - {}",
-                    error_response.error.r#type, error_response.error.message
-                )
-            } else {
-                format!("Claude API error: {}", error_text)
-            };
-
-            return Err(ZeptoError::from(parse_provider_error(status, &body)));
-        }
+        // Build and send request
+        let request = self.build_request(messages, tools, model, options, None)?;
+        let response = self.send(&request).await?;
 
         let claude_response: ClaudeResponse = response.json().await?;
         Ok(convert_response(claude_response))
@@ -229,55 +335,8 @@ impl LLMProvider for ClaudeProvider {
         use futures::StreamExt;
 
         let model = model.unwrap_or(DEFAULT_MODEL);
-        let (mut system, claude_messages) = convert_messages(messages)?;
-
-        // Append structured output instructions to system prompt if needed
-        if let Some(suffix) = options.output_format.to_claude_system_suffix() {
-            let base = system.unwrap_or_default();
-            system = Some(format!("{}{}", base, suffix));
-        }
-
-        let request = ClaudeRequest {
-            model: model.to_string(),
-            max_tokens: options.max_tokens.unwrap_or(8192),
-            messages: claude_messages,
-            system,
-            tools: if tools.is_empty() {
-                None
-            } else {
-                Some(convert_tools(tools))
-            },
-            temperature: options.temperature,
-            top_p: options.top_p,
-            stop_sequences: options.stop,
-            stream: Some(true),
-        };
-
-        let response = self
-            .client
-            .post(CLAUDE_API_URL)
-            .headers(self.auth_headers())
-            .header("anthropic-version", ANTHROPIC_VERSION)
-            .header("content-type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let error_text = response.text().await.unwrap_or_default();
-            let body = if let Ok(error_response) =
-                serde_json::from_str::<ClaudeErrorResponse>(&error_text)
-            {
-                format!(
-                    "Claude API error: {} - {}",
-                    error_response.error.r#type, error_response.error.message
-                )
-            } else {
-                format!("Claude API error: {}", error_text)
-            };
-            return Err(ZeptoError::from(parse_provider_error(status, &body)));
-        }
+        let request = self.build_request(messages, tools, model, options, Some(true))?;
+        let response = self.send(&request).await?;
 
         let (tx, rx) = tokio::sync::mpsc::channel::<StreamEvent>(32);
         let byte_stream = response.bytes_stream();
@@ -290,6 +349,8 @@ impl LLMProvider for ClaudeProvider {
             let mut current_tool_json = String::new();
             let mut input_tokens: u32 = 0;
             let mut output_tokens: u32 = 0;
+            let mut cache_creation_tokens: u32 = 0;
+            let mut cache_read_tokens: u32 = 0;
             let mut line_buffer = String::new();
 
             tokio::pin!(byte_stream);
@@ -341,6 +402,9 @@ impl LLMProvider for ClaudeProvider {
                             if let Some(msg) = &sse.message {
                                 if let Some(usage) = &msg.usage {
                                     input_tokens = usage.input_tokens.unwrap_or(0);
+                                    cache_creation_tokens =
+                                        usage.cache_creation_input_tokens.unwrap_or(0);
+                                    cache_read_tokens = usage.cache_read_input_tokens.unwrap_or(0);
                                 }
                             }
                         }
@@ -368,6 +432,17 @@ impl LLMProvider for ClaudeProvider {
                                             }
                                         }
                                     }
+                                    Some("thinking_delta") => {
+                                        if let Some(text) = &delta.thinking {
+                                            if tx
+                                                .send(StreamEvent::ReasoningDelta(text.clone()))
+                                                .await
+                                                .is_err()
+                                            {
+                                                return;
+                                            }
+                                        }
+                                    }
                                     Some("input_json_delta") => {
                                         if let Some(json_chunk) = &delta.partial_json {
                                             current_tool_json.push_str(json_chunk);
@@ -400,7 +475,12 @@ impl LLMProvider for ClaudeProvider {
                                     .send(StreamEvent::ToolCalls(std::mem::take(&mut tool_calls)))
                                     .await;
                             }
-                            let usage = super::Usage::new(input_tokens, output_tokens);
+                            let usage = claude_usage(
+                                input_tokens,
+                                output_tokens,
+                                cache_creation_tokens,
+                                cache_read_tokens,
+                            );
                             let _ = tx
                                 .send(StreamEvent::Done {
                                     content: assembled_content.clone(),
@@ -419,7 +499,12 @@ impl LLMProvider for ClaudeProvider {
                     .send(StreamEvent::ToolCalls(std::mem::take(&mut tool_calls)))
                     .await;
             }
-            let usage = super::Usage::new(input_tokens, output_tokens);
+            let usage = claude_usage(
+                input_tokens,
+                output_tokens,
+                cache_creation_tokens,
+                cache_read_tokens,
+            );
             let _ = tx
                 .send(StreamEvent::Done {
                     content: assembled_content,
@@ -438,6 +523,10 @@ impl LLMProvider for ClaudeProvider {
     fn name(&self) -> &str {
         "claude"
     }
+
+    fn supports_forced_tool_choice(&self) -> bool {
+        true
+    }
 }
 
 // ============================================================================
@@ -455,7 +544,7 @@ struct ClaudeRequest {
     messages: Vec<ClaudeMessage>,
     /// System prompt (separate from messages in Claude API)
     #[serde(skip_serializing_if = "Option::is_none")]
-    system: Option<String>,
+    system: Option<ClaudeSystem>,
     /// Available tools
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<ClaudeTool>>,
@@ -471,6 +560,69 @@ struct ClaudeRequest {
     /// Whether to stream the response
     #[serde(skip_serializing_if = "Option::is_none")]
     stream: Option<bool>,
+    /// Extended thinking configuration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<ClaudeThinking>,
+    /// Tool use requirement and parallel tool use switch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<ClaudeToolChoice>,
+}
+
+/// Tool choice configuration.
+#[derive(Debug, Clone, Serialize)]
+struct ClaudeToolChoice {
+    /// "auto", "any", "tool" or "none"
+    #[serde(rename = "type")]
+    choice_type: &'static str,
+    /// Tool name when `type` is "tool"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    /// Limit the response to a single tool call
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    disable_parallel_tool_use: bool,
+}
+
+/// Extended thinking configuration.
+#[derive(Debug, Clone, Serialize)]
+struct ClaudeThinking {
+    #[serde(rename = "type")]
+    thinking_type: &'static str,
+    budget_tokens: u32,
+}
+
+/// System prompt - plain text, or blocks when a cache breakpoint is set.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+enum ClaudeSystem {
+    /// Plain system prompt
+    Text(String),
+    /// System prompt blocks
+    Blocks(Vec<ClaudeSystemBlock>),
+}
+
+/// A text block in the system prompt.
+#[derive(Debug, Clone, Serialize)]
+struct ClaudeSystemBlock {
+    #[serde(rename = "type")]
+    block_type: &'static str,
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_control: Option<CacheControl>,
+}
+
+/// Prompt cache breakpoint marker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CacheControl {
+    #[serde(rename = "type")]
+    cache_type: String,
+}
+
+impl CacheControl {
+    fn ephemeral() -> Self {
+        Self {
+            cache_type: "ephemeral".to_string(),
+        }
+    }
 }
 
 /// A message in Claude's format.
@@ -498,13 +650,19 @@ enum ClaudeContent {
 enum ClaudeContentBlock {
     /// Text content
     #[serde(rename = "text")]
-    Text { text: String },
+    Text {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
     /// Tool use (assistant requesting to call a tool)
     #[serde(rename = "tool_use")]
     ToolUse {
         id: String,
         name: String,
         input: serde_json::Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
     },
     /// Tool result (user providing result of tool execution)
     #[serde(rename = "tool_result")]
@@ -513,7 +671,19 @@ enum ClaudeContentBlock {
         content: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         is_error: Option<bool>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    /// Extended thinking (signed by the API)
+    #[serde(rename = "thinking")]
+    Thinking {
+        thinking: String,
+        #[serde(default)]
+        signature: String,
     },
+    /// Thinking the API redacted; `data` is an encrypted payload
+    #[serde(rename = "redacted_thinking")]
+    RedactedThinking { data: String },
 }
 
 /// Claude tool definition.
@@ -525,6 +695,9 @@ struct ClaudeTool {
     description: String,
     /// JSON Schema for tool parameters
     input_schema: serde_json::Value,
+    /// Cache breakpoint (set on the last tool only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_control: Option<CacheControl>,
 }
 
 // ============================================================================
@@ -559,10 +732,16 @@ struct ClaudeError {
 /// Claude token usage.
 #[derive(Debug, Deserialize)]
 struct ClaudeUsage {
-    /// Tokens in the input
+    /// Uncached tokens in the input
     input_tokens: u32,
     /// Tokens in the output
     output_tokens: u32,
+    /// Input tokens written to the prompt cache
+    #[serde(default)]
+    cache_creation_input_tokens: u32,
+    /// Input tokens read from the prompt cache
+    #[serde(default)]
+    cache_read_input_tokens: u32,
 }
 
 // ============================================================================
@@ -597,6 +776,8 @@ struct SseDelta {
     #[serde(default)]
     partial_json: Option<String>,
     #[serde(default)]
+    thinking: Option<String>,
+    #[serde(default)]
     stop_reason: Option<String>,
 }
 
@@ -619,6 +800,10 @@ struct SseUsage {
     input_tokens: Option<u32>,
     #[serde(default)]
     output_tokens: Option<u32>,
+    #[serde(default)]
+    cache_creation_input_tokens: Option<u32>,
+    #[serde(default)]
+    cache_read_input_tokens: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -680,11 +865,19 @@ fn convert_messages(messages: Vec<Message>) -> Result<(Option<String>, Vec<Claud
 
                 // Check if this message has tool calls
                 if let Some(tool_calls) = msg.tool_calls {
-                    let mut blocks: Vec<ClaudeContentBlock> = Vec::new();
+                    // Thinking must lead the turn, exactly as the API sent it
+                    let mut blocks: Vec<ClaudeContentBlock> = msg
+                        .thinking
+                        .into_iter()
+                        .filter_map(thinking_to_block)
+                        .collect();
 
                     // Add text content if present
                     if !msg.content.is_empty() {
-                        blocks.push(ClaudeContentBlock::Text { text: msg.content });
+                        blocks.push(ClaudeContentBlock::Text {
+                            text: msg.content,
+                            cache_control: None,
+                        });
                     }
 
                     // Add tool use blocks
@@ -697,6 +890,7 @@ fn convert_messages(messages: Vec<Message>) -> Result<(Option<String>, Vec<Claud
                             id: tc.id,
                             name: tc.name,
                             input,
+                            cache_control: None,
                         });
                     }
 
@@ -719,6 +913,7 @@ fn convert_messages(messages: Vec<Message>) -> Result<(Option<String>, Vec<Claud
                         tool_use_id: tool_call_id,
                         content: msg.content,
                         is_error: None,
+                        cache_control: None,
                     });
                 }
             }
@@ -736,6 +931,65 @@ fn convert_messages(messages: Vec<Message>) -> Result<(Option<String>, Vec<Claud
     Ok((system, claude_messages))
 }
 
+/// Convert a stored thinking block back to Claude's format.
+///
+/// Unsigned blocks (e.g. reasoning recorded from another provider) cannot be
+/// verified by the API and are dropped.
+fn thinking_to_block(block: ThinkingBlock) -> Option<ClaudeContentBlock> {
+    if block.redacted {
+        return Some(ClaudeContentBlock::RedactedThinking {
+            data: block.content,
+        });
+    }
+    block
+        .signature
+        .map(|signature| ClaudeContentBlock::Thinking {
+            thinking: block.content,
+            signature,
+        })
+}
+
+/// Thinking configuration for a request, if reasoning was requested and the
+/// model supports extended thinking.
+fn thinking_config(model: &str, options: &ChatOptions) -> Option<ClaudeThinking> {
+    if reasoning_support(model) != ReasoningSupport::ThinkingBudget {
+        return None;
+    }
+    let budget = options.resolved_thinking_budget()?;
+    Some(ClaudeThinking {
+        thinking_type: "enabled",
+        budget_tokens: budget.max(MIN_THINKING_BUDGET),
+    })
+}
+
+/// Claude's `tool_choice` for the requested choice and parallelism, or `None`
+/// when both are left at the API default.
+fn claude_tool_choice(
+    choice: Option<&ToolChoice>,
+    parallel_tool_calls: Option<bool>,
+) -> Option<ClaudeToolChoice> {
+    let disable_parallel_tool_use = parallel_tool_calls == Some(false);
+    let (choice_type, name) = match choice {
+        None if !disable_parallel_tool_use => return None,
+        None | Some(ToolChoice::Auto) => ("auto", None),
+        // Parallelism does not apply when tools are off.
+        Some(ToolChoice::None) => {
+            return Some(ClaudeToolChoice {
+                choice_type: "none",
+                name: None,
+                disable_parallel_tool_use: false,
+            })
+        }
+        Some(ToolChoice::Required) => ("any", None),
+        Some(ToolChoice::Tool(name)) => ("tool", Some(name.clone())),
+    };
+    Some(ClaudeToolChoice {
+        choice_type,
+        name,
+        disable_parallel_tool_use,
+    })
+}
+
 /// Convert ZeptoClaw tool definitions to Claude API format.
 fn convert_tools(tools: Vec<ToolDefinition>) -> Vec<ClaudeTool> {
     tools
@@ -744,24 +998,96 @@ fn convert_tools(tools: Vec<ToolDefinition>) -> Vec<ClaudeTool> {
             name: t.name,
             description: t.description,
             input_schema: t.parameters,
+            cache_control: None,
         })
         .collect()
 }
 
+/// Mark the system prompt, the tool list and the latest message as prompt
+/// cache breakpoints.
+///
+/// Anthropic caches the request prefix up to each breakpoint, so the stable
+/// system prompt and tool definitions are reused across tool-loop
+/// iterations, and the rolling breakpoint on the last message lets the next
+/// request read the conversation so far from cache. This uses three of the
+/// four breakpoints the API allows.
+fn apply_cache_breakpoints(request: &mut ClaudeRequest) {
+    if let Some(ClaudeSystem::Text(text)) = request.system.take() {
+        request.system = Some(if text.is_empty() {
+            ClaudeSystem::Text(text)
+        } else {
+            ClaudeSystem::Blocks(vec![ClaudeSystemBlock {
+                block_type: "text",
+                text,
+                cache_control: Some(CacheControl::ephemeral()),
+            }])
+        });
+    }
+
+    if let Some(last) = request.tools.as_mut().and_then(|t| t.last_mut()) {
+        last.cache_control = Some(CacheControl::ephemeral());
+    }
+
+    if let Some(last) = request.messages.last_mut() {
+        if let ClaudeContent::Text(text) = &mut last.content {
+            if text.is_empty() {
+                return;
+            }
+            last.content = ClaudeContent::Blocks(vec![ClaudeContentBlock::Text {
+                text: std::mem::take(text),
+                cache_control: None,
+            }]);
+        }
+        if let ClaudeContent::Blocks(blocks) = &mut last.content {
+            match blocks.last_mut() {
+                Some(ClaudeContentBlock::Text {
+                    text,
+                    cache_control,
+                }) if !text.is_empty() => *cache_control = Some(CacheControl::ephemeral()),
+                Some(ClaudeContentBlock::ToolUse { cache_control, .. })
+                | Some(ClaudeContentBlock::ToolResult { cache_control, .. }) => {
+                    *cache_control = Some(CacheControl::ephemeral())
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Build `Usage` from Claude's counters.
+///
+/// Claude reports uncached, cache-write and cache-read input tokens
+/// separately; `prompt_tokens` is their sum so it matches other providers.
+fn claude_usage(
+    input_tokens: u32,
+    output_tokens: u32,
+    cache_creation_tokens: u32,
+    cache_read_tokens: u32,
+) -> Usage {
+    Usage::new(
+        input_tokens + cache_creation_tokens + cache_read_tokens,
+        output_tokens,
+    )
+    .with_cache_tokens(cache_creation_tokens, cache_read_tokens)
+}
+
 /// Convert Claude API response to ZeptoClaw LLMResponse.
 fn convert_response(response: ClaudeResponse) -> LLMResponse {
     let mut content = String::new();
     let mut tool_calls: Vec<LLMToolCall> = Vec::new();
+    let mut thinking: Vec<ThinkingBlock> = Vec::new();
 
     for block in response.content {
         match block {
-            ClaudeContentBlock::Text { text } => {
+            ClaudeContentBlock::Text { text, .. } => {
                 if !content.is_empty() {
                     content.push('\n');
                 }
                 content.push_str(&text);
             }
-            ClaudeContentBlock::ToolUse { id, name, input } => {
+            ClaudeContentBlock::ToolUse {
+                id, name, input, ..
+            } => {
                 // Convert input Value back to JSON string
                 let arguments = serde_json::to_string(&input).unwrap_or_else(|_| "{}".to_string());
                 tool_calls.push(LLMToolCall::new(&id, &name, &arguments));
@@ -769,15 +1095,30 @@ fn convert_response(response: ClaudeResponse) -> LLMResponse {
             ClaudeContentBlock::ToolResult { .. } => {
                 // Tool results shouldn't appear in responses, but handle gracefully
             }
+            ClaudeContentBlock::Thinking {
+                thinking: text,
+                signature,
+            } => {
+                thinking.push(ThinkingBlock::new(&text, Some(&signature)));
+            }
+            ClaudeContentBlock::RedactedThinking { data } => {
+                thinking.push(ThinkingBlock::redacted(&data));
+            }
         }
     }
 
-    let usage = Usage::new(response.usage.input_tokens, response.usage.output_tokens);
+    let usage = claude_usage(
+        response.usage.input_tokens,
+        response.usage.output_tokens,
+        response.usage.cache_creation_input_tokens,
+        response.usage.cache_read_input_tokens,
+    );
 
     LLMResponse {
         content,
         tool_calls,
         usage: Some(usage),
+        thinking,
     }
 }
 
@@ -947,10 +1288,13 @@ mod tests {
         let response = ClaudeResponse {
             content: vec![ClaudeContentBlock::Text {
                 text: "Hello, world!".to_string(),
+                cache_control: None,
             }],
             usage: ClaudeUsage {
                 input_tokens: 10,
                 output_tokens: 5,
+                cache_creation_input_tokens: 0,
+                cache_read_input_tokens: 0,
             },
             stop_reason: Some("end_turn".to_string()),
         };
@@ -967,22 +1311,302 @@ mod tests {
         assert_eq!(usage.total_tokens, 15);
     }
 
+    #[test]
+    fn test_convert_response_reports_cache_usage() {
+        let response: ClaudeResponse = serde_json::from_str(
+            r#"{
+                "content": [{"type": "text", "text": "Hi"}],
+                "usage": {
+                    "input_tokens": 50,
+                    "output_tokens": 20,
+                    "cache_creation_input_tokens": 300,
+                    "cache_read_input_tokens": 2000
+                },
+                "stop_reason": "end_turn"
+            }"#,
+        )
+        .unwrap();
+
+        let usage = convert_response(response).usage.unwrap();
+        assert_eq!(usage.prompt_tokens, 2350);
+        assert_eq!(usage.cache_creation_tokens, 300);
+        assert_eq!(usage.cache_read_tokens, 2000);
+        assert_eq!(usage.uncached_prompt_tokens(), 50);
+        assert_eq!(usage.total_tokens, 2370);
+    }
+
+    fn cache_test_inputs() -> (Vec<Message>, Vec<ToolDefinition>) {
+        let messages = vec![
+            Message::system("You are helpful."),
+            Message::user("Search for rust"),
+            Message::assistant_with_tools(
+                "",
+                vec![ToolCall::new("toolu_1", "web_search", r#"{"q":"rust"}"#)],
+            ),
+            Message::tool_result("toolu_1", "results"),
+        ];
+        let tools = vec![
+            ToolDefinition::new(
+                "web_search",
+                "Search",
+                serde_json::json!({"type": "object"}),
+            ),
+            ToolDefinition::new("read_file", "Read", serde_json::json!({"type": "object"})),
+        ];
+        (messages, tools)
+    }
+
+    #[test]
+    fn test_build_request_sets_cache_breakpoints() {
+        let provider = ClaudeProvider::new("key");
+        let (messages, tools) = cache_test_inputs();
+        let request = provider
+            .build_request(messages, tools, "m", ChatOptions::default(), None)
+            .unwrap();
+        let json = serde_json::to_value(&request).unwrap();
+
+        let ephemeral = serde_json::json!({"type": "ephemeral"});
+        assert_eq!(json["system"][0]["text"], "You are helpful.");
+        assert_eq!(json["system"][0]["cache_control"], ephemeral);
+        assert!(json["tools"][0].get("cache_control").is_none());
+        assert_eq!(json["tools"][1]["cache_control"], ephemeral);
+
+        let messages = json["messages"].as_array().unwrap();
+        let last = &messages[messages.len() - 1]["content"][0];
+        assert_eq!(last["type"], "tool_result");
+        assert_eq!(last["cache_control"], ephemeral);
+        assert!(messages[0]["content"].is_string());
+        assert_eq!(json.to_string().matches("ephemeral").count(), 3);
+    }
+
+    #[test]
+    fn test_build_request_rolls_breakpoint_onto_text_message() {
+        let provider = ClaudeProvider::new("key");
+        let request = provider
+            .build_request(
+                vec![Message::user("Hello")],
+                vec![],
+                "m",
+                ChatOptions::default(),
+                None,
+            )
+            .unwrap();
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert!(json.get("system").is_none());
+        assert_eq!(json["messages"][0]["content"][0]["text"], "Hello");
+        assert_eq!(
+            json["messages"][0]["content"][0]["cache_control"]["type"],
+            "ephemeral"
+        );
+    }
+
+    #[test]
+    fn test_build_request_without_prompt_caching() {
+        let provider = ClaudeProvider::new("key").with_prompt_caching(false);
+        let (messages, tools) = cache_test_inputs();
+        let request = provider
+            .build_request(messages, tools, "m", ChatOptions::default(), None)
+            .unwrap();
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(json["system"], "You are helpful.");
+        assert!(!json.to_string().contains("cache_control"));
+    }
+
+    fn thinking_test_messages() -> Vec<Message> {
+        vec![
+            Message::user("Search for rust"),
+            Message::assistant_with_tools(
+                "",
+                vec![ToolCall::new("toolu_1", "web_search", r#"{"q":"rust"}"#)],
+            )
+            .with_thinking(vec![
+                ThinkingBlock::new("Need a search", Some("sig-1")),
+                ThinkingBlock::redacted("opaque"),
+                ThinkingBlock::new("unsigned", None),
+            ]),
+            Message::tool_result("toolu_1", "results"),
+        ]
+    }
+
+    #[test]
+    fn test_build_request_enables_thinking() {
+        let provider = ClaudeProvider::new("key");
+        let options = ChatOptions::new()
+            .with_max_tokens(4096)
+            .with_temperature(0.7)
+            .with_reasoning_effort(crate::providers::ReasoningEffort::Medium);
+        let request = provider
+            .build_request(
+                thinking_test_messages(),
+                vec![],
+                "claude-sonnet-4-5-20250929",
+                options,
+                None,
+            )
+            .unwrap();
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(json["thinking"]["type"], "enabled");
+        assert_eq!(json["thinking"]["budget_tokens"], 8192);
+        assert_eq!(json["max_tokens"], 4096 + 8192);
+        assert!(json.get("temperature").is_none());
+
+        // Signed and redacted blocks lead the tool-use turn; unsigned ones are dropped
+        let blocks = json["messages"][1]["content"].as_array().unwrap();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0]["type"], "thinking");
+        assert_eq!(blocks[0]["thinking"], "Need a search");
+        assert_eq!(blocks[0]["signature"], "sig-1");
+        assert_eq!(blocks[1]["type"], "redacted_thinking");
+        assert_eq!(blocks[1]["data"], "opaque");
+        assert_eq!(blocks[2]["type"], "tool_use");
+    }
+
+    #[test]
+    fn test_build_request_maps_tool_choice() {
+        let provider = ClaudeProvider::new("key");
+        let tools = vec![ToolDefinition::new(
+            "search",
+            "Search",
+            serde_json::json!({"type": "object"}),
+        )];
+        let build = |options: ChatOptions| {
+            let request = provider
+                .build_request(
+                    vec![Message::user("hi")],
+                    tools.clone(),
+                    "claude-sonnet-4-5-20250929",
+                    options,
+                    None,
+                )
+                .unwrap();
+            serde_json::to_value(&request).unwrap()
+        };
+
+        let json = build(ChatOptions::new());
+        assert!(json.get("tool_choice").is_none());
+
+        let json = build(ChatOptions::new().with_tool_choice(ToolChoice::Required));
+        assert_eq!(json["tool_choice"]["type"], "any");
+
+        let json = build(
+            ChatOptions::new()
+                .with_tool_choice(ToolChoice::Tool("search".to_string()))
+                .with_parallel_tool_calls(false),
+        );
+        assert_eq!(json["tool_choice"]["type"], "tool");
+        assert_eq!(json["tool_choice"]["name"], "search");
+        assert_eq!(json["tool_choice"]["disable_parallel_tool_use"], true);
+
+        let json = build(ChatOptions::new().with_parallel_tool_calls(false));
+        assert_eq!(json["tool_choice"]["type"], "auto");
+
+        let json = build(ChatOptions::new().with_tool_choice(ToolChoice::None));
+        assert_eq!(json["tool_choice"]["type"], "none");
+        assert!(json["tool_choice"]
+            .get("disable_parallel_tool_use")
+            .is_none());
+    }
+
+    #[test]
+    fn test_forced_tool_choice_disables_thinking() {
+        let provider = ClaudeProvider::new("key");
+        let tools = vec![ToolDefinition::new(
+            "search",
+            "Search",
+            serde_json::json!({"type": "object"}),
+        )];
+        let options = ChatOptions::new()
+            .with_thinking_budget(4096)
+            .with_tool_choice(ToolChoice::Required);
+        let request = provider
+            .build_request(
+                vec![Message::user("hi")],
+                tools,
+                "claude-sonnet-4-5-20250929",
+                options,
+                None,
+            )
+            .unwrap();
+        let json = serde_json::to_value(&request).unwrap();
+        assert!(json.get("thinking").is_none());
+        assert_eq!(json["tool_choice"]["type"], "any");
+    }
+
+    #[test]
+    fn test_build_request_without_thinking_strips_blocks() {
+        let provider = ClaudeProvider::new("key");
+        let unsupported = ChatOptions::new().with_thinking_budget(4096);
+        for (model, options) in [
+            ("claude-sonnet-4-5-20250929", ChatOptions::new()),
+            ("claude-3-5-sonnet-20241022", unsupported),
+        ] {
+            let request = provider
+                .build_request(thinking_test_messages(), vec![], model, options, None)
+                .unwrap();
+            let json = serde_json::to_value(&request).unwrap();
+            assert!(json.get("thinking").is_none(), "{model}");
+            assert!(!json.to_string().contains("Need a search"), "{model}");
+        }
+    }
+
+    #[test]
+    fn test_convert_response_collects_thinking() {
+        let response: ClaudeResponse = serde_json::from_str(
+            r#"{
+                "content": [
+                    {"type": "thinking", "thinking": "Let me check", "signature": "abc"},
+                    {"type": "redacted_thinking", "data": "xyz"},
+                    {"type": "text", "text": "Done"}
+                ],
+                "usage": {"input_tokens": 10, "output_tokens": 5},
+                "stop_reason": "end_turn"
+            }"#,
+        )
+        .unwrap();
+
+        let converted = convert_response(response);
+        assert_eq!(converted.content, "Done");
+        assert_eq!(
+            converted.thinking,
+            vec![
+                ThinkingBlock::new("Let me check", Some("abc")),
+                ThinkingBlock::redacted("xyz"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_sse_thinking_delta() {
+        let data = r#"{"type":"content_block_delta","index":0,"delta":{"type":"thinking_delta","thinking":"Hmm"}}"#;
+        let event: SseEvent = serde_json::from_str(data).unwrap();
+        let delta = event.delta.unwrap();
+        assert_eq!(delta.delta_type.as_deref(), Some("thinking_delta"));
+        assert_eq!(delta.thinking.as_deref(), Some("Hmm"));
+    }
+
     #[test]
     fn test_convert_response_with_tool_calls() {
         let response = ClaudeResponse {
             content: vec![
                 ClaudeContentBlock::Text {
                     text: "Let me search for that.".to_string(),
+                    cache_control: None,
                 },
                 ClaudeContentBlock::ToolUse {
                     id: "toolu_01".to_string(),
                     name: "web_search".to_string(),
                     input: serde_json::json!({"query": "rust programming"}),
+                    cache_control: None,
                 },
             ],
             usage: ClaudeUsage {
                 input_tokens: 20,
                 output_tokens: 30,
+                cache_creation_input_tokens: 0,
+                cache_read_input_tokens: 0,
             },
             stop_reason: Some("tool_use".to_string()),
         };
@@ -1005,14 +1629,18 @@ mod tests {
             content: vec![
                 ClaudeContentBlock::Text {
                     text: "First part.".to_string(),
+                    cache_control: None,
                 },
                 ClaudeContentBlock::Text {
                     text: "Second part.".to_string(),
+                    cache_control: None,
                 },
             ],
             usage: ClaudeUsage {
                 input_tokens: 10,
                 output_tokens: 10,
+                cache_creation_input_tokens: 0,
+                cache_read_input_tokens: 0,
             },
             stop_reason: Some("end_turn".to_string()),
         };
@@ -1031,12 +1659,14 @@ mod tests {
                 role: "user".to_string(),
                 content: ClaudeContent::Text("Hello".to_string()),
             }],
-            system: Some("You are helpful.".to_string()),
+            system: Some(ClaudeSystem::Text("You are helpful.".to_string())),
             tools: None,
             temperature: Some(0.7),
             top_p: None,
             stop_sequences: None,
             stream: None,
+            thinking: None,
+            tool_choice: None,
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -1062,6 +1692,8 @@ mod tests {
             top_p: None,
             stop_sequences: None,
             stream: None,
+            thinking: None,
+            tool_choice: None,
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -1079,6 +1711,7 @@ mod tests {
         // Text block
         let text_block = ClaudeContentBlock::Text {
             text: "Hello".to_string(),
+            cache_control: None,
         };
         let json = serde_json::to_string(&text_block).unwrap();
         assert!(json.contains(r#""type":"text""#));
@@ -1089,6 +1722,7 @@ mod tests {
             id: "call_1".to_string(),
             name: "search".to_string(),
             input: serde_json::json!({"q": "test"}),
+            cache_control: None,
         };
         let json = serde_json::to_string(&tool_use).unwrap();
         assert!(json.contains(r#""type":"tool_use""#));
@@ -1100,6 +1734,7 @@ mod tests {
             tool_use_id: "call_1".to_string(),
             content: "Result".to_string(),
             is_error: None,
+            cache_control: None,
         };
         let json = serde_json::to_string(&tool_result).unwrap();
         assert!(json.contains(r#""type":"tool_result""#));
@@ -1146,6 +1781,8 @@ mod tests {
             top_p: None,
             stop_sequences: None,
             stream: Some(true),
+            thinking: None,
+            tool_choice: None,
         };
         let json = serde_json::to_string(&request).unwrap();
         assert!(json.contains(r#""stream":true"#));
@@ -1163,6 +1800,8 @@ mod tests {
             top_p: None,
             stop_sequences: None,
             stream: None,
+            thinking: None,
+            tool_choice: None,
         };
         let json = serde_json::to_string(&request).unwrap();
         assert!(!json.contains("stream"));
@@ -196,6 +196,14 @@ impl LLMProvider for FallbackProvider {
         self.primary.default_model()
     }
 
+    fn supports_forced_tool_choice(&self) -> bool {
+        self.primary.supports_forced_tool_choice() && self.fallback.supports_forced_tool_choice()
+    }
+
+    fn supports_response_schema(&self) -> bool {
+        self.primary.supports_response_schema() && self.fallback.supports_response_schema()
+    }
+
     async fn chat(
         &self,
         messages: Vec<Message>,
@@ -0,0 +1,1314 @@
+//! Google Gemini Provider Implementation
+//!
+//! This module implements the `LLMProvider` trait for the native Gemini API
+//! (`generateContent` / `streamGenerateContent`). Unlike the OpenAI
+//! compatibility endpoint it supports inline image and PDF parts, system
+//! instructions, function-calling modes, safety settings, thinking and the
+//! full `usageMetadata` breakdown.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use zeptoclaw::providers::{gemini::GeminiProvider, ChatOptions, LLMProvider};
+//! use zeptoclaw::session::Message;
+//!
+//! async fn example() {
+//!     let provider = GeminiProvider::new("your-api-key")
+//!         .with_safety_settings([("HARM_CATEGORY_HARASSMENT", "BLOCK_ONLY_HIGH")]);
+//!
+//!     let messages = vec![
+//!         Message::system("You are a helpful assistant."),
+//!         Message::user("Hello!"),
+//!     ];
+//!
+//!     let response = provider
+//!         .chat(messages, vec![], None, ChatOptions::default())
+//!         .await
+//!         .unwrap();
+//!
+//!     println!("Gemini: {}", response.content);
+//! }
+//! ```
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use crate::error::{ProviderError, Result, ZeptoError};
+use crate::session::{Message, Role, ThinkingBlock};
+
+use super::ratelimit::{RateLimitInfo, RateLimiter};
+use super::structured::OutputFormat;
+use super::{
+    parse_provider_error, ChatOptions, LLMProvider, LLMResponse, LLMToolCall, StreamEvent,
+    ToolChoice, ToolDefinition, Usage,
+};
+
+/// The Gemini API endpoint URL.
+const GEMINI_API_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+
+/// The default Gemini model to use.
+/// Can be overridden at compile time with `ZEPTOCLAW_GEMINI_DEFAULT_MODEL` env var.
+const DEFAULT_MODEL: &str = match option_env!("ZEPTOCLAW_GEMINI_DEFAULT_MODEL") {
+    Some(v) => v,
+    None => "gemini-2.5-flash",
+};
+
+// ============================================================================
+// Gemini API Request Types
+// ============================================================================
+
+/// Gemini `generateContent` request body.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiRequest {
+    contents: Vec<GeminiContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_instruction: Option<GeminiContent>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<GeminiTool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_config: Option<GeminiToolConfig>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    safety_settings: Vec<GeminiSafetySetting>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generation_config: Option<GeminiGenerationConfig>,
+}
+
+/// A conversation turn ("user" or "model") made of parts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GeminiContent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
+    #[serde(default)]
+    parts: Vec<GeminiPart>,
+}
+
+/// One part of a turn. Exactly one of the payload fields is set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiPart {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    /// Marks a text part as the model's reasoning
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    thought: bool,
+    /// Opaque reasoning signature the API requires back on function calls
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    thought_signature: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inline_data: Option<GeminiInlineData>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    function_call: Option<GeminiFunctionCall>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    function_response: Option<GeminiFunctionResponse>,
+}
+
+/// Base64 file content sent inline (images, PDFs).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiInlineData {
+    mime_type: String,
+    data: String,
+}
+
+/// A function call requested by the model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GeminiFunctionCall {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    name: String,
+    #[serde(default)]
+    args: Value,
+}
+
+/// The result of a function call, sent back as a user turn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GeminiFunctionResponse {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    name: String,
+    response: Value,
+}
+
+/// Tool declarations.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiTool {
+    function_declarations: Vec<GeminiFunctionDeclaration>,
+}
+
+/// A single function declaration.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiFunctionDeclaration {
+    name: String,
+    description: String,
+    /// Full JSON Schema (the legacy `parameters` field only takes an OpenAPI subset)
+    parameters_json_schema: Value,
+}
+
+/// Function-calling behaviour.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiToolConfig {
+    function_calling_config: GeminiFunctionCallingConfig,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiFunctionCallingConfig {
+    /// "AUTO", "ANY" or "NONE"
+    mode: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    allowed_function_names: Vec<String>,
+}
+
+/// A per-category content safety threshold.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct GeminiSafetySetting {
+    category: String,
+    threshold: String,
+}
+
+/// Sampling and output controls.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiGenerationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_output_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_json_schema: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking_config: Option<GeminiThinkingConfig>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiThinkingConfig {
+    thinking_budget: u32,
+    include_thoughts: bool,
+}
+
+// ============================================================================
+// Gemini API Response Types
+// ============================================================================
+
+/// Gemini `generateContent` response body (also each streamed chunk).
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiResponse {
+    #[serde(default)]
+    candidates: Vec<GeminiCandidate>,
+    #[serde(default)]
+    usage_metadata: Option<GeminiUsageMetadata>,
+    #[serde(default)]
+    prompt_feedback: Option<GeminiPromptFeedback>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiCandidate {
+    #[serde(default)]
+    content: Option<GeminiContent>,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+/// Why the prompt itself was rejected.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiPromptFeedback {
+    #[serde(default)]
+    block_reason: Option<String>,
+}
+
+/// Token accounting reported by Gemini.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiUsageMetadata {
+    #[serde(default)]
+    prompt_token_count: u32,
+    #[serde(default)]
+    candidates_token_count: u32,
+    /// Reasoning tokens, billed as output
+    #[serde(default)]
+    thoughts_token_count: u32,
+    /// Prompt tokens served from the context cache
+    #[serde(default)]
+    cached_content_token_count: u32,
+}
+
+impl GeminiUsageMetadata {
+    /// Convert to `Usage`, counting thinking tokens as completion tokens.
+    fn to_usage(&self) -> Usage {
+        Usage::new(
+            self.prompt_token_count,
+            self.candidates_token_count + self.thoughts_token_count,
+        )
+        .with_cache_tokens(0, self.cached_content_token_count)
+    }
+}
+
+/// Gemini API error response.
+#[derive(Debug, Deserialize)]
+struct GeminiErrorResponse {
+    error: GeminiError,
+}
+
+/// Gemini API error details.
+#[derive(Debug, Deserialize)]
+struct GeminiError {
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    status: String,
+}
+
+// ============================================================================
+// Gemini Provider
+// ============================================================================
+
+/// Native Google Gemini LLM provider.
+///
+/// Implements the `LLMProvider` trait for the Gemini `generateContent` API.
+pub struct GeminiProvider {
+    /// API key sent as `x-goog-api-key`
+    api_key: String,
+    /// API base URL (up to and including the version segment)
+    api_base: String,
+    /// HTTP client for making requests
+    client: Client,
+    /// Safety thresholds sent with every request
+    safety_settings: Vec<GeminiSafetySetting>,
+    /// Client-side request pacing, shared with other clients of the same key
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl GeminiProvider {
+    /// Create a new Gemini provider with the given API key.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::providers::gemini::GeminiProvider;
+    /// use zeptoclaw::providers::LLMProvider;
+    ///
+    /// let provider = GeminiProvider::new("AIza-xxx");
+    /// assert_eq!(provider.name(), "gemini");
+    /// ```
+    pub fn new(api_key: &str) -> Self {
+        Self::with_base_url(api_key, GEMINI_API_URL)
+    }
+
+    /// Create a new Gemini provider with a custom base URL.
+    ///
+    /// # Arguments
+    /// * `api_key` - API key
+    /// * `api_base` - Base URL including the API version, e.g.
+    ///   `https://generativelanguage.googleapis.com/v1beta` (trailing slash removed)
+    pub fn with_base_url(api_key: &str, api_base: &str) -> Self {
+        Self::with_client(
+            api_key,
+            api_base,
+            Client::builder()
+                .timeout(std::time::Duration::from_secs(120))
+                .build()
+                .unwrap_or_else(|_| Client::new()),
+        )
+    }
+
+    /// Create a new Gemini provider with a custom HTTP client.
+    pub fn with_client(api_key: &str, api_base: &str, client: Client) -> Self {
+        Self {
+            api_key: api_key.to_string(),
+            api_base: api_base.trim_end_matches('/').to_string(),
+            client,
+            safety_settings: Vec::new(),
+            rate_limiter: None,
+        }
+    }
+
+    /// Set safety thresholds as `(category, threshold)` pairs, e.g.
+    /// `("HARM_CATEGORY_HATE_SPEECH", "BLOCK_ONLY_HIGH")`.
+    pub fn with_safety_settings<I, C, T>(mut self, settings: I) -> Self
+    where
+        I: IntoIterator<Item = (C, T)>,
+        C: Into<String>,
+        T: Into<String>,
+    {
+        self.safety_settings = settings
+            .into_iter()
+            .map(|(category, threshold)| GeminiSafetySetting {
+                category: category.into(),
+                threshold: threshold.into(),
+            })
+            .collect();
+        self
+    }
+
+    /// Pace requests through a rate limiter.
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Send a request to `models/{model}:{method}` through the rate limiter,
+    /// returning the response with its parsed rate-limit headers.
+    async fn post(
+        &self,
+        model: &str,
+        method: &str,
+        request: &GeminiRequest,
+    ) -> Result<(reqwest::Response, RateLimitInfo)> {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await?;
+        }
+
+        let model = model.strip_prefix("models/").unwrap_or(model);
+        let response = self
+            .client
+            .post(format!("{}/models/{}:{}", self.api_base, model, method))
+            .header("x-goog-api-key", &self.api_key)
+            .header("Content-Type", "application/json")
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| ZeptoError::Provider(format!("Gemini request failed: {}", e)))?;
+
+        let rate_limit = RateLimitInfo::from_headers(response.headers());
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.observe(&rate_limit);
+        }
+        Ok((response, rate_limit))
+    }
+
+    /// Turn a non-success response into a typed provider error.
+    async fn error_from_response(
+        response: reqwest::Response,
+        rate_limit: RateLimitInfo,
+    ) -> ZeptoError {
+        let status = response.status().as_u16();
+        let error_text = response.text().await.unwrap_or_default();
+        ZeptoError::from(gemini_error(status, &error_text).with_rate_limit_info(rate_limit))
+    }
+
+    /// Build the request for this provider's settings.
+    fn build_request(
+        &self,
+        model: &str,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &ChatOptions,
+    ) -> GeminiRequest {
+        let mut request = build_request(model, messages, tools, options);
+        request.safety_settings = self.safety_settings.clone();
+        request
+    }
+}
+
+// ============================================================================
+// Conversion Functions
+// ============================================================================
+
+/// Convert ZeptoClaw messages to Gemini contents plus a system instruction.
+///
+/// System messages are merged into `systemInstruction`, assistant turns
+/// become `model` turns, and tool results become `functionResponse` parts
+/// on a user turn. Adjacent turns with the same role are merged so parallel
+/// function responses travel together.
+fn convert_messages(messages: &[Message]) -> (Vec<GeminiContent>, Option<GeminiContent>) {
+    let mut system_parts: Vec<GeminiPart> = Vec::new();
+    let mut contents: Vec<GeminiContent> = Vec::new();
+    // Gemini matches function responses by name, not by id.
+    let mut call_names: HashMap<String, String> = HashMap::new();
+
+    for msg in messages {
+        let (role, parts) = match msg.role {
+            Role::System => {
+                if !msg.content.is_empty() {
+                    system_parts.push(text_part(&msg.content));
+                }
+                continue;
+            }
+            Role::User => {
+                let mut parts = Vec::new();
+                if !msg.content.is_empty() {
+                    parts.push(text_part(&msg.content));
+                }
+                parts.extend(msg.media.iter().map(|media| GeminiPart {
+                    inline_data: Some(GeminiInlineData {
+                        mime_type: media.mime_type.clone(),
+                        data: media.data.clone(),
+                    }),
+                    ..Default::default()
+                }));
+                ("user", parts)
+            }
+            Role::Assistant => {
+                let mut parts = Vec::new();
+                if !msg.content.is_empty() {
+                    parts.push(text_part(&msg.content));
+                }
+                let mut signature = msg
+                    .thinking
+                    .iter()
+                    .find_map(|block| block.signature.clone());
+                for call in msg.tool_calls.iter().flatten() {
+                    call_names.insert(call.id.clone(), call.name.clone());
+                    let args = serde_json::from_str::<Value>(&call.arguments)
+                        .ok()
+                        .filter(Value::is_object)
+                        .unwrap_or_else(|| json!({}));
+                    parts.push(GeminiPart {
+                        // The signature belongs on the first call of the turn.
+                        thought_signature: signature.take(),
+                        function_call: Some(GeminiFunctionCall {
+                            id: None,
+                            name: call.name.clone(),
+                            args,
+                        }),
+                        ..Default::default()
+                    });
+                }
+                ("model", parts)
+            }
+            Role::Tool => {
+                let id = msg.tool_call_id.clone().unwrap_or_default();
+                let name = call_names.get(&id).cloned().unwrap_or_else(|| id.clone());
+                let response = serde_json::from_str::<Value>(&msg.content)
+                    .ok()
+                    .filter(Value::is_object)
+                    .unwrap_or_else(|| json!({ "content": msg.content }));
+                let part = GeminiPart {
+                    function_response: Some(GeminiFunctionResponse {
+                        id: None,
+                        name,
+                        response,
+                    }),
+                    ..Default::default()
+                };
+                ("user", vec![part])
+            }
+        };
+
+        if parts.is_empty() {
+            continue;
+        }
+        match contents.last_mut() {
+            Some(last) if last.role.as_deref() == Some(role) => last.parts.extend(parts),
+            _ => contents.push(GeminiContent {
+                role: Some(role.to_string()),
+                parts,
+            }),
+        }
+    }
+
+    let system_instruction = (!system_parts.is_empty()).then_some(GeminiContent {
+        role: None,
+        parts: system_parts,
+    });
+    (contents, system_instruction)
+}
+
+fn text_part(text: &str) -> GeminiPart {
+    GeminiPart {
+        text: Some(text.to_string()),
+        ..Default::default()
+    }
+}
+
+/// Convert ZeptoClaw tool definitions to Gemini function declarations.
+fn convert_tools(tools: &[ToolDefinition]) -> Vec<GeminiTool> {
+    if tools.is_empty() {
+        return Vec::new();
+    }
+    vec![GeminiTool {
+        function_declarations: tools
+            .iter()
+            .map(|t| GeminiFunctionDeclaration {
+                name: t.name.clone(),
+                description: t.description.clone(),
+                parameters_json_schema: t.parameters.clone(),
+            })
+            .collect(),
+    }]
+}
+
+/// Whether a Gemini model accepts `thinkingConfig`.
+fn supports_thinking(model: &str) -> bool {
+    let model = model.rsplit('/').next().unwrap_or(model);
+    model.starts_with("gemini-2.5") || model.starts_with("gemini-3")
+}
+
+/// Build a Gemini request payload (without provider-level safety settings).
+fn build_request(
+    model: &str,
+    messages: &[Message],
+    tools: &[ToolDefinition],
+    options: &ChatOptions,
+) -> GeminiRequest {
+    let (contents, system_instruction) = convert_messages(messages);
+
+    let (response_mime_type, response_json_schema) = match &options.output_format {
+        OutputFormat::Text => (None, None),
+        OutputFormat::Json => (Some("application/json"), None),
+        OutputFormat::JsonSchema { schema, .. } => (Some("application/json"), Some(schema.clone())),
+    };
+
+    let thinking_config = options
+        .resolved_thinking_budget()
+        .filter(|_| supports_thinking(model))
+        .map(|budget| GeminiThinkingConfig {
+            thinking_budget: budget,
+            include_thoughts: true,
+        });
+
+    let tool_config = (!tools.is_empty()).then(|| {
+        let (mode, allowed_function_names) = match &options.tool_choice {
+            None | Some(ToolChoice::Auto) => ("AUTO", Vec::new()),
+            Some(ToolChoice::None) => ("NONE", Vec::new()),
+            Some(ToolChoice::Required) => ("ANY", Vec::new()),
+            Some(ToolChoice::Tool(name)) => ("ANY", vec![name.clone()]),
+        };
+        GeminiToolConfig {
+            function_calling_config: GeminiFunctionCallingConfig {
+                mode,
+                allowed_function_names,
+            },
+        }
+    });
+
+    GeminiRequest {
+        contents,
+        system_instruction,
+        tools: convert_tools(tools),
+        tool_config,
+        safety_settings: Vec::new(),
+        generation_config: Some(GeminiGenerationConfig {
+            max_output_tokens: options.max_tokens,
+            temperature: options.temperature,
+            top_p: options.top_p,
+            stop_sequences: options.stop.clone(),
+            response_mime_type,
+            response_json_schema,
+            thinking_config,
+        }),
+    }
+}
+
+/// Response parts folded into ZeptoClaw's shape.
+#[derive(Debug, Default)]
+struct CollectedParts {
+    text: String,
+    thoughts: String,
+    signature: Option<String>,
+    tool_calls: Vec<LLMToolCall>,
+}
+
+impl CollectedParts {
+    /// Fold one candidate's parts in, returning (text, thought) deltas.
+    fn absorb(&mut self, parts: Vec<GeminiPart>) -> Vec<StreamEvent> {
+        let mut deltas = Vec::new();
+        for part in parts {
+            if let Some(signature) = part.thought_signature {
+                self.signature.get_or_insert(signature);
+            }
+            if let Some(call) = part.function_call {
+                let id = call
+                    .id
+                    .unwrap_or_else(|| format!("call_{}", uuid::Uuid::new_v4().simple()));
+                let args = if call.args.is_null() {
+                    "{}".to_string()
+                } else {
+                    call.args.to_string()
+                };
+                self.tool_calls
+                    .push(LLMToolCall::new(&id, &call.name, &args));
+            } else if let Some(text) = part.text.filter(|t| !t.is_empty()) {
+                if part.thought {
+                    self.thoughts.push_str(&text);
+                    deltas.push(StreamEvent::ReasoningDelta(text));
+                } else {
+                    self.text.push_str(&text);
+                    deltas.push(StreamEvent::Delta(text));
+                }
+            }
+        }
+        deltas
+    }
+
+    /// Reasoning to keep on the turn: readable thoughts plus the signature.
+    fn thinking(&self) -> Vec<ThinkingBlock> {
+        if self.thoughts.is_empty() && self.signature.is_none() {
+            return Vec::new();
+        }
+        vec![ThinkingBlock::new(
+            &self.thoughts,
+            self.signature.as_deref(),
+        )]
+    }
+}
+
+/// Error for a prompt Gemini refused to answer at all.
+fn blocked_prompt_error(response: &GeminiResponse) -> Option<ZeptoError> {
+    if !response.candidates.is_empty() {
+        return None;
+    }
+    let reason = response
+        .prompt_feedback
+        .as_ref()
+        .and_then(|f| f.block_reason.as_deref())?;
+    Some(ZeptoError::from(ProviderError::InvalidRequest(format!(
+        "Gemini blocked the prompt: {}",
+        reason
+    ))))
+}
+
+/// Convert a Gemini response to ZeptoClaw LLMResponse.
+fn convert_response(response: GeminiResponse) -> Result<LLMResponse> {
+    if let Some(err) = blocked_prompt_error(&response) {
+        return Err(err);
+    }
+
+    let mut collected = CollectedParts::default();
+    if let Some(candidate) = response.candidates.into_iter().next() {
+        if let Some(reason) = candidate
+            .finish_reason
+            .as_deref()
+            .filter(|r| !matches!(*r, "STOP" | "MAX_TOKENS"))
+        {
+            warn!("Gemini response finished with reason {}", reason);
+        }
+        if let Some(content) = candidate.content {
+            collected.absorb(content.parts);
+        }
+    }
+
+    let thinking = collected.thinking();
+    let mut llm_response = if collected.tool_calls.is_empty() {
+        LLMResponse::text(&collected.text)
+    } else {
+        LLMResponse::with_tools(&collected.text, collected.tool_calls)
+    };
+    if let Some(usage) = response.usage_metadata {
+        llm_response = llm_response.with_usage(usage.to_usage());
+    }
+    if !thinking.is_empty() {
+        llm_response = llm_response.with_thinking(thinking);
+    }
+    Ok(llm_response)
+}
+
+/// Map a Gemini error body to a typed provider error.
+///
+/// Gemini reports a bad API key as 400 `INVALID_ARGUMENT` and missing
+/// permissions as 403, both of which are auth failures here.
+fn gemini_error(status: u16, error_text: &str) -> ProviderError {
+    let parsed = serde_json::from_str::<GeminiErrorResponse>(error_text).ok();
+    let body = match &parsed {
+        Some(r) => format!("Gemini API error: {} - {}", r.error.status, r.error.message),
+        None => format!("Gemini API error: {}", error_text),
+    };
+    let auth_failure = status == 403
+        || parsed.as_ref().is_some_and(|r| {
+            r.error.status == "UNAUTHENTICATED" || r.error.message.contains("API key not valid")
+        });
+    if auth_failure {
+        ProviderError::Auth(body)
+    } else {
+        parse_provider_error(status, &body)
+    }
+}
+
+// ============================================================================
+// LLMProvider Implementation
+// ============================================================================
+
+#[async_trait]
+impl LLMProvider for GeminiProvider {
+    async fn chat(
+        &self,
+        messages: Vec<Message>,
+        tools: Vec<ToolDefinition>,
+        model: Option<&str>,
+        options: ChatOptions,
+    ) -> Result<LLMResponse> {
+        let model = model.unwrap_or(DEFAULT_MODEL);
+        let request = self.build_request(model, &messages, &tools, &options);
+        debug!("Gemini request to model {}", model);
+
+        let (response, rate_limit) = self.post(model, "generateContent", &request).await?;
+        if !response.status().is_success() {
+            return Err(Self::error_from_response(response, rate_limit).await);
+        }
+
+        let gemini_response: GeminiResponse = response
+            .json()
+            .await
+            .map_err(|e| ZeptoError::Provider(format!("Failed to parse Gemini response: {}", e)))?;
+
+        info!("Gemini response received");
+        convert_response(gemini_response)
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: Vec<Message>,
+        tools: Vec<ToolDefinition>,
+        model: Option<&str>,
+        options: ChatOptions,
+    ) -> Result<tokio::sync::mpsc::Receiver<StreamEvent>> {
+        use futures::StreamExt;
+
+        let model = model.unwrap_or(DEFAULT_MODEL);
+        let request = self.build_request(model, &messages, &tools, &options);
+        debug!("Gemini streaming request to model {}", model);
+
+        let (response, rate_limit) = self
+            .post(model, "streamGenerateContent?alt=sse", &request)
+            .await?;
+        if !response.status().is_success() {
+            return Err(Self::error_from_response(response, rate_limit).await);
+        }
+
+        let (tx, rx) = tokio::sync::mpsc::channel::<StreamEvent>(32);
+        let byte_stream = response.bytes_stream();
+
+        tokio::spawn(async move {
+            let mut collected = CollectedParts::default();
+            let mut usage: Option<Usage> = None;
+            let mut line_buffer = String::new();
+
+            tokio::pin!(byte_stream);
+
+            while let Some(chunk_result) = byte_stream.next().await {
+                let chunk = match chunk_result {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        let _ = tx
+                            .send(StreamEvent::Error(ZeptoError::Provider(format!(
+                                "Stream read error: {}",
+                                e
+                            ))))
+                            .await;
+                        return;
+                    }
+                };
+
+                line_buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+                while let Some(newline_pos) = line_buffer.find('\n') {
+                    let line = line_buffer[..newline_pos].trim().to_string();
+                    line_buffer = line_buffer[newline_pos + 1..].to_string();
+
+                    let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+                        continue;
+                    };
+                    let stream_chunk: GeminiResponse = match serde_json::from_str(data) {
+                        Ok(v) => v,
+                        Err(_) => continue,
+                    };
+
+                    if let Some(err) = blocked_prompt_error(&stream_chunk) {
+                        let _ = tx.send(StreamEvent::Error(err)).await;
+                        return;
+                    }
+                    if let Some(chunk_usage) = stream_chunk.usage_metadata {
+                        usage = Some(chunk_usage.to_usage());
+                    }
+                    for candidate in stream_chunk.candidates.into_iter().take(1) {
+                        let Some(content) = candidate.content else {
+                            continue;
+                        };
+                        for delta in collected.absorb(content.parts) {
+                            if tx.send(delta).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+
+            if !collected.tool_calls.is_empty() {
+                let _ = tx.send(StreamEvent::ToolCalls(collected.tool_calls)).await;
+            }
+
+            let _ = tx
+                .send(StreamEvent::Done {
+                    content: collected.text,
+                    usage,
+                })
+                .await;
+        });
+
+        Ok(rx)
+    }
+
+    fn default_model(&self) -> &str {
+        DEFAULT_MODEL
+    }
+
+    fn name(&self) -> &str {
+        "gemini"
+    }
+
+    fn supports_forced_tool_choice(&self) -> bool {
+        true
+    }
+
+    fn supports_response_schema(&self) -> bool {
+        true
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::{MediaPart, ToolCall};
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[derive(Debug, Clone)]
+    struct RecordedRequest {
+        path: String,
+        query: String,
+        api_key: String,
+        body: Value,
+    }
+
+    /// Minimal HTTP/1.1 server answering every request with `status`,
+    /// `content_type` and `body`, recording what it receives.
+    async fn spawn_mock_server(
+        status: u16,
+        content_type: &'static str,
+        body: String,
+    ) -> (String, Arc<Mutex<Vec<RecordedRequest>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/v1beta", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&requests);
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let recorded = Arc::clone(&recorded);
+                let body = body.clone();
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 4096];
+                    let header_end = loop {
+                        let n = stream.read(&mut chunk).await.unwrap_or(0);
+                        if n == 0 {
+                            return;
+                        }
+                        buf.extend_from_slice(&chunk[..n]);
+                        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                            break pos;
+                        }
+                    };
+                    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+                    let header = |wanted: &str| {
+                        head.lines()
+                            .find_map(|line| {
+                                let (name, value) = line.split_once(':')?;
+                                name.eq_ignore_ascii_case(wanted)
+                                    .then(|| value.trim().to_string())
+                            })
+                            .unwrap_or_default()
+                    };
+                    let content_length = header("content-length").parse::<usize>().unwrap_or(0);
+                    while buf.len() < header_end + 4 + content_length {
+                        let n = stream.read(&mut chunk).await.unwrap_or(0);
+                        if n == 0 {
+                            break;
+                        }
+                        buf.extend_from_slice(&chunk[..n]);
+                    }
+
+                    let target = head
+                        .lines()
+                        .next()
+                        .and_then(|line| line.split(' ').nth(1))
+                        .unwrap_or("");
+                    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+                    recorded.lock().unwrap().push(RecordedRequest {
+                        path: path.to_string(),
+                        query: query.to_string(),
+                        api_key: header("x-goog-api-key"),
+                        body: serde_json::from_slice(&buf[header_end + 4..]).unwrap_or(Value::Null),
+                    });
+
+                    let response = format!(
+                        "HTTP/1.1 {} OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        content_type,
+                        body.len(),
+                        body
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        (base_url, requests)
+    }
+
+    fn tool(name: &str) -> ToolDefinition {
+        ToolDefinition::new(
+            name,
+            "Look something up",
+            json!({"type": "object", "properties": {"q": {"type": "string"}}}),
+        )
+    }
+
+    #[test]
+    fn test_gemini_provider_creation() {
+        let provider = GeminiProvider::new("AIza-test");
+        assert_eq!(provider.name(), "gemini");
+        assert_eq!(provider.default_model(), DEFAULT_MODEL);
+        assert_eq!(provider.api_base, GEMINI_API_URL);
+    }
+
+    #[test]
+    fn test_with_base_url_trims_trailing_slash() {
+        let provider = GeminiProvider::with_base_url("k", "http://localhost:9000/v1beta/");
+        assert_eq!(provider.api_base, "http://localhost:9000/v1beta");
+    }
+
+    #[test]
+    fn test_convert_messages_system_instruction_and_roles() {
+        let (contents, system) = convert_messages(&[
+            Message::system("Be brief."),
+            Message::user("Hi"),
+            Message::assistant("Hello"),
+        ]);
+
+        let system = system.unwrap();
+        assert!(system.role.is_none());
+        assert_eq!(system.parts[0].text.as_deref(), Some("Be brief."));
+        assert_eq!(contents.len(), 2);
+        assert_eq!(contents[0].role.as_deref(), Some("user"));
+        assert_eq!(contents[1].role.as_deref(), Some("model"));
+    }
+
+    #[test]
+    fn test_convert_messages_inline_media() {
+        let msg = Message::user("What is this?").with_media(vec![
+            MediaPart::new("image/png", &[0x89, b'P', b'N', b'G']),
+            MediaPart::new("application/pdf", b"%PDF-1.7"),
+        ]);
+        let (contents, _) = convert_messages(&[msg]);
+
+        let parts = &contents[0].parts;
+        assert_eq!(parts.len(), 3);
+        let image = parts[1].inline_data.as_ref().unwrap();
+        assert_eq!(image.mime_type, "image/png");
+        assert_eq!(image.data, "iVBORw==");
+        assert_eq!(
+            parts[2].inline_data.as_ref().unwrap().mime_type,
+            "application/pdf"
+        );
+    }
+
+    #[test]
+    fn test_convert_messages_function_round_trip() {
+        let (contents, _) = convert_messages(&[
+            Message::user("Search twice"),
+            Message::assistant_with_tools(
+                "",
+                vec![
+                    ToolCall::new("call_a", "search", r#"{"q":"rust"}"#),
+                    ToolCall::new("call_b", "search", r#"{"q":"tokio"}"#),
+                ],
+            )
+            .with_thinking(vec![ThinkingBlock::new("", Some("sig-1"))]),
+            Message::tool_result("call_a", r#"{"hits":3}"#),
+            Message::tool_result("call_b", "no results"),
+        ]);
+
+        assert_eq!(contents.len(), 3);
+        let calls = &contents[1].parts;
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].thought_signature.as_deref(), Some("sig-1"));
+        assert!(calls[1].thought_signature.is_none());
+        assert_eq!(calls[0].function_call.as_ref().unwrap().args["q"], "rust");
+
+        // Both results share one user turn.
+        let results = &contents[2].parts;
+        assert_eq!(contents[2].role.as_deref(), Some("user"));
+        assert_eq!(results.len(), 2);
+        let first = results[0].function_response.as_ref().unwrap();
+        assert_eq!(first.name, "search");
+        assert_eq!(first.response["hits"], 3);
+        let second = results[1].function_response.as_ref().unwrap();
+        assert_eq!(second.response["content"], "no results");
+    }
+
+    #[test]
+    fn test_build_request_generation_config() {
+        let options = ChatOptions::new()
+            .with_max_tokens(256)
+            .with_temperature(0.2)
+            .with_output_format(OutputFormat::json_schema(
+                "answer",
+                json!({"type": "object"}),
+            ));
+        let request = build_request("gemini-2.0-flash", &[Message::user("hi")], &[], &options);
+        let value = serde_json::to_value(&request).unwrap();
+
+        let config = &value["generationConfig"];
+        assert_eq!(config["maxOutputTokens"], 256);
+        assert_eq!(config["responseMimeType"], "application/json");
+        assert_eq!(config["responseJsonSchema"]["type"], "object");
+        assert!(value.get("tools").is_none());
+        assert!(value.get("toolConfig").is_none());
+    }
+
+    #[test]
+    fn test_build_request_tools_and_mode() {
+        let request = build_request(
+            "gemini-2.5-flash",
+            &[Message::user("hi")],
+            &[tool("search")],
+            &ChatOptions::default(),
+        );
+        let value = serde_json::to_value(&request).unwrap();
+
+        let declaration = &value["tools"][0]["functionDeclarations"][0];
+        assert_eq!(declaration["name"], "search");
+        assert_eq!(declaration["parametersJsonSchema"]["type"], "object");
+        assert_eq!(value["toolConfig"]["functionCallingConfig"]["mode"], "AUTO");
+
+        let options = ChatOptions::new().with_tool_choice(ToolChoice::Tool("search".into()));
+        let request = build_request(
+            "gemini-2.5-flash",
+            &[Message::user("hi")],
+            &[tool("search")],
+            &options,
+        );
+        let value = serde_json::to_value(&request).unwrap();
+        let config = &value["toolConfig"]["functionCallingConfig"];
+        assert_eq!(config["mode"], "ANY");
+        assert_eq!(config["allowedFunctionNames"][0], "search");
+    }
+
+    #[test]
+    fn test_build_request_thinking_only_for_thinking_models() {
+        let options = ChatOptions::new().with_thinking_budget(4096);
+
+        let request = build_request("gemini-2.5-pro", &[Message::user("hi")], &[], &options);
+        let value = serde_json::to_value(&request).unwrap();
+        let thinking = &value["generationConfig"]["thinkingConfig"];
+        assert_eq!(thinking["thinkingBudget"], 4096);
+        assert_eq!(thinking["includeThoughts"], true);
+
+        let request = build_request("gemini-1.5-pro", &[Message::user("hi")], &[], &options);
+        let value = serde_json::to_value(&request).unwrap();
+        assert!(value["generationConfig"].get("thinkingConfig").is_none());
+    }
+
+    #[test]
+    fn test_convert_response_usage_and_thoughts() {
+        let response: GeminiResponse = serde_json::from_value(json!({
+            "candidates": [{
+                "content": {"role": "model", "parts": [
+                    {"text": "Let me think", "thought": true},
+                    {"text": "Answer"}
+                ]},
+                "finishReason": "STOP"
+            }],
+            "usageMetadata": {
+                "promptTokenCount": 100,
+                "candidatesTokenCount": 20,
+                "thoughtsTokenCount": 30,
+                "cachedContentTokenCount": 60,
+                "totalTokenCount": 150
+            }
+        }))
+        .unwrap();
+
+        let llm = convert_response(response).unwrap();
+        assert_eq!(llm.content, "Answer");
+        assert_eq!(llm.thinking[0].content, "Let me think");
+        let usage = llm.usage.unwrap();
+        assert_eq!(usage.prompt_tokens, 100);
+        assert_eq!(usage.completion_tokens, 50);
+        assert_eq!(usage.cache_read_tokens, 60);
+    }
+
+    #[test]
+    fn test_convert_response_blocked_prompt_is_error() {
+        let response: GeminiResponse = serde_json::from_value(json!({
+            "promptFeedback": {"blockReason": "SAFETY"}
+        }))
+        .unwrap();
+
+        let err = convert_response(response).unwrap_err();
+        assert!(err.to_string().contains("SAFETY"));
+    }
+
+    #[test]
+    fn test_gemini_error_invalid_key_is_auth() {
+        let body = json!({"error": {
+            "code": 400,
+            "message": "API key not valid. Please pass a valid API key.",
+            "status": "INVALID_ARGUMENT"
+        }})
+        .to_string();
+        assert!(matches!(gemini_error(400, &body), ProviderError::Auth(_)));
+        assert!(matches!(
+            gemini_error(
+                400,
+                r#"{"error":{"message":"bad","status":"INVALID_ARGUMENT"}}"#
+            ),
+            ProviderError::InvalidRequest(_)
+        ));
+        assert!(matches!(
+            gemini_error(429, "quota"),
+            ProviderError::RateLimit(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_chat_against_mock_server() {
+        let body = json!({
+            "candidates": [{
+                "content": {"role": "model", "parts": [{
+                    "functionCall": {"name": "search", "args": {"q": "rust"}},
+                    "thoughtSignature": "sig-xyz"
+                }]},
+                "finishReason": "STOP"
+            }],
+            "usageMetadata": {"promptTokenCount": 12, "candidatesTokenCount": 5}
+        })
+        .to_string();
+        let (base_url, requests) = spawn_mock_server(200, "application/json", body).await;
+
+        let provider = GeminiProvider::with_base_url("AIza-mock", &base_url)
+            .with_safety_settings([("HARM_CATEGORY_HARASSMENT", "BLOCK_ONLY_HIGH")]);
+        let response = provider
+            .chat(
+                vec![Message::system("sys"), Message::user("find rust")],
+                vec![tool("search")],
+                Some("gemini-2.5-flash"),
+                ChatOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.tool_calls.len(), 1);
+        assert_eq!(response.tool_calls[0].name, "search");
+        assert_eq!(response.tool_calls[0].arguments, r#"{"q":"rust"}"#);
+        assert_eq!(response.thinking[0].signature.as_deref(), Some("sig-xyz"));
+        assert_eq!(response.usage.unwrap().total_tokens, 17);
+
+        let recorded = requests.lock().unwrap();
+        let request = &recorded[0];
+        assert_eq!(
+            request.path,
+            "/v1beta/models/gemini-2.5-flash:generateContent"
+        );
+        assert_eq!(request.api_key, "AIza-mock");
+        assert_eq!(request.body["systemInstruction"]["parts"][0]["text"], "sys");
+        assert_eq!(
+            request.body["safetySettings"][0]["threshold"],
+            "BLOCK_ONLY_HIGH"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chat_stream_against_mock_server() {
+        let chunks = [
+            json!({"candidates": [{"content": {"role": "model", "parts": [
+                {"text": "Pondering", "thought": true}
+            ]}}]}),
+            json!({"candidates": [{"content": {"role": "model", "parts": [{"text": "Hel"}]}}]}),
+            json!({
+                "candidates": [{"content": {"role": "model", "parts": [{"text": "lo"}]},
+                                "finishReason": "STOP"}],
+                "usageMetadata": {"promptTokenCount": 4, "candidatesTokenCount": 2}
+            }),
+        ];
+        let body: String = chunks
+            .iter()
+            .map(|chunk| format!("data: {}\r\n\r\n", chunk))
+            .collect();
+        let (base_url, requests) = spawn_mock_server(200, "text/event-stream", body).await;
+
+        let provider = GeminiProvider::with_base_url("AIza-mock", &base_url);
+        let mut rx = provider
+            .chat_stream(
+                vec![Message::user("hi")],
+                vec![],
+                Some("gemini-2.5-flash"),
+                ChatOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+
+        assert!(matches!(&events[0], StreamEvent::ReasoningDelta(t) if t == "Pondering"));
+        assert!(matches!(&events[1], StreamEvent::Delta(t) if t == "Hel"));
+        match events.last().unwrap() {
+            StreamEvent::Done { content, usage } => {
+                assert_eq!(content, "Hello");
+                assert_eq!(usage.as_ref().unwrap().completion_tokens, 2);
+            }
+            other => panic!("expected Done, got {:?}", other),
+        }
+
+        let recorded = requests.lock().unwrap();
+        assert_eq!(
+            recorded[0].path,
+            "/v1beta/models/gemini-2.5-flash:streamGenerateContent"
+        );
+        assert_eq!(recorded[0].query, "alt=sse");
+    }
+
+    #[tokio::test]
+    async fn test_chat_error_against_mock_server() {
+        let body = json!({"error": {
+            "code": 429,
+            "message": "Resource has been exhausted",
+            "status": "RESOURCE_EXHAUSTED"
+        }})
+        .to_string();
+        let (base_url, _) = spawn_mock_server(429, "application/json", body).await;
+
+        let provider = GeminiProvider::with_base_url("AIza-mock", &base_url);
+        let err = provider
+            .chat(
+                vec![Message::user("hi")],
+                vec![],
+                None,
+                ChatOptions::default(),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            ZeptoError::ProviderTyped(ProviderError::RateLimit(_))
+        ));
+    }
+}
@@ -0,0 +1,874 @@
+//! API key pooling for a single provider.
+//!
+//! [`KeyPoolProvider`] spreads requests for one provider across several API
+//! keys. Each key is backed by its own inner provider (and therefore its own
+//! rate limiter) and its own [`ProviderHealth`]. A key that is rate limited
+//! hands the request to the next key and is tried last until its retry-after
+//! expires; a key that fails with an Auth or Billing error is benched and
+//! skipped until its bench period ends.
+//!
+//! Pool health can be written to a JSON snapshot so `zeptoclaw status` can
+//! show it from outside the gateway process.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use zeptoclaw::providers::keypool::{mask_api_key, KeyPoolProvider, KeyStrategy};
+//! use zeptoclaw::providers::claude::ClaudeProvider;
+//!
+//! let keys = ["sk-ant-key-one", "sk-ant-key-two"];
+//! let members: Vec<(String, Box<dyn LLMProvider>)> = keys
+//!     .iter()
+//!     .map(|key| (mask_api_key(key), Box::new(ClaudeProvider::new(key)) as Box<dyn LLMProvider>))
+//!     .collect();
+//! let provider = KeyPoolProvider::new("anthropic", members, KeyStrategy::RoundRobin, 3, 30);
+//! ```
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::config::Config;
+use crate::error::{ProviderError, Result, ZeptoError};
+use crate::session::Message;
+
+use super::rotation::ProviderHealth;
+use super::{ChatOptions, LLMProvider, LLMResponse, RateLimiter, StreamEvent, ToolDefinition};
+
+// ============================================================================
+// Key Strategy
+// ============================================================================
+
+/// Strategy for choosing which pooled key serves a request.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyStrategy {
+    /// Cycle through healthy keys in order.
+    #[default]
+    RoundRobin,
+    /// Prefer the key whose last rate limit is oldest (never-limited keys first).
+    LeastRateLimited,
+}
+
+impl KeyStrategy {
+    /// Config-file spelling of the strategy.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyStrategy::RoundRobin => "round_robin",
+            KeyStrategy::LeastRateLimited => "least_rate_limited",
+        }
+    }
+}
+
+/// Mask an API key for display, keeping only its last four characters.
+///
+/// # Example
+/// ```
+/// use zeptoclaw::providers::keypool::mask_api_key;
+///
+/// assert_eq!(mask_api_key("sk-ant-api03-abcdwxyz"), "...wxyz");
+/// assert_eq!(mask_api_key("short"), "****");
+/// ```
+pub fn mask_api_key(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() <= 8 {
+        return "****".to_string();
+    }
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("...{}", tail)
+}
+
+/// Default location of the health snapshot for a provider's key pool.
+pub fn key_pool_state_path(provider: &str) -> PathBuf {
+    Config::dir()
+        .join("key_pools")
+        .join(format!("{}.json", provider))
+}
+
+fn now_epoch() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn now_epoch_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+// ============================================================================
+// Snapshots
+// ============================================================================
+
+/// Observed state of one pooled key.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyState {
+    /// Serving requests.
+    Healthy,
+    /// Past the failure threshold; only probed after the cooldown.
+    Unhealthy,
+    /// Failed Auth or Billing; skipped until the bench period ends.
+    Benched,
+}
+
+impl KeyState {
+    /// Human-readable label.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyState::Healthy => "healthy",
+            KeyState::Unhealthy => "unhealthy",
+            KeyState::Benched => "benched",
+        }
+    }
+}
+
+/// Health of one pooled key at a point in time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KeyHealthSnapshot {
+    /// Masked key label (see [`mask_api_key`]).
+    pub label: String,
+    /// Current state.
+    pub state: KeyState,
+    /// Consecutive failures.
+    pub failures: u32,
+    /// Epoch seconds of the last rate limit, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_rate_limited: Option<u64>,
+    /// Epoch seconds when the bench ends, if benched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub benched_until: Option<u64>,
+    /// Error that benched the key, if benched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bench_reason: Option<String>,
+}
+
+/// Health of a whole key pool, as written to disk.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KeyPoolSnapshot {
+    /// Provider id (e.g. "anthropic").
+    pub provider: String,
+    /// Selection strategy in use.
+    pub strategy: KeyStrategy,
+    /// Epoch seconds when the snapshot was taken.
+    pub updated_at: u64,
+    /// Per-key health, in configured order.
+    pub keys: Vec<KeyHealthSnapshot>,
+}
+
+impl KeyPoolSnapshot {
+    /// Load a snapshot written by a running pool, if one exists.
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+}
+
+// ============================================================================
+// KeyPoolProvider
+// ============================================================================
+
+/// One API key in the pool.
+struct PooledKey {
+    label: String,
+    provider: Box<dyn LLMProvider>,
+    health: ProviderHealth,
+    /// Epoch secs of the last rate limit (0 = never).
+    last_rate_limited: AtomicU64,
+    /// Epoch secs when the bench ends (0 = not benched).
+    benched_until: AtomicU64,
+    bench_reason: Mutex<Option<String>>,
+    /// Epoch millis until which a 429 asked us to leave this key alone.
+    held_until_ms: AtomicU64,
+    /// The key's rate limiter, shared with its provider, if known.
+    limiter: Option<Arc<RateLimiter>>,
+}
+
+impl PooledKey {
+    fn is_benched(&self, now: u64) -> bool {
+        self.benched_until.load(Ordering::Relaxed) > now
+    }
+
+    /// Whether the provider has asked for this key to wait.
+    fn is_held(&self, now_ms: u64) -> bool {
+        self.held_until_ms.load(Ordering::Relaxed) > now_ms
+            || self
+                .limiter
+                .as_ref()
+                .is_some_and(|limiter| limiter.blocked_until().is_some())
+    }
+
+    fn snapshot(&self, now: u64) -> KeyHealthSnapshot {
+        let benched = self.is_benched(now);
+        let state = if benched {
+            KeyState::Benched
+        } else if self.health.is_healthy() {
+            KeyState::Healthy
+        } else {
+            KeyState::Unhealthy
+        };
+        let last_rate_limited = self.last_rate_limited.load(Ordering::Relaxed);
+        KeyHealthSnapshot {
+            label: self.label.clone(),
+            state,
+            failures: self.health.failures(),
+            last_rate_limited: (last_rate_limited > 0).then_some(last_rate_limited),
+            benched_until: benched.then(|| self.benched_until.load(Ordering::Relaxed)),
+            bench_reason: if benched {
+                self.bench_reason
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .clone()
+            } else {
+                None
+            },
+        }
+    }
+}
+
+/// What a failed request means for the key that served it.
+enum KeyFailure {
+    /// The key itself is unusable (Auth or Billing): bench it.
+    Bench,
+    /// The key is rate limited: try another key.
+    RateLimited,
+    /// Not key-specific: return the error as is.
+    Other,
+}
+
+impl KeyFailure {
+    fn classify(err: &ZeptoError) -> Self {
+        match err {
+            ZeptoError::ProviderTyped(ProviderError::Auth(_) | ProviderError::Billing(_)) => {
+                KeyFailure::Bench
+            }
+            ZeptoError::ProviderTyped(ProviderError::RateLimit(_)) => KeyFailure::RateLimited,
+            _ => KeyFailure::Other,
+        }
+    }
+}
+
+/// A provider that load-balances one backend across a pool of API keys.
+///
+/// Keys are chosen per request by [`KeyStrategy`] among healthy, unbenched
+/// keys, with keys still waiting out a rate limit tried last. When every
+/// unbenched key is unhealthy, the one that failed longest ago is probed.
+/// When every key is benched, requests fail with an Auth error.
+pub struct KeyPoolProvider {
+    provider_name: String,
+    keys: Vec<PooledKey>,
+    strategy: KeyStrategy,
+    /// Seconds a key stays benched after an Auth or Billing error.
+    bench_secs: u64,
+    /// Seconds a rate-limited key is tried last when the 429 gave no wait.
+    cooldown_secs: u64,
+    /// Atomic counter for round-robin (wraps around).
+    next_index: AtomicU32,
+    /// Where health snapshots are written, if anywhere.
+    state_path: Option<PathBuf>,
+}
+
+impl fmt::Debug for KeyPoolProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let labels: Vec<&str> = self.keys.iter().map(|k| k.label.as_str()).collect();
+        f.debug_struct("KeyPoolProvider")
+            .field("provider", &self.provider_name)
+            .field("keys", &labels)
+            .field("strategy", &self.strategy)
+            .finish()
+    }
+}
+
+impl KeyPoolProvider {
+    /// Create a key pool.
+    ///
+    /// # Arguments
+    /// * `provider_name` - Provider id the keys belong to (e.g. "anthropic").
+    /// * `keys` - `(label, provider)` pairs, one inner provider per API key.
+    /// * `strategy` - How to pick a key for each request.
+    /// * `failure_threshold` - Consecutive rate limits before a key is unhealthy.
+    /// * `cooldown_secs` - Seconds before an unhealthy key is probed again.
+    ///
+    /// # Panics
+    /// Panics if `keys` is empty.
+    pub fn new(
+        provider_name: &str,
+        keys: Vec<(String, Box<dyn LLMProvider>)>,
+        strategy: KeyStrategy,
+        failure_threshold: u32,
+        cooldown_secs: u64,
+    ) -> Self {
+        assert!(
+            !keys.is_empty(),
+            "KeyPoolProvider requires at least one key"
+        );
+
+        let keys = keys
+            .into_iter()
+            .map(|(label, provider)| PooledKey {
+                label,
+                provider,
+                health: ProviderHealth::new(failure_threshold, cooldown_secs),
+                last_rate_limited: AtomicU64::new(0),
+                benched_until: AtomicU64::new(0),
+                bench_reason: Mutex::new(None),
+                held_until_ms: AtomicU64::new(0),
+                limiter: None,
+            })
+            .collect();
+
+        Self {
+            provider_name: provider_name.to_string(),
+            keys,
+            strategy,
+            bench_secs: 3600,
+            cooldown_secs,
+            next_index: AtomicU32::new(0),
+            state_path: None,
+        }
+    }
+
+    /// Set how long a key stays benched after an Auth or Billing error.
+    pub fn with_bench_secs(mut self, bench_secs: u64) -> Self {
+        self.bench_secs = bench_secs;
+        self
+    }
+
+    /// Attach each key's rate limiter, in pool order.
+    ///
+    /// Keys whose limiter is holding requests are tried last instead of
+    /// sleeping through the hold while another key is free.
+    pub fn with_rate_limiters(mut self, limiters: Vec<Option<Arc<RateLimiter>>>) -> Self {
+        for (key, limiter) in self.keys.iter_mut().zip(limiters) {
+            key.limiter = limiter;
+        }
+        self
+    }
+
+    /// Write health snapshots to `path` on every state change.
+    ///
+    /// An initial snapshot is written immediately, replacing any left over
+    /// from a previous run.
+    pub fn with_state_path(mut self, path: PathBuf) -> Self {
+        self.state_path = Some(path);
+        self.persist();
+        self
+    }
+
+    /// Number of keys in the pool.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Always false: a pool holds at least one key.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Current health of every key.
+    pub fn snapshot(&self) -> KeyPoolSnapshot {
+        let now = now_epoch();
+        KeyPoolSnapshot {
+            provider: self.provider_name.clone(),
+            strategy: self.strategy,
+            updated_at: now,
+            keys: self.keys.iter().map(|k| k.snapshot(now)).collect(),
+        }
+    }
+
+    fn persist(&self) {
+        let Some(path) = self.state_path.as_deref() else {
+            return;
+        };
+        let result = serde_json::to_string_pretty(&self.snapshot())
+            .map_err(std::io::Error::other)
+            .and_then(|json| {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(path, json)
+            });
+        if let Err(e) = result {
+            debug!(path = %path.display(), error = %e, "Key pool: failed to write health snapshot");
+        }
+    }
+
+    /// Indices of the keys to try for one request, in order.
+    fn selection_order(&self) -> Vec<usize> {
+        let now = now_epoch();
+        let len = self.keys.len();
+        let start = self.next_index.fetch_add(1, Ordering::Relaxed) as usize;
+
+        let mut healthy: Vec<usize> = (0..len)
+            .map(|offset| (start + offset) % len)
+            .filter(|&i| !self.keys[i].is_benched(now) && self.keys[i].health.is_healthy())
+            .collect();
+
+        if healthy.is_empty() {
+            // Every unbenched key is unhealthy: probe the one that failed longest ago.
+            return self
+                .keys
+                .iter()
+                .enumerate()
+                .filter(|(_, k)| !k.is_benched(now))
+                .min_by_key(|(_, k)| k.health.last_failure_epoch())
+                .map(|(i, _)| vec![i])
+                .unwrap_or_default();
+        }
+
+        if self.strategy == KeyStrategy::LeastRateLimited {
+            // Stable sort keeps round-robin order among equally limited keys.
+            healthy.sort_by_key(|&i| self.keys[i].last_rate_limited.load(Ordering::Relaxed));
+        }
+        // Keys waiting out a rate limit go last, keeping their order.
+        let now_ms = now_epoch_ms();
+        healthy.sort_by_key(|&i| self.keys[i].is_held(now_ms));
+        healthy
+    }
+
+    /// Record a failure against key `index`.
+    ///
+    /// Returns `true` when the request should move on to the next key.
+    fn record_key_failure(&self, index: usize, err: &ZeptoError) -> bool {
+        let key = &self.keys[index];
+        match KeyFailure::classify(err) {
+            KeyFailure::Bench => {
+                let until = now_epoch() + self.bench_secs;
+                key.benched_until.store(until, Ordering::Relaxed);
+                *key.bench_reason
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(err.to_string());
+                warn!(
+                    provider = %self.provider_name,
+                    key = %key.label,
+                    bench_secs = self.bench_secs,
+                    error = %err,
+                    "Key pool: benching API key"
+                );
+                self.persist();
+                true
+            }
+            KeyFailure::RateLimited => {
+                let hold = match err {
+                    ZeptoError::ProviderTyped(e) => e.retry_after(),
+                    _ => None,
+                }
+                .unwrap_or(Duration::from_secs(self.cooldown_secs));
+                key.held_until_ms
+                    .store(now_epoch_ms() + hold.as_millis() as u64, Ordering::Relaxed);
+                key.last_rate_limited.store(now_epoch(), Ordering::Relaxed);
+                key.health.record_failure();
+                info!(
+                    provider = %self.provider_name,
+                    key = %key.label,
+                    "Key pool: API key rate limited, trying next"
+                );
+                self.persist();
+                true
+            }
+            KeyFailure::Other => false,
+        }
+    }
+
+    fn record_key_success(&self, index: usize) {
+        let key = &self.keys[index];
+        if key.health.failures() > 0 {
+            key.health.record_success();
+            self.persist();
+        }
+    }
+
+    fn all_benched_error(&self) -> ZeptoError {
+        ZeptoError::ProviderTyped(ProviderError::Auth(format!(
+            "all {} API keys for {} are benched",
+            self.keys.len(),
+            self.provider_name
+        )))
+    }
+}
+
+#[async_trait]
+impl LLMProvider for KeyPoolProvider {
+    fn name(&self) -> &str {
+        self.keys[0].provider.name()
+    }
+
+    fn default_model(&self) -> &str {
+        self.keys[0].provider.default_model()
+    }
+
+    fn supports_forced_tool_choice(&self) -> bool {
+        self.keys[0].provider.supports_forced_tool_choice()
+    }
+
+    fn supports_response_schema(&self) -> bool {
+        self.keys[0].provider.supports_response_schema()
+    }
+
+    async fn chat(
+        &self,
+        messages: Vec<Message>,
+        tools: Vec<ToolDefinition>,
+        model: Option<&str>,
+        options: ChatOptions,
+    ) -> Result<LLMResponse> {
+        let mut last_err = None;
+
+        for index in self.selection_order() {
+            match self.keys[index]
+                .provider
+                .chat(messages.clone(), tools.clone(), model, options.clone())
+                .await
+            {
+                Ok(response) => {
+                    self.record_key_success(index);
+                    return Ok(response);
+                }
+                Err(err) => {
+                    if !self.record_key_failure(index, &err) {
+                        return Err(err);
+                    }
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| self.all_benched_error()))
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: Vec<Message>,
+        tools: Vec<ToolDefinition>,
+        model: Option<&str>,
+        options: ChatOptions,
+    ) -> Result<tokio::sync::mpsc::Receiver<StreamEvent>> {
+        let mut last_err = None;
+
+        for index in self.selection_order() {
+            match self.keys[index]
+                .provider
+                .chat_stream(messages.clone(), tools.clone(), model, options.clone())
+                .await
+            {
+                Ok(receiver) => {
+                    self.record_key_success(index);
+                    return Ok(receiver);
+                }
+                Err(err) => {
+                    if !self.record_key_failure(index, &err) {
+                        return Err(err);
+                    }
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| self.all_benched_error()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    /// A provider that counts calls and returns a fixed outcome.
+    struct KeyProvider {
+        name: &'static str,
+        calls: Arc<AtomicU32>,
+        error: Option<fn() -> ZeptoError>,
+    }
+
+    impl KeyProvider {
+        fn ok(name: &'static str) -> (Arc<AtomicU32>, Box<dyn LLMProvider>) {
+            Self::build(name, None)
+        }
+
+        fn failing(
+            name: &'static str,
+            error: fn() -> ZeptoError,
+        ) -> (Arc<AtomicU32>, Box<dyn LLMProvider>) {
+            Self::build(name, Some(error))
+        }
+
+        fn build(
+            name: &'static str,
+            error: Option<fn() -> ZeptoError>,
+        ) -> (Arc<AtomicU32>, Box<dyn LLMProvider>) {
+            let calls = Arc::new(AtomicU32::new(0));
+            let provider = KeyProvider {
+                name,
+                calls: Arc::clone(&calls),
+                error,
+            };
+            (calls, Box::new(provider))
+        }
+    }
+
+    #[async_trait]
+    impl LLMProvider for KeyProvider {
+        fn name(&self) -> &str {
+            "claude"
+        }
+
+        fn default_model(&self) -> &str {
+            "key-model"
+        }
+
+        async fn chat(
+            &self,
+            _messages: Vec<Message>,
+            _tools: Vec<ToolDefinition>,
+            _model: Option<&str>,
+            _options: ChatOptions,
+        ) -> Result<LLMResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            match self.error {
+                Some(error) => Err(error()),
+                None => Ok(LLMResponse::text(self.name)),
+            }
+        }
+    }
+
+    fn rate_limited() -> ZeptoError {
+        ZeptoError::ProviderTyped(ProviderError::RateLimit("429".into()))
+    }
+
+    fn auth_failed() -> ZeptoError {
+        ZeptoError::ProviderTyped(ProviderError::Auth("invalid x-api-key".into()))
+    }
+
+    fn pool(members: Vec<Box<dyn LLMProvider>>, strategy: KeyStrategy) -> KeyPoolProvider {
+        let keys = members
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| (format!("...key{}", i), p))
+            .collect();
+        KeyPoolProvider::new("anthropic", keys, strategy, 3, 30)
+    }
+
+    async fn ask(pool: &KeyPoolProvider) -> Result<LLMResponse> {
+        pool.chat(vec![], vec![], None, ChatOptions::default())
+            .await
+    }
+
+    #[test]
+    fn test_mask_api_key() {
+        assert_eq!(mask_api_key("sk-ant-api03-secretABCD"), "...ABCD");
+        assert_eq!(mask_api_key("12345678"), "****");
+        assert_eq!(mask_api_key(""), "****");
+    }
+
+    #[test]
+    fn test_key_pool_name_and_model_come_from_first_key() {
+        let (_, a) = KeyProvider::ok("a");
+        let (_, b) = KeyProvider::ok("b");
+        let pool = pool(vec![a, b], KeyStrategy::RoundRobin);
+        assert_eq!(pool.name(), "claude");
+        assert_eq!(pool.default_model(), "key-model");
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_key_pool_round_robin() {
+        let (a_calls, a) = KeyProvider::ok("a");
+        let (b_calls, b) = KeyProvider::ok("b");
+        let pool = pool(vec![a, b], KeyStrategy::RoundRobin);
+
+        let answers: Vec<String> = [
+            ask(&pool).await.unwrap(),
+            ask(&pool).await.unwrap(),
+            ask(&pool).await.unwrap(),
+        ]
+        .into_iter()
+        .map(|r| r.content)
+        .collect();
+
+        assert_eq!(answers, vec!["a", "b", "a"]);
+        assert_eq!(a_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(b_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_key_pool_rate_limit_moves_to_next_key() {
+        let (_, a) = KeyProvider::failing("a", rate_limited);
+        let (b_calls, b) = KeyProvider::ok("b");
+        let pool = pool(vec![a, b], KeyStrategy::RoundRobin);
+
+        let response = ask(&pool).await.unwrap();
+        assert_eq!(response.content, "b");
+        assert_eq!(b_calls.load(Ordering::SeqCst), 1);
+
+        let snapshot = pool.snapshot();
+        assert_eq!(snapshot.keys[0].failures, 1);
+        assert!(snapshot.keys[0].last_rate_limited.is_some());
+        assert_eq!(snapshot.keys[1].last_rate_limited, None);
+    }
+
+    #[tokio::test]
+    async fn test_key_pool_skips_key_right_after_rate_limit() {
+        let (a_calls, a) = KeyProvider::failing("a", || {
+            let info = crate::providers::RateLimitInfo {
+                retry_after: Some(Duration::from_secs(30)),
+                ..Default::default()
+            };
+            ZeptoError::ProviderTyped(
+                ProviderError::RateLimit("429".into()).with_rate_limit_info(info),
+            )
+        });
+        let (b_calls, b) = KeyProvider::ok("b");
+        let pool = pool(vec![a, b], KeyStrategy::RoundRobin);
+
+        // Round robin would start the second and fourth requests at key a.
+        for _ in 0..4 {
+            assert_eq!(ask(&pool).await.unwrap().content, "b");
+        }
+        assert_eq!(a_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(b_calls.load(Ordering::SeqCst), 4);
+        assert_eq!(pool.snapshot().keys[0].state, KeyState::Healthy);
+    }
+
+    #[tokio::test]
+    async fn test_key_pool_tries_key_with_blocked_limiter_last() {
+        let (a_calls, a) = KeyProvider::ok("a");
+        let (_, b) = KeyProvider::ok("b");
+        let limiter = Arc::new(RateLimiter::new());
+        limiter.observe(&crate::providers::RateLimitInfo {
+            retry_after: Some(Duration::from_secs(30)),
+            ..Default::default()
+        });
+        let pool =
+            pool(vec![a, b], KeyStrategy::RoundRobin).with_rate_limiters(vec![Some(limiter), None]);
+
+        for _ in 0..2 {
+            assert_eq!(ask(&pool).await.unwrap().content, "b");
+        }
+        assert_eq!(a_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_key_pool_least_rate_limited_prefers_unlimited_key() {
+        let (a_calls, a) = KeyProvider::ok("a");
+        let (_, b) = KeyProvider::ok("b");
+        let pool = pool(vec![a, b], KeyStrategy::LeastRateLimited);
+        pool.keys[0]
+            .last_rate_limited
+            .store(now_epoch(), Ordering::Relaxed);
+
+        for _ in 0..3 {
+            assert_eq!(ask(&pool).await.unwrap().content, "b");
+        }
+        assert_eq!(a_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_key_pool_benches_key_on_auth_error() {
+        let (a_calls, a) = KeyProvider::failing("a", auth_failed);
+        let (_, b) = KeyProvider::ok("b");
+        let pool = pool(vec![a, b], KeyStrategy::RoundRobin);
+
+        for _ in 0..4 {
+            assert_eq!(ask(&pool).await.unwrap().content, "b");
+        }
+        // The bad key was tried once, then benched for good.
+        assert_eq!(a_calls.load(Ordering::SeqCst), 1);
+
+        let snapshot = pool.snapshot();
+        assert_eq!(snapshot.keys[0].state, KeyState::Benched);
+        assert!(snapshot.keys[0]
+            .bench_reason
+            .as_deref()
+            .unwrap()
+            .contains("invalid x-api-key"));
+        assert_eq!(snapshot.keys[1].state, KeyState::Healthy);
+    }
+
+    #[tokio::test]
+    async fn test_key_pool_all_benched_returns_auth_error() {
+        let (_, a) = KeyProvider::failing("a", auth_failed);
+        let pool = pool(vec![a], KeyStrategy::RoundRobin);
+
+        let first = ask(&pool).await.unwrap_err();
+        assert!(first.to_string().contains("invalid x-api-key"));
+
+        let second = ask(&pool).await.unwrap_err();
+        assert!(matches!(
+            second,
+            ZeptoError::ProviderTyped(ProviderError::Auth(ref msg)) if msg.contains("benched")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_key_pool_bench_expires() {
+        let (_, a) = KeyProvider::failing("a", auth_failed);
+        let (_, b) = KeyProvider::ok("b");
+        let pool = pool(vec![a, b], KeyStrategy::RoundRobin).with_bench_secs(0);
+
+        ask(&pool).await.unwrap();
+        assert_ne!(pool.snapshot().keys[0].state, KeyState::Benched);
+    }
+
+    #[tokio::test]
+    async fn test_key_pool_server_error_is_not_key_specific() {
+        let (_, a) = KeyProvider::failing("a", || {
+            ZeptoError::ProviderTyped(ProviderError::ServerError("503".into()))
+        });
+        let (b_calls, b) = KeyProvider::ok("b");
+        let pool = pool(vec![a, b], KeyStrategy::RoundRobin);
+
+        assert!(ask(&pool).await.is_err());
+        assert_eq!(b_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(pool.snapshot().keys[0].failures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_key_pool_all_rate_limited_returns_rate_limit() {
+        let (_, a) = KeyProvider::failing("a", rate_limited);
+        let (_, b) = KeyProvider::failing("b", rate_limited);
+        let pool = pool(vec![a, b], KeyStrategy::RoundRobin);
+
+        let err = ask(&pool).await.unwrap_err();
+        assert!(matches!(
+            err,
+            ZeptoError::ProviderTyped(ProviderError::RateLimit(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_key_pool_writes_snapshot() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("key_pools").join("anthropic.json");
+        let (_, a) = KeyProvider::failing("a", auth_failed);
+        let (_, b) = KeyProvider::ok("b");
+        let pool = pool(vec![a, b], KeyStrategy::LeastRateLimited).with_state_path(path.clone());
+
+        let initial = KeyPoolSnapshot::load(&path).unwrap();
+        assert_eq!(initial.provider, "anthropic");
+        assert_eq!(initial.strategy, KeyStrategy::LeastRateLimited);
+        assert!(initial.keys.iter().all(|k| k.state == KeyState::Healthy));
+
+        ask(&pool).await.unwrap();
+        let updated = KeyPoolSnapshot::load(&path).unwrap();
+        assert_eq!(updated.keys[0].state, KeyState::Benched);
+        assert_eq!(updated.keys[0].label, "...key0");
+    }
+
+    #[test]
+    fn test_key_strategy_serde() {
+        let json = serde_json::to_string(&KeyStrategy::LeastRateLimited).unwrap();
+        assert_eq!(json, "\"least_rate_limited\"");
+        let parsed: KeyStrategy = serde_json::from_str("\"round_robin\"").unwrap();
+        assert_eq!(parsed, KeyStrategy::RoundRobin);
+    }
+}
@@ -23,10 +23,15 @@
 
 pub mod claude;
 pub mod fallback;
+pub mod gemini;
+pub mod keypool;
 pub mod openai;
+pub mod ratelimit;
+pub mod reasoning;
 mod registry;
 pub mod retry;
 pub mod rotation;
+pub mod router;
 pub mod structured;
 mod types;
 
@@ -47,7 +52,11 @@ use crate::error::ProviderError;
 
 pub use claude::ClaudeProvider;
 pub use fallback::FallbackProvider;
+pub use gemini::GeminiProvider;
+pub use keypool::{KeyPoolProvider, KeyStrategy};
 pub use openai::OpenAIProvider;
+pub use ratelimit::{RateLimitInfo, RateLimiter};
+pub use reasoning::{reasoning_support, ReasoningEffort, ReasoningSupport};
 pub use registry::{
     configured_provider_names, configured_unsupported_provider_names, provider_config_by_name,
     resolve_runtime_provider, resolve_runtime_providers, ProviderSpec, RuntimeProviderSelection,
@@ -55,9 +64,11 @@ pub use registry::{
 };
 pub use retry::RetryProvider;
 pub use rotation::{RotationProvider, RotationStrategy};
-pub use structured::{validate_json_response, OutputFormat};
+pub use router::{ComplexityTier, RouterProvider};
+pub use structured::{validate_json_response, OutputFormat, StructuredChat, StructuredOutput};
 pub use types::{
-    ChatOptions, LLMProvider, LLMResponse, LLMToolCall, StreamEvent, ToolDefinition, Usage,
+    ChatOptions, LLMProvider, LLMResponse, LLMToolCall, StreamEvent, ToolChoice, ToolDefinition,
+    Usage,
 };
 
 /// Parse an HTTP status code and response body into a structured [`ProviderError`].
@@ -69,7 +80,7 @@ pub fn parse_provider_error(status: u16, body: &str) -> ProviderError {
         401 => ProviderError::Auth(body.to_string()),
         402 => ProviderError::Billing(body.to_string()),
         404 => ProviderError::ModelNotFound(body.to_string()),
-        429 => ProviderError::RateLimit(body.to_string()),
+        429 => ProviderError::RateLimit(body.into()),
         400 => ProviderError::InvalidRequest(body.to_string()),
         500..=599 => ProviderError::ServerError(body.to_string()),
         _ => ProviderError::Unknown(format!("HTTP {}: {}", status, body)),
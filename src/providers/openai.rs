@@ -30,14 +30,17 @@ use async_trait::async_trait;
 use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use tracing::{debug, info};
 
 use crate::error::{Result, ZeptoError};
-use crate::session::{Message, Role};
+use crate::session::{Message, Role, ThinkingBlock};
 
+use super::ratelimit::{RateLimitInfo, RateLimiter};
+use super::reasoning::{reasoning_support, ReasoningSupport};
 use super::{
-    parse_provider_error, ChatOptions, LLMProvider, LLMResponse, LLMToolCall, ToolDefinition, Usage,
+    parse_provider_error, ChatOptions, LLMProvider, LLMResponse, LLMToolCall, StreamEvent,
+    ToolChoice, ToolDefinition, Usage,
 };
 
 /// The OpenAI API endpoint URL.
@@ -85,6 +88,15 @@ struct OpenAIRequest {
     /// Response format (e.g., json_object, json_schema)
     #[serde(skip_serializing_if = "Option::is_none")]
     response_format: Option<serde_json::Value>,
+    /// Reasoning effort for o-series and GPT-5 models
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<&'static str>,
+    /// "auto", "none", "required" or a specific function
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<serde_json::Value>,
+    /// Whether the model may call several tools in one response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parallel_tool_calls: Option<bool>,
 }
 
 /// A message in OpenAI's format.
@@ -170,6 +182,9 @@ struct OpenAIResponseMessage {
     content: Option<String>,
     /// Tool calls made by the model
     tool_calls: Option<Vec<OpenAIToolCallResponse>>,
+    /// Reasoning text from compatible backends (e.g. DeepSeek, vLLM)
+    #[serde(default)]
+    reasoning_content: Option<String>,
 }
 
 /// A tool call in the response.
@@ -188,6 +203,28 @@ struct OpenAIUsage {
     prompt_tokens: u32,
     /// Tokens in the completion
     completion_tokens: u32,
+    /// Prompt token breakdown (includes cached tokens when reported)
+    #[serde(default)]
+    prompt_tokens_details: Option<OpenAIPromptTokensDetails>,
+}
+
+/// OpenAI prompt token breakdown.
+#[derive(Debug, Default, Deserialize)]
+struct OpenAIPromptTokensDetails {
+    /// Prompt tokens served from the automatic prompt cache
+    #[serde(default)]
+    cached_tokens: u32,
+}
+
+impl OpenAIUsage {
+    /// Convert to `Usage`, carrying over cached prompt tokens.
+    fn to_usage(&self) -> Usage {
+        let cached = self
+            .prompt_tokens_details
+            .as_ref()
+            .map_or(0, |d| d.cached_tokens);
+        Usage::new(self.prompt_tokens, self.completion_tokens).with_cache_tokens(0, cached)
+    }
 }
 
 /// OpenAI streaming chunk response body.
@@ -218,6 +255,9 @@ struct OpenAIStreamDelta {
     /// Incremental tool call fragments
     #[serde(default)]
     tool_calls: Option<Vec<OpenAIStreamToolCallDelta>>,
+    /// Incremental reasoning text from compatible backends
+    #[serde(default)]
+    reasoning_content: Option<String>,
 }
 
 /// Streamed tool call fragment.
@@ -288,6 +328,8 @@ pub struct OpenAIProvider {
     client: Client,
     /// Preferred token field by model to avoid repeated fallback retries
     model_token_fields: Mutex<HashMap<String, MaxTokenField>>,
+    /// Client-side request pacing, shared with other clients of the same key
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl OpenAIProvider {
@@ -315,6 +357,7 @@ impl OpenAIProvider {
                 .build()
                 .unwrap_or_else(|_| Client::new()),
             model_token_fields: Mutex::new(HashMap::new()),
+            rate_limiter: None,
         }
     }
 
@@ -341,6 +384,7 @@ impl OpenAIProvider {
                 .build()
                 .unwrap_or_else(|_| Client::new()),
             model_token_fields: Mutex::new(HashMap::new()),
+            rate_limiter: None,
         }
     }
 
@@ -359,7 +403,41 @@ impl OpenAIProvider {
             api_base: api_base.trim_end_matches('/').to_string(),
             client,
             model_token_fields: Mutex::new(HashMap::new()),
+            rate_limiter: None,
+        }
+    }
+
+    /// Pace requests through a rate limiter.
+    ///
+    /// The limiter is updated from `x-ratelimit-*` and `retry-after` headers
+    /// on every response.
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Send a chat completion request through the rate limiter, returning the
+    /// response with its parsed rate-limit headers.
+    async fn post(&self, request: &OpenAIRequest) -> Result<(reqwest::Response, RateLimitInfo)> {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await?;
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/chat/completions", self.api_base))
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| ZeptoError::Provider(format!("OpenAI request failed: {}", e)))?;
+
+        let rate_limit = RateLimitInfo::from_headers(response.headers());
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.observe(&rate_limit);
         }
+        Ok((response, rate_limit))
     }
 
     /// Get the preferred token field for a model, defaulting to `max_tokens`.
@@ -442,9 +520,10 @@ fn convert_tools(tools: Vec<ToolDefinition>) -> Vec<OpenAITool> {
 fn convert_response(response: OpenAIResponse) -> LLMResponse {
     let choice = response.choices.into_iter().next();
 
-    let (content, tool_calls) = match choice {
+    let (content, tool_calls, reasoning) = match choice {
         Some(c) => {
             let content = c.message.content.unwrap_or_default();
+            let reasoning = c.message.reasoning_content.filter(|r| !r.is_empty());
             let tool_calls = c
                 .message
                 .tool_calls
@@ -456,9 +535,9 @@ fn convert_response(response: OpenAIResponse) -> LLMResponse {
                         .collect()
                 })
                 .unwrap_or_default();
-            (content, tool_calls)
+            (content, tool_calls, reasoning)
         }
-        None => (String::new(), Vec::new()),
+        None => (String::new(), Vec::new(), None),
     };
 
     let mut llm_response = if tool_calls.is_empty() {
@@ -468,8 +547,11 @@ fn convert_response(response: OpenAIResponse) -> LLMResponse {
     };
 
     if let Some(usage) = response.usage {
-        llm_response =
-            llm_response.with_usage(Usage::new(usage.prompt_tokens, usage.completion_tokens));
+        llm_response = llm_response.with_usage(usage.to_usage());
+    }
+    if let Some(reasoning) = reasoning {
+        // Unsigned: kept for display only, never sent back to the API
+        llm_response = llm_response.with_thinking(vec![ThinkingBlock::new(&reasoning, None)]);
     }
 
     llm_response
@@ -488,6 +570,28 @@ fn build_request(
         MaxTokenField::MaxCompletionTokens => (None, options.max_tokens),
     };
 
+    let reasoning_effort = if reasoning_support(model) == ReasoningSupport::Effort {
+        options.reasoning_effort.map(|e| e.as_str())
+    } else {
+        None
+    };
+    // Reasoning models only accept default sampling parameters.
+    let (temperature, top_p) = if reasoning_effort.is_some() {
+        (None, None)
+    } else {
+        (options.temperature, options.top_p)
+    };
+
+    // Tool controls are rejected when no tools are offered.
+    let (tool_choice, parallel_tool_calls) = if tools.is_empty() {
+        (None, None)
+    } else {
+        (
+            options.tool_choice.as_ref().map(openai_tool_choice),
+            options.parallel_tool_calls,
+        )
+    };
+
     OpenAIRequest {
         model: model.to_string(),
         messages: convert_messages(messages.to_vec()),
@@ -498,11 +602,27 @@ fn build_request(
         },
         max_tokens,
         max_completion_tokens,
-        temperature: options.temperature,
-        top_p: options.top_p,
+        temperature,
+        top_p,
         stop: options.stop.clone(),
         stream: None,
         response_format: options.output_format.to_openai_response_format(),
+        reasoning_effort,
+        tool_choice,
+        parallel_tool_calls,
+    }
+}
+
+/// OpenAI's `tool_choice` value for a tool choice.
+fn openai_tool_choice(choice: &ToolChoice) -> serde_json::Value {
+    match choice {
+        ToolChoice::Auto => serde_json::json!("auto"),
+        ToolChoice::None => serde_json::json!("none"),
+        ToolChoice::Required => serde_json::json!("required"),
+        ToolChoice::Tool(name) => serde_json::json!({
+            "type": "function",
+            "function": { "name": name }
+        }),
     }
 }
 
@@ -511,20 +631,20 @@ fn apply_stream_chunk(
     assembled_content: &mut String,
     pending_tool_calls: &mut Vec<PendingToolCall>,
     usage: &mut Option<Usage>,
-) -> Vec<String> {
+) -> Vec<StreamEvent> {
     if let Some(chunk_usage) = chunk.usage {
-        *usage = Some(Usage::new(
-            chunk_usage.prompt_tokens,
-            chunk_usage.completion_tokens,
-        ));
+        *usage = Some(chunk_usage.to_usage());
     }
 
     let mut deltas = Vec::new();
 
     for choice in chunk.choices {
+        if let Some(reasoning) = choice.delta.reasoning_content.filter(|r| !r.is_empty()) {
+            deltas.push(StreamEvent::ReasoningDelta(reasoning));
+        }
         if let Some(content) = choice.delta.content {
             assembled_content.push_str(&content);
-            deltas.push(content);
+            deltas.push(StreamEvent::Delta(content));
         }
 
         if let Some(tool_call_deltas) = choice.delta.tool_calls {
@@ -605,15 +725,7 @@ impl LLMProvider for OpenAIProvider {
             let request = build_request(model, &messages, &tools, &options, token_field);
             debug!("OpenAI request to model {} with {:?}", model, token_field);
 
-            let response = self
-                .client
-                .post(format!("{}/chat/completions", self.api_base))
-                .header("Authorization", format!("Bearer {}", self.api_key))
-                .header("Content-Type", "application/json")
-                .json(&request)
-                .send()
-                .await
-                .map_err(|e| ZeptoError::Provider(format!("OpenAI request failed: {}", e)))?;
+            let (response, rate_limit) = self.post(&request).await?;
 
             if response.status().is_success() {
                 let openai_response: OpenAIResponse = response.json().await.map_err(|e| {
@@ -656,10 +768,9 @@ impl LLMProvider for OpenAIProvider {
                 format!("OpenAI API error: {}", error_text)
             };
 
-            return Err(ZeptoError::from(parse_provider_error(
-                status.as_u16(),
-                &body,
-            )));
+            return Err(ZeptoError::from(
+                parse_provider_error(status.as_u16(), &body).with_rate_limit_info(rate_limit),
+            ));
         }
     }
 
@@ -686,15 +797,7 @@ impl LLMProvider for OpenAIProvider {
                 model, token_field
             );
 
-            let response = self
-                .client
-                .post(format!("{}/chat/completions", self.api_base))
-                .header("Authorization", format!("Bearer {}", self.api_key))
-                .header("Content-Type", "application/json")
-                .json(&request)
-                .send()
-                .await
-                .map_err(|e| ZeptoError::Provider(format!("OpenAI request failed: {}", e)))?;
+            let (response, rate_limit) = self.post(&request).await?;
 
             if response.status().is_success() {
                 let (tx, rx) = tokio::sync::mpsc::channel::<StreamEvent>(32);
@@ -760,7 +863,7 @@ impl LLMProvider for OpenAIProvider {
                             );
 
                             for delta in deltas {
-                                if tx.send(StreamEvent::Delta(delta)).await.is_err() {
+                                if tx.send(delta).await.is_err() {
                                     return;
                                 }
                             }
@@ -818,10 +921,9 @@ impl LLMProvider for OpenAIProvider {
                 format!("OpenAI API error: {}", error_text)
             };
 
-            return Err(ZeptoError::from(parse_provider_error(
-                status.as_u16(),
-                &body,
-            )));
+            return Err(ZeptoError::from(
+                parse_provider_error(status.as_u16(), &body).with_rate_limit_info(rate_limit),
+            ));
         }
     }
 
@@ -832,6 +934,14 @@ impl LLMProvider for OpenAIProvider {
     fn name(&self) -> &str {
         "openai"
     }
+
+    fn supports_forced_tool_choice(&self) -> bool {
+        true
+    }
+
+    fn supports_response_schema(&self) -> bool {
+        true
+    }
 }
 
 // ============================================================================
@@ -966,6 +1076,7 @@ mod tests {
         let response = OpenAIResponse {
             choices: vec![OpenAIChoice {
                 message: OpenAIResponseMessage {
+                    reasoning_content: None,
                     content: Some("Hello!".to_string()),
                     tool_calls: None,
                 },
@@ -973,6 +1084,7 @@ mod tests {
             usage: Some(OpenAIUsage {
                 prompt_tokens: 10,
                 completion_tokens: 5,
+                prompt_tokens_details: None,
             }),
         };
         let converted = convert_response(response);
@@ -987,11 +1099,25 @@ mod tests {
         assert_eq!(usage.total_tokens, 15);
     }
 
+    #[test]
+    fn test_usage_reports_cached_prompt_tokens() {
+        let usage: OpenAIUsage = serde_json::from_str(
+            r#"{"prompt_tokens":2000,"completion_tokens":50,"prompt_tokens_details":{"cached_tokens":1536}}"#,
+        )
+        .unwrap();
+        let usage = usage.to_usage();
+        assert_eq!(usage.prompt_tokens, 2000);
+        assert_eq!(usage.cache_read_tokens, 1536);
+        assert_eq!(usage.cache_creation_tokens, 0);
+        assert_eq!(usage.uncached_prompt_tokens(), 464);
+    }
+
     #[test]
     fn test_convert_response_with_tool_calls() {
         let response = OpenAIResponse {
             choices: vec![OpenAIChoice {
                 message: OpenAIResponseMessage {
+                    reasoning_content: None,
                     content: Some("".to_string()),
                     tool_calls: Some(vec![OpenAIToolCallResponse {
                         id: "call_123".to_string(),
@@ -1030,6 +1156,7 @@ mod tests {
         let response = OpenAIResponse {
             choices: vec![OpenAIChoice {
                 message: OpenAIResponseMessage {
+                    reasoning_content: None,
                     content: None,
                     tool_calls: Some(vec![OpenAIToolCallResponse {
                         id: "call_1".to_string(),
@@ -1067,6 +1194,9 @@ mod tests {
             stop: None,
             stream: None,
             response_format: None,
+            reasoning_effort: None,
+            tool_choice: None,
+            parallel_tool_calls: None,
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -1102,6 +1232,9 @@ mod tests {
             stop: None,
             stream: None,
             response_format: None,
+            reasoning_effort: None,
+            tool_choice: None,
+            parallel_tool_calls: None,
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -1144,6 +1277,108 @@ mod tests {
         assert_eq!(tool_calls[1].function.name, "tool_b");
     }
 
+    #[test]
+    fn test_build_request_sets_reasoning_effort() {
+        let messages = vec![Message::user("Hello")];
+        let options = ChatOptions::new()
+            .with_temperature(0.7)
+            .with_reasoning_effort(crate::providers::ReasoningEffort::High);
+
+        let request = build_request(
+            "o3-mini",
+            &messages,
+            &[],
+            &options,
+            MaxTokenField::MaxTokens,
+        );
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["reasoning_effort"], "high");
+        assert!(json.get("temperature").is_none());
+
+        let request = build_request("gpt-4o", &messages, &[], &options, MaxTokenField::MaxTokens);
+        let json = serde_json::to_value(&request).unwrap();
+        assert!(json.get("reasoning_effort").is_none());
+        assert!((json["temperature"].as_f64().unwrap() - 0.7).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_build_request_maps_tool_choice() {
+        let messages = vec![Message::user("Hello")];
+        let tools = vec![ToolDefinition::new(
+            "search",
+            "Search the web",
+            serde_json::json!({"type": "object"}),
+        )];
+        let options = ChatOptions::new()
+            .with_tool_choice(ToolChoice::Tool("search".to_string()))
+            .with_parallel_tool_calls(false);
+
+        let request = build_request(
+            "gpt-5.1",
+            &messages,
+            &tools,
+            &options,
+            MaxTokenField::MaxTokens,
+        );
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["tool_choice"]["type"], "function");
+        assert_eq!(json["tool_choice"]["function"]["name"], "search");
+        assert_eq!(json["parallel_tool_calls"], false);
+
+        let options = ChatOptions::new().with_tool_choice(ToolChoice::Required);
+        let request = build_request(
+            "gpt-5.1",
+            &messages,
+            &tools,
+            &options,
+            MaxTokenField::MaxTokens,
+        );
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["tool_choice"], "required");
+
+        // Without tools the controls are omitted.
+        let request = build_request(
+            "gpt-5.1",
+            &messages,
+            &[],
+            &options,
+            MaxTokenField::MaxTokens,
+        );
+        let json = serde_json::to_value(&request).unwrap();
+        assert!(json.get("tool_choice").is_none());
+        assert!(json.get("parallel_tool_calls").is_none());
+    }
+
+    #[test]
+    fn test_convert_response_reads_reasoning_content() {
+        let response: OpenAIResponse = serde_json::from_str(
+            r#"{"choices":[{"message":{"content":"42","reasoning_content":"6 times 7"}}]}"#,
+        )
+        .unwrap();
+        let converted = convert_response(response);
+        assert_eq!(converted.content, "42");
+        assert_eq!(
+            converted.thinking,
+            vec![ThinkingBlock::new("6 times 7", None)]
+        );
+    }
+
+    #[test]
+    fn test_apply_stream_chunk_emits_reasoning() {
+        let chunk: OpenAIStreamChunk = serde_json::from_str(
+            r#"{"choices":[{"delta":{"reasoning_content":"thinking","content":"answer"}}]}"#,
+        )
+        .unwrap();
+        let mut assembled = String::new();
+        let mut usage = None;
+        let events = apply_stream_chunk(chunk, &mut assembled, &mut Vec::new(), &mut usage);
+
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], StreamEvent::ReasoningDelta(t) if t == "thinking"));
+        assert!(matches!(&events[1], StreamEvent::Delta(t) if t == "answer"));
+        assert_eq!(assembled, "answer");
+    }
+
     #[test]
     fn test_build_request_with_max_tokens_field() {
         let messages = vec![Message::user("Hello")];
@@ -1207,6 +1442,7 @@ mod tests {
         let chunk = OpenAIStreamChunk {
             choices: vec![OpenAIStreamChoice {
                 delta: OpenAIStreamDelta {
+                    reasoning_content: None,
                     content: Some("Hello".to_string()),
                     tool_calls: None,
                 },
@@ -1214,6 +1450,7 @@ mod tests {
             usage: Some(OpenAIUsage {
                 prompt_tokens: 10,
                 completion_tokens: 5,
+                prompt_tokens_details: None,
             }),
         };
 
@@ -1223,7 +1460,8 @@ mod tests {
 
         let deltas = apply_stream_chunk(chunk, &mut assembled, &mut pending_tool_calls, &mut usage);
 
-        assert_eq!(deltas, vec!["Hello".to_string()]);
+        assert_eq!(deltas.len(), 1);
+        assert!(matches!(&deltas[0], StreamEvent::Delta(text) if text == "Hello"));
         assert_eq!(assembled, "Hello");
         let usage = usage.expect("usage should be set");
         assert_eq!(usage.prompt_tokens, 10);
@@ -1345,6 +1583,7 @@ mod tests {
         let first = OpenAIStreamChunk {
             choices: vec![OpenAIStreamChoice {
                 delta: OpenAIStreamDelta {
+                    reasoning_content: None,
                     content: None,
                     tool_calls: Some(vec![OpenAIStreamToolCallDelta {
                         index: 0,
@@ -1362,6 +1601,7 @@ mod tests {
         let second = OpenAIStreamChunk {
             choices: vec![OpenAIStreamChoice {
                 delta: OpenAIStreamDelta {
+                    reasoning_content: None,
                     content: None,
                     tool_calls: Some(vec![OpenAIStreamToolCallDelta {
                         index: 0,
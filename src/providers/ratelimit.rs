@@ -0,0 +1,610 @@
+//! Provider rate-limit headers and client-side request pacing.
+//!
+//! Providers report their limits on every response: Anthropic through
+//! `anthropic-ratelimit-*` headers, OpenAI (and compatible backends) through
+//! `x-ratelimit-*`, and both send `retry-after` on 429. [`RateLimitInfo`]
+//! parses those headers, and [`RateLimiter`] uses them to pace requests
+//! before the provider has to reject them.
+//!
+//! Limiters are shared per provider and API key through
+//! [`RateLimiter::shared`], so every session in a gateway draws from the same
+//! bucket.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use reqwest::header::HeaderMap;
+use sha2::{Digest, Sha256};
+use tokio::time::Instant;
+use tracing::debug;
+
+use crate::error::{ProviderError, RateLimitError, Result, ZeptoError};
+
+/// Rate-limit state reported by a provider in response headers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateLimitInfo {
+    /// How long the provider asked us to wait (`retry-after`)
+    pub retry_after: Option<Duration>,
+    /// Requests allowed per window
+    pub requests_limit: Option<u64>,
+    /// Requests left in the current window
+    pub requests_remaining: Option<u64>,
+    /// Time until the request window resets
+    pub requests_reset: Option<Duration>,
+    /// Tokens allowed per window
+    pub tokens_limit: Option<u64>,
+    /// Tokens left in the current window
+    pub tokens_remaining: Option<u64>,
+    /// Time until the token window resets
+    pub tokens_reset: Option<Duration>,
+}
+
+impl RateLimitInfo {
+    /// Parse rate-limit headers from a provider response.
+    ///
+    /// # Example
+    /// ```
+    /// use reqwest::header::{HeaderMap, HeaderValue};
+    /// use std::time::Duration;
+    /// use zeptoclaw::providers::RateLimitInfo;
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("retry-after", HeaderValue::from_static("12"));
+    /// headers.insert("x-ratelimit-remaining-requests", HeaderValue::from_static("0"));
+    /// headers.insert("x-ratelimit-reset-requests", HeaderValue::from_static("1m30s"));
+    ///
+    /// let info = RateLimitInfo::from_headers(&headers);
+    /// assert_eq!(info.retry_after, Some(Duration::from_secs(12)));
+    /// assert_eq!(info.requests_reset, Some(Duration::from_secs(90)));
+    /// ```
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let get = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        };
+        let first = |names: &[&str]| names.iter().find_map(|name| get(name));
+        let count = |names: &[&str]| first(names).and_then(|v| v.parse::<u64>().ok());
+        let reset = |names: &[&str]| first(names).and_then(parse_reset);
+
+        let retry_after = get("retry-after-ms")
+            .and_then(|v| v.parse::<f64>().ok())
+            .and_then(|ms| seconds(ms / 1000.0))
+            .or_else(|| get("retry-after").and_then(parse_retry_after));
+
+        Self {
+            retry_after,
+            requests_limit: count(&[
+                "anthropic-ratelimit-requests-limit",
+                "x-ratelimit-limit-requests",
+            ]),
+            requests_remaining: count(&[
+                "anthropic-ratelimit-requests-remaining",
+                "x-ratelimit-remaining-requests",
+            ]),
+            requests_reset: reset(&[
+                "anthropic-ratelimit-requests-reset",
+                "x-ratelimit-reset-requests",
+            ]),
+            tokens_limit: count(&[
+                "anthropic-ratelimit-tokens-limit",
+                "anthropic-ratelimit-input-tokens-limit",
+                "x-ratelimit-limit-tokens",
+            ]),
+            tokens_remaining: count(&[
+                "anthropic-ratelimit-tokens-remaining",
+                "anthropic-ratelimit-input-tokens-remaining",
+                "x-ratelimit-remaining-tokens",
+            ]),
+            tokens_reset: reset(&[
+                "anthropic-ratelimit-tokens-reset",
+                "anthropic-ratelimit-input-tokens-reset",
+                "x-ratelimit-reset-tokens",
+            ]),
+        }
+    }
+
+    /// Whether no rate-limit headers were present.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// How long to wait before the next request, if the provider said so.
+    ///
+    /// `retry-after` wins; otherwise the reset time of an exhausted request
+    /// or token window is used.
+    pub fn wait_hint(&self) -> Option<Duration> {
+        if self.retry_after.is_some() {
+            return self.retry_after;
+        }
+        let requests = self
+            .requests_reset
+            .filter(|_| self.requests_remaining == Some(0));
+        let tokens = self
+            .tokens_reset
+            .filter(|_| self.tokens_remaining == Some(0));
+        requests.max(tokens)
+    }
+}
+
+/// Parse a `retry-after` value: delay seconds or an HTTP date.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(secs) = value.parse::<f64>() {
+        return seconds(secs);
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some(until(at.with_timezone(&chrono::Utc)))
+}
+
+/// Parse a window reset value: an RFC 3339 timestamp (Anthropic), a
+/// duration such as `6m0s` or `20ms` (OpenAI), or plain seconds.
+fn parse_reset(value: &str) -> Option<Duration> {
+    if let Ok(at) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(until(at.with_timezone(&chrono::Utc)));
+    }
+    if let Ok(secs) = value.parse::<f64>() {
+        return seconds(secs);
+    }
+    parse_duration_spec(value)
+}
+
+/// Longest wait a provider header can ask for; larger values are clamped.
+const MAX_HEADER_WAIT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Convert header seconds to a duration, clamped to [`MAX_HEADER_WAIT`].
+///
+/// Negative and NaN values are rejected.
+fn seconds(secs: f64) -> Option<Duration> {
+    if secs.is_nan() || secs < 0.0 {
+        return None;
+    }
+    let duration = Duration::try_from_secs_f64(secs).unwrap_or(MAX_HEADER_WAIT);
+    Some(duration.min(MAX_HEADER_WAIT))
+}
+
+/// Time from now until `at`, zero if it has passed.
+fn until(at: chrono::DateTime<chrono::Utc>) -> Duration {
+    (at - chrono::Utc::now())
+        .to_std()
+        .unwrap_or_default()
+        .min(MAX_HEADER_WAIT)
+}
+
+/// Parse a compound duration such as `1h2m3.5s`, `6m0s` or `250ms`.
+fn parse_duration_spec(value: &str) -> Option<Duration> {
+    let mut total = 0.0f64;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let split = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..split].parse().ok()?;
+        rest = &rest[split..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let factor = match &rest[..unit_len] {
+            "h" => 3600.0,
+            "m" => 60.0,
+            "s" => 1.0,
+            "ms" => 0.001,
+            _ => return None,
+        };
+        total += number * factor;
+        rest = &rest[unit_len..];
+    }
+    seconds(total)
+}
+
+/// Longest wait [`RateLimiter::acquire`] sleeps through by default; matches
+/// the retry layer's default `max_retry_after_ms`.
+const DEFAULT_MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// Limiters shared across provider instances, keyed by provider and key.
+static SHARED_LIMITERS: Lazy<Mutex<HashMap<String, Arc<RateLimiter>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Token-bucket request limiter that adapts to provider rate-limit headers.
+///
+/// Without a configured rate the limiter learns the request limit from the
+/// provider's headers (both Anthropic and OpenAI report per-minute limits).
+/// Independently of the bucket, it holds all requests back while the
+/// provider reports an exhausted window or has asked us to retry later.
+/// Waits longer than the limiter's maximum fail instead of sleeping.
+#[derive(Debug)]
+pub struct RateLimiter {
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    /// Maximum burst size; zero while the rate is unknown
+    capacity: f64,
+    /// Available request tokens
+    tokens: f64,
+    /// Refill rate in requests per second; zero means unlimited
+    refill_per_sec: f64,
+    /// Whether the rate was configured (and should not be learned)
+    fixed: bool,
+    last_refill: Instant,
+    /// Hold all requests until this instant
+    blocked_until: Option<Instant>,
+    /// Longest wait `acquire` sleeps through
+    max_wait: Duration,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateLimiter {
+    /// Create a limiter that learns its rate from provider headers.
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(BucketState {
+                capacity: 0.0,
+                tokens: 0.0,
+                refill_per_sec: 0.0,
+                fixed: false,
+                last_refill: Instant::now(),
+                blocked_until: None,
+                max_wait: DEFAULT_MAX_WAIT,
+            }),
+        }
+    }
+
+    /// Get the limiter shared by every client of `provider` using `api_key`.
+    ///
+    /// The key is only stored as a hash.
+    pub fn shared(provider: &str, api_key: &str) -> Arc<Self> {
+        let digest = Sha256::digest(api_key.as_bytes());
+        let id = format!("{}:{}", provider, hex::encode(&digest[..8]));
+        let mut limiters = SHARED_LIMITERS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        Arc::clone(limiters.entry(id).or_default())
+    }
+
+    /// Fix the request rate instead of learning it from headers.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::providers::RateLimiter;
+    ///
+    /// let limiter = RateLimiter::new().with_requests_per_minute(60);
+    /// ```
+    pub fn with_requests_per_minute(self, rpm: u32) -> Self {
+        self.set_requests_per_minute(rpm);
+        self
+    }
+
+    /// Fix the request rate of an existing (possibly shared) limiter.
+    pub fn set_requests_per_minute(&self, rpm: u32) {
+        let mut state = self.lock();
+        state.set_rate(rpm as f64);
+        state.fixed = true;
+    }
+
+    /// When the provider's hold on requests ends, if one is in force.
+    ///
+    /// Set from `retry-after` and exhausted-window headers; pacing by the
+    /// token bucket does not count.
+    pub fn blocked_until(&self) -> Option<Instant> {
+        self.lock()
+            .blocked_until
+            .filter(|until| *until > Instant::now())
+    }
+
+    /// Set the longest wait [`acquire`](Self::acquire) sleeps through.
+    ///
+    /// Use the retry layer's `max_retry_after_ms` so a long provider hold
+    /// fails the request instead of parking every caller behind it.
+    pub fn set_max_wait(&self, max_wait: Duration) {
+        self.lock().max_wait = max_wait;
+    }
+
+    /// Wait until a request may be sent, then take a slot.
+    ///
+    /// Returns a rate-limit error carrying the remaining wait when it is
+    /// longer than the limiter's maximum wait.
+    pub async fn acquire(&self) -> Result<()> {
+        loop {
+            let wait = match self.try_acquire_at(Instant::now()) {
+                Ok(()) => return Ok(()),
+                Err(wait) => wait,
+            };
+            if wait > self.lock().max_wait {
+                let info = RateLimitInfo {
+                    retry_after: Some(wait),
+                    ..Default::default()
+                };
+                return Err(ZeptoError::ProviderTyped(ProviderError::RateLimit(
+                    RateLimitError::new("Provider rate limit: requests are on hold")
+                        .with_info(info),
+                )));
+            }
+            debug!(wait_ms = wait.as_millis() as u64, "Pacing provider request");
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Take a slot if one is available at `now`, or report how long to wait.
+    fn try_acquire_at(&self, now: Instant) -> std::result::Result<(), Duration> {
+        let mut state = self.lock();
+        if let Some(until) = state.blocked_until {
+            if until > now {
+                return Err(until - now);
+            }
+            state.blocked_until = None;
+        }
+        if state.refill_per_sec <= 0.0 {
+            return Ok(());
+        }
+
+        let elapsed = now
+            .saturating_duration_since(state.last_refill)
+            .as_secs_f64();
+        state.tokens = (state.tokens + elapsed * state.refill_per_sec).min(state.capacity);
+        state.last_refill = now;
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            Ok(())
+        } else {
+            let secs = (1.0 - state.tokens) / state.refill_per_sec;
+            Err(Duration::from_secs_f64(secs))
+        }
+    }
+
+    /// Update the limiter from a provider response's rate-limit headers.
+    pub fn observe(&self, info: &RateLimitInfo) {
+        self.observe_at(info, Instant::now());
+    }
+
+    fn observe_at(&self, info: &RateLimitInfo, now: Instant) {
+        let mut state = self.lock();
+
+        if !state.fixed {
+            if let Some(limit) = info.requests_limit.filter(|l| *l > 0) {
+                if (limit as f64 - state.capacity).abs() > f64::EPSILON {
+                    let first = state.capacity == 0.0;
+                    state.set_rate(limit as f64);
+                    if first {
+                        state.last_refill = now;
+                    }
+                }
+            }
+        }
+        if let Some(remaining) = info.requests_remaining {
+            if state.refill_per_sec > 0.0 {
+                state.tokens = state.tokens.min(remaining as f64);
+            }
+        }
+        if let Some(wait) = info.wait_hint() {
+            let until = now + wait;
+            if state.blocked_until.is_none_or(|current| current < until) {
+                debug!(
+                    wait_ms = wait.as_millis() as u64,
+                    "Provider rate limit reached"
+                );
+                state.blocked_until = Some(until);
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BucketState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl BucketState {
+    /// Set a per-minute rate, starting with a full bucket.
+    fn set_rate(&mut self, per_minute: f64) {
+        self.capacity = per_minute;
+        self.tokens = per_minute;
+        self.refill_per_sec = per_minute / 60.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_parse_anthropic_headers() {
+        let reset = (chrono::Utc::now() + chrono::Duration::seconds(30)).to_rfc3339();
+        let info = RateLimitInfo::from_headers(&headers(&[
+            ("anthropic-ratelimit-requests-limit", "50"),
+            ("anthropic-ratelimit-requests-remaining", "0"),
+            ("anthropic-ratelimit-requests-reset", &reset),
+            ("anthropic-ratelimit-input-tokens-remaining", "1200"),
+        ]));
+        assert_eq!(info.requests_limit, Some(50));
+        assert_eq!(info.requests_remaining, Some(0));
+        assert_eq!(info.tokens_remaining, Some(1200));
+        let wait = info.wait_hint().unwrap();
+        assert!(wait > Duration::from_secs(28) && wait <= Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_parse_openai_headers() {
+        let info = RateLimitInfo::from_headers(&headers(&[
+            ("x-ratelimit-limit-requests", "500"),
+            ("x-ratelimit-remaining-requests", "499"),
+            ("x-ratelimit-reset-requests", "120ms"),
+            ("x-ratelimit-remaining-tokens", "0"),
+            ("x-ratelimit-reset-tokens", "6m0s"),
+        ]));
+        assert_eq!(info.requests_limit, Some(500));
+        assert_eq!(info.requests_reset, Some(Duration::from_millis(120)));
+        // Only the exhausted token window counts
+        assert_eq!(info.wait_hint(), Some(Duration::from_secs(360)));
+    }
+
+    #[test]
+    fn test_retry_after_variants() {
+        let secs = RateLimitInfo::from_headers(&headers(&[("retry-after", "7")]));
+        assert_eq!(secs.retry_after, Some(Duration::from_secs(7)));
+
+        let ms = RateLimitInfo::from_headers(&headers(&[
+            ("retry-after-ms", "1500"),
+            ("retry-after", "2"),
+        ]));
+        assert_eq!(ms.retry_after, Some(Duration::from_millis(1500)));
+
+        let date = (chrono::Utc::now() + chrono::Duration::seconds(20)).to_rfc2822();
+        let dated = RateLimitInfo::from_headers(&headers(&[("retry-after", &date)]));
+        let wait = dated.retry_after.unwrap();
+        assert!(wait > Duration::from_secs(18) && wait <= Duration::from_secs(20));
+
+        let bogus = RateLimitInfo::from_headers(&headers(&[("retry-after", "soon")]));
+        assert!(bogus.is_empty());
+    }
+
+    #[test]
+    fn test_out_of_range_headers_are_clamped() {
+        let info = RateLimitInfo::from_headers(&headers(&[
+            ("retry-after", "1e300"),
+            ("x-ratelimit-reset-requests", "1e300"),
+            ("x-ratelimit-reset-tokens", "99999999999999999999999h"),
+        ]));
+        assert_eq!(info.retry_after, Some(MAX_HEADER_WAIT));
+        assert_eq!(info.requests_reset, Some(MAX_HEADER_WAIT));
+        assert_eq!(info.tokens_reset, Some(MAX_HEADER_WAIT));
+
+        let ms = RateLimitInfo::from_headers(&headers(&[("retry-after-ms", "1e308")]));
+        assert_eq!(ms.retry_after, Some(MAX_HEADER_WAIT));
+
+        let negative = RateLimitInfo::from_headers(&headers(&[("retry-after", "-5")]));
+        assert!(negative.is_empty());
+        let nan = RateLimitInfo::from_headers(&headers(&[("retry-after-ms", "NaN")]));
+        assert!(nan.is_empty());
+    }
+
+    #[test]
+    fn test_parse_duration_spec() {
+        assert_eq!(
+            parse_duration_spec("1h2m3.5s"),
+            Some(Duration::from_secs_f64(3723.5))
+        );
+        assert_eq!(parse_duration_spec("20ms"), Some(Duration::from_millis(20)));
+        assert_eq!(parse_duration_spec("3x"), None);
+    }
+
+    #[test]
+    fn test_unconfigured_limiter_does_not_pace() {
+        let limiter = RateLimiter::new();
+        let now = Instant::now();
+        for _ in 0..100 {
+            assert!(limiter.try_acquire_at(now).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_bucket_paces_after_burst() {
+        let limiter = RateLimiter::new().with_requests_per_minute(2);
+        let now = Instant::now();
+        assert!(limiter.try_acquire_at(now).is_ok());
+        assert!(limiter.try_acquire_at(now).is_ok());
+        let wait = limiter.try_acquire_at(now).unwrap_err();
+        assert_eq!(wait.as_secs(), 30);
+        assert!(limiter.try_acquire_at(now + wait).is_ok());
+    }
+
+    #[test]
+    fn test_observe_learns_limit_and_remaining() {
+        let limiter = RateLimiter::new();
+        let now = Instant::now();
+        let info = RateLimitInfo {
+            requests_limit: Some(60),
+            requests_remaining: Some(1),
+            ..Default::default()
+        };
+        limiter.observe_at(&info, now);
+        assert!(limiter.try_acquire_at(now).is_ok());
+        // One request per second at 60 rpm
+        let wait = limiter.try_acquire_at(now).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_observe_retry_after_blocks_everyone() {
+        let limiter = RateLimiter::new();
+        let now = Instant::now();
+        let info = RateLimitInfo {
+            retry_after: Some(Duration::from_secs(5)),
+            ..Default::default()
+        };
+        limiter.observe_at(&info, now);
+        assert_eq!(
+            limiter.try_acquire_at(now).unwrap_err(),
+            Duration::from_secs(5)
+        );
+        assert!(limiter.try_acquire_at(now + Duration::from_secs(5)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_acquire_fails_when_hold_exceeds_max_wait() {
+        let limiter = RateLimiter::new();
+        limiter.set_max_wait(Duration::from_secs(60));
+        let info = RateLimitInfo {
+            retry_after: Some(Duration::from_secs(3600)),
+            ..Default::default()
+        };
+        limiter.observe(&info);
+
+        let err = limiter.acquire().await.unwrap_err();
+        let ZeptoError::ProviderTyped(ProviderError::RateLimit(err)) = err else {
+            panic!("expected a rate-limit error, got {err:?}");
+        };
+        let wait = err.retry_after().unwrap();
+        assert!(wait > Duration::from_secs(3590) && wait <= Duration::from_secs(3600));
+
+        // Short holds are still waited out
+        let limiter = RateLimiter::new();
+        limiter.observe(&RateLimitInfo {
+            retry_after: Some(Duration::from_millis(20)),
+            ..Default::default()
+        });
+        limiter.acquire().await.unwrap();
+    }
+
+    #[test]
+    fn test_configured_rate_is_not_relearned() {
+        let limiter = RateLimiter::new().with_requests_per_minute(1);
+        let now = Instant::now();
+        let info = RateLimitInfo {
+            requests_limit: Some(1000),
+            ..Default::default()
+        };
+        limiter.observe_at(&info, now);
+        assert!(limiter.try_acquire_at(now).is_ok());
+        assert!(limiter.try_acquire_at(now).is_err());
+    }
+
+    #[test]
+    fn test_shared_limiter_per_provider_and_key() {
+        let a = RateLimiter::shared("test-shared", "key-1");
+        let b = RateLimiter::shared("test-shared", "key-1");
+        let c = RateLimiter::shared("test-shared", "key-2");
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+    }
+}
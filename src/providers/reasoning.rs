@@ -0,0 +1,201 @@
+//! Reasoning (extended thinking) options and model capability detection.
+//!
+//! Reasoning models expose their controls differently: Anthropic models take
+//! a thinking token budget, while OpenAI o-series and GPT-5 models take a
+//! coarse effort level. [`ReasoningEffort`] is the shared knob, and
+//! [`reasoning_support`] tells a provider which form a model accepts so
+//! options are silently dropped for models that would reject them.
+
+use serde::{Deserialize, Serialize};
+
+/// Minimum thinking budget accepted by Anthropic.
+pub const MIN_THINKING_BUDGET: u32 = 1024;
+
+/// Coarse reasoning effort level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    /// Fast, shallow reasoning
+    Low,
+    /// Balanced reasoning
+    Medium,
+    /// Deep reasoning
+    High,
+}
+
+impl ReasoningEffort {
+    /// The value sent as OpenAI's `reasoning_effort`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReasoningEffort::Low => "low",
+            ReasoningEffort::Medium => "medium",
+            ReasoningEffort::High => "high",
+        }
+    }
+
+    /// Thinking budget used for Anthropic models when only an effort is set.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::providers::ReasoningEffort;
+    ///
+    /// assert_eq!(ReasoningEffort::Low.thinking_budget(), 2048);
+    /// ```
+    pub fn thinking_budget(&self) -> u32 {
+        match self {
+            ReasoningEffort::Low => 2_048,
+            ReasoningEffort::Medium => 8_192,
+            ReasoningEffort::High => 24_576,
+        }
+    }
+}
+
+impl std::str::FromStr for ReasoningEffort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "low" => Ok(ReasoningEffort::Low),
+            "medium" => Ok(ReasoningEffort::Medium),
+            "high" => Ok(ReasoningEffort::High),
+            other => Err(format!(
+                "unknown reasoning effort '{}': expected low, medium or high",
+                other
+            )),
+        }
+    }
+}
+
+/// How a model accepts reasoning controls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReasoningSupport {
+    /// The model has no reasoning controls
+    None,
+    /// Anthropic extended thinking with a token budget
+    ThinkingBudget,
+    /// OpenAI-style `reasoning_effort`
+    Effort,
+}
+
+/// Detect which reasoning controls a model accepts.
+///
+/// # Example
+/// ```
+/// use zeptoclaw::providers::{reasoning_support, ReasoningSupport};
+///
+/// assert_eq!(reasoning_support("claude-sonnet-4-5-20250929"), ReasoningSupport::ThinkingBudget);
+/// assert_eq!(reasoning_support("o3-mini"), ReasoningSupport::Effort);
+/// assert_eq!(reasoning_support("gpt-4o-mini"), ReasoningSupport::None);
+/// ```
+pub fn reasoning_support(model: &str) -> ReasoningSupport {
+    // Strip routing prefixes such as "anthropic/" or "openai/".
+    let model = model
+        .rsplit('/')
+        .next()
+        .unwrap_or(model)
+        .to_ascii_lowercase();
+
+    if model.starts_with("claude-") {
+        let thinking = model.contains("3-7-sonnet")
+            || ["sonnet-4", "opus-4", "haiku-4"]
+                .iter()
+                .any(|family| model.contains(family));
+        return if thinking {
+            ReasoningSupport::ThinkingBudget
+        } else {
+            ReasoningSupport::None
+        };
+    }
+
+    let effort = ["o1", "o3", "o4", "gpt-5"].iter().any(|prefix| {
+        model == *prefix
+            || model
+                .strip_prefix(prefix)
+                .is_some_and(|rest| rest.starts_with(['-', '.']))
+    });
+    if effort && !model.starts_with("o1-mini") && !model.contains("-chat") {
+        ReasoningSupport::Effort
+    } else {
+        ReasoningSupport::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claude_thinking_models() {
+        for model in [
+            "claude-sonnet-4-5-20250929",
+            "claude-opus-4-6",
+            "claude-3-7-sonnet-20250219",
+            "claude-haiku-4-5",
+            "anthropic/claude-sonnet-4",
+        ] {
+            assert_eq!(
+                reasoning_support(model),
+                ReasoningSupport::ThinkingBudget,
+                "{model}"
+            );
+        }
+        assert_eq!(
+            reasoning_support("claude-3-5-sonnet-20241022"),
+            ReasoningSupport::None
+        );
+        assert_eq!(
+            reasoning_support("claude-3-haiku-20240307"),
+            ReasoningSupport::None
+        );
+    }
+
+    #[test]
+    fn test_openai_effort_models() {
+        for model in [
+            "o1",
+            "o3",
+            "o3-mini",
+            "o4-mini",
+            "gpt-5",
+            "gpt-5.1",
+            "openai/o3",
+        ] {
+            assert_eq!(
+                reasoning_support(model),
+                ReasoningSupport::Effort,
+                "{model}"
+            );
+        }
+        for model in [
+            "gpt-4o",
+            "gpt-4o-mini",
+            "o1-mini",
+            "gpt-5-chat-latest",
+            "omni",
+        ] {
+            assert_eq!(reasoning_support(model), ReasoningSupport::None, "{model}");
+        }
+    }
+
+    #[test]
+    fn test_effort_parse_and_serde() {
+        assert_eq!(
+            "HIGH".parse::<ReasoningEffort>().unwrap(),
+            ReasoningEffort::High
+        );
+        assert!("max".parse::<ReasoningEffort>().is_err());
+        let json = serde_json::to_string(&ReasoningEffort::Medium).unwrap();
+        assert_eq!(json, "\"medium\"");
+    }
+
+    #[test]
+    fn test_thinking_budgets_meet_minimum() {
+        for effort in [
+            ReasoningEffort::Low,
+            ReasoningEffort::Medium,
+            ReasoningEffort::High,
+        ] {
+            assert!(effort.thinking_budget() >= MIN_THINKING_BUDGET);
+        }
+    }
+}
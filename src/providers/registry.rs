@@ -17,7 +17,7 @@ pub struct ProviderSpec {
     pub runtime_supported: bool,
     /// Default API base URL (None = native OpenAI endpoint).
     pub default_base_url: Option<&'static str>,
-    /// The underlying backend ("anthropic" or "openai") for routing.
+    /// The underlying backend ("anthropic", "openai" or "gemini") for routing.
     pub backend: &'static str,
 }
 
@@ -30,10 +30,12 @@ pub struct RuntimeProviderSelection {
     pub api_key: String,
     /// Optional provider base URL.
     pub api_base: Option<String>,
-    /// The underlying backend type ("anthropic" or "openai").
+    /// The underlying backend type ("anthropic", "openai" or "gemini").
     pub backend: &'static str,
     /// Resolved credential (OAuth token or API key).
     pub credential: ResolvedCredential,
+    /// Further API keys pooled with `api_key` (empty unless using API key auth).
+    pub extra_api_keys: Vec<String>,
 }
 
 /// Provider registry in priority order.
@@ -121,9 +123,7 @@ pub fn provider_config_by_name<'a>(config: &'a Config, name: &str) -> Option<&'a
 }
 
 fn configured_api_key(provider: Option<&ProviderConfig>) -> Option<&str> {
-    provider
-        .and_then(|p| p.api_key.as_deref())
-        .and_then(|k| if k.is_empty() { None } else { Some(k) })
+    provider.and_then(|p| p.pooled_api_keys().first().copied())
 }
 
 /// Returns all configured provider ids in registry order.
@@ -194,20 +194,68 @@ pub fn resolve_runtime_providers(config: &Config) -> Vec<RuntimeProviderSelectio
                 Some(base)
             }
         });
-        let api_base = user_base.or_else(|| spec.default_base_url.map(String::from));
+        let backend = resolve_backend(spec, provider);
+        // The registry default URL is specific to the registry backend.
+        let api_base = user_base.or_else(|| {
+            (backend == spec.backend)
+                .then_some(spec.default_base_url)
+                .flatten()
+                .map(String::from)
+        });
+
+        // Key pooling only applies to API key auth; OAuth has a single token.
+        let extra_api_keys = match (&credential, provider) {
+            (ResolvedCredential::ApiKey(_), Some(p)) => p
+                .pooled_api_keys()
+                .into_iter()
+                .skip(1)
+                .map(String::from)
+                .collect(),
+            _ => Vec::new(),
+        };
 
         resolved.push(RuntimeProviderSelection {
             name: spec.name,
             api_key: api_key_str,
             api_base,
-            backend: spec.backend,
+            backend,
             credential,
+            extra_api_keys,
         });
     }
 
     resolved
 }
 
+/// Backends a provider's `backend` setting may select.
+const SUPPORTED_BACKENDS: &[&str] = &["anthropic", "openai", "gemini"];
+
+/// The backend for a provider: its configured override when recognized,
+/// otherwise the registry default.
+fn resolve_backend(spec: &ProviderSpec, provider: Option<&ProviderConfig>) -> &'static str {
+    let Some(requested) = provider
+        .and_then(|p| p.backend.as_deref())
+        .map(str::trim)
+        .filter(|b| !b.is_empty())
+    else {
+        return spec.backend;
+    };
+    match SUPPORTED_BACKENDS
+        .iter()
+        .find(|b| b.eq_ignore_ascii_case(requested))
+    {
+        Some(backend) => backend,
+        None => {
+            tracing::warn!(
+                provider = spec.name,
+                backend = requested,
+                "Unknown provider backend; using the default"
+            );
+            spec.backend
+        }
+    }
+}
+
 /// Resolve a credential for a single provider.
 ///
 /// Returns `Some((credential, api_key_string))` or `None` if no credential is available.
@@ -317,6 +365,39 @@ mod tests {
         assert_eq!(selected.api_base, None);
     }
 
+    #[test]
+    fn test_resolve_runtime_provider_pools_extra_api_keys() {
+        let mut config = Config::default();
+        config.providers.anthropic = Some(ProviderConfig {
+            api_key: Some("sk-ant-1".to_string()),
+            api_keys: vec![
+                "sk-ant-2".to_string(),
+                "".to_string(),
+                "sk-ant-1".to_string(),
+                "sk-ant-3".to_string(),
+            ],
+            ..Default::default()
+        });
+
+        let selected = resolve_runtime_provider(&config).expect("provider should resolve");
+        assert_eq!(selected.api_key, "sk-ant-1");
+        assert_eq!(selected.extra_api_keys, vec!["sk-ant-2", "sk-ant-3"]);
+    }
+
+    #[test]
+    fn test_api_keys_alone_configure_provider() {
+        let mut config = Config::default();
+        config.providers.openai = Some(ProviderConfig {
+            api_keys: vec!["sk-a".to_string(), "sk-b".to_string()],
+            ..Default::default()
+        });
+
+        assert_eq!(configured_provider_names(&config), vec!["openai"]);
+        let selected = resolve_runtime_provider(&config).expect("provider should resolve");
+        assert_eq!(selected.api_key, "sk-a");
+        assert_eq!(selected.extra_api_keys, vec!["sk-b"]);
+    }
+
     #[test]
     fn test_resolve_runtime_provider_openai_base_url() {
         let mut config = Config::default();
@@ -416,6 +497,37 @@ mod tests {
             .contains("generativelanguage"));
     }
 
+    #[test]
+    fn test_gemini_native_backend_drops_openai_shim_url() {
+        let mut config = Config::default();
+        config.providers.gemini = Some(ProviderConfig {
+            api_key: Some("AIza-test".to_string()),
+            backend: Some("Gemini".to_string()),
+            ..Default::default()
+        });
+
+        let selected = resolve_runtime_provider(&config).expect("provider should resolve");
+        assert_eq!(selected.backend, "gemini");
+        assert_eq!(selected.api_base, None);
+    }
+
+    #[test]
+    fn test_unknown_backend_falls_back_to_registry_default() {
+        let mut config = Config::default();
+        config.providers.groq = Some(ProviderConfig {
+            api_key: Some("gsk-test".to_string()),
+            backend: Some("bogus".to_string()),
+            ..Default::default()
+        });
+
+        let selected = resolve_runtime_provider(&config).expect("provider should resolve");
+        assert_eq!(selected.backend, "openai");
+        assert_eq!(
+            selected.api_base.as_deref(),
+            Some("https://api.groq.com/openai/v1")
+        );
+    }
+
     #[test]
     fn test_user_base_url_overrides_default() {
         let mut config = Config::default();
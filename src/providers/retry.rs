@@ -2,6 +2,8 @@
 //!
 //! Wraps any [`LLMProvider`] to transparently retry transient errors such as
 //! HTTP 429 (rate limit), 5xx (server errors), and provider overload conditions.
+//! When a rate-limit error says how long to wait (`retry-after` or an
+//! exhausted window's reset time), that wait is used instead of backoff.
 //!
 //! # Example
 //!
@@ -17,6 +19,8 @@
 //! // Use `provider` as any other LLMProvider — retries happen automatically.
 //! ```
 
+use std::time::Duration;
+
 use async_trait::async_trait;
 use tracing::warn;
 
@@ -60,6 +64,8 @@ pub struct RetryProvider {
     base_delay_ms: u64,
     /// Maximum delay cap in milliseconds. Default: 30000 (30 seconds).
     max_delay_ms: u64,
+    /// Longest provider-requested wait to honor before giving up. Default: 60000.
+    max_retry_after_ms: u64,
 }
 
 impl std::fmt::Debug for RetryProvider {
@@ -69,6 +75,7 @@ impl std::fmt::Debug for RetryProvider {
             .field("max_retries", &self.max_retries)
             .field("base_delay_ms", &self.base_delay_ms)
             .field("max_delay_ms", &self.max_delay_ms)
+            .field("max_retry_after_ms", &self.max_retry_after_ms)
             .finish()
     }
 }
@@ -80,6 +87,7 @@ impl RetryProvider {
     /// - `max_retries`: 3
     /// - `base_delay_ms`: 1000 (1 second)
     /// - `max_delay_ms`: 30000 (30 seconds)
+    /// - `max_retry_after_ms`: 60000 (60 seconds)
     ///
     /// # Arguments
     /// * `inner` - The provider to wrap with retry logic
@@ -89,6 +97,7 @@ impl RetryProvider {
             max_retries: 3,
             base_delay_ms: 1000,
             max_delay_ms: 30_000,
+            max_retry_after_ms: 60_000,
         }
     }
 
@@ -123,6 +132,55 @@ impl RetryProvider {
         self.max_delay_ms = max_delay_ms;
         self
     }
+
+    /// Set the longest provider-requested wait to honor.
+    ///
+    /// Rate-limit errors that ask for a longer wait are returned immediately
+    /// instead of blocking the caller.
+    ///
+    /// # Arguments
+    /// * `max_retry_after_ms` - Maximum honored wait in milliseconds
+    pub fn with_max_retry_after_ms(mut self, max_retry_after_ms: u64) -> Self {
+        self.max_retry_after_ms = max_retry_after_ms;
+        self
+    }
+
+    /// Whether `err` should be retried: it must be transient, and any wait
+    /// the provider asked for must fit within `max_retry_after_ms`.
+    fn should_retry(&self, err: &ZeptoError) -> bool {
+        if !is_retryable(err) {
+            return false;
+        }
+        match retry_after(err) {
+            Some(wait) if wait > Duration::from_millis(self.max_retry_after_ms) => {
+                warn!(
+                    provider = self.inner.name(),
+                    retry_after_ms = wait.as_millis() as u64,
+                    max_retry_after_ms = self.max_retry_after_ms,
+                    "Provider asked for a longer wait than allowed; not retrying"
+                );
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Sleep before the next attempt: exactly as long as the provider asked,
+    /// or exponential backoff with jitter when it did not say.
+    async fn wait_before_retry(&self, attempt: u32, last_err: Option<&ZeptoError>) {
+        match last_err.and_then(retry_after) {
+            Some(wait) => tokio::time::sleep(wait).await,
+            None => delay_with_jitter(attempt, self.base_delay_ms, self.max_delay_ms).await,
+        }
+    }
+}
+
+/// The wait a provider requested in a rate-limit error, if any.
+pub fn retry_after(err: &ZeptoError) -> Option<Duration> {
+    match err {
+        ZeptoError::ProviderTyped(pe) => pe.retry_after(),
+        _ => None,
+    }
 }
 
 /// Check whether a [`ZeptoError`] represents a transient failure that should be retried.
@@ -214,6 +272,14 @@ impl LLMProvider for RetryProvider {
         self.inner.default_model()
     }
 
+    fn supports_forced_tool_choice(&self) -> bool {
+        self.inner.supports_forced_tool_choice()
+    }
+
+    fn supports_response_schema(&self) -> bool {
+        self.inner.supports_response_schema()
+    }
+
     async fn chat(
         &self,
         messages: Vec<Message>,
@@ -235,7 +301,7 @@ impl LLMProvider for RetryProvider {
                         "Retrying chat request after transient error"
                     );
                 }
-                delay_with_jitter(attempt - 1, self.base_delay_ms, self.max_delay_ms).await;
+                self.wait_before_retry(attempt - 1, last_err.as_ref()).await;
             }
 
             match self
@@ -245,7 +311,7 @@ impl LLMProvider for RetryProvider {
             {
                 Ok(response) => return Ok(response),
                 Err(err) => {
-                    if !is_retryable(&err) {
+                    if !self.should_retry(&err) {
                         return Err(err);
                     }
                     last_err = Some(err);
@@ -264,7 +330,8 @@ impl LLMProvider for RetryProvider {
                     "Retrying chat request after transient error"
                 );
             }
-            delay_with_jitter(self.max_retries - 1, self.base_delay_ms, self.max_delay_ms).await;
+            self.wait_before_retry(self.max_retries - 1, last_err.as_ref())
+                .await;
         }
         self.inner.chat(messages, tools, model, options).await
     }
@@ -290,7 +357,7 @@ impl LLMProvider for RetryProvider {
                         "Retrying chat_stream request after transient error"
                     );
                 }
-                delay_with_jitter(attempt - 1, self.base_delay_ms, self.max_delay_ms).await;
+                self.wait_before_retry(attempt - 1, last_err.as_ref()).await;
             }
 
             match self
@@ -300,7 +367,7 @@ impl LLMProvider for RetryProvider {
             {
                 Ok(receiver) => return Ok(receiver),
                 Err(err) => {
-                    if !is_retryable(&err) {
+                    if !self.should_retry(&err) {
                         return Err(err);
                     }
                     last_err = Some(err);
@@ -319,7 +386,8 @@ impl LLMProvider for RetryProvider {
                     "Retrying chat_stream request after transient error"
                 );
             }
-            delay_with_jitter(self.max_retries - 1, self.base_delay_ms, self.max_delay_ms).await;
+            self.wait_before_retry(self.max_retries - 1, last_err.as_ref())
+                .await;
         }
         self.inner
             .chat_stream(messages, tools, model, options)
@@ -374,6 +442,7 @@ mod tests {
         assert_eq!(provider.max_retries, 3);
         assert_eq!(provider.base_delay_ms, 1000);
         assert_eq!(provider.max_delay_ms, 30_000);
+        assert_eq!(provider.max_retry_after_ms, 60_000);
     }
 
     #[test]
@@ -799,4 +868,87 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap().content, "recovered");
     }
+
+    /// A mock provider that returns rate-limit errors carrying a retry-after hint.
+    struct RetryAfterProvider {
+        calls: std::sync::atomic::AtomicU32,
+        failures: u32,
+        retry_after: Duration,
+    }
+
+    #[async_trait]
+    impl LLMProvider for RetryAfterProvider {
+        fn name(&self) -> &str {
+            "retry-after"
+        }
+
+        fn default_model(&self) -> &str {
+            "test-model"
+        }
+
+        async fn chat(
+            &self,
+            _messages: Vec<Message>,
+            _tools: Vec<ToolDefinition>,
+            _model: Option<&str>,
+            _options: ChatOptions,
+        ) -> Result<LLMResponse> {
+            use crate::error::{ProviderError, RateLimitError};
+            use crate::providers::RateLimitInfo;
+            let count = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if count < self.failures {
+                let info = RateLimitInfo {
+                    retry_after: Some(self.retry_after),
+                    ..Default::default()
+                };
+                Err(
+                    ProviderError::RateLimit(RateLimitError::new("slow down").with_info(info))
+                        .into(),
+                )
+            } else {
+                Ok(LLMResponse::text("recovered"))
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_provider_honors_retry_after() {
+        let inner = RetryAfterProvider {
+            calls: std::sync::atomic::AtomicU32::new(0),
+            failures: 1,
+            retry_after: Duration::from_millis(50),
+        };
+        // Backoff would take far longer than the requested wait
+        let provider = RetryProvider::new(Box::new(inner))
+            .with_base_delay_ms(10_000)
+            .with_max_delay_ms(10_000);
+
+        let start = std::time::Instant::now();
+        let result = provider
+            .chat(vec![], vec![], None, ChatOptions::default())
+            .await;
+
+        assert_eq!(result.unwrap().content, "recovered");
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(50));
+        assert!(elapsed < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_retry_provider_gives_up_when_retry_after_too_long() {
+        let inner = RetryAfterProvider {
+            calls: std::sync::atomic::AtomicU32::new(0),
+            failures: 10,
+            retry_after: Duration::from_secs(3600),
+        };
+        let provider = RetryProvider::new(Box::new(inner)).with_max_retry_after_ms(1_000);
+
+        let err = provider
+            .chat(vec![], vec![], None, ChatOptions::default())
+            .await
+            .unwrap_err();
+
+        assert_eq!(retry_after(&err), Some(Duration::from_secs(3600)));
+        assert!(err.to_string().contains("retry after 3600.0s"));
+    }
 }
@@ -54,7 +54,7 @@ pub enum RotationStrategy {
 // ============================================================================
 
 /// Health state for a single provider in the rotation.
-struct ProviderHealth {
+pub(crate) struct ProviderHealth {
     /// Consecutive failure count.
     failure_count: AtomicU32,
     /// Timestamp (epoch secs) of last failure.
@@ -67,7 +67,7 @@ struct ProviderHealth {
 
 impl ProviderHealth {
     /// Create a new health tracker.
-    fn new(failure_threshold: u32, cooldown_secs: u64) -> Self {
+    pub(crate) fn new(failure_threshold: u32, cooldown_secs: u64) -> Self {
         Self {
             failure_count: AtomicU32::new(0),
             last_failure_epoch: AtomicU64::new(0),
@@ -78,7 +78,7 @@ impl ProviderHealth {
 
     /// Returns `true` if this provider is considered healthy (below failure threshold
     /// or cooldown has elapsed and it should be probed).
-    fn is_healthy(&self) -> bool {
+    pub(crate) fn is_healthy(&self) -> bool {
         let failures = self.failure_count.load(Ordering::Relaxed);
         if failures < self.failure_threshold {
             return true;
@@ -94,7 +94,7 @@ impl ProviderHealth {
     }
 
     /// Record a successful request -- resets the failure counter.
-    fn record_success(&self) {
+    pub(crate) fn record_success(&self) {
         let prev = self.failure_count.swap(0, Ordering::Relaxed);
         if prev >= self.failure_threshold {
             info!(
@@ -106,7 +106,7 @@ impl ProviderHealth {
 
     /// Record a failed request -- increments the failure counter and updates
     /// the last-failure timestamp.
-    fn record_failure(&self) {
+    pub(crate) fn record_failure(&self) {
         let prev = self.failure_count.fetch_add(1, Ordering::Relaxed);
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -121,6 +121,16 @@ impl ProviderHealth {
             );
         }
     }
+
+    /// Current consecutive failure count.
+    pub(crate) fn failures(&self) -> u32 {
+        self.failure_count.load(Ordering::Relaxed)
+    }
+
+    /// Timestamp (epoch secs) of the last failure, or 0 if it never failed.
+    pub(crate) fn last_failure_epoch(&self) -> u64 {
+        self.last_failure_epoch.load(Ordering::Relaxed)
+    }
 }
 
 impl fmt::Debug for ProviderHealth {
@@ -267,6 +277,18 @@ impl LLMProvider for RotationProvider {
         self.providers[0].0.default_model()
     }
 
+    fn supports_forced_tool_choice(&self) -> bool {
+        self.providers
+            .iter()
+            .all(|(p, _)| p.supports_forced_tool_choice())
+    }
+
+    fn supports_response_schema(&self) -> bool {
+        self.providers
+            .iter()
+            .all(|(p, _)| p.supports_response_schema())
+    }
+
     async fn chat(
         &self,
         messages: Vec<Message>,
@@ -0,0 +1,665 @@
+//! Model router - decorator that picks a model per request by task complexity.
+//!
+//! Wraps any [`LLMProvider`] and replaces the requested model with a tiered
+//! one. Each turn is classified as simple, standard or complex by the rules in
+//! [`RouterConfig`]: keyword rules, the number of tool iterations since the
+//! user's last message, attachments, and message length. When no rule
+//! decides, an optional cheap classifier call breaks the tie. Standard turns
+//! keep the requested model.
+//!
+//! The decision is made once per user turn and reused for that turn's tool
+//! iterations, so the classifier is called at most once per turn and the
+//! model only changes mid-turn when the tool-iteration rule escalates it.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use zeptoclaw::config::RouterConfig;
+//! use zeptoclaw::providers::router::RouterProvider;
+//! use zeptoclaw::providers::claude::ClaudeProvider;
+//!
+//! let config = RouterConfig {
+//!     enabled: true,
+//!     simple_model: Some("claude-haiku-4-5".into()),
+//!     complex_model: Some("claude-opus-4-6".into()),
+//!     ..Default::default()
+//! };
+//! let provider = RouterProvider::new(Box::new(ClaudeProvider::new("api-key")), config);
+//! // "thanks!" now goes to Haiku; long research prompts go to Opus.
+//! ```
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::config::RouterConfig;
+use crate::error::Result;
+use crate::session::{Message, Role};
+use crate::utils::metrics::MetricsCollector;
+
+use super::{ChatOptions, LLMProvider, LLMResponse, StreamEvent, ToolDefinition};
+
+/// Longest slice of the user's message sent to the classifier.
+const CLASSIFIER_MAX_CHARS: usize = 4000;
+
+const CLASSIFIER_PROMPT: &str = "Classify how much reasoning the user's request needs. \
+Answer with exactly one word: simple (greetings, thanks, short factual questions), \
+standard (ordinary tasks), or complex (multi-step research, planning, large code changes).";
+
+/// Task complexity tier, ordered from cheapest to most capable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplexityTier {
+    /// Chit-chat and trivial questions.
+    Simple,
+    /// Ordinary requests; uses the requested model.
+    Standard,
+    /// Multi-step or long-form work.
+    Complex,
+}
+
+impl ComplexityTier {
+    /// Lowercase tier name, as used in config and metrics.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComplexityTier::Simple => "simple",
+            ComplexityTier::Standard => "standard",
+            ComplexityTier::Complex => "complex",
+        }
+    }
+}
+
+impl std::str::FromStr for ComplexityTier {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "simple" => Ok(ComplexityTier::Simple),
+            "standard" => Ok(ComplexityTier::Standard),
+            "complex" => Ok(ComplexityTier::Complex),
+            other => Err(format!(
+                "unknown complexity tier '{}': expected simple, standard or complex",
+                other
+            )),
+        }
+    }
+}
+
+/// Route messages containing any of `keywords` (case-insensitive) to `tier`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeywordRule {
+    /// Substrings to look for in the user's message.
+    pub keywords: Vec<String>,
+    /// Tier to use when one matches.
+    pub tier: ComplexityTier,
+}
+
+/// Most turns whose routing decision is remembered at once.
+const MAX_CACHED_TURNS: usize = 256;
+
+/// The outcome of classifying one request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteDecision {
+    /// Chosen tier.
+    pub tier: ComplexityTier,
+    /// Which rule decided (e.g. "keyword", "short_message", "classifier").
+    pub reason: &'static str,
+}
+
+impl RouteDecision {
+    fn new(tier: ComplexityTier, reason: &'static str) -> Self {
+        Self { tier, reason }
+    }
+}
+
+/// A decorator provider that routes each request to a tiered model.
+pub struct RouterProvider {
+    /// The wrapped provider that performs actual LLM requests.
+    inner: Box<dyn LLMProvider>,
+    config: RouterConfig,
+    /// Where routing decisions are counted, if anywhere.
+    metrics: Option<Arc<MetricsCollector>>,
+    /// Decisions for recent user turns, keyed by [`turn_key`].
+    turns: Mutex<HashMap<u64, RouteDecision>>,
+}
+
+impl std::fmt::Debug for RouterProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RouterProvider")
+            .field("inner", &self.inner.name())
+            .field("simple_model", &self.config.simple_model)
+            .field("complex_model", &self.config.complex_model)
+            .field("classifier_model", &self.config.classifier_model)
+            .finish()
+    }
+}
+
+impl RouterProvider {
+    /// Wrap `inner` with routing rules from `config`.
+    pub fn new(inner: Box<dyn LLMProvider>, config: RouterConfig) -> Self {
+        Self {
+            inner,
+            config,
+            metrics: None,
+            turns: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count routing decisions (and classifier token usage) in `metrics`.
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Classify a request using the configured rules only.
+    ///
+    /// Returns `None` when no rule applies; [`RouterProvider::chat`] then asks
+    /// the classifier model, if one is configured, or uses the standard tier.
+    pub fn classify(&self, messages: &[Message], options: &ChatOptions) -> Option<RouteDecision> {
+        let last_user = messages.iter().rposition(|m| m.role == Role::User);
+        let content = last_user
+            .map(|i| messages[i].content.as_str())
+            .unwrap_or_default();
+
+        let lower = content.to_lowercase();
+        for rule in &self.config.keyword_rules {
+            let matched = rule
+                .keywords
+                .iter()
+                .map(|k| k.trim().to_lowercase())
+                .any(|k| !k.is_empty() && lower.contains(&k));
+            if matched {
+                return Some(RouteDecision::new(rule.tier, "keyword"));
+            }
+        }
+
+        let tool_iterations = tool_iterations(messages);
+        if let Some(decision) = self.escalation(tool_iterations) {
+            return Some(decision);
+        }
+
+        let chars = content.chars().count();
+        let by_length = if chars >= self.config.complex_min_chars {
+            Some(RouteDecision::new(ComplexityTier::Complex, "long_message"))
+        } else if chars <= self.config.simple_max_chars && tool_iterations == 0 {
+            // A short message that already led to tool calls is not trivial.
+            Some(RouteDecision::new(ComplexityTier::Simple, "short_message"))
+        } else {
+            None
+        };
+
+        if options.has_attachments {
+            let floor = self.config.attachment_tier;
+            if by_length.is_none_or(|d| d.tier < floor) {
+                return Some(RouteDecision::new(floor, "attachment"));
+            }
+        }
+
+        by_length
+    }
+
+    /// The complex tier once a turn reaches `complex_tool_iterations`.
+    fn escalation(&self, tool_iterations: usize) -> Option<RouteDecision> {
+        let threshold = self.config.complex_tool_iterations;
+        (threshold > 0 && tool_iterations >= threshold)
+            .then(|| RouteDecision::new(ComplexityTier::Complex, "tool_iterations"))
+    }
+
+    /// Ask the classifier model for a tier. Any failure yields `None`.
+    async fn classify_with_model(&self, messages: &[Message]) -> Option<RouteDecision> {
+        let model = self.config.classifier_model.as_deref()?;
+        let content = messages
+            .iter()
+            .rev()
+            .find(|m| m.role == Role::User)
+            .map(|m| {
+                m.content
+                    .chars()
+                    .take(CLASSIFIER_MAX_CHARS)
+                    .collect::<String>()
+            })?;
+
+        let prompt = vec![Message::system(CLASSIFIER_PROMPT), Message::user(&content)];
+        let options = ChatOptions::new().with_max_tokens(8).with_temperature(0.0);
+        let response = match self.inner.chat(prompt, vec![], Some(model), options).await {
+            Ok(response) => response,
+            Err(e) => {
+                warn!(model, error = %e, "Router: classifier call failed");
+                return None;
+            }
+        };
+        if let (Some(metrics), Some(usage)) = (self.metrics.as_ref(), response.usage.as_ref()) {
+            metrics.record_usage(usage);
+        }
+
+        let answer = response.content.to_ascii_lowercase();
+        [
+            ComplexityTier::Complex,
+            ComplexityTier::Standard,
+            ComplexityTier::Simple,
+        ]
+        .into_iter()
+        .find(|tier| answer.contains(tier.as_str()))
+        .map(|tier| RouteDecision::new(tier, "classifier"))
+    }
+
+    /// Classify a request and pick its model.
+    ///
+    /// Tool iterations reuse the decision made when the turn started, unless
+    /// they push the turn over the tool-iteration threshold.
+    async fn route(
+        &self,
+        messages: &[Message],
+        model: Option<&str>,
+        options: &ChatOptions,
+    ) -> (RouteDecision, Option<String>) {
+        let key = turn_key(messages);
+        let cached = self.turns.lock().unwrap().get(&key).copied();
+        let decision = match cached {
+            Some(decision) => self
+                .escalation(tool_iterations(messages))
+                .filter(|escalated| escalated.tier > decision.tier)
+                .unwrap_or(decision),
+            None => match self.classify(messages, options) {
+                Some(decision) => decision,
+                None => match self.classify_with_model(messages).await {
+                    Some(decision) => decision,
+                    None => RouteDecision::new(ComplexityTier::Standard, "default"),
+                },
+            },
+        };
+        if cached != Some(decision) {
+            let mut turns = self.turns.lock().unwrap();
+            if turns.len() >= MAX_CACHED_TURNS && !turns.contains_key(&key) {
+                turns.clear();
+            }
+            turns.insert(key, decision);
+        }
+
+        let tier_model = match decision.tier {
+            ComplexityTier::Simple => self.config.simple_model.as_deref(),
+            ComplexityTier::Standard => None,
+            ComplexityTier::Complex => self.config.complex_model.as_deref(),
+        }
+        .filter(|m| !m.trim().is_empty());
+        let routed = tier_model.or(model).map(String::from);
+
+        let label = routed
+            .as_deref()
+            .unwrap_or_else(|| self.inner.default_model());
+        debug!(
+            tier = decision.tier.as_str(),
+            reason = decision.reason,
+            model = label,
+            "Router: routed request"
+        );
+        if let Some(metrics) = &self.metrics {
+            metrics.record_route(decision.tier.as_str(), label);
+        }
+
+        (decision, routed)
+    }
+}
+
+/// Assistant turns with tool calls since the user last spoke.
+fn tool_iterations(messages: &[Message]) -> usize {
+    let Some(last_user) = messages.iter().rposition(|m| m.role == Role::User) else {
+        return 0;
+    };
+    messages[last_user + 1..]
+        .iter()
+        .filter(|m| {
+            m.role == Role::Assistant && m.tool_calls.as_ref().is_some_and(|c| !c.is_empty())
+        })
+        .count()
+}
+
+/// Identify a user turn by the conversation up to its last user message.
+///
+/// Tool iterations of the same turn share a key; the history before the
+/// message keeps identical messages in different sessions apart.
+fn turn_key(messages: &[Message]) -> u64 {
+    let end = messages
+        .iter()
+        .rposition(|m| m.role == Role::User)
+        .map_or(0, |i| i + 1);
+    let mut hasher = DefaultHasher::new();
+    for message in &messages[..end] {
+        std::mem::discriminant(&message.role).hash(&mut hasher);
+        message.content.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+#[async_trait]
+impl LLMProvider for RouterProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn default_model(&self) -> &str {
+        self.inner.default_model()
+    }
+
+    fn supports_forced_tool_choice(&self) -> bool {
+        self.inner.supports_forced_tool_choice()
+    }
+
+    fn supports_response_schema(&self) -> bool {
+        self.inner.supports_response_schema()
+    }
+
+    async fn chat(
+        &self,
+        messages: Vec<Message>,
+        tools: Vec<ToolDefinition>,
+        model: Option<&str>,
+        options: ChatOptions,
+    ) -> Result<LLMResponse> {
+        let (_, routed) = self.route(&messages, model, &options).await;
+        self.inner
+            .chat(messages, tools, routed.as_deref(), options)
+            .await
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: Vec<Message>,
+        tools: Vec<ToolDefinition>,
+        model: Option<&str>,
+        options: ChatOptions,
+    ) -> Result<tokio::sync::mpsc::Receiver<StreamEvent>> {
+        let (_, routed) = self.route(&messages, model, &options).await;
+        self.inner
+            .chat_stream(messages, tools, routed.as_deref(), options)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::ToolCall;
+
+    /// A provider that records the model of every call and answers with a
+    /// fixed classifier reply.
+    struct ModelRecorder {
+        models: Arc<Mutex<Vec<Option<String>>>>,
+        reply: &'static str,
+    }
+
+    #[async_trait]
+    impl LLMProvider for ModelRecorder {
+        fn name(&self) -> &str {
+            "recorder"
+        }
+
+        fn default_model(&self) -> &str {
+            "default-model"
+        }
+
+        async fn chat(
+            &self,
+            _messages: Vec<Message>,
+            _tools: Vec<ToolDefinition>,
+            model: Option<&str>,
+            _options: ChatOptions,
+        ) -> Result<LLMResponse> {
+            self.models.lock().unwrap().push(model.map(String::from));
+            Ok(LLMResponse::text(self.reply))
+        }
+    }
+
+    fn router_with(
+        config: RouterConfig,
+        reply: &'static str,
+    ) -> (RouterProvider, Arc<Mutex<Vec<Option<String>>>>) {
+        let models = Arc::new(Mutex::new(Vec::new()));
+        let inner = ModelRecorder {
+            models: Arc::clone(&models),
+            reply,
+        };
+        (RouterProvider::new(Box::new(inner), config), models)
+    }
+
+    fn tiered() -> RouterConfig {
+        RouterConfig {
+            enabled: true,
+            simple_model: Some("small".into()),
+            complex_model: Some("large".into()),
+            ..Default::default()
+        }
+    }
+
+    fn tool_turn() -> Message {
+        Message::assistant_with_tools(
+            "",
+            vec![ToolCall {
+                id: "call_1".into(),
+                name: "shell".into(),
+                arguments: "{}".into(),
+            }],
+        )
+    }
+
+    #[test]
+    fn test_short_message_is_simple() {
+        let (router, _) = router_with(tiered(), "");
+        let decision = router
+            .classify(&[Message::user("thanks!")], &ChatOptions::new())
+            .unwrap();
+        assert_eq!(decision.tier, ComplexityTier::Simple);
+        assert_eq!(decision.reason, "short_message");
+    }
+
+    #[test]
+    fn test_long_message_is_complex() {
+        let (router, _) = router_with(tiered(), "");
+        let long = "research ".repeat(400);
+        let decision = router
+            .classify(&[Message::user(&long)], &ChatOptions::new())
+            .unwrap();
+        assert_eq!(decision.tier, ComplexityTier::Complex);
+    }
+
+    #[test]
+    fn test_mid_length_message_is_undecided() {
+        let (router, _) = router_with(tiered(), "");
+        let medium = "word ".repeat(100);
+        assert!(router
+            .classify(&[Message::user(&medium)], &ChatOptions::new())
+            .is_none());
+    }
+
+    #[test]
+    fn test_keyword_rule_wins() {
+        let mut config = tiered();
+        config.keyword_rules = vec![KeywordRule {
+            keywords: vec!["Deep Dive".into()],
+            tier: ComplexityTier::Complex,
+        }];
+        let (router, _) = router_with(config, "");
+        let decision = router
+            .classify(&[Message::user("quick deep dive?")], &ChatOptions::new())
+            .unwrap();
+        assert_eq!(
+            decision,
+            RouteDecision::new(ComplexityTier::Complex, "keyword")
+        );
+    }
+
+    #[test]
+    fn test_tool_heavy_turn_is_complex() {
+        let (router, _) = router_with(tiered(), "");
+        let mut messages = vec![Message::user("fix it")];
+        for _ in 0..4 {
+            messages.push(tool_turn());
+            messages.push(Message::tool_result("call_1", "ok"));
+        }
+        let decision = router.classify(&messages, &ChatOptions::new()).unwrap();
+        assert_eq!(decision.reason, "tool_iterations");
+
+        // One tool round on a short message is no longer "simple".
+        let messages = vec![
+            Message::user("fix it"),
+            tool_turn(),
+            Message::tool_result("call_1", "ok"),
+        ];
+        assert!(router.classify(&messages, &ChatOptions::new()).is_none());
+    }
+
+    #[test]
+    fn test_attachment_raises_tier() {
+        let (router, _) = router_with(tiered(), "");
+        let options = ChatOptions::new().with_attachments(true);
+        let decision = router
+            .classify(&[Message::user("what's this?")], &options)
+            .unwrap();
+        assert_eq!(
+            decision,
+            RouteDecision::new(ComplexityTier::Standard, "attachment")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_chat_dispatches_to_tier_model() {
+        let (router, models) = router_with(tiered(), "");
+        router
+            .chat(
+                vec![Message::user("hi")],
+                vec![],
+                Some("requested"),
+                ChatOptions::new(),
+            )
+            .await
+            .unwrap();
+        let long = "plan ".repeat(500);
+        router
+            .chat(
+                vec![Message::user(&long)],
+                vec![],
+                Some("requested"),
+                ChatOptions::new(),
+            )
+            .await
+            .unwrap();
+
+        let models = models.lock().unwrap().clone();
+        assert_eq!(
+            models,
+            vec![Some("small".to_string()), Some("large".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_standard_keeps_requested_model_and_records_metrics() {
+        let metrics = Arc::new(MetricsCollector::new());
+        let (router, models) = router_with(tiered(), "");
+        let router = router.with_metrics(Arc::clone(&metrics));
+        let medium = "word ".repeat(100);
+        router
+            .chat(
+                vec![Message::user(&medium)],
+                vec![],
+                Some("requested"),
+                ChatOptions::new(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            models.lock().unwrap().clone(),
+            vec![Some("requested".to_string())]
+        );
+        let routes = metrics.route_counts();
+        assert_eq!(
+            routes.get(&("standard".to_string(), "requested".to_string())),
+            Some(&1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_classifier_breaks_ties() {
+        let mut config = tiered();
+        config.classifier_model = Some("tiny".into());
+        let (router, models) = router_with(config, "Complex.");
+        let medium = "word ".repeat(100);
+        router
+            .chat(
+                vec![Message::user(&medium)],
+                vec![],
+                Some("requested"),
+                ChatOptions::new(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            models.lock().unwrap().clone(),
+            vec![Some("tiny".to_string()), Some("large".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_decision_is_reused_for_tool_iterations() {
+        let mut config = tiered();
+        config.classifier_model = Some("tiny".into());
+        let (router, models) = router_with(config, "simple");
+        let medium = "word ".repeat(100);
+        let mut messages = vec![Message::user(&medium)];
+        for _ in 0..3 {
+            router
+                .chat(
+                    messages.clone(),
+                    vec![],
+                    Some("requested"),
+                    ChatOptions::new(),
+                )
+                .await
+                .unwrap();
+            messages.push(tool_turn());
+            messages.push(Message::tool_result("call_1", "ok"));
+        }
+        // The classifier runs once; every iteration keeps its tier.
+        assert_eq!(
+            models.lock().unwrap().clone(),
+            vec![
+                Some("tiny".to_string()),
+                Some("small".to_string()),
+                Some("small".to_string()),
+                Some("small".to_string()),
+            ]
+        );
+
+        // Reaching the tool-iteration threshold still escalates.
+        messages.push(tool_turn());
+        messages.push(Message::tool_result("call_1", "ok"));
+        let (decision, routed) = router
+            .route(&messages, Some("requested"), &ChatOptions::new())
+            .await;
+        assert_eq!(decision.reason, "tool_iterations");
+        assert_eq!(routed.as_deref(), Some("large"));
+
+        // A new user message starts a new turn.
+        messages.push(Message::assistant("done"));
+        messages.push(Message::user("thanks!"));
+        let (decision, _) = router
+            .route(&messages, Some("requested"), &ChatOptions::new())
+            .await;
+        assert_eq!(decision.reason, "short_message");
+    }
+
+    #[test]
+    fn test_tier_parse() {
+        assert_eq!(
+            "COMPLEX".parse::<ComplexityTier>().unwrap(),
+            ComplexityTier::Complex
+        );
+        assert!("huge".parse::<ComplexityTier>().is_err());
+        assert!(ComplexityTier::Simple < ComplexityTier::Complex);
+    }
+}
@@ -14,6 +14,13 @@
 //! - **OpenAI**: Uses the native `response_format` parameter.
 //! - **Claude**: Appends JSON instructions to the system prompt.
 //!
+//! [`StructuredChat`] builds on this to get a schema-valid answer from any
+//! provider: it validates each response against the schema and re-prompts
+//! with the validation errors until the answer passes or the repair budget
+//! runs out. Providers that can force a tool call but have no native schema
+//! mode (Claude) are asked for the answer as a forced tool call, which follows
+//! the schema far more reliably than prompt instructions.
+//!
 //! # Example
 //!
 //! ```rust
@@ -42,6 +49,12 @@
 //! ```
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::debug;
+
+use crate::error::ZeptoError;
+use crate::providers::{ChatOptions, LLMProvider, ToolChoice, ToolDefinition};
+use crate::session::{Message, ToolCall};
 
 /// Output format configuration for LLM responses.
 ///
@@ -302,6 +315,213 @@ pub fn validate_json_response(
     }
 }
 
+/// Default number of re-prompts after the first invalid answer.
+pub const DEFAULT_MAX_REPAIRS: u32 = 2;
+
+/// A validated structured answer.
+#[derive(Debug, Clone)]
+pub struct StructuredOutput {
+    /// The parsed JSON value, guaranteed to match the requested format.
+    pub value: Value,
+    /// Number of requests made, including the first (1 = no repairs needed).
+    pub attempts: u32,
+}
+
+/// Requests JSON output from a provider and repairs invalid answers.
+///
+/// Each response is parsed and validated against the schema. On failure the
+/// model sees its previous answer and the list of validation errors, and is
+/// asked again, up to `max_repairs` times.
+///
+/// # Example
+/// ```no_run
+/// use zeptoclaw::providers::structured::{OutputFormat, StructuredChat};
+/// use zeptoclaw::providers::{ChatOptions, ClaudeProvider};
+/// use zeptoclaw::session::Message;
+/// use serde_json::json;
+///
+/// # tokio_test::block_on(async {
+/// let provider = ClaudeProvider::new("sk-ant-...");
+/// let format = OutputFormat::json_schema("person", json!({
+///     "type": "object",
+///     "properties": { "name": { "type": "string" } },
+///     "required": ["name"]
+/// }));
+///
+/// let output = StructuredChat::new(&provider, format)
+///     .with_max_repairs(3)
+///     .chat(vec![Message::user("Who wrote Dune?")], None, ChatOptions::new())
+///     .await
+///     .unwrap();
+/// println!("{}", output.value["name"]);
+/// # });
+/// ```
+pub struct StructuredChat<'a> {
+    provider: &'a dyn LLMProvider,
+    format: OutputFormat,
+    max_repairs: u32,
+    use_tool_call: bool,
+}
+
+impl<'a> StructuredChat<'a> {
+    /// Create a helper for the given provider and format.
+    ///
+    /// The forced tool call path is enabled when the format carries a schema
+    /// and the provider can force a tool call but cannot enforce the schema
+    /// natively (see [`LLMProvider::supports_forced_tool_choice`]).
+    pub fn new(provider: &'a dyn LLMProvider, format: OutputFormat) -> Self {
+        let use_tool_call =
+            provider.supports_forced_tool_choice() && !provider.supports_response_schema();
+        Self {
+            provider,
+            format,
+            max_repairs: DEFAULT_MAX_REPAIRS,
+            use_tool_call,
+        }
+    }
+
+    /// Set how many times an invalid answer is sent back for repair.
+    pub fn with_max_repairs(mut self, max_repairs: u32) -> Self {
+        self.max_repairs = max_repairs;
+        self
+    }
+
+    /// Force (or disable) asking for the answer as a tool call.
+    ///
+    /// Only takes effect for [`OutputFormat::JsonSchema`].
+    pub fn with_tool_call(mut self, enabled: bool) -> Self {
+        self.use_tool_call = enabled;
+        self
+    }
+
+    /// Send `messages` and return the first answer that validates.
+    ///
+    /// Returns a provider error listing the last validation failure when
+    /// every attempt is invalid, and `ZeptoError::Config` when the format
+    /// is [`OutputFormat::Text`].
+    pub async fn chat(
+        &self,
+        mut messages: Vec<Message>,
+        model: Option<&str>,
+        options: ChatOptions,
+    ) -> crate::error::Result<StructuredOutput> {
+        if self.format.is_text() {
+            return Err(ZeptoError::Config(
+                "Structured output requires a JSON output format".to_string(),
+            ));
+        }
+
+        let tool = self.answer_tool();
+        let (tools, options) = match &tool {
+            Some(def) => (
+                vec![def.clone()],
+                options
+                    .with_output_format(OutputFormat::Text)
+                    .with_tool_choice(ToolChoice::Tool(def.name.clone())),
+            ),
+            None => (Vec::new(), options.with_output_format(self.format.clone())),
+        };
+
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let response = self
+                .provider
+                .chat(messages.clone(), tools.clone(), model, options.clone())
+                .await?;
+
+            let errors = match &tool {
+                Some(def) => match response.tool_calls.iter().find(|c| c.name == def.name) {
+                    Some(call) => match self.check(&call.arguments) {
+                        Ok(value) => return Ok(StructuredOutput { value, attempts }),
+                        Err(errors) => {
+                            messages.push(Message::assistant_with_tools(
+                                &response.content,
+                                vec![ToolCall::new(&call.id, &call.name, &call.arguments)],
+                            ));
+                            messages.push(Message::tool_result(
+                                &call.id,
+                                &format!(
+                                    "Error: {}. Call `{}` again with corrected input.",
+                                    errors, def.name
+                                ),
+                            ));
+                            errors
+                        }
+                    },
+                    None => {
+                        let errors = format!("the answer was not given through `{}`", def.name);
+                        messages.push(Message::assistant(&response.content));
+                        messages.push(Message::user(&format!(
+                            "Your reply was invalid: {}. Call `{}` with the answer.",
+                            errors, def.name
+                        )));
+                        errors
+                    }
+                },
+                None => match self.check(&response.content) {
+                    Ok(value) => return Ok(StructuredOutput { value, attempts }),
+                    Err(errors) => {
+                        messages.push(Message::assistant(&response.content));
+                        messages.push(Message::user(&format!(
+                            "Your reply was invalid: {}. Reply again with only the corrected JSON.",
+                            errors
+                        )));
+                        errors
+                    }
+                },
+            };
+
+            if attempts > self.max_repairs {
+                return Err(ZeptoError::Provider(format!(
+                    "Structured output still invalid after {} attempt(s): {}",
+                    attempts, errors
+                )));
+            }
+            debug!(attempt = attempts, errors = %errors, "Repairing structured output");
+        }
+    }
+
+    /// The tool used to carry the answer, when the tool call path applies.
+    fn answer_tool(&self) -> Option<ToolDefinition> {
+        match &self.format {
+            OutputFormat::JsonSchema { name, schema, .. } if self.use_tool_call => {
+                Some(ToolDefinition::new(
+                    name,
+                    "Provide the final answer. Always use this tool to respond.",
+                    schema.clone(),
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Parse and validate one answer, returning a model-readable error list.
+    pub(crate) fn check(&self, raw: &str) -> Result<Value, String> {
+        let value: Value = serde_json::from_str(strip_code_fence(raw))
+            .map_err(|e| format!("invalid JSON ({})", e))?;
+        if let OutputFormat::JsonSchema { schema, .. } = &self.format {
+            crate::tools::schema::validate(schema, &value).map_err(|errors| errors.join("; "))?;
+        }
+        Ok(value)
+    }
+}
+
+/// Strip a surrounding markdown code fence, which models add despite
+/// being told not to.
+fn strip_code_fence(raw: &str) -> &str {
+    let trimmed = raw.trim();
+    let Some(body) = trimmed.strip_prefix("```") else {
+        return trimmed;
+    };
+    let body = body.strip_suffix("```").unwrap_or(body);
+    // Drop the info string (e.g. `json`) on the opening line.
+    match body.split_once('\n') {
+        Some((_, rest)) => rest.trim(),
+        None => body.trim(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -556,4 +776,197 @@ mod tests {
         let cloned = original.clone();
         assert_eq!(original, cloned);
     }
+
+    type Request = (Vec<Message>, Vec<ToolDefinition>, ChatOptions);
+
+    struct ScriptedProvider {
+        forced_tool_choice: bool,
+        responses: std::sync::Mutex<Vec<crate::providers::LLMResponse>>,
+        requests: std::sync::Mutex<Vec<Request>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(forced_tool_choice: bool, responses: Vec<crate::providers::LLMResponse>) -> Self {
+            Self {
+                forced_tool_choice,
+                responses: std::sync::Mutex::new(responses.into_iter().rev().collect()),
+                requests: std::sync::Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl LLMProvider for ScriptedProvider {
+        async fn chat(
+            &self,
+            messages: Vec<Message>,
+            tools: Vec<ToolDefinition>,
+            _model: Option<&str>,
+            options: ChatOptions,
+        ) -> crate::error::Result<crate::providers::LLMResponse> {
+            self.requests
+                .lock()
+                .unwrap()
+                .push((messages, tools, options));
+            Ok(self
+                .responses
+                .lock()
+                .unwrap()
+                .pop()
+                .expect("script exhausted"))
+        }
+
+        fn default_model(&self) -> &str {
+            "test-model"
+        }
+
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        fn supports_forced_tool_choice(&self) -> bool {
+            self.forced_tool_choice
+        }
+    }
+
+    fn person_format() -> OutputFormat {
+        OutputFormat::json_schema(
+            "person",
+            json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "age": { "type": "integer" }
+                },
+                "required": ["name", "age"]
+            }),
+        )
+    }
+
+    #[test]
+    fn test_strip_code_fence() {
+        assert_eq!(strip_code_fence("```json\n{\"a\": 1}\n```"), "{\"a\": 1}");
+        assert_eq!(strip_code_fence("  {\"a\": 1} "), "{\"a\": 1}");
+    }
+
+    #[tokio::test]
+    async fn test_structured_chat_repairs_invalid_answer() {
+        use crate::providers::LLMResponse;
+
+        let provider = ScriptedProvider::new(
+            false,
+            vec![
+                LLMResponse::text(r#"{"name": "Alice", "age": "thirty"}"#),
+                LLMResponse::text("```json\n{\"name\": \"Alice\", \"age\": 30}\n```"),
+            ],
+        );
+
+        let output = StructuredChat::new(&provider, person_format())
+            .chat(
+                vec![Message::user("Describe Alice")],
+                None,
+                ChatOptions::new(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(output.value, json!({"name": "Alice", "age": 30}));
+        assert_eq!(output.attempts, 2);
+
+        let requests = provider.requests.lock().unwrap();
+        assert!(requests[0].2.output_format.is_json());
+        let repair = requests[1].0.last().unwrap();
+        assert!(repair
+            .content
+            .contains("field 'age' must be an integer, got string"));
+    }
+
+    #[tokio::test]
+    async fn test_structured_chat_gives_up_after_max_repairs() {
+        use crate::providers::LLMResponse;
+
+        let provider = ScriptedProvider::new(
+            false,
+            vec![LLMResponse::text("nope"), LLMResponse::text("still nope")],
+        );
+
+        let err = StructuredChat::new(&provider, OutputFormat::json())
+            .with_max_repairs(1)
+            .chat(vec![Message::user("hi")], None, ChatOptions::new())
+            .await
+            .unwrap_err();
+
+        assert!(err
+            .to_string()
+            .contains("Structured output still invalid after 2 attempt(s): invalid JSON"));
+    }
+
+    #[tokio::test]
+    async fn test_structured_chat_uses_forced_tool_when_supported() {
+        use crate::providers::{LLMResponse, LLMToolCall};
+
+        let provider = ScriptedProvider::new(
+            true,
+            vec![
+                LLMResponse::with_tools(
+                    "",
+                    vec![LLMToolCall::new("t1", "person", r#"{"name": "Bob"}"#)],
+                ),
+                LLMResponse::with_tools(
+                    "",
+                    vec![LLMToolCall::new(
+                        "t2",
+                        "person",
+                        r#"{"name": "Bob", "age": 41}"#,
+                    )],
+                ),
+            ],
+        );
+
+        let output = StructuredChat::new(&provider, person_format())
+            .chat(
+                vec![Message::user("Describe Bob")],
+                None,
+                ChatOptions::new(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(output.value["age"], 41);
+        let requests = provider.requests.lock().unwrap();
+        let (messages, tools, options) = &requests[1];
+        assert_eq!(tools[0].name, "person");
+        assert_eq!(
+            options.tool_choice,
+            Some(ToolChoice::Tool("person".to_string()))
+        );
+        assert!(options.output_format.is_text());
+        let result = messages.last().unwrap();
+        assert!(result.is_tool_result());
+        assert!(result.content.contains("missing required field 'age'"));
+    }
+
+    #[test]
+    fn test_tool_call_path_follows_wrapped_provider() {
+        use crate::providers::{FallbackProvider, RetryProvider};
+
+        let wrapped = RetryProvider::new(Box::new(ScriptedProvider::new(true, Vec::new())));
+        assert!(StructuredChat::new(&wrapped, person_format()).use_tool_call);
+
+        // A chain only forces tool calls when every member can.
+        let chain = FallbackProvider::new(
+            Box::new(ScriptedProvider::new(true, Vec::new())),
+            Box::new(ScriptedProvider::new(false, Vec::new())),
+        );
+        assert!(!StructuredChat::new(&chain, person_format()).use_tool_call);
+    }
+
+    #[tokio::test]
+    async fn test_structured_chat_rejects_text_format() {
+        let provider = ScriptedProvider::new(false, Vec::new());
+        let result = StructuredChat::new(&provider, OutputFormat::Text)
+            .chat(vec![Message::user("hi")], None, ChatOptions::new())
+            .await;
+        assert!(matches!(result, Err(ZeptoError::Config(_))));
+    }
 }
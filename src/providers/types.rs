@@ -7,14 +7,17 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
 use crate::error::{Result, ZeptoError};
+use crate::providers::reasoning::ReasoningEffort;
 use crate::providers::structured::OutputFormat;
-use crate::session::Message;
+use crate::session::{Message, ThinkingBlock};
 
 /// Events emitted during streaming LLM responses.
 #[derive(Debug)]
 pub enum StreamEvent {
     /// A chunk of text content from the LLM.
     Delta(String),
+    /// A chunk of reasoning (thinking) text, streamed before the answer.
+    ReasoningDelta(String),
     /// Tool calls detected mid-stream (triggers fallback to non-streaming tool loop).
     ToolCalls(Vec<LLMToolCall>),
     /// Stream complete — carries the full assembled content and usage stats.
@@ -133,6 +136,55 @@ pub trait LLMProvider: Send + Sync {
             .await;
         Ok(rx)
     }
+
+    /// Whether [`ToolChoice::Tool`] makes the model call that tool.
+    ///
+    /// Wrappers report what the providers they send requests to support.
+    fn supports_forced_tool_choice(&self) -> bool {
+        false
+    }
+
+    /// Whether [`OutputFormat::JsonSchema`] is enforced by the API rather
+    /// than only described in the prompt.
+    fn supports_response_schema(&self) -> bool {
+        false
+    }
+}
+
+/// Whether and which tool the model must call.
+///
+/// Deserializes from `"auto"`, `"none"`, `"required"` or
+/// `{"tool": "<name>"}`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolChoice {
+    /// The model decides (provider default)
+    #[default]
+    Auto,
+    /// The model must answer without calling tools
+    None,
+    /// The model must call at least one tool
+    Required,
+    /// The model must call the named tool
+    Tool(String),
+}
+
+impl std::fmt::Display for ToolChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ToolChoice::Auto => write!(f, "auto"),
+            ToolChoice::None => write!(f, "none"),
+            ToolChoice::Required => write!(f, "required"),
+            ToolChoice::Tool(name) => write!(f, "tool {}", name),
+        }
+    }
+}
+
+impl ToolChoice {
+    /// Whether this choice forces a tool call.
+    pub fn forces_call(&self) -> bool {
+        matches!(self, ToolChoice::Required | ToolChoice::Tool(_))
+    }
 }
 
 /// Options for chat completion requests.
@@ -150,6 +202,16 @@ pub struct ChatOptions {
     pub stop: Option<Vec<String>>,
     /// Output format (text, JSON, or JSON schema)
     pub output_format: OutputFormat,
+    /// Reasoning effort for models that support it
+    pub reasoning_effort: Option<ReasoningEffort>,
+    /// Explicit thinking token budget (Anthropic); overrides the effort default
+    pub thinking_budget: Option<u32>,
+    /// The user's message carries an attachment (a routing hint; not sent)
+    pub has_attachments: bool,
+    /// Force or forbid tool calls; only sent when tools are offered
+    pub tool_choice: Option<ToolChoice>,
+    /// Allow several tool calls in one response (provider default when unset)
+    pub parallel_tool_calls: Option<bool>,
 }
 
 impl ChatOptions {
@@ -254,6 +316,111 @@ impl ChatOptions {
         self.output_format = output_format;
         self
     }
+
+    /// Request reasoning at the given effort level.
+    ///
+    /// Ignored for models without reasoning support.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::providers::{ChatOptions, ReasoningEffort};
+    ///
+    /// let options = ChatOptions::new().with_reasoning_effort(ReasoningEffort::High);
+    /// assert_eq!(options.reasoning_effort, Some(ReasoningEffort::High));
+    /// ```
+    pub fn with_reasoning_effort(mut self, effort: ReasoningEffort) -> Self {
+        self.reasoning_effort = Some(effort);
+        self
+    }
+
+    /// Set an explicit thinking token budget.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::providers::ChatOptions;
+    ///
+    /// let options = ChatOptions::new().with_thinking_budget(4096);
+    /// assert_eq!(options.thinking_budget, Some(4096));
+    /// ```
+    pub fn with_thinking_budget(mut self, budget: u32) -> Self {
+        self.thinking_budget = Some(budget);
+        self
+    }
+
+    /// Mark whether the user's message carries an attachment.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::providers::ChatOptions;
+    ///
+    /// let options = ChatOptions::new().with_attachments(true);
+    /// assert!(options.has_attachments);
+    /// ```
+    pub fn with_attachments(mut self, has_attachments: bool) -> Self {
+        self.has_attachments = has_attachments;
+        self
+    }
+
+    /// Set whether and which tool the model must call.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::providers::{ChatOptions, ToolChoice};
+    ///
+    /// let options = ChatOptions::new().with_tool_choice(ToolChoice::Tool("web_search".into()));
+    /// assert!(options.tool_choice.unwrap().forces_call());
+    /// ```
+    pub fn with_tool_choice(mut self, tool_choice: ToolChoice) -> Self {
+        self.tool_choice = Some(tool_choice);
+        self
+    }
+
+    /// Allow or forbid several tool calls in one response.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::providers::ChatOptions;
+    ///
+    /// let options = ChatOptions::new().with_parallel_tool_calls(false);
+    /// assert_eq!(options.parallel_tool_calls, Some(false));
+    /// ```
+    pub fn with_parallel_tool_calls(mut self, parallel: bool) -> Self {
+        self.parallel_tool_calls = Some(parallel);
+        self
+    }
+
+    /// Options for the follow-up request after tool results: a forced tool
+    /// call is relaxed to `Auto` so the model can give its answer.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::providers::{ChatOptions, ToolChoice};
+    ///
+    /// let options = ChatOptions::new().with_tool_choice(ToolChoice::Required);
+    /// assert_eq!(options.after_tool_results().tool_choice, Some(ToolChoice::Auto));
+    /// ```
+    pub fn after_tool_results(mut self) -> Self {
+        if self
+            .tool_choice
+            .as_ref()
+            .is_some_and(ToolChoice::forces_call)
+        {
+            self.tool_choice = Some(ToolChoice::Auto);
+        }
+        self
+    }
+
+    /// Whether any reasoning option is set.
+    pub fn wants_reasoning(&self) -> bool {
+        self.reasoning_effort.is_some() || self.thinking_budget.is_some()
+    }
+
+    /// Thinking budget to request, if any: the explicit budget wins over the
+    /// effort default.
+    pub fn resolved_thinking_budget(&self) -> Option<u32> {
+        self.thinking_budget
+            .or_else(|| self.reasoning_effort.map(|e| e.thinking_budget()))
+    }
 }
 
 /// Response from an LLM chat completion request.
@@ -265,6 +432,9 @@ pub struct LLMResponse {
     pub tool_calls: Vec<LLMToolCall>,
     /// Token usage information (if available)
     pub usage: Option<Usage>,
+    /// Reasoning blocks produced before the answer (if any)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub thinking: Vec<ThinkingBlock>,
 }
 
 impl LLMResponse {
@@ -286,6 +456,7 @@ impl LLMResponse {
             content: content.to_string(),
             tool_calls: vec![],
             usage: None,
+            thinking: Vec::new(),
         }
     }
 
@@ -308,6 +479,7 @@ impl LLMResponse {
             content: content.to_string(),
             tool_calls,
             usage: None,
+            thinking: Vec::new(),
         }
     }
 
@@ -332,6 +504,12 @@ impl LLMResponse {
         self.usage = Some(usage);
         self
     }
+
+    /// Attach reasoning blocks to this response.
+    pub fn with_thinking(mut self, thinking: Vec<ThinkingBlock>) -> Self {
+        self.thinking = thinking;
+        self
+    }
 }
 
 /// A tool call made by the LLM.
@@ -404,6 +582,14 @@ pub struct Usage {
     pub completion_tokens: u32,
     /// Total tokens used (prompt + completion)
     pub total_tokens: u32,
+    /// Prompt tokens written to the provider's prompt cache (included in
+    /// `prompt_tokens`)
+    #[serde(default)]
+    pub cache_creation_tokens: u32,
+    /// Prompt tokens served from the provider's prompt cache (included in
+    /// `prompt_tokens`)
+    #[serde(default)]
+    pub cache_read_tokens: u32,
 }
 
 impl Usage {
@@ -425,8 +611,34 @@ impl Usage {
             prompt_tokens,
             completion_tokens,
             total_tokens: prompt_tokens + completion_tokens,
+            cache_creation_tokens: 0,
+            cache_read_tokens: 0,
         }
     }
+
+    /// Attach prompt cache counters.
+    ///
+    /// Both counts are portions of `prompt_tokens`, not additions to it.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::providers::Usage;
+    ///
+    /// let usage = Usage::new(1000, 50).with_cache_tokens(0, 800);
+    /// assert_eq!(usage.uncached_prompt_tokens(), 200);
+    /// ```
+    pub fn with_cache_tokens(mut self, creation: u32, read: u32) -> Self {
+        self.cache_creation_tokens = creation;
+        self.cache_read_tokens = read;
+        self
+    }
+
+    /// Prompt tokens billed at the regular input price.
+    pub fn uncached_prompt_tokens(&self) -> u32 {
+        self.prompt_tokens
+            .saturating_sub(self.cache_creation_tokens)
+            .saturating_sub(self.cache_read_tokens)
+    }
 }
 
 #[cfg(test)]
@@ -439,6 +651,7 @@ mod tests {
             content: "Hello".to_string(),
             tool_calls: vec![],
             usage: None,
+            thinking: Vec::new(),
         };
         assert_eq!(response.content, "Hello");
         assert!(!response.has_tool_calls());
@@ -510,6 +723,31 @@ mod tests {
         assert!(options.stop.is_none());
     }
 
+    #[test]
+    fn test_tool_choice_serde() {
+        let parsed: Vec<ToolChoice> =
+            serde_json::from_str(r#"["auto", "none", "required", {"tool": "web_search"}]"#)
+                .unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                ToolChoice::Auto,
+                ToolChoice::None,
+                ToolChoice::Required,
+                ToolChoice::Tool("web_search".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_after_tool_results_keeps_none() {
+        let options = ChatOptions::new().with_tool_choice(ToolChoice::None);
+        assert_eq!(
+            options.after_tool_results().tool_choice,
+            Some(ToolChoice::None)
+        );
+    }
+
     #[test]
     fn test_tool_definition() {
         let tool = ToolDefinition {
@@ -17,11 +17,11 @@
 
 use async_trait::async_trait;
 use std::process::Stdio;
-use std::time::Duration;
 use tokio::process::Command;
 use tracing::warn;
 
-use super::types::{CommandOutput, ContainerConfig, ContainerRuntime, RuntimeError, RuntimeResult};
+use super::cancel::{exec_container_name, output_with_timeout, Teardown};
+use super::types::{CommandOutput, ContainerConfig, ContainerRuntime, RuntimeResult};
 
 /// Apple Container runtime for macOS
 ///
@@ -123,7 +123,8 @@ impl ContainerRuntime for AppleContainerRuntime {
             Test thoroughly before production use."
         );
 
-        let mut args = vec!["run".to_string()];
+        let name = exec_container_name();
+        let mut args = vec!["run".to_string(), "--name".to_string(), name.clone()];
 
         // Add image if specified
         if let Some(ref image) = self.image {
@@ -187,15 +188,16 @@ impl ContainerRuntime for AppleContainerRuntime {
         args.push(command.to_string());
 
         let mut cmd = Command::new("container");
-        cmd.args(&args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
-
-        // Execute with timeout
-        let output = tokio::time::timeout(Duration::from_secs(config.timeout_secs), cmd.output())
-            .await
-            .map_err(|_| RuntimeError::Timeout(config.timeout_secs))?
-            .map_err(|e| RuntimeError::ExecutionFailed(e.to_string()))?;
+        cmd.args(&args);
+
+        // Execute with timeout; the container is killed by name if the call
+        // is timed out or cancelled
+        let output = output_with_timeout(
+            cmd,
+            config.timeout_secs,
+            Teardown::container("container", &name),
+        )
+        .await?;
 
         Ok(CommandOutput::new(
             String::from_utf8_lossy(&output.stdout).to_string(),
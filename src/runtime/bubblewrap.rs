@@ -0,0 +1,265 @@
+//! Bubblewrap runtime implementation (Linux only)
+//!
+//! Executes commands with `bwrap`, which builds a throwaway mount namespace
+//! from bind mounts using unprivileged user namespaces. No daemon or image is
+//! needed: the host's system directories are mounted read-only and only the
+//! workspace (plus configured mounts) is visible beyond them.
+
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::process::Stdio;
+use tokio::process::Command;
+
+use super::cancel::{output_with_timeout, Teardown};
+use super::session::SessionLaunch;
+use super::types::{CommandOutput, ContainerConfig, ContainerRuntime, RuntimeResult};
+
+/// `PATH` inside the sandbox; the host environment is not inherited.
+const SANDBOX_PATH: &str = "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
+
+/// Bubblewrap runtime that executes commands in unprivileged namespaces
+#[derive(Debug, Clone)]
+pub struct BubblewrapRuntime {
+    /// Host paths bound read-only at the same location
+    read_only_paths: Vec<PathBuf>,
+    /// Whether to share the host network namespace
+    network: bool,
+    /// Extra bind mounts from config (host:container or host:container:ro format)
+    extra_mounts: Vec<String>,
+}
+
+impl BubblewrapRuntime {
+    /// Create a new Bubblewrap runtime with the default system paths
+    pub fn new() -> Self {
+        Self {
+            read_only_paths: ["/usr", "/bin", "/sbin", "/lib", "/lib64", "/etc"]
+                .iter()
+                .map(PathBuf::from)
+                .collect(),
+            network: false,
+            extra_mounts: Vec::new(),
+        }
+    }
+
+    /// Replace the read-only system paths
+    pub fn with_read_only_paths(mut self, paths: Vec<PathBuf>) -> Self {
+        self.read_only_paths = paths;
+        self
+    }
+
+    /// Share (or isolate) the host network
+    pub fn with_network(mut self, network: bool) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Add extra bind mounts (host:container or host:container:ro format)
+    pub fn with_extra_mounts(mut self, mounts: Vec<String>) -> Self {
+        self.extra_mounts = mounts;
+        self
+    }
+
+    /// Build the `bwrap` arguments up to and including the `sh` to run.
+    fn build_args(&self, config: &ContainerConfig) -> Vec<String> {
+        let mut args: Vec<String> = [
+            "--unshare-all",
+            "--die-with-parent",
+            "--new-session",
+            "--proc",
+            "/proc",
+            "--dev",
+            "/dev",
+            "--tmpfs",
+            "/tmp",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        if self.network {
+            args.push("--share-net".to_string());
+        }
+
+        // System directories; `-try` skips paths missing on this distro
+        for path in &self.read_only_paths {
+            let path = path.to_string_lossy().to_string();
+            args.extend(["--ro-bind-try".to_string(), path.clone(), path]);
+        }
+
+        // Add mounts from ContainerConfig
+        for (host, container, readonly) in &config.mounts {
+            let flag = if *readonly { "--ro-bind" } else { "--bind" };
+            args.extend([
+                flag.to_string(),
+                host.to_string_lossy().to_string(),
+                container.to_string_lossy().to_string(),
+            ]);
+        }
+
+        // Add extra mounts from runtime config
+        for mount in &self.extra_mounts {
+            let (spec, readonly) = match mount.strip_suffix(":ro") {
+                Some(spec) => (spec, true),
+                None => (mount.as_str(), false),
+            };
+            let (host, container) = spec.split_once(':').unwrap_or((spec, spec));
+            let flag = if readonly { "--ro-bind" } else { "--bind" };
+            args.extend([flag.to_string(), host.to_string(), container.to_string()]);
+        }
+
+        // Environment: a minimal base plus the configured variables
+        args.extend([
+            "--setenv".to_string(),
+            "PATH".to_string(),
+            SANDBOX_PATH.to_string(),
+        ]);
+        let home = config
+            .workdir
+            .as_ref()
+            .map(|w| w.to_string_lossy().to_string())
+            .unwrap_or_else(|| "/tmp".to_string());
+        args.extend(["--setenv".to_string(), "HOME".to_string(), home]);
+        for (key, value) in &config.env {
+            args.extend(["--setenv".to_string(), key.clone(), value.clone()]);
+        }
+
+        // Add working directory
+        if let Some(ref workdir) = config.workdir {
+            args.push("--chdir".to_string());
+            args.push(workdir.to_string_lossy().to_string());
+        }
+
+        args.push("sh".to_string());
+        args
+    }
+}
+
+impl Default for BubblewrapRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ContainerRuntime for BubblewrapRuntime {
+    fn name(&self) -> &str {
+        "bwrap"
+    }
+
+    async fn is_available(&self) -> bool {
+        // Check that bwrap is installed and user namespaces are permitted
+        Command::new("bwrap")
+            .args(["--unshare-user", "--ro-bind", "/", "/", "true"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .map(|s| s.success())
+            .unwrap_or(false)
+    }
+
+    async fn execute(
+        &self,
+        command: &str,
+        config: &ContainerConfig,
+    ) -> RuntimeResult<CommandOutput> {
+        let mut cmd = Command::new("bwrap");
+        cmd.args(self.build_args(config))
+            .arg("-c")
+            .arg(command)
+            .env_clear();
+
+        // Execute with timeout. Killing bwrap's process group on timeout or
+        // cancellation tears down the sandbox's PID namespace with it.
+        let output = output_with_timeout(cmd, config.timeout_secs, Teardown::ProcessGroup).await?;
+
+        Ok(CommandOutput::new(
+            String::from_utf8_lossy(&output.stdout).to_string(),
+            String::from_utf8_lossy(&output.stderr).to_string(),
+            output.status.code(),
+        ))
+    }
+
+    async fn start_session(
+        &self,
+        _session_id: &str,
+        config: &ContainerConfig,
+    ) -> RuntimeResult<Option<SessionLaunch>> {
+        // `--die-with-parent` ends the namespace with the shell, so no
+        // teardown is needed.
+        let mut shell = Command::new("bwrap");
+        shell.args(self.build_args(config)).env_clear();
+        Ok(Some(SessionLaunch::new(shell)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(args: &[String], needle: &[&str]) -> Option<usize> {
+        args.windows(needle.len())
+            .position(|w| w.iter().zip(needle).all(|(a, b)| a == b))
+    }
+
+    #[test]
+    fn test_bubblewrap_runtime_default() {
+        let runtime = BubblewrapRuntime::default();
+        assert_eq!(runtime.name(), "bwrap");
+        assert!(!runtime.network);
+        assert!(runtime.read_only_paths.contains(&PathBuf::from("/usr")));
+    }
+
+    #[test]
+    fn test_bubblewrap_build_args() {
+        let runtime = BubblewrapRuntime::new().with_extra_mounts(vec![
+            "/srv/data:/data:ro".to_string(),
+            "/srv/out:/out".to_string(),
+        ]);
+        let config = ContainerConfig::new()
+            .with_workdir(PathBuf::from("/home/u/ws"))
+            .with_mount(
+                PathBuf::from("/home/u/ws"),
+                PathBuf::from("/home/u/ws"),
+                false,
+            )
+            .with_env("FOO", "bar");
+
+        let args = runtime.build_args(&config);
+        assert!(position(&args, &["--unshare-all"]).is_some());
+        assert!(position(&args, &["--share-net"]).is_none());
+        assert!(position(&args, &["--ro-bind-try", "/usr", "/usr"]).is_some());
+        assert!(position(&args, &["--bind", "/home/u/ws", "/home/u/ws"]).is_some());
+        assert!(position(&args, &["--ro-bind", "/srv/data", "/data"]).is_some());
+        assert!(position(&args, &["--bind", "/srv/out", "/out"]).is_some());
+        assert!(position(&args, &["--setenv", "FOO", "bar"]).is_some());
+        assert!(position(&args, &["--chdir", "/home/u/ws"]).is_some());
+        assert_eq!(args.last().map(String::as_str), Some("sh"));
+    }
+
+    #[test]
+    fn test_bubblewrap_build_args_with_network() {
+        let args = BubblewrapRuntime::new()
+            .with_network(true)
+            .build_args(&ContainerConfig::new());
+        assert!(position(&args, &["--share-net"]).is_some());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires bwrap"]
+    async fn test_bubblewrap_runtime_isolation() {
+        let workspace = tempfile::tempdir().unwrap();
+        let path = workspace.path().to_path_buf();
+        let config = ContainerConfig::new()
+            .with_workdir(path.clone())
+            .with_mount(path.clone(), path, false);
+        let runtime = BubblewrapRuntime::new();
+
+        let output = runtime
+            .execute("echo hi > f && cat f && ls /root", &config)
+            .await
+            .unwrap();
+        assert_eq!(output.stdout.lines().next(), Some("hi"));
+        assert!(!output.success());
+    }
+}
@@ -0,0 +1,160 @@
+//! Cleanup for commands that time out or whose caller goes away.
+//!
+//! `kill_on_drop` only reaches the direct child. A shell that started a
+//! pipeline or background job leaves those processes running, and killing a
+//! `docker run` client leaves its container running. Runtimes therefore run
+//! one-shot commands through [`output_with_timeout`], which tears down the
+//! whole unit of work unless the command finishes normally.
+
+use std::process::{Output, Stdio};
+use std::time::Duration;
+use tokio::process::Command;
+
+use super::types::{RuntimeError, RuntimeResult};
+
+/// How to stop everything a command started.
+#[derive(Debug)]
+pub(super) enum Teardown {
+    /// Run the command as leader of a new process group and kill the group.
+    ProcessGroup,
+    /// Kill the named container with `<program> kill <name>`.
+    Container { program: &'static str, name: String },
+}
+
+impl Teardown {
+    /// Teardown for a container started with `--name <name>`.
+    pub fn container(program: &'static str, name: &str) -> Self {
+        Self::Container {
+            program,
+            name: name.to_string(),
+        }
+    }
+}
+
+/// A unique container name for a one-shot command.
+pub(super) fn exec_container_name() -> String {
+    format!("zeptoclaw-exec-{}", uuid::Uuid::new_v4().simple())
+}
+
+/// Runs `cmd` to completion within `timeout_secs`.
+///
+/// If the timeout fires or the returned future is dropped first, the
+/// command's process group or container is killed along with the child.
+pub(super) async fn output_with_timeout(
+    mut cmd: Command,
+    timeout_secs: u64,
+    teardown: Teardown,
+) -> RuntimeResult<Output> {
+    #[cfg(unix)]
+    if matches!(teardown, Teardown::ProcessGroup) {
+        cmd.process_group(0);
+    }
+    let child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| RuntimeError::ExecutionFailed(e.to_string()))?;
+
+    let mut guard = TeardownGuard {
+        teardown: Some(teardown),
+        pid: child.id(),
+    };
+    let output = tokio::time::timeout(Duration::from_secs(timeout_secs), child.wait_with_output())
+        .await
+        .map_err(|_| RuntimeError::Timeout(timeout_secs))?
+        .map_err(|e| RuntimeError::ExecutionFailed(e.to_string()))?;
+    guard.teardown = None;
+    Ok(output)
+}
+
+/// Runs its teardown on drop unless the command completed.
+struct TeardownGuard {
+    teardown: Option<Teardown>,
+    pid: Option<u32>,
+}
+
+impl Drop for TeardownGuard {
+    fn drop(&mut self) {
+        match self.teardown.take() {
+            None => {}
+            Some(Teardown::ProcessGroup) => {
+                // The leader has not been reaped yet (`kill_on_drop` reaps
+                // it later), so its pid still names this group.
+                #[cfg(unix)]
+                if let Some(pgid) = self.pid.and_then(|pid| libc::pid_t::try_from(pid).ok()) {
+                    // SAFETY: killpg has no memory-safety preconditions.
+                    unsafe {
+                        libc::killpg(pgid, libc::SIGKILL);
+                    }
+                }
+            }
+            Some(Teardown::Container { program, name }) => {
+                let mut kill = std::process::Command::new(program);
+                kill.args(["kill", &name])
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null());
+                match tokio::runtime::Handle::try_current() {
+                    Ok(handle) => {
+                        handle.spawn_blocking(move || kill.output());
+                    }
+                    Err(_) => {
+                        let _ = kill.output();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    /// Whether `pid` is still running (zombies count as gone).
+    fn is_running(pid: u32) -> bool {
+        std::fs::read_to_string(format!("/proc/{}/stat", pid))
+            .ok()
+            .and_then(|stat| {
+                stat.rsplit_once(')')
+                    .and_then(|(_, rest)| rest.split_whitespace().next().map(str::to_string))
+            })
+            .is_some_and(|state| state != "Z")
+    }
+
+    async fn background_pid(cancel_after: Duration, pid_file: &std::path::Path) -> u32 {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg(format!("sleep 60 & echo $! > {}; wait", pid_file.display()));
+        let run = output_with_timeout(cmd, 60, Teardown::ProcessGroup);
+        assert!(tokio::time::timeout(cancel_after, run).await.is_err());
+        std::fs::read_to_string(pid_file)
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_command_kills_background_children() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("pid");
+        let pid = background_pid(Duration::from_millis(500), &pid_file).await;
+
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while is_running(pid) && std::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert!(!is_running(pid), "background sleep {} survived", pid);
+    }
+
+    #[tokio::test]
+    async fn test_completed_command_leaves_group_alone() {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("echo done");
+        let output = output_with_timeout(cmd, 5, Teardown::ProcessGroup)
+            .await
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "done");
+    }
+}
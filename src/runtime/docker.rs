@@ -4,9 +4,10 @@
 
 use async_trait::async_trait;
 use std::process::Stdio;
-use std::time::Duration;
 use tokio::process::Command;
 
+use super::cancel::{exec_container_name, output_with_timeout, Teardown};
+use super::session::SessionLaunch;
 use super::types::{CommandOutput, ContainerConfig, ContainerRuntime, RuntimeError, RuntimeResult};
 
 /// Docker runtime that executes commands in isolated containers
@@ -66,43 +67,11 @@ impl DockerRuntime {
         self.cpu_limit = None;
         self
     }
-}
-
-impl Default for DockerRuntime {
-    fn default() -> Self {
-        Self::new("alpine:latest")
-    }
-}
 
-#[async_trait]
-impl ContainerRuntime for DockerRuntime {
-    fn name(&self) -> &str {
-        "docker"
-    }
-
-    async fn is_available(&self) -> bool {
-        // Check if docker is installed and running
-        Command::new("docker")
-            .args(["info"])
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status()
-            .await
-            .map(|s| s.success())
-            .unwrap_or(false)
-    }
-
-    async fn execute(
-        &self,
-        command: &str,
-        config: &ContainerConfig,
-    ) -> RuntimeResult<CommandOutput> {
-        let mut args = vec![
-            "run".to_string(),
-            "--rm".to_string(),
-            "--network".to_string(),
-            self.network.clone(),
-        ];
+    /// Network, resource limit, workdir and mount options shared by
+    /// one-shot and session containers.
+    fn container_options(&self, config: &ContainerConfig) -> Vec<String> {
+        let mut args = vec!["--network".to_string(), self.network.clone()];
 
         // Add resource limits
         if let Some(ref mem) = self.memory_limit {
@@ -140,6 +109,47 @@ impl ContainerRuntime for DockerRuntime {
             args.push("-v".to_string());
             args.push(mount.clone());
         }
+        args
+    }
+}
+
+impl Default for DockerRuntime {
+    fn default() -> Self {
+        Self::new("alpine:latest")
+    }
+}
+
+#[async_trait]
+impl ContainerRuntime for DockerRuntime {
+    fn name(&self) -> &str {
+        "docker"
+    }
+
+    async fn is_available(&self) -> bool {
+        // Check if docker is installed and running
+        Command::new("docker")
+            .args(["info"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .map(|s| s.success())
+            .unwrap_or(false)
+    }
+
+    async fn execute(
+        &self,
+        command: &str,
+        config: &ContainerConfig,
+    ) -> RuntimeResult<CommandOutput> {
+        let name = exec_container_name();
+        let mut args = vec![
+            "run".to_string(),
+            "--rm".to_string(),
+            "--name".to_string(),
+            name.clone(),
+        ];
+        args.extend(self.container_options(config));
 
         // Add environment variables
         for (key, value) in &config.env {
@@ -154,15 +164,16 @@ impl ContainerRuntime for DockerRuntime {
         args.push(command.to_string());
 
         let mut cmd = Command::new("docker");
-        cmd.args(&args)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+        cmd.args(&args);
 
-        // Execute with timeout
-        let output = tokio::time::timeout(Duration::from_secs(config.timeout_secs), cmd.output())
-            .await
-            .map_err(|_| RuntimeError::Timeout(config.timeout_secs))?
-            .map_err(|e| RuntimeError::ExecutionFailed(e.to_string()))?;
+        // Execute with timeout; killing the client alone would leave the
+        // container running, so it is killed by name on timeout or cancel
+        let output = output_with_timeout(
+            cmd,
+            config.timeout_secs,
+            Teardown::container("docker", &name),
+        )
+        .await?;
 
         Ok(CommandOutput::new(
             String::from_utf8_lossy(&output.stdout).to_string(),
@@ -170,6 +181,63 @@ impl ContainerRuntime for DockerRuntime {
             output.status.code(),
         ))
     }
+
+    async fn start_session(
+        &self,
+        _session_id: &str,
+        config: &ContainerConfig,
+    ) -> RuntimeResult<Option<SessionLaunch>> {
+        // A detached container that idles until removed; commands reach it
+        // through `docker exec`.
+        let name = format!("zeptoclaw-session-{}", uuid::Uuid::new_v4().simple());
+        let mut args = vec![
+            "run".to_string(),
+            "-d".to_string(),
+            "--rm".to_string(),
+            "--name".to_string(),
+            name.clone(),
+            "--label".to_string(),
+            "zeptoclaw.session=true".to_string(),
+        ];
+        args.extend(self.container_options(config));
+        args.extend([
+            self.image.clone(),
+            "tail".to_string(),
+            "-f".to_string(),
+            "/dev/null".to_string(),
+        ]);
+
+        let started = Command::new("docker")
+            .args(&args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()
+            .await
+            .map_err(|e| RuntimeError::StartFailed(e.to_string()))?;
+        if !started.status.success() {
+            return Err(RuntimeError::StartFailed(
+                String::from_utf8_lossy(&started.stderr).trim().to_string(),
+            ));
+        }
+
+        let mut shell = Command::new("docker");
+        shell.args(["exec", "-i"]);
+        if let Some(ref workdir) = config.workdir {
+            shell.arg("-w").arg(workdir);
+        }
+        for (key, value) in &config.env {
+            shell.arg("-e").arg(format!("{}={}", key, value));
+        }
+        shell.arg(&name).arg("sh");
+
+        let mut teardown = std::process::Command::new("docker");
+        teardown
+            .args(["rm", "-f", &name])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+
+        Ok(Some(SessionLaunch::new(shell).with_teardown(teardown)))
+    }
 }
 
 #[cfg(test)]
@@ -7,10 +7,15 @@ use crate::security::validate_extra_mounts;
 
 use super::docker::DockerRuntime;
 use super::native::NativeRuntime;
+use super::podman::PodmanRuntime;
 use super::types::{ContainerRuntime, RuntimeError, RuntimeResult};
 
 #[cfg(target_os = "macos")]
 use super::apple::AppleContainerRuntime;
+#[cfg(target_os = "linux")]
+use super::bubblewrap::BubblewrapRuntime;
+#[cfg(target_os = "linux")]
+use super::sandbox::{is_deniable_syscall, SandboxRuntime};
 
 /// Create a container runtime from configuration
 pub async fn create_runtime(config: &RuntimeConfig) -> RuntimeResult<Arc<dyn ContainerRuntime>> {
@@ -80,6 +85,112 @@ pub async fn create_runtime(config: &RuntimeConfig) -> RuntimeResult<Arc<dyn Con
                 ))
             }
         }
+        RuntimeType::Sandbox => {
+            #[cfg(target_os = "linux")]
+            {
+                let sandbox = &config.sandbox;
+                if let Some(name) = sandbox
+                    .allowed_syscalls
+                    .iter()
+                    .find(|name| !is_deniable_syscall(name))
+                {
+                    return Err(RuntimeError::NotAvailable(format!(
+                        "runtime.sandbox.allowed_syscalls: '{}' is not a syscall the sandbox denies",
+                        name
+                    )));
+                }
+
+                let runtime = SandboxRuntime::new()
+                    .with_read_only_paths(
+                        sandbox
+                            .read_only_paths
+                            .iter()
+                            .map(|p| crate::config::expand_home(p))
+                            .collect(),
+                    )
+                    .with_network(sandbox.allow_network)
+                    .with_allowed_syscalls(sandbox.allowed_syscalls.clone())
+                    .with_cpu_time_limit(sandbox.cpu_time_secs)
+                    .with_memory_limit_mb(sandbox.memory_limit_mb)
+                    .with_max_processes(sandbox.max_processes);
+
+                if let Some(reason) = SandboxRuntime::unavailable_reason() {
+                    return Err(RuntimeError::NotAvailable(reason));
+                }
+
+                Ok(Arc::new(runtime))
+            }
+            #[cfg(not(target_os = "linux"))]
+            {
+                Err(RuntimeError::NotAvailable(
+                    "Sandbox runtime is only available on Linux".to_string(),
+                ))
+            }
+        }
+        RuntimeType::Podman => {
+            let extra_mounts =
+                validate_extra_mounts(&config.podman.extra_mounts, &config.mount_allowlist_path)
+                    .map_err(|e| RuntimeError::NotAvailable(e.to_string()))?;
+
+            let runtime = PodmanRuntime::new(&config.podman.image)
+                .with_network(&config.podman.network)
+                .with_extra_mounts(extra_mounts);
+
+            let runtime = if let Some(ref mem) = config.podman.memory_limit {
+                runtime.with_memory_limit(mem)
+            } else {
+                runtime
+            };
+
+            let runtime = if let Some(ref cpu) = config.podman.cpu_limit {
+                runtime.with_cpu_limit(cpu)
+            } else {
+                runtime
+            };
+
+            if !runtime.is_available().await {
+                return Err(RuntimeError::NotAvailable(
+                    "Podman is not installed or not configured for this user".to_string(),
+                ));
+            }
+
+            Ok(Arc::new(runtime))
+        }
+        RuntimeType::Bubblewrap => {
+            #[cfg(target_os = "linux")]
+            {
+                let extra_mounts =
+                    validate_extra_mounts(&config.bwrap.extra_mounts, &config.mount_allowlist_path)
+                        .map_err(|e| RuntimeError::NotAvailable(e.to_string()))?;
+
+                let runtime = BubblewrapRuntime::new()
+                    .with_read_only_paths(
+                        config
+                            .bwrap
+                            .read_only_paths
+                            .iter()
+                            .map(|p| crate::config::expand_home(p))
+                            .collect(),
+                    )
+                    .with_network(config.bwrap.network)
+                    .with_extra_mounts(extra_mounts);
+
+                if !runtime.is_available().await {
+                    return Err(RuntimeError::NotAvailable(
+                        "bwrap is not installed or unprivileged user namespaces are disabled"
+                            .to_string(),
+                    ));
+                }
+
+                Ok(Arc::new(runtime))
+            }
+            #[cfg(not(target_os = "linux"))]
+            {
+                Err(RuntimeError::NotAvailable(
+                    "Bubblewrap runtime is only available on Linux".to_string(),
+                ))
+            }
+        }
     }
 }
 
@@ -93,6 +204,12 @@ pub async fn available_runtimes() -> Vec<&'static str> {
         available.push("docker");
     }
 
+    // Check Podman
+    let podman = PodmanRuntime::default();
+    if podman.is_available().await {
+        available.push("podman");
+    }
+
     // Check Apple Container (macOS only)
     #[cfg(target_os = "macos")]
     {
@@ -102,6 +219,17 @@ pub async fn available_runtimes() -> Vec<&'static str> {
         }
     }
 
+    // Check Landlock/seccomp sandbox and Bubblewrap (Linux only)
+    #[cfg(target_os = "linux")]
+    {
+        if SandboxRuntime::unavailable_reason().is_none() {
+            available.push("sandbox");
+        }
+        if BubblewrapRuntime::default().is_available().await {
+            available.push("bwrap");
+        }
+    }
+
     available
 }
 
@@ -150,6 +278,75 @@ mod tests {
         assert!(config.allow_experimental);
     }
 
+    #[tokio::test]
+    async fn test_create_sandbox_runtime() {
+        let config = RuntimeConfig {
+            runtime_type: RuntimeType::Sandbox,
+            ..Default::default()
+        };
+        match create_runtime(&config).await {
+            Ok(runtime) => assert_eq!(runtime.name(), "sandbox"),
+            Err(err) => assert!(matches!(err, RuntimeError::NotAvailable(_))),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_create_sandbox_runtime_rejects_unknown_syscall() {
+        let mut config = RuntimeConfig {
+            runtime_type: RuntimeType::Sandbox,
+            ..Default::default()
+        };
+        config.sandbox.allowed_syscalls.push("read".to_string());
+
+        let err_text = create_runtime(&config)
+            .await
+            .err()
+            .map(|e| e.to_string())
+            .unwrap_or_default();
+        assert!(err_text.contains("allowed_syscalls"));
+    }
+
+    #[tokio::test]
+    async fn test_create_podman_runtime_with_extra_mounts_requires_allowlist() {
+        let mut config = RuntimeConfig {
+            runtime_type: RuntimeType::Podman,
+            mount_allowlist_path: "/nonexistent/allowlist.json".to_string(),
+            ..Default::default()
+        };
+        config
+            .podman
+            .extra_mounts
+            .push("/tmp:/workspace/tmp".to_string());
+
+        let result = create_runtime(&config).await;
+        let err_text = result.err().map(|err| err.to_string()).unwrap_or_default();
+        assert!(err_text.contains("allowlist"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_create_bwrap_runtime_with_extra_mounts_requires_allowlist() {
+        let mut config = RuntimeConfig {
+            runtime_type: RuntimeType::Bubblewrap,
+            mount_allowlist_path: "/nonexistent/allowlist.json".to_string(),
+            ..Default::default()
+        };
+        config.bwrap.extra_mounts.push("/tmp:/data".to_string());
+
+        let result = create_runtime(&config).await;
+        let err_text = result.err().map(|err| err.to_string()).unwrap_or_default();
+        assert!(err_text.contains("allowlist"));
+    }
+
+    #[test]
+    fn test_runtime_type_serde_names() {
+        let podman: RuntimeType = serde_json::from_str("\"podman\"").unwrap();
+        assert_eq!(podman, RuntimeType::Podman);
+        let bwrap: RuntimeType = serde_json::from_str("\"bwrap\"").unwrap();
+        assert_eq!(bwrap, RuntimeType::Bubblewrap);
+    }
+
     #[tokio::test]
     async fn test_create_docker_runtime_with_extra_mounts_requires_allowlist() {
         let mut config = RuntimeConfig::default();
@@ -5,17 +5,36 @@
 //! - Native: Direct execution (no isolation, uses application-level security)
 //! - Docker: Docker container isolation (Linux, macOS, Windows)
 //! - Apple Container: Apple's native container technology (macOS only)
+//! - Sandbox: Landlock + seccomp confined native execution (Linux only)
+//! - Podman: Rootless, daemonless container isolation
+//! - Bubblewrap: Unprivileged namespace isolation via `bwrap` (Linux only)
+//!
+//! Any runtime can be wrapped in a `SessionRuntime` to keep one persistent
+//! shell per agent session.
 
 #[cfg(target_os = "macos")]
 pub mod apple;
+#[cfg(target_os = "linux")]
+pub mod bubblewrap;
+mod cancel;
 pub mod docker;
 pub mod factory;
 pub mod native;
+pub mod podman;
+#[cfg(target_os = "linux")]
+pub mod sandbox;
+pub mod session;
 pub mod types;
 
 #[cfg(target_os = "macos")]
 pub use apple::AppleContainerRuntime;
+#[cfg(target_os = "linux")]
+pub use bubblewrap::BubblewrapRuntime;
 pub use docker::DockerRuntime;
 pub use factory::{available_runtimes, create_runtime};
 pub use native::NativeRuntime;
+pub use podman::PodmanRuntime;
+#[cfg(target_os = "linux")]
+pub use sandbox::SandboxRuntime;
+pub use session::{shutdown_sessions, SessionLaunch, SessionRuntime};
 pub use types::{CommandOutput, ContainerConfig, ContainerRuntime, RuntimeError, RuntimeResult};
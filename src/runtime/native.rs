@@ -4,11 +4,11 @@
 //! This is the fallback when no container runtime is configured.
 
 use async_trait::async_trait;
-use std::process::Stdio;
-use std::time::Duration;
 use tokio::process::Command;
 
-use super::types::{CommandOutput, ContainerConfig, ContainerRuntime, RuntimeError, RuntimeResult};
+use super::cancel::{output_with_timeout, Teardown};
+use super::session::SessionLaunch;
+use super::types::{CommandOutput, ContainerConfig, ContainerRuntime, RuntimeResult};
 
 /// Native runtime that executes commands directly on the host
 #[derive(Debug, Clone, Default)]
@@ -50,14 +50,9 @@ impl ContainerRuntime for NativeRuntime {
             cmd.env(key, value);
         }
 
-        // Capture output
-        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
-
-        // Execute with timeout
-        let output = tokio::time::timeout(Duration::from_secs(config.timeout_secs), cmd.output())
-            .await
-            .map_err(|_| RuntimeError::Timeout(config.timeout_secs))?
-            .map_err(|e| RuntimeError::ExecutionFailed(e.to_string()))?;
+        // Execute with timeout; the command's whole process group is killed
+        // if the call is timed out or cancelled
+        let output = output_with_timeout(cmd, config.timeout_secs, Teardown::ProcessGroup).await?;
 
         Ok(CommandOutput::new(
             String::from_utf8_lossy(&output.stdout).to_string(),
@@ -65,11 +60,27 @@ impl ContainerRuntime for NativeRuntime {
             output.status.code(),
         ))
     }
+
+    async fn start_session(
+        &self,
+        _session_id: &str,
+        config: &ContainerConfig,
+    ) -> RuntimeResult<Option<SessionLaunch>> {
+        let mut shell = Command::new("sh");
+        if let Some(ref workdir) = config.workdir {
+            shell.current_dir(workdir);
+        }
+        for (key, value) in &config.env {
+            shell.env(key, value);
+        }
+        Ok(Some(SessionLaunch::new(shell)))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runtime::RuntimeError;
 
     #[tokio::test]
     async fn test_native_runtime_available() {
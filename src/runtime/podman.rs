@@ -0,0 +1,307 @@
+//! Podman runtime implementation
+//!
+//! Executes commands inside rootless Podman containers. Podman is daemonless,
+//! so it works on hosts where the Docker daemon is not allowed.
+
+use async_trait::async_trait;
+use std::process::Stdio;
+use tokio::process::Command;
+
+use super::cancel::{exec_container_name, output_with_timeout, Teardown};
+use super::session::SessionLaunch;
+use super::types::{CommandOutput, ContainerConfig, ContainerRuntime, RuntimeError, RuntimeResult};
+
+/// Podman runtime that executes commands in isolated rootless containers
+#[derive(Debug, Clone)]
+pub struct PodmanRuntime {
+    /// Container image to use
+    image: String,
+    /// Memory limit (e.g., "512m")
+    memory_limit: Option<String>,
+    /// CPU limit (e.g., "1.0")
+    cpu_limit: Option<String>,
+    /// Network mode
+    network: String,
+    /// Extra volume mounts from config (host:container or host:container:ro format)
+    extra_mounts: Vec<String>,
+}
+
+impl PodmanRuntime {
+    /// Create a new Podman runtime with the specified image
+    pub fn new(image: &str) -> Self {
+        Self {
+            image: image.to_string(),
+            memory_limit: Some("512m".to_string()),
+            cpu_limit: Some("1.0".to_string()),
+            network: "none".to_string(),
+            extra_mounts: Vec::new(),
+        }
+    }
+
+    /// Set memory limit
+    pub fn with_memory_limit(mut self, limit: &str) -> Self {
+        self.memory_limit = Some(limit.to_string());
+        self
+    }
+
+    /// Set CPU limit
+    pub fn with_cpu_limit(mut self, limit: &str) -> Self {
+        self.cpu_limit = Some(limit.to_string());
+        self
+    }
+
+    /// Set network mode
+    pub fn with_network(mut self, network: &str) -> Self {
+        self.network = network.to_string();
+        self
+    }
+
+    /// Add extra volume mounts (host:container or host:container:ro format)
+    pub fn with_extra_mounts(mut self, mounts: Vec<String>) -> Self {
+        self.extra_mounts = mounts;
+        self
+    }
+
+    /// Disable resource limits
+    pub fn without_limits(mut self) -> Self {
+        self.memory_limit = None;
+        self.cpu_limit = None;
+        self
+    }
+
+    /// Build the `podman run` arguments for a command in container `name`.
+    fn build_args(&self, name: &str, command: &str, config: &ContainerConfig) -> Vec<String> {
+        let mut args = vec![
+            "run".to_string(),
+            "--rm".to_string(),
+            "--name".to_string(),
+            name.to_string(),
+            // Podman stops the container itself if the client is killed
+            // before it finishes.
+            "--timeout".to_string(),
+            config.timeout_secs.to_string(),
+        ];
+        args.extend(self.container_options(config));
+
+        // Add environment variables
+        for (key, value) in &config.env {
+            args.push("-e".to_string());
+            args.push(format!("{}={}", key, value));
+        }
+
+        // Add image and command
+        args.push(self.image.clone());
+        args.push("sh".to_string());
+        args.push("-c".to_string());
+        args.push(command.to_string());
+        args
+    }
+
+    /// Network, resource limit, workdir and mount options shared by
+    /// one-shot and session containers.
+    fn container_options(&self, config: &ContainerConfig) -> Vec<String> {
+        let mut args = vec![
+            "--network".to_string(),
+            self.network.clone(),
+            "--security-opt".to_string(),
+            "no-new-privileges".to_string(),
+        ];
+
+        // Add resource limits
+        if let Some(ref mem) = self.memory_limit {
+            args.push("--memory".to_string());
+            args.push(mem.clone());
+        }
+        if let Some(ref cpu) = self.cpu_limit {
+            args.push("--cpus".to_string());
+            args.push(cpu.clone());
+        }
+
+        // Add working directory
+        if let Some(ref workdir) = config.workdir {
+            args.push("-w".to_string());
+            args.push(workdir.to_string_lossy().to_string());
+        }
+
+        // Add volume mounts from ContainerConfig
+        for (host, container, readonly) in &config.mounts {
+            let mount_spec = if *readonly {
+                format!(
+                    "{}:{}:ro",
+                    host.to_string_lossy(),
+                    container.to_string_lossy()
+                )
+            } else {
+                format!("{}:{}", host.to_string_lossy(), container.to_string_lossy())
+            };
+            args.push("-v".to_string());
+            args.push(mount_spec);
+        }
+
+        // Add extra mounts from runtime config (host:container or host:container:ro format)
+        for mount in &self.extra_mounts {
+            args.push("-v".to_string());
+            args.push(mount.clone());
+        }
+        args
+    }
+}
+
+impl Default for PodmanRuntime {
+    fn default() -> Self {
+        Self::new("docker.io/library/alpine:latest")
+    }
+}
+
+#[async_trait]
+impl ContainerRuntime for PodmanRuntime {
+    fn name(&self) -> &str {
+        "podman"
+    }
+
+    async fn is_available(&self) -> bool {
+        // Check if podman is installed and can reach its storage
+        Command::new("podman")
+            .args(["info"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .map(|s| s.success())
+            .unwrap_or(false)
+    }
+
+    async fn execute(
+        &self,
+        command: &str,
+        config: &ContainerConfig,
+    ) -> RuntimeResult<CommandOutput> {
+        let name = exec_container_name();
+        let mut cmd = Command::new("podman");
+        cmd.args(self.build_args(&name, command, config));
+
+        // Execute with timeout; the container is killed by name if the call
+        // is timed out or cancelled
+        let output = output_with_timeout(
+            cmd,
+            config.timeout_secs,
+            Teardown::container("podman", &name),
+        )
+        .await?;
+
+        Ok(CommandOutput::new(
+            String::from_utf8_lossy(&output.stdout).to_string(),
+            String::from_utf8_lossy(&output.stderr).to_string(),
+            output.status.code(),
+        ))
+    }
+
+    async fn start_session(
+        &self,
+        _session_id: &str,
+        config: &ContainerConfig,
+    ) -> RuntimeResult<Option<SessionLaunch>> {
+        // A detached container that idles until removed; commands reach it
+        // through `podman exec`.
+        let name = format!("zeptoclaw-session-{}", uuid::Uuid::new_v4().simple());
+        let mut args = vec![
+            "run".to_string(),
+            "-d".to_string(),
+            "--rm".to_string(),
+            "--name".to_string(),
+            name.clone(),
+            "--label".to_string(),
+            "zeptoclaw.session=true".to_string(),
+        ];
+        args.extend(self.container_options(config));
+        args.extend([
+            self.image.clone(),
+            "tail".to_string(),
+            "-f".to_string(),
+            "/dev/null".to_string(),
+        ]);
+
+        let started = Command::new("podman")
+            .args(&args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .output()
+            .await
+            .map_err(|e| RuntimeError::StartFailed(e.to_string()))?;
+        if !started.status.success() {
+            return Err(RuntimeError::StartFailed(
+                String::from_utf8_lossy(&started.stderr).trim().to_string(),
+            ));
+        }
+
+        let mut shell = Command::new("podman");
+        shell.args(["exec", "-i"]);
+        if let Some(ref workdir) = config.workdir {
+            shell.arg("-w").arg(workdir);
+        }
+        for (key, value) in &config.env {
+            shell.arg("-e").arg(format!("{}={}", key, value));
+        }
+        shell.arg(&name).arg("sh");
+
+        let mut teardown = std::process::Command::new("podman");
+        teardown
+            .args(["rm", "-f", &name])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+
+        Ok(Some(SessionLaunch::new(shell).with_teardown(teardown)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_podman_runtime_default() {
+        let runtime = PodmanRuntime::default();
+        assert_eq!(runtime.name(), "podman");
+        assert_eq!(runtime.image, "docker.io/library/alpine:latest");
+        assert_eq!(runtime.network, "none");
+        assert_eq!(runtime.memory_limit, Some("512m".to_string()));
+    }
+
+    #[test]
+    fn test_podman_build_args() {
+        let runtime = PodmanRuntime::new("alpine")
+            .without_limits()
+            .with_extra_mounts(vec!["/data:/data:ro".to_string()]);
+        let config = ContainerConfig::new()
+            .with_workdir(PathBuf::from("/workspace"))
+            .with_mount(
+                PathBuf::from("/home/u/ws"),
+                PathBuf::from("/workspace"),
+                false,
+            )
+            .with_env("FOO", "bar")
+            .with_timeout(30);
+
+        let args = runtime.build_args("zc-test", "echo hi", &config).join(" ");
+        assert!(args.starts_with("run --rm --name zc-test --timeout 30 --network none"));
+        assert!(args.contains("-w /workspace"));
+        assert!(args.contains("-v /home/u/ws:/workspace"));
+        assert!(args.contains("-v /data:/data:ro"));
+        assert!(args.contains("-e FOO=bar"));
+        assert!(args.ends_with("alpine sh -c echo hi"));
+        assert!(!args.contains("--memory"));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Podman"]
+    async fn test_podman_runtime_echo() {
+        let runtime = PodmanRuntime::default();
+        let output = runtime
+            .execute("echo hello", &ContainerConfig::new())
+            .await
+            .unwrap();
+        assert!(output.success());
+        assert_eq!(output.stdout.trim(), "hello");
+    }
+}
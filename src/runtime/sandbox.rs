@@ -0,0 +1,821 @@
+//! Sandbox runtime implementation (Linux only)
+//!
+//! Executes commands on the host like the native runtime, but confines them
+//! with kernel features that need no daemon or root privileges:
+//! - Landlock restricts filesystem access to the workspace (read-write) and
+//!   a set of read-only paths.
+//! - A seccomp filter denies network sockets and dangerous syscalls
+//!   (mounting, tracing, kernel modules, namespaces, ...).
+//! - rlimits cap CPU time, address space and the processes a command may
+//!   start.
+//!
+//! The restrictions are applied between `fork` and `exec`, so they cover the
+//! shell and everything it spawns but never the agent process itself.
+
+use async_trait::async_trait;
+use std::fs::OpenOptions;
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+use super::cancel::{output_with_timeout, Teardown};
+use super::types::{CommandOutput, ContainerConfig, ContainerRuntime, RuntimeError, RuntimeResult};
+
+// Landlock ABI (include/uapi/linux/landlock.h); not exposed by libc.
+const LANDLOCK_CREATE_RULESET_VERSION: u32 = 1 << 0;
+const LANDLOCK_RULE_PATH_BENEATH: u32 = 1;
+
+const ACCESS_FS_EXECUTE: u64 = 1 << 0;
+const ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+const ACCESS_FS_READ_FILE: u64 = 1 << 2;
+const ACCESS_FS_READ_DIR: u64 = 1 << 3;
+/// All access rights defined by Landlock ABI v1 (bits 0 through 12).
+const ACCESS_FS_ABI_V1: u64 = (1 << 13) - 1;
+const ACCESS_FS_REFER: u64 = 1 << 13;
+const ACCESS_FS_TRUNCATE: u64 = 1 << 14;
+const ACCESS_FS_IOCTL_DEV: u64 = 1 << 15;
+
+/// Rights that may be granted on a regular file (the rest only apply to
+/// directories).
+const ACCESS_FS_FILE: u64 = ACCESS_FS_EXECUTE
+    | ACCESS_FS_WRITE_FILE
+    | ACCESS_FS_READ_FILE
+    | ACCESS_FS_TRUNCATE
+    | ACCESS_FS_IOCTL_DEV;
+const ACCESS_FS_READ_ONLY: u64 = ACCESS_FS_EXECUTE | ACCESS_FS_READ_FILE | ACCESS_FS_READ_DIR;
+
+#[repr(C)]
+struct LandlockRulesetAttr {
+    handled_access_fs: u64,
+}
+
+#[repr(C, packed)]
+struct LandlockPathBeneathAttr {
+    allowed_access: u64,
+    parent_fd: i32,
+}
+
+// Seccomp filter plumbing (linux/audit.h, linux/seccomp.h).
+const AUDIT_ARCH_X86_64: u32 = 0xC000_003E;
+#[cfg(target_arch = "x86_64")]
+const AUDIT_ARCH: Option<u32> = Some(AUDIT_ARCH_X86_64);
+#[cfg(target_arch = "aarch64")]
+const AUDIT_ARCH: Option<u32> = Some(0xC000_00B7);
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+const AUDIT_ARCH: Option<u32> = None;
+
+/// Offsets into `struct seccomp_data`.
+const SECCOMP_DATA_NR: u32 = 0;
+const SECCOMP_DATA_ARCH: u32 = 4;
+const SECCOMP_DATA_ARG0: u32 = 16;
+
+/// Set in the syscall number of x32-ABI calls on x86_64. Those share the
+/// x86_64 audit arch, so without an explicit check they would slip past
+/// every number-based rule.
+const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+/// `setrlimit` takes a different resource type on glibc and musl.
+#[cfg(target_env = "gnu")]
+type RlimitResource = libc::__rlimit_resource_t;
+#[cfg(not(target_env = "gnu"))]
+type RlimitResource = libc::c_int;
+
+/// Device files every command gets: `(path, writable)`.
+const DEVICE_PATHS: &[(&str, bool)] = &[
+    ("/dev/null", true),
+    ("/dev/zero", false),
+    ("/dev/urandom", false),
+];
+
+/// Syscalls denied unless listed in `allowed_syscalls`.
+const DENIED_SYSCALLS: &[(&str, libc::c_long)] = &[
+    ("ptrace", libc::SYS_ptrace),
+    ("process_vm_readv", libc::SYS_process_vm_readv),
+    ("process_vm_writev", libc::SYS_process_vm_writev),
+    ("mount", libc::SYS_mount),
+    ("umount2", libc::SYS_umount2),
+    ("pivot_root", libc::SYS_pivot_root),
+    ("chroot", libc::SYS_chroot),
+    ("fsopen", libc::SYS_fsopen),
+    ("fsmount", libc::SYS_fsmount),
+    ("move_mount", libc::SYS_move_mount),
+    ("open_tree", libc::SYS_open_tree),
+    ("unshare", libc::SYS_unshare),
+    ("setns", libc::SYS_setns),
+    ("reboot", libc::SYS_reboot),
+    ("kexec_load", libc::SYS_kexec_load),
+    ("kexec_file_load", libc::SYS_kexec_file_load),
+    ("init_module", libc::SYS_init_module),
+    ("finit_module", libc::SYS_finit_module),
+    ("delete_module", libc::SYS_delete_module),
+    ("swapon", libc::SYS_swapon),
+    ("swapoff", libc::SYS_swapoff),
+    ("acct", libc::SYS_acct),
+    ("bpf", libc::SYS_bpf),
+    ("perf_event_open", libc::SYS_perf_event_open),
+    ("userfaultfd", libc::SYS_userfaultfd),
+    ("io_uring_setup", libc::SYS_io_uring_setup),
+    ("keyctl", libc::SYS_keyctl),
+    ("add_key", libc::SYS_add_key),
+    ("request_key", libc::SYS_request_key),
+    ("open_by_handle_at", libc::SYS_open_by_handle_at),
+    ("settimeofday", libc::SYS_settimeofday),
+    ("clock_settime", libc::SYS_clock_settime),
+    ("sethostname", libc::SYS_sethostname),
+    ("setdomainname", libc::SYS_setdomainname),
+    #[cfg(target_arch = "x86_64")]
+    ("iopl", libc::SYS_iopl),
+    #[cfg(target_arch = "x86_64")]
+    ("ioperm", libc::SYS_ioperm),
+];
+
+/// Returns true if `name` is a syscall the sandbox denies by default.
+pub fn is_deniable_syscall(name: &str) -> bool {
+    DENIED_SYSCALLS.iter().any(|(n, _)| *n == name)
+}
+
+/// Sandbox runtime that executes commands on the host under Landlock,
+/// seccomp and rlimits
+#[derive(Debug, Clone)]
+pub struct SandboxRuntime {
+    /// Paths readable (and executable) by sandboxed commands
+    read_only_paths: Vec<PathBuf>,
+    /// Whether network sockets are allowed
+    allow_network: bool,
+    /// Syscalls removed from the deny list
+    allowed_syscalls: Vec<String>,
+    /// RLIMIT_CPU in seconds
+    cpu_time_secs: Option<u64>,
+    /// RLIMIT_AS in megabytes
+    memory_limit_mb: Option<u64>,
+    /// Processes a command may start on top of the user's current count
+    /// (RLIMIT_NPROC)
+    max_processes: Option<u64>,
+}
+
+impl SandboxRuntime {
+    /// Create a new sandbox runtime with the default read-only system paths
+    pub fn new() -> Self {
+        Self {
+            read_only_paths: ["/usr", "/bin", "/sbin", "/lib", "/lib64", "/etc"]
+                .iter()
+                .map(PathBuf::from)
+                .collect(),
+            allow_network: false,
+            allowed_syscalls: Vec::new(),
+            cpu_time_secs: Some(60),
+            memory_limit_mb: Some(1024),
+            max_processes: Some(128),
+        }
+    }
+
+    /// Replace the read-only paths
+    pub fn with_read_only_paths(mut self, paths: Vec<PathBuf>) -> Self {
+        self.read_only_paths = paths;
+        self
+    }
+
+    /// Allow or deny network sockets
+    pub fn with_network(mut self, allow: bool) -> Self {
+        self.allow_network = allow;
+        self
+    }
+
+    /// Permit syscalls that are denied by default
+    pub fn with_allowed_syscalls(mut self, syscalls: Vec<String>) -> Self {
+        self.allowed_syscalls = syscalls;
+        self
+    }
+
+    /// Set the CPU time limit in seconds
+    pub fn with_cpu_time_limit(mut self, secs: Option<u64>) -> Self {
+        self.cpu_time_secs = secs;
+        self
+    }
+
+    /// Set the address space limit in megabytes
+    pub fn with_memory_limit_mb(mut self, mb: Option<u64>) -> Self {
+        self.memory_limit_mb = mb;
+        self
+    }
+
+    /// Set how many processes a command may start
+    pub fn with_max_processes(mut self, max: Option<u64>) -> Self {
+        self.max_processes = max;
+        self
+    }
+
+    /// Disable resource limits
+    pub fn without_limits(mut self) -> Self {
+        self.cpu_time_secs = None;
+        self.memory_limit_mb = None;
+        self.max_processes = None;
+        self
+    }
+
+    /// Explains why the sandbox cannot run here, or `None` if it can.
+    pub fn unavailable_reason() -> Option<String> {
+        if AUDIT_ARCH.is_none() {
+            return Some(format!(
+                "seccomp filtering is not supported on {}",
+                std::env::consts::ARCH
+            ));
+        }
+        if landlock_abi_version().is_none() {
+            return Some(
+                "Landlock is not supported or not enabled by this kernel (requires Linux 5.13+ with the landlock LSM)"
+                    .to_string(),
+            );
+        }
+        None
+    }
+
+    /// Builds the Landlock ruleset for one command.
+    fn build_ruleset(&self, abi: u32, config: &ContainerConfig) -> RuntimeResult<OwnedFd> {
+        let mut handled = ACCESS_FS_ABI_V1;
+        if abi >= 2 {
+            handled |= ACCESS_FS_REFER;
+        }
+        if abi >= 3 {
+            handled |= ACCESS_FS_TRUNCATE;
+        }
+        if abi >= 5 {
+            handled |= ACCESS_FS_IOCTL_DEV;
+        }
+
+        let attr = LandlockRulesetAttr {
+            handled_access_fs: handled,
+        };
+        // SAFETY: `attr` is a valid ruleset attribute and the size matches.
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                &attr as *const LandlockRulesetAttr,
+                std::mem::size_of::<LandlockRulesetAttr>(),
+                0u32,
+            )
+        };
+        if fd < 0 {
+            return Err(RuntimeError::StartFailed(format!(
+                "landlock_create_ruleset failed: {}",
+                io::Error::last_os_error()
+            )));
+        }
+        // SAFETY: the kernel returned a fresh file descriptor that we own.
+        let ruleset = unsafe { OwnedFd::from_raw_fd(fd as RawFd) };
+
+        let read_write = handled;
+        let read_only = ACCESS_FS_READ_ONLY & handled;
+        for path in &self.read_only_paths {
+            add_path_rule(&ruleset, path, read_only)?;
+        }
+        for (path, writable) in DEVICE_PATHS {
+            let access = if *writable { read_write } else { read_only };
+            add_path_rule(&ruleset, Path::new(path), access)?;
+        }
+        if let Some(ref workdir) = config.workdir {
+            add_path_rule(&ruleset, workdir, read_write)?;
+        }
+        for (host, _, readonly) in &config.mounts {
+            let access = if *readonly { read_only } else { read_write };
+            add_path_rule(&ruleset, host, access)?;
+        }
+
+        Ok(ruleset)
+    }
+
+    /// Builds the seccomp BPF program.
+    fn build_filter(&self, arch: u32) -> Vec<libc::sock_filter> {
+        let deny = |errno: i32| ret(libc::SECCOMP_RET_ERRNO | errno as u32);
+
+        let mut program = vec![
+            load(SECCOMP_DATA_ARCH),
+            jump_eq(arch, 1, 0),
+            ret(libc::SECCOMP_RET_KILL_PROCESS),
+            load(SECCOMP_DATA_NR),
+        ];
+        if arch == AUDIT_ARCH_X86_64 {
+            program.push(jump_ge(X32_SYSCALL_BIT, 0, 1));
+            program.push(ret(libc::SECCOMP_RET_KILL_PROCESS));
+        }
+
+        for (name, nr) in DENIED_SYSCALLS {
+            if self.allowed_syscalls.iter().any(|a| a == name) {
+                continue;
+            }
+            program.push(jump_eq(*nr as u32, 0, 1));
+            program.push(deny(libc::EPERM));
+        }
+
+        if !self.allow_network {
+            // Only Unix domain sockets may be created.
+            program.push(jump_eq(libc::SYS_socket as u32, 0, 3));
+            program.push(load(SECCOMP_DATA_ARG0));
+            program.push(jump_eq(libc::AF_UNIX as u32, 1, 0));
+            program.push(deny(libc::EACCES));
+        }
+
+        program.push(ret(libc::SECCOMP_RET_ALLOW));
+        program
+    }
+
+    fn rlimits(&self) -> Vec<(RlimitResource, u64)> {
+        let mut limits = Vec::new();
+        if let Some(secs) = self.cpu_time_secs {
+            limits.push((libc::RLIMIT_CPU, secs));
+        }
+        if let Some(mb) = self.memory_limit_mb {
+            limits.push((libc::RLIMIT_AS, mb.saturating_mul(1024 * 1024)));
+        }
+        if let Some(max) = self.max_processes {
+            // RLIMIT_NPROC counts every task of the real UID, including the
+            // agent's own threads and anything else the user runs.
+            // SAFETY: getuid has no preconditions and cannot fail.
+            let running = tasks_of_uid(unsafe { libc::getuid() });
+            limits.push((libc::RLIMIT_NPROC, running.saturating_add(max)));
+        }
+        limits
+    }
+}
+
+/// Number of tasks (threads) whose real UID is `uid`, as RLIMIT_NPROC counts
+/// them. Processes that exit or are hidden while scanning are skipped.
+fn tasks_of_uid(uid: libc::uid_t) -> u64 {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return 0;
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            e.file_name()
+                .to_string_lossy()
+                .bytes()
+                .all(|b| b.is_ascii_digit())
+        })
+        .filter_map(|e| std::fs::read_to_string(e.path().join("status")).ok())
+        .filter_map(|status| {
+            let mut real_uid = None;
+            let mut threads = None;
+            for line in status.lines() {
+                if let Some(rest) = line.strip_prefix("Uid:") {
+                    real_uid = rest.split_whitespace().next()?.parse::<libc::uid_t>().ok();
+                } else if let Some(rest) = line.strip_prefix("Threads:") {
+                    threads = rest.trim().parse::<u64>().ok();
+                }
+            }
+            (real_uid? == uid).then_some(threads.unwrap_or(1))
+        })
+        .sum()
+}
+
+impl Default for SandboxRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ContainerRuntime for SandboxRuntime {
+    fn name(&self) -> &str {
+        "sandbox"
+    }
+
+    async fn is_available(&self) -> bool {
+        Self::unavailable_reason().is_none()
+    }
+
+    async fn execute(
+        &self,
+        command: &str,
+        config: &ContainerConfig,
+    ) -> RuntimeResult<CommandOutput> {
+        if let Some(reason) = Self::unavailable_reason() {
+            return Err(RuntimeError::NotAvailable(reason));
+        }
+        let abi = landlock_abi_version().unwrap_or(1);
+        let arch = AUDIT_ARCH.unwrap_or_default();
+
+        let ruleset = self.build_ruleset(abi, config)?;
+        let ruleset_fd = ruleset.as_raw_fd();
+        let filter = self.build_filter(arch);
+        let limits = self.rlimits();
+
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+
+        // Set working directory if specified
+        if let Some(ref workdir) = config.workdir {
+            cmd.current_dir(workdir);
+        }
+
+        // Set environment variables
+        for (key, value) in &config.env {
+            cmd.env(key, value);
+        }
+
+        // SAFETY: the closure runs in the forked child and only makes raw
+        // syscalls on data prepared before the fork; it does not allocate.
+        unsafe {
+            cmd.pre_exec(move || confine_current_process(ruleset_fd, &filter, &limits));
+        }
+
+        // Execute with timeout; the command's whole process group is killed
+        // if the call is timed out or cancelled
+        let output = output_with_timeout(cmd, config.timeout_secs, Teardown::ProcessGroup).await?;
+        drop(ruleset);
+
+        Ok(CommandOutput::new(
+            String::from_utf8_lossy(&output.stdout).to_string(),
+            String::from_utf8_lossy(&output.stderr).to_string(),
+            output.status.code(),
+        ))
+    }
+}
+
+/// Returns the kernel's Landlock ABI version, or `None` when Landlock is
+/// unavailable.
+fn landlock_abi_version() -> Option<u32> {
+    // SAFETY: querying the ABI version takes no pointers.
+    let version = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_create_ruleset,
+            std::ptr::null::<LandlockRulesetAttr>(),
+            0usize,
+            LANDLOCK_CREATE_RULESET_VERSION,
+        )
+    };
+    (version > 0).then_some(version as u32)
+}
+
+/// Grants `access` beneath `path`. Paths that do not exist are skipped, so
+/// defaults like `/lib64` work across distributions.
+fn add_path_rule(ruleset: &OwnedFd, path: &Path, access: u64) -> RuntimeResult<()> {
+    let file = match OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_PATH)
+        .open(path)
+    {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => {
+            return Err(RuntimeError::StartFailed(format!(
+                "cannot open sandbox path {}: {}",
+                path.display(),
+                e
+            )))
+        }
+    };
+
+    let is_dir = file.metadata().map(|m| m.is_dir()).unwrap_or(false);
+    let rule = LandlockPathBeneathAttr {
+        allowed_access: if is_dir {
+            access
+        } else {
+            access & ACCESS_FS_FILE
+        },
+        parent_fd: file.as_raw_fd(),
+    };
+    // SAFETY: both file descriptors are open and `rule` outlives the call.
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_add_rule,
+            ruleset.as_raw_fd(),
+            LANDLOCK_RULE_PATH_BENEATH,
+            &rule as *const LandlockPathBeneathAttr,
+            0u32,
+        )
+    };
+    if result < 0 {
+        return Err(RuntimeError::StartFailed(format!(
+            "landlock_add_rule for {} failed: {}",
+            path.display(),
+            io::Error::last_os_error()
+        )));
+    }
+    Ok(())
+}
+
+/// Applies rlimits, Landlock and seccomp to the calling process.
+///
+/// Runs between `fork` and `exec`, so it must stay async-signal-safe.
+fn confine_current_process(
+    ruleset_fd: RawFd,
+    filter: &[libc::sock_filter],
+    limits: &[(RlimitResource, u64)],
+) -> io::Result<()> {
+    fn check(result: libc::c_long) -> io::Result<()> {
+        if result < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    // SAFETY: plain syscalls on values owned by the caller.
+    unsafe {
+        for (resource, value) in limits {
+            let limit = libc::rlimit {
+                rlim_cur: *value,
+                rlim_max: *value,
+            };
+            check(libc::setrlimit(*resource, &limit) as libc::c_long)?;
+        }
+
+        check(libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) as libc::c_long)?;
+        check(libc::syscall(
+            libc::SYS_landlock_restrict_self,
+            ruleset_fd,
+            0u32,
+        ))?;
+
+        let program = libc::sock_fprog {
+            len: filter.len() as libc::c_ushort,
+            filter: filter.as_ptr() as *mut libc::sock_filter,
+        };
+        check(libc::prctl(
+            libc::PR_SET_SECCOMP,
+            libc::SECCOMP_MODE_FILTER,
+            &program as *const libc::sock_fprog,
+        ) as libc::c_long)?;
+    }
+    Ok(())
+}
+
+fn load(offset: u32) -> libc::sock_filter {
+    instruction(libc::BPF_LD | libc::BPF_W | libc::BPF_ABS, 0, 0, offset)
+}
+
+fn jump_eq(value: u32, jump_true: u8, jump_false: u8) -> libc::sock_filter {
+    instruction(
+        libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K,
+        jump_true,
+        jump_false,
+        value,
+    )
+}
+
+fn jump_ge(value: u32, jump_true: u8, jump_false: u8) -> libc::sock_filter {
+    instruction(
+        libc::BPF_JMP | libc::BPF_JGE | libc::BPF_K,
+        jump_true,
+        jump_false,
+        value,
+    )
+}
+
+fn ret(value: u32) -> libc::sock_filter {
+    instruction(libc::BPF_RET | libc::BPF_K, 0, 0, value)
+}
+
+fn instruction(code: u32, jt: u8, jf: u8, k: u32) -> libc::sock_filter {
+    libc::sock_filter {
+        code: code as u16,
+        jt,
+        jf,
+        k,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Skips a test on kernels without Landlock.
+    macro_rules! require_sandbox {
+        () => {
+            if let Some(reason) = SandboxRuntime::unavailable_reason() {
+                eprintln!("skipping: {}", reason);
+                return;
+            }
+        };
+    }
+
+    fn workspace_config(dir: &Path) -> ContainerConfig {
+        ContainerConfig::new()
+            .with_workdir(dir.to_path_buf())
+            .with_mount(dir.to_path_buf(), dir.to_path_buf(), false)
+    }
+
+    #[test]
+    fn test_sandbox_runtime_name() {
+        assert_eq!(SandboxRuntime::new().name(), "sandbox");
+    }
+
+    #[test]
+    fn test_is_deniable_syscall() {
+        assert!(is_deniable_syscall("ptrace"));
+        assert!(is_deniable_syscall("mount"));
+        assert!(!is_deniable_syscall("read"));
+    }
+
+    /// Runs a seccomp program against a syscall, like the kernel would.
+    fn run_filter(program: &[libc::sock_filter], arch: u32, nr: u32, arg0: u32) -> u32 {
+        let mut acc = 0;
+        let mut pc = 0;
+        loop {
+            let insn = program[pc];
+            let code = insn.code as u32;
+            pc += 1;
+            if code == libc::BPF_LD | libc::BPF_W | libc::BPF_ABS {
+                acc = match insn.k {
+                    SECCOMP_DATA_NR => nr,
+                    SECCOMP_DATA_ARCH => arch,
+                    SECCOMP_DATA_ARG0 => arg0,
+                    other => panic!("unexpected load offset {}", other),
+                };
+            } else if code == libc::BPF_RET | libc::BPF_K {
+                return insn.k;
+            } else {
+                let taken = match code {
+                    c if c == libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K => acc == insn.k,
+                    c if c == libc::BPF_JMP | libc::BPF_JGE | libc::BPF_K => acc >= insn.k,
+                    other => panic!("unexpected instruction {:#x}", other),
+                };
+                pc += if taken { insn.jt } else { insn.jf } as usize;
+            }
+        }
+    }
+
+    #[test]
+    fn test_filter_kills_x32_syscalls() {
+        let program = SandboxRuntime::new().build_filter(AUDIT_ARCH_X86_64);
+        let ptrace = libc::SYS_ptrace as u32;
+        let eperm = libc::SECCOMP_RET_ERRNO | libc::EPERM as u32;
+
+        assert_eq!(run_filter(&program, AUDIT_ARCH_X86_64, ptrace, 0), eperm);
+        assert_eq!(
+            run_filter(&program, AUDIT_ARCH_X86_64, ptrace | X32_SYSCALL_BIT, 0),
+            libc::SECCOMP_RET_KILL_PROCESS
+        );
+        assert_eq!(
+            run_filter(
+                &program,
+                AUDIT_ARCH_X86_64,
+                libc::SYS_socket as u32 | X32_SYSCALL_BIT,
+                libc::AF_INET as u32
+            ),
+            libc::SECCOMP_RET_KILL_PROCESS
+        );
+        assert_eq!(
+            run_filter(&program, AUDIT_ARCH_X86_64, libc::SYS_read as u32, 0),
+            libc::SECCOMP_RET_ALLOW
+        );
+        assert_eq!(
+            run_filter(&program, 0x4000_0003, libc::SYS_read as u32, 0),
+            libc::SECCOMP_RET_KILL_PROCESS
+        );
+    }
+
+    #[test]
+    fn test_build_filter_respects_allowed_syscalls() {
+        let arch = AUDIT_ARCH_X86_64;
+        let strict = SandboxRuntime::new().build_filter(arch);
+        let relaxed = SandboxRuntime::new()
+            .with_allowed_syscalls(vec!["ptrace".to_string()])
+            .with_network(true)
+            .build_filter(arch);
+        // Each allowed syscall drops two instructions, network drops four.
+        assert_eq!(strict.len(), relaxed.len() + 2 + 4);
+        assert_eq!(strict[0].k, SECCOMP_DATA_ARCH);
+        assert_eq!(strict.last().unwrap().k, libc::SECCOMP_RET_ALLOW);
+    }
+
+    #[test]
+    fn test_rlimits() {
+        let limits = SandboxRuntime::new()
+            .with_memory_limit_mb(Some(256))
+            .rlimits();
+        assert!(limits.contains(&(libc::RLIMIT_AS, 256 * 1024 * 1024)));
+        assert!(SandboxRuntime::new().without_limits().rlimits().is_empty());
+    }
+
+    #[test]
+    fn test_max_processes_is_relative_to_running_tasks() {
+        // SAFETY: getuid has no preconditions and cannot fail.
+        let uid = unsafe { libc::getuid() };
+        let running = tasks_of_uid(uid);
+        // At least this test process and its threads
+        assert!(running >= 1);
+
+        let limits = SandboxRuntime::new().with_max_processes(Some(16)).rlimits();
+        let (_, nproc) = limits
+            .iter()
+            .find(|(resource, _)| *resource == libc::RLIMIT_NPROC)
+            .unwrap();
+        assert!(*nproc >= running + 16);
+    }
+
+    #[tokio::test]
+    async fn test_sandbox_unavailable_is_reported() {
+        let runtime = SandboxRuntime::new();
+        if runtime.is_available().await {
+            return;
+        }
+        let result = runtime.execute("true", &ContainerConfig::new()).await;
+        assert!(matches!(result, Err(RuntimeError::NotAvailable(_))));
+    }
+
+    #[tokio::test]
+    async fn test_sandbox_runs_commands_in_workspace() {
+        require_sandbox!();
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = SandboxRuntime::new();
+
+        let output = runtime
+            .execute(
+                "echo hello > out.txt && cat out.txt",
+                &workspace_config(dir.path()),
+            )
+            .await
+            .unwrap();
+        assert!(output.success(), "{}", output.format());
+        assert_eq!(output.stdout.trim(), "hello");
+    }
+
+    #[tokio::test]
+    async fn test_sandbox_blocks_writes_outside_workspace() {
+        require_sandbox!();
+        let workspace = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let target = outside.path().join("escape.txt");
+        let runtime = SandboxRuntime::new();
+
+        let output = runtime
+            .execute(
+                &format!("echo pwned > {}", target.display()),
+                &workspace_config(workspace.path()),
+            )
+            .await
+            .unwrap();
+        assert!(!output.success());
+        assert!(!target.exists());
+    }
+
+    #[tokio::test]
+    async fn test_sandbox_read_only_mount() {
+        require_sandbox!();
+        let workspace = tempfile::tempdir().unwrap();
+        let shared = tempfile::tempdir().unwrap();
+        std::fs::write(shared.path().join("data.txt"), "shared").unwrap();
+        let config = workspace_config(workspace.path()).with_mount(
+            shared.path().to_path_buf(),
+            shared.path().to_path_buf(),
+            true,
+        );
+        let runtime = SandboxRuntime::new();
+
+        let read = runtime
+            .execute(
+                &format!("cat {}/data.txt", shared.path().display()),
+                &config,
+            )
+            .await
+            .unwrap();
+        assert_eq!(read.stdout, "shared");
+
+        let write = runtime
+            .execute(
+                &format!("echo x > {}/data.txt", shared.path().display()),
+                &config,
+            )
+            .await
+            .unwrap();
+        assert!(!write.success());
+    }
+
+    #[tokio::test]
+    async fn test_sandbox_denies_network_sockets() {
+        require_sandbox!();
+        if !Path::new("/bin/bash").exists() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        // bash's /dev/tcp needs socket(AF_INET), which the filter rejects
+        // with EACCES before any connection is attempted.
+        let command = "/bin/bash -c 'exec 3<>/dev/tcp/127.0.0.1/9'";
+
+        let denied = SandboxRuntime::new()
+            .execute(command, &workspace_config(dir.path()))
+            .await
+            .unwrap();
+        assert!(!denied.success());
+        assert!(
+            denied.stderr.contains("Permission denied"),
+            "{}",
+            denied.stderr
+        );
+
+        let allowed = SandboxRuntime::new()
+            .with_network(true)
+            .execute(command, &workspace_config(dir.path()))
+            .await
+            .unwrap();
+        assert!(
+            !allowed.stderr.contains("Permission denied"),
+            "{}",
+            allowed.stderr
+        );
+    }
+}
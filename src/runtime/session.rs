@@ -0,0 +1,562 @@
+//! Persistent shell sessions
+//!
+//! By default every shell command runs in a fresh process (or container), so
+//! `cd`, exported variables, installed packages and background jobs are lost
+//! between tool calls. [`SessionRuntime`] wraps another runtime and instead
+//! keeps one long-lived shell per agent session: the inner runtime starts it
+//! (a host `sh`, or `docker exec` into a session container, ...) and commands
+//! are fed to its stdin one at a time.
+//!
+//! Sessions are torn down after an idle TTL, when a command times out or
+//! exits the shell, and by [`shutdown_sessions`] at gateway shutdown.
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout, Command};
+use tracing::{debug, info, warn};
+
+use super::types::{CommandOutput, ContainerConfig, ContainerRuntime, RuntimeError, RuntimeResult};
+
+/// How a runtime starts the long-lived shell for a session.
+#[derive(Debug)]
+pub struct SessionLaunch {
+    /// Command that starts `sh` reading commands from stdin
+    pub shell: Command,
+    /// Command that releases session resources (e.g. removes the container)
+    pub teardown: Option<std::process::Command>,
+}
+
+impl SessionLaunch {
+    /// Launch a shell that needs no teardown.
+    pub fn new(shell: Command) -> Self {
+        Self {
+            shell,
+            teardown: None,
+        }
+    }
+
+    /// Run `teardown` when the session ends.
+    pub fn with_teardown(mut self, teardown: std::process::Command) -> Self {
+        self.teardown = Some(teardown);
+        self
+    }
+}
+
+/// Every live session pool, so [`shutdown_sessions`] can reach them.
+static POOLS: Lazy<Mutex<Vec<Weak<SessionPool>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Tear down every persistent shell session in the process.
+pub async fn shutdown_sessions() {
+    let pools: Vec<Arc<SessionPool>> = {
+        let mut registry = POOLS.lock().unwrap_or_else(|e| e.into_inner());
+        registry.retain(|pool| pool.strong_count() > 0);
+        registry.iter().filter_map(Weak::upgrade).collect()
+    };
+    for pool in pools {
+        pool.close_all().await;
+    }
+}
+
+/// Runtime wrapper that keeps one shell alive per session.
+///
+/// Commands whose [`ContainerConfig::session`] is `None`, or whose inner
+/// runtime does not support sessions, run through the inner runtime as usual.
+pub struct SessionRuntime {
+    inner: Arc<dyn ContainerRuntime>,
+    pool: Arc<SessionPool>,
+}
+
+impl SessionRuntime {
+    /// Wrap `inner`, closing sessions idle for longer than `idle_ttl`.
+    pub fn new(inner: Arc<dyn ContainerRuntime>, idle_ttl: Duration) -> Self {
+        let pool = Arc::new(SessionPool {
+            sessions: Mutex::new(HashMap::new()),
+            idle_ttl,
+        });
+        POOLS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Arc::downgrade(&pool));
+
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let weak = Arc::downgrade(&pool);
+            let interval = idle_ttl.clamp(Duration::from_secs(1), Duration::from_secs(60));
+            handle.spawn(async move {
+                loop {
+                    tokio::time::sleep(interval).await;
+                    match weak.upgrade() {
+                        Some(pool) => pool.reap_idle().await,
+                        None => break,
+                    }
+                }
+            });
+        }
+
+        Self { inner, pool }
+    }
+
+    /// Number of sessions currently tracked.
+    pub fn session_count(&self) -> usize {
+        self.pool.slots().len()
+    }
+
+    /// Close every session held by this runtime.
+    pub async fn close_all(&self) {
+        self.pool.close_all().await;
+    }
+}
+
+#[async_trait]
+impl ContainerRuntime for SessionRuntime {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn is_available(&self) -> bool {
+        self.inner.is_available().await
+    }
+
+    async fn execute(
+        &self,
+        command: &str,
+        config: &ContainerConfig,
+    ) -> RuntimeResult<CommandOutput> {
+        let Some(ref session_id) = config.session else {
+            return self.inner.execute(command, config).await;
+        };
+
+        let slot = self.pool.slot(session_id);
+        let mut guard = slot.lock().await;
+
+        let expired = guard
+            .as_ref()
+            .is_some_and(|s| s.last_used.elapsed() > self.pool.idle_ttl);
+        if expired {
+            if let Some(session) = guard.take() {
+                session.close().await;
+            }
+        }
+
+        if guard.is_none() {
+            match self.inner.start_session(session_id, config).await? {
+                Some(launch) => {
+                    info!(session = %session_id, runtime = %self.inner.name(), "Starting persistent shell session");
+                    *guard = Some(ShellSession::spawn(launch)?);
+                }
+                None => {
+                    drop(guard);
+                    self.pool.remove(session_id);
+                    return self.inner.execute(command, config).await;
+                }
+            }
+        }
+
+        // Taken out of the slot while the command runs: if this call is
+        // cancelled, the session is dropped (killing the shell) instead of
+        // being left mid-command for the next caller.
+        let Some(mut session) = guard.take() else {
+            return Err(RuntimeError::StartFailed(
+                "shell session unavailable".to_string(),
+            ));
+        };
+        let result = tokio::time::timeout(
+            Duration::from_secs(config.timeout_secs),
+            session.run(command),
+        )
+        .await;
+
+        match result {
+            Ok(Ok(RunOutcome {
+                output,
+                alive: true,
+            })) => {
+                session.last_used = Instant::now();
+                *guard = Some(session);
+                Ok(output)
+            }
+            Ok(Ok(RunOutcome {
+                output,
+                alive: false,
+            })) => {
+                debug!(session = %session_id, "Shell session exited");
+                session.close().await;
+                Ok(output)
+            }
+            Ok(Err(e)) => {
+                session.close().await;
+                Err(e)
+            }
+            Err(_) => {
+                // The shell is stuck on the command; start over next time.
+                warn!(session = %session_id, "Command timed out, resetting shell session");
+                session.close().await;
+                Err(RuntimeError::Timeout(config.timeout_secs))
+            }
+        }
+    }
+}
+
+type SessionSlot = Arc<tokio::sync::Mutex<Option<ShellSession>>>;
+
+struct SessionPool {
+    sessions: Mutex<HashMap<String, SessionSlot>>,
+    idle_ttl: Duration,
+}
+
+impl SessionPool {
+    fn slot(&self, session_id: &str) -> SessionSlot {
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(sessions.entry(session_id.to_string()).or_default())
+    }
+
+    fn slots(&self) -> Vec<(String, SessionSlot)> {
+        let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        sessions
+            .iter()
+            .map(|(id, slot)| (id.clone(), Arc::clone(slot)))
+            .collect()
+    }
+
+    fn remove(&self, session_id: &str) {
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        sessions.remove(session_id);
+    }
+
+    async fn reap_idle(&self) {
+        for (id, slot) in self.slots() {
+            // A busy session is not idle.
+            let Ok(mut guard) = slot.try_lock() else {
+                continue;
+            };
+            let idle = guard
+                .as_ref()
+                .is_none_or(|s| s.last_used.elapsed() > self.idle_ttl);
+            if idle {
+                if let Some(session) = guard.take() {
+                    debug!(session = %id, "Closing idle shell session");
+                    session.close().await;
+                }
+                drop(guard);
+                self.remove(&id);
+            }
+        }
+    }
+
+    async fn close_all(&self) {
+        let slots: Vec<SessionSlot> = {
+            let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+            sessions.drain().map(|(_, slot)| slot).collect()
+        };
+        for slot in slots {
+            if let Some(session) = slot.lock().await.take() {
+                session.close().await;
+            }
+        }
+    }
+}
+
+struct RunOutcome {
+    output: CommandOutput,
+    /// False if the command ended the shell (e.g. `exit`).
+    alive: bool,
+}
+
+/// A running shell plus the pipes used to drive it.
+struct ShellSession {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    stderr: BufReader<ChildStderr>,
+    /// Random line prefix that marks the end of a command's output
+    marker: String,
+    last_used: Instant,
+    teardown: Option<std::process::Command>,
+}
+
+impl ShellSession {
+    fn spawn(launch: SessionLaunch) -> RuntimeResult<Self> {
+        let SessionLaunch {
+            mut shell,
+            teardown,
+        } = launch;
+        let mut child = shell
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| RuntimeError::StartFailed(e.to_string()))?;
+
+        let (Some(stdin), Some(stdout), Some(stderr)) =
+            (child.stdin.take(), child.stdout.take(), child.stderr.take())
+        else {
+            return Err(RuntimeError::StartFailed(
+                "shell session pipes unavailable".to_string(),
+            ));
+        };
+
+        Ok(Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+            stderr: BufReader::new(stderr),
+            marker: format!("__ZEPTOCLAW_{}__", uuid::Uuid::new_v4().simple()),
+            last_used: Instant::now(),
+            teardown,
+        })
+    }
+
+    async fn run(&mut self, command: &str) -> RuntimeResult<RunOutcome> {
+        // `command eval` keeps a syntax error from killing a POSIX shell, and
+        // stdin is detached so commands cannot swallow the next script.
+        let script = format!(
+            "command eval '{}' </dev/null\n\
+             __zc_status=$?\n\
+             printf '\\n%s %d\\n' '{marker}' \"$__zc_status\"\n\
+             printf '\\n%s\\n' '{marker}' >&2\n",
+            command.replace('\'', "'\\''"),
+            marker = self.marker
+        );
+        self.stdin.write_all(script.as_bytes()).await?;
+        self.stdin.flush().await?;
+
+        let (stdout, stderr) = tokio::join!(
+            read_until_marker(&mut self.stdout, &self.marker),
+            read_until_marker(&mut self.stderr, &self.marker)
+        );
+        let (stdout, status) = stdout?;
+        let (stderr, _) = stderr?;
+
+        match status {
+            Some(code) => Ok(RunOutcome {
+                output: CommandOutput::new(stdout, stderr, Some(code)),
+                alive: true,
+            }),
+            None => {
+                let code = self.child.wait().await.ok().and_then(|s| s.code());
+                Ok(RunOutcome {
+                    output: CommandOutput::new(stdout, stderr, code),
+                    alive: false,
+                })
+            }
+        }
+    }
+
+    /// Stops the shell and runs the teardown command.
+    async fn close(mut self) {
+        let _ = self.child.kill().await;
+        if let Some(mut teardown) = self.teardown.take() {
+            let _ = tokio::task::spawn_blocking(move || teardown.output()).await;
+        }
+    }
+}
+
+impl Drop for ShellSession {
+    fn drop(&mut self) {
+        // Sessions dropped without `close` (e.g. on runtime drop) still
+        // release their resources; the child is killed by `kill_on_drop`.
+        if let Some(mut teardown) = self.teardown.take() {
+            match tokio::runtime::Handle::try_current() {
+                Ok(handle) => {
+                    handle.spawn_blocking(move || teardown.output());
+                }
+                Err(_) => {
+                    let _ = teardown.output();
+                }
+            }
+        }
+    }
+}
+
+/// Reads output up to the marker line.
+///
+/// Returns the output (without the newline the script adds before the
+/// marker) and the exit status printed after it. The status is `None` if the
+/// stream ended first, i.e. the shell exited.
+async fn read_until_marker<R: AsyncRead + Unpin>(
+    reader: &mut BufReader<R>,
+    marker: &str,
+) -> RuntimeResult<(String, Option<i32>)> {
+    let mut output = Vec::new();
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).await? == 0 {
+            return Ok((String::from_utf8_lossy(&output).to_string(), None));
+        }
+        if let Some(rest) = line.strip_prefix(marker.as_bytes()) {
+            if output.last() == Some(&b'\n') {
+                output.pop();
+            }
+            let status = String::from_utf8_lossy(rest).trim().parse().unwrap_or(0);
+            return Ok((String::from_utf8_lossy(&output).to_string(), Some(status)));
+        }
+        output.extend_from_slice(&line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::NativeRuntime;
+
+    fn session_runtime(ttl: Duration) -> SessionRuntime {
+        SessionRuntime::new(Arc::new(NativeRuntime::new()), ttl)
+    }
+
+    fn session_config(dir: &std::path::Path, session: &str) -> ContainerConfig {
+        ContainerConfig::new()
+            .with_workdir(dir.to_path_buf())
+            .with_session(session)
+    }
+
+    #[tokio::test]
+    async fn test_session_keeps_cwd_and_env() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        let runtime = session_runtime(Duration::from_secs(60));
+        let config = session_config(dir.path(), "s1");
+
+        let first = runtime
+            .execute("cd sub && export GREETING=hi", &config)
+            .await
+            .unwrap();
+        assert!(first.success());
+
+        let second = runtime
+            .execute("basename \"$PWD\"; echo $GREETING", &config)
+            .await
+            .unwrap();
+        assert_eq!(second.stdout, "sub\nhi\n");
+        assert_eq!(runtime.session_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sessions_are_isolated() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = session_runtime(Duration::from_secs(60));
+
+        runtime
+            .execute("export ONLY_A=1", &session_config(dir.path(), "a"))
+            .await
+            .unwrap();
+        let other = runtime
+            .execute("echo \"[$ONLY_A]\"", &session_config(dir.path(), "b"))
+            .await
+            .unwrap();
+        assert_eq!(other.stdout.trim(), "[]");
+        assert_eq!(runtime.session_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_session_output_and_exit_codes() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = session_runtime(Duration::from_secs(60));
+        let config = session_config(dir.path(), "s");
+
+        let output = runtime
+            .execute("printf 'no newline'; echo oops >&2; false", &config)
+            .await
+            .unwrap();
+        assert_eq!(output.stdout, "no newline");
+        assert_eq!(output.stderr, "oops\n");
+        assert_eq!(output.exit_code, Some(1));
+
+        // Quotes and syntax errors do not break the session.
+        let quoted = runtime.execute("echo 'it'\"'\"'s'", &config).await.unwrap();
+        assert_eq!(quoted.stdout, "it's\n");
+        let broken = runtime.execute("if", &config).await.unwrap();
+        assert!(!broken.success());
+        let after = runtime.execute("echo still here", &config).await.unwrap();
+        assert_eq!(after.stdout, "still here\n");
+    }
+
+    #[tokio::test]
+    async fn test_session_exit_restarts_shell() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = session_runtime(Duration::from_secs(60));
+        let config = session_config(dir.path(), "s");
+
+        runtime.execute("export KEPT=1", &config).await.unwrap();
+        let exited = runtime.execute("exit 3", &config).await.unwrap();
+        assert_eq!(exited.exit_code, Some(3));
+
+        let fresh = runtime.execute("echo \"[$KEPT]\"", &config).await.unwrap();
+        assert_eq!(fresh.stdout.trim(), "[]");
+    }
+
+    #[tokio::test]
+    async fn test_session_timeout_resets_shell() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = session_runtime(Duration::from_secs(60));
+        let config = session_config(dir.path(), "s").with_timeout(1);
+
+        runtime.execute("export KEPT=1", &config).await.unwrap();
+        let result = runtime.execute("sleep 10", &config).await;
+        assert!(matches!(result, Err(RuntimeError::Timeout(1))));
+
+        let fresh = runtime.execute("echo \"[$KEPT]\"", &config).await.unwrap();
+        assert_eq!(fresh.stdout.trim(), "[]");
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_command_resets_shell() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = session_runtime(Duration::from_secs(60));
+        let config = session_config(dir.path(), "s");
+
+        runtime.execute("export KEPT=1", &config).await.unwrap();
+        let cancelled = tokio::time::timeout(
+            Duration::from_millis(200),
+            runtime.execute("sleep 10", &config),
+        )
+        .await;
+        assert!(cancelled.is_err());
+
+        let fresh = runtime.execute("echo \"[$KEPT]\"", &config).await.unwrap();
+        assert_eq!(fresh.stdout.trim(), "[]");
+    }
+
+    #[tokio::test]
+    async fn test_idle_sessions_are_reaped() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = session_runtime(Duration::from_millis(50));
+        runtime
+            .execute("true", &session_config(dir.path(), "s"))
+            .await
+            .unwrap();
+        assert_eq!(runtime.session_count(), 1);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        runtime.pool.reap_idle().await;
+        assert_eq!(runtime.session_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_without_session_uses_inner_runtime() {
+        let runtime = session_runtime(Duration::from_secs(60));
+        runtime
+            .execute("export X=1", &ContainerConfig::new())
+            .await
+            .unwrap();
+        assert_eq!(runtime.session_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_sessions_closes_all() {
+        let dir = tempfile::tempdir().unwrap();
+        let runtime = session_runtime(Duration::from_secs(60));
+        runtime
+            .execute("true", &session_config(dir.path(), "s"))
+            .await
+            .unwrap();
+
+        shutdown_sessions().await;
+        assert_eq!(runtime.session_count(), 0);
+    }
+}
@@ -4,6 +4,8 @@ use async_trait::async_trait;
 use std::path::PathBuf;
 use thiserror::Error;
 
+use super::session::SessionLaunch;
+
 /// Errors that can occur during runtime operations
 #[derive(Error, Debug)]
 pub enum RuntimeError {
@@ -93,6 +95,8 @@ pub struct ContainerConfig {
     pub env: Vec<(String, String)>,
     /// Command timeout in seconds
     pub timeout_secs: u64,
+    /// Persistent shell session to run in (see [`super::SessionRuntime`])
+    pub session: Option<String>,
 }
 
 impl ContainerConfig {
@@ -127,6 +131,12 @@ impl ContainerConfig {
         self.timeout_secs = secs;
         self
     }
+
+    /// Run in the persistent shell session with this ID
+    pub fn with_session(mut self, session: &str) -> Self {
+        self.session = Some(session.to_string());
+        self
+    }
 }
 
 /// Trait for container runtimes
@@ -144,6 +154,19 @@ pub trait ContainerRuntime: Send + Sync {
         command: &str,
         config: &ContainerConfig,
     ) -> RuntimeResult<CommandOutput>;
+
+    /// Prepare a long-lived shell for a persistent session.
+    ///
+    /// Returns `None` if this runtime does not support sessions; commands
+    /// then run one at a time through `execute`.
+    async fn start_session(
+        &self,
+        session_id: &str,
+        config: &ContainerConfig,
+    ) -> RuntimeResult<Option<SessionLaunch>> {
+        let _ = (session_id, config);
+        Ok(None)
+    }
 }
 
 #[cfg(test)]
@@ -7,15 +7,20 @@
 //!
 //! The engine is designed to be constructed once and reused across many
 //! invocations -- all regex patterns are compiled at construction time.
+//! Rules from `safety.policy_rules` are merged with the built-in set.
 
 use regex::{Regex, RegexSet};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
 
 // ---------------------------------------------------------------------------
 // Public types
 // ---------------------------------------------------------------------------
 
 /// How severe a policy violation is.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum PolicySeverity {
     /// Must be addressed immediately -- processing should stop.
     Critical,
@@ -28,7 +33,8 @@ pub enum PolicySeverity {
 }
 
 /// What the caller should do about a violation.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum PolicyAction {
     /// Stop processing and return an error.
     Block,
@@ -53,16 +59,49 @@ pub struct PolicyViolation {
     pub matched_text: Option<String>,
 }
 
+/// A user-defined policy rule from `safety.policy_rules`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyRuleConfig {
+    /// Rule name; a built-in rule with the same name is replaced.
+    pub name: String,
+    /// Regex, or literal text when `literal` is set.
+    pub pattern: String,
+    /// Match `pattern` as literal text instead of a regex.
+    #[serde(default)]
+    pub literal: bool,
+    /// Match case-insensitively.
+    #[serde(default)]
+    pub case_insensitive: bool,
+    /// Severity reported for violations.
+    #[serde(default = "default_rule_severity")]
+    pub severity: PolicySeverity,
+    /// Action to take on a match.
+    #[serde(default = "default_rule_action")]
+    pub action: PolicyAction,
+    /// Human-readable description shown when the rule matches.
+    #[serde(default)]
+    pub description: String,
+}
+
+fn default_rule_severity() -> PolicySeverity {
+    PolicySeverity::High
+}
+
+fn default_rule_action() -> PolicyAction {
+    PolicyAction::Block
+}
+
 // ---------------------------------------------------------------------------
 // Internal rule definition
 // ---------------------------------------------------------------------------
 
-/// A compiled policy rule. Constructed once inside `PolicyEngine::new()`.
+/// A compiled policy rule, built-in or from config.
+#[derive(Clone)]
 struct CompiledRule {
-    name: &'static str,
+    name: String,
     severity: PolicySeverity,
     action: PolicyAction,
-    description: &'static str,
+    description: String,
     /// Individual compiled regex used to extract the matched text.
     pattern: Regex,
 }
@@ -159,26 +198,67 @@ impl PolicyEngine {
     /// silently skipped -- this mirrors the approach used by the existing
     /// `ShellSecurityConfig`.
     pub fn new() -> Self {
-        let patterns: Vec<&str> = RULE_DEFS.iter().map(|(_, _, _, _, pat)| *pat).collect();
-
-        let set = RegexSet::new(&patterns).expect("static policy patterns must compile");
-
         let rules: Vec<CompiledRule> = RULE_DEFS
             .iter()
             .filter_map(|(name, sev, act, desc, pat)| {
                 Regex::new(pat).ok().map(|regex| CompiledRule {
-                    name,
+                    name: name.to_string(),
                     severity: sev.clone(),
                     action: act.clone(),
-                    description: desc,
+                    description: desc.to_string(),
                     pattern: regex,
                 })
             })
             .collect();
 
+        let set = build_set(&rules).expect("static policy patterns must compile");
         Self { set, rules }
     }
 
+    /// Add a user-defined rule.
+    ///
+    /// A rule named like an existing one replaces it; otherwise it is
+    /// appended. Returns `ZeptoError::Config` if the pattern is invalid.
+    pub fn add_rule(&mut self, rule: &PolicyRuleConfig) -> Result<()> {
+        let pattern = super::compile_rule_pattern(
+            &rule.name,
+            &rule.pattern,
+            rule.literal,
+            rule.case_insensitive,
+        )?;
+        let description = if rule.description.trim().is_empty() {
+            format!("Matched custom rule '{}'", rule.name)
+        } else {
+            rule.description.clone()
+        };
+        let compiled = CompiledRule {
+            name: rule.name.clone(),
+            severity: rule.severity.clone(),
+            action: rule.action.clone(),
+            description,
+            pattern,
+        };
+
+        // Build the new rules and set aside so a failure leaves both untouched
+        let mut rules = self.rules.clone();
+        match rules.iter_mut().find(|r| r.name == rule.name) {
+            Some(existing) => *existing = compiled,
+            None => rules.push(compiled),
+        }
+        // The pattern compiled on its own, so this only fails on size limits
+        let set = build_set(&rules).map_err(|e| {
+            crate::error::ZeptoError::Config(format!("safety rule '{}': {}", rule.name, e))
+        })?;
+        self.rules = rules;
+        self.set = set;
+        Ok(())
+    }
+
+    /// Names of all configured rules.
+    pub fn rule_names(&self) -> Vec<&str> {
+        self.rules.iter().map(|r| r.name.as_str()).collect()
+    }
+
     /// Check `input` against all policy rules.
     ///
     /// Returns a (possibly empty) list of violations. Multiple rules can
@@ -197,10 +277,10 @@ impl PolicyEngine {
             let matched_text = rule.pattern.find(input).map(|m| m.as_str().to_string());
 
             violations.push(PolicyViolation {
-                rule_name: rule.name.to_string(),
+                rule_name: rule.name.clone(),
                 severity: rule.severity.clone(),
                 action: rule.action.clone(),
-                description: rule.description.to_string(),
+                description: rule.description.clone(),
                 matched_text,
             });
         }
@@ -209,6 +289,11 @@ impl PolicyEngine {
     }
 }
 
+/// Build the first-pass set; indices correspond to `rules`.
+fn build_set(rules: &[CompiledRule]) -> std::result::Result<RegexSet, regex::Error> {
+    RegexSet::new(rules.iter().map(|r| r.pattern.as_str()))
+}
+
 impl Default for PolicyEngine {
     fn default() -> Self {
         Self::new()
@@ -369,4 +454,89 @@ mod tests {
             "case-insensitive SQL check failed"
         );
     }
+
+    // -- Configured rules ---------------------------------------------------
+
+    fn custom_rule(name: &str, pattern: &str, literal: bool) -> PolicyRuleConfig {
+        PolicyRuleConfig {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            literal,
+            case_insensitive: false,
+            severity: PolicySeverity::Critical,
+            action: PolicyAction::Block,
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_custom_rule_appended() {
+        let mut e = engine();
+        e.add_rule(&custom_rule("customer_id", r"CID-\d{6}", false))
+            .unwrap();
+        let v = e.check("customer CID-123456");
+        let hit = v.iter().find(|v| v.rule_name == "customer_id").unwrap();
+        assert_eq!(hit.severity, PolicySeverity::Critical);
+        assert_eq!(hit.matched_text.as_deref(), Some("CID-123456"));
+        assert!(hit.description.contains("customer_id"));
+        // Built-ins still apply
+        assert!(!e.check("read /etc/passwd").is_empty());
+    }
+
+    #[test]
+    fn test_custom_rule_replaces_builtin() {
+        let mut e = engine();
+        let mut rule = custom_rule("sensitive_env", "NEVER_MATCHES_ANYTHING", true);
+        rule.action = PolicyAction::Warn;
+        e.add_rule(&rule).unwrap();
+        assert!(e.check("DATABASE_URL=postgres://").is_empty());
+        assert_eq!(
+            e.rule_names()
+                .iter()
+                .filter(|n| **n == "sensitive_env")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_invalid_custom_rule_rejected() {
+        let mut e = engine();
+        assert!(e.add_rule(&custom_rule("bad", "[", false)).is_err());
+        // Engine still works after a rejected rule
+        assert!(!e.check("read /etc/passwd").is_empty());
+    }
+
+    #[test]
+    fn test_invalid_replacement_keeps_rules_and_set_aligned() {
+        let mut e = engine();
+        e.add_rule(&custom_rule("customer_id", r"CID-\d{6}", false))
+            .unwrap();
+        let names = e.rule_names().join(",");
+
+        // Replacing a built-in and a custom rule with bad patterns fails
+        // without touching either rule.
+        assert!(e
+            .add_rule(&custom_rule("system_file_access", "(", false))
+            .is_err());
+        assert!(e.add_rule(&custom_rule("customer_id", "[", false)).is_err());
+        assert_eq!(e.rule_names().join(","), names);
+
+        // Set indexes still point at the matching rules.
+        let v = e.check("read /etc/passwd for CID-123456");
+        let mut hits: Vec<&str> = v.iter().map(|v| v.rule_name.as_str()).collect();
+        hits.sort_unstable();
+        assert_eq!(hits, vec!["customer_id", "system_file_access"]);
+        let cid = v.iter().find(|v| v.rule_name == "customer_id").unwrap();
+        assert_eq!(cid.matched_text.as_deref(), Some("CID-123456"));
+    }
+
+    #[test]
+    fn test_policy_rule_config_deserialize_defaults() {
+        let rule: PolicyRuleConfig =
+            serde_json::from_str(r#"{"name": "x", "pattern": "y"}"#).unwrap();
+        assert_eq!(rule.severity, PolicySeverity::High);
+        assert_eq!(rule.action, PolicyAction::Block);
+        assert!(!rule.literal);
+    }
 }
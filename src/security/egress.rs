@@ -0,0 +1,757 @@
+//! Network egress policy
+//!
+//! Central SSRF protection for tools that make outbound HTTP requests.
+//! An [`EgressPolicy`] is compiled from `tools.egress` and hands out
+//! per-tool [`EgressRules`], which check:
+//!
+//! - domain allow and deny lists (a domain entry also covers subdomains)
+//! - allowed ports
+//! - private, link-local and loopback addresses, both for IP literals and
+//!   for every address a hostname resolves to
+//!
+//! Clients built with [`EgressRules::client_builder`] re-check every
+//! redirect hop and resolve hostnames through a filtering DNS resolver, so
+//! the addresses that were checked are the ones that get connected to.
+//! Every violation is recorded as an [`AuditCategory::NetworkEgress`] event.
+
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, OnceLock};
+
+use regex::Regex;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::{ClientBuilder, Url};
+use tokio::net::lookup_host;
+
+use crate::audit::{log_audit_event, AuditCategory, AuditSeverity};
+use crate::config::{EgressConfig, EgressToolOverride};
+use crate::error::{Result, ZeptoError};
+
+/// Maximum number of redirects followed by clients from [`EgressRules::client_builder`].
+const MAX_REDIRECTS: usize = 5;
+
+/// Error returned from inside reqwest (DNS resolver or redirect policy) so
+/// [`EgressRules::map_request_error`] can recognise egress denials.
+#[derive(Debug)]
+struct EgressViolation(String);
+
+impl fmt::Display for EgressViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for EgressViolation {}
+
+/// Compiled `tools.egress` configuration.
+#[derive(Debug, Clone)]
+pub struct EgressPolicy {
+    /// Global settings
+    config: EgressConfig,
+}
+
+impl Default for EgressPolicy {
+    fn default() -> Self {
+        Self::from_config(&EgressConfig::default())
+    }
+}
+
+impl EgressPolicy {
+    /// Create a policy from `tools.egress` configuration.
+    pub fn from_config(config: &EgressConfig) -> Self {
+        Self {
+            config: config.clone(),
+        }
+    }
+
+    /// Restrict `tool` to the host and port of its configured `endpoint`,
+    /// allowing that server even when it is private or local.
+    ///
+    /// For tools that only ever talk to one operator-chosen server (such as
+    /// r8r on `localhost`). Does nothing when `tools.egress.tools.<tool>` is
+    /// set, so an explicit override always wins.
+    pub fn pin_tool_endpoint(&mut self, tool: &str, endpoint: &str) -> Result<()> {
+        if self.config.tools.contains_key(tool) {
+            return Ok(());
+        }
+        let invalid = |reason: String| {
+            ZeptoError::Config(format!(
+                "Invalid {} endpoint '{}': {}",
+                tool, endpoint, reason
+            ))
+        };
+        let url = Url::parse(endpoint).map_err(|e| invalid(e.to_string()))?;
+        let host = match parse_host(&url) {
+            Some(UrlHost::Domain(domain)) => domain,
+            Some(UrlHost::Ip(ip)) => ip.to_string(),
+            None => return Err(invalid("no host".to_string())),
+        };
+        self.config.tools.insert(
+            tool.to_string(),
+            EgressToolOverride {
+                allow_domains: Some(vec![host]),
+                deny_domains: Vec::new(),
+                block_private: Some(false),
+                allowed_ports: url.port_or_known_default().map(|port| vec![port]),
+            },
+        );
+        Ok(())
+    }
+
+    /// Effective rules for `tool`, with its override (if any) applied.
+    pub fn for_tool(&self, tool: &str) -> EgressRules {
+        let empty = EgressToolOverride::default();
+        let tool_override = self.config.tools.get(tool).unwrap_or(&empty);
+
+        let allow_domains = tool_override
+            .allow_domains
+            .as_ref()
+            .unwrap_or(&self.config.allow_domains);
+        let deny_domains = self
+            .config
+            .deny_domains
+            .iter()
+            .chain(&tool_override.deny_domains);
+
+        EgressRules {
+            inner: Arc::new(RulesInner {
+                tool: Some(tool.to_string()),
+                allow_domains: allow_domains
+                    .iter()
+                    .map(String::as_str)
+                    .filter_map(normalize_domain)
+                    .collect(),
+                deny_domains: deny_domains
+                    .map(String::as_str)
+                    .filter_map(normalize_domain)
+                    .collect(),
+                block_private: tool_override
+                    .block_private
+                    .unwrap_or(self.config.block_private),
+                allowed_ports: tool_override
+                    .allowed_ports
+                    .clone()
+                    .unwrap_or_else(|| self.config.allowed_ports.clone()),
+            }),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RulesInner {
+    /// Tool the rules apply to, for audit messages
+    tool: Option<String>,
+    /// Normalized allowed domains (empty = any)
+    allow_domains: Vec<String>,
+    /// Normalized denied domains
+    deny_domains: Vec<String>,
+    /// Whether private/local addresses are blocked
+    block_private: bool,
+    /// Allowed ports (empty = any)
+    allowed_ports: Vec<u16>,
+}
+
+/// Egress rules for a single tool.
+///
+/// Cheap to clone; the default blocks private, link-local and loopback
+/// addresses and allows everything else.
+#[derive(Debug, Clone)]
+pub struct EgressRules {
+    inner: Arc<RulesInner>,
+}
+
+impl Default for EgressRules {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(RulesInner {
+                tool: None,
+                allow_domains: Vec::new(),
+                deny_domains: Vec::new(),
+                block_private: true,
+                allowed_ports: Vec::new(),
+            }),
+        }
+    }
+}
+
+impl EgressRules {
+    /// Check a URL's host and port without resolving DNS.
+    ///
+    /// IP literal hosts are checked against the private ranges here, since
+    /// they never reach the DNS resolver.
+    pub fn check_url(&self, url: &Url) -> Result<()> {
+        self.evaluate_url(url)
+            .map_err(|detail| self.violation(detail))
+    }
+
+    /// Parse `url` and check it with [`check_url`](Self::check_url).
+    pub fn check_url_str(&self, url: &str) -> Result<()> {
+        let parsed = Url::parse(url)
+            .map_err(|e| ZeptoError::Tool(format!("Invalid URL '{}': {}", url, e)))?;
+        self.check_url(&parsed)
+    }
+
+    /// Check a URL, then resolve its hostname and check every address.
+    ///
+    /// Returns the first resolved address so callers that connect by other
+    /// means can pin it, or `None` for IP literals.
+    pub async fn resolve(&self, url: &Url) -> Result<Option<(String, SocketAddr)>> {
+        self.check_url(url)?;
+
+        let host = match parse_host(url) {
+            Some(UrlHost::Domain(host)) => host,
+            Some(UrlHost::Ip(_)) => return Ok(None),
+            None => return Err(self.violation("URL has no host".to_string())),
+        };
+        let port = url.port_or_known_default().unwrap_or(443);
+
+        let addrs: Vec<SocketAddr> = lookup_host((host.as_str(), port))
+            .await
+            .map_err(|e| ZeptoError::Tool(format!("DNS lookup failed for '{}': {}", host, e)))?
+            .collect();
+        self.evaluate_addrs(&host, &addrs)
+            .map_err(|detail| self.violation(detail))?;
+
+        Ok(addrs.into_iter().next().map(|addr| (host, addr)))
+    }
+
+    /// A client builder that enforces these rules on redirects and DNS.
+    ///
+    /// Callers must still [`check_url`](Self::check_url) the initial request
+    /// URL; redirect hops and resolved addresses are checked by the client.
+    pub fn client_builder(&self) -> ClientBuilder {
+        let rules = self.clone();
+        let redirect = reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                return attempt.error("too many redirects");
+            }
+            match rules.evaluate_url(attempt.url()) {
+                Ok(()) => attempt.follow(),
+                Err(detail) => {
+                    rules.audit(&format!("redirect: {}", detail));
+                    attempt.error(EgressViolation(rules.message(&detail)))
+                }
+            }
+        });
+
+        let builder = reqwest::Client::builder().redirect(redirect);
+        if self.inner.block_private {
+            builder.dns_resolver(Arc::new(EgressResolver {
+                rules: self.clone(),
+            }))
+        } else {
+            builder
+        }
+    }
+
+    /// Convert a request error into `SecurityViolation` if it was caused by
+    /// these rules, or `ZeptoError::Tool` prefixed with `context` otherwise.
+    pub fn map_request_error(&self, err: reqwest::Error, context: &str) -> ZeptoError {
+        let mut source: Option<&(dyn std::error::Error + 'static)> = Some(&err);
+        while let Some(e) = source {
+            if let Some(violation) = e.downcast_ref::<EgressViolation>() {
+                return ZeptoError::SecurityViolation(violation.0.clone());
+            }
+            source = e.source();
+        }
+        ZeptoError::Tool(format!("{}: {}", context, err))
+    }
+
+    /// Check every http(s) URL that appears in a shell command.
+    ///
+    /// Used for command-based tools (custom tools, plugins) whose requests
+    /// are made by other programs such as `curl`. This is best effort: URLs
+    /// assembled at run time by the command itself are not seen.
+    pub async fn check_command_urls(&self, command: &str) -> Result<()> {
+        static URL_RE: OnceLock<Regex> = OnceLock::new();
+        let re = URL_RE.get_or_init(|| Regex::new(r#"(?i)https?://[^\s'"`<>|;&()]+"#).unwrap());
+
+        for m in re.find_iter(command) {
+            let url = Url::parse(m.as_str()).map_err(|e| {
+                self.violation(format!(
+                    "unparseable URL '{}' in command: {}",
+                    m.as_str(),
+                    e
+                ))
+            })?;
+            self.resolve(&url).await?;
+        }
+        Ok(())
+    }
+
+    fn evaluate_url(&self, url: &Url) -> std::result::Result<(), String> {
+        let inner = &self.inner;
+        let host = match parse_host(url) {
+            Some(UrlHost::Domain(domain)) => {
+                self.evaluate_domain(&domain)?;
+                if inner.block_private && is_local_hostname(&domain) {
+                    return Err(format!("host '{}' is a local address", domain));
+                }
+                domain
+            }
+            Some(UrlHost::Ip(ip)) => self.evaluate_ip_literal(ip)?,
+            None => return Err(format!("URL '{}' has no host", url)),
+        };
+
+        if !inner.allowed_ports.is_empty() {
+            let port = url.port_or_known_default();
+            if !port.is_some_and(|p| inner.allowed_ports.contains(&p)) {
+                return Err(format!(
+                    "port {} on '{}' is not allowed",
+                    port.map(|p| p.to_string())
+                        .unwrap_or_else(|| "(none)".to_string()),
+                    host
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn evaluate_domain(&self, domain: &str) -> std::result::Result<(), String> {
+        let inner = &self.inner;
+        if let Some(pattern) = inner
+            .deny_domains
+            .iter()
+            .find(|p| domain_matches(domain, p))
+        {
+            return Err(format!(
+                "host '{}' matches denied domain '{}'",
+                domain, pattern
+            ));
+        }
+        if !inner.allow_domains.is_empty()
+            && !inner
+                .allow_domains
+                .iter()
+                .any(|p| domain_matches(domain, p))
+        {
+            return Err(format!("host '{}' is not an allowed domain", domain));
+        }
+        Ok(())
+    }
+
+    fn evaluate_ip_literal(&self, ip: IpAddr) -> std::result::Result<String, String> {
+        let text = ip.to_string();
+        // IP literals only pass a domain allowlist when listed verbatim
+        self.evaluate_domain(&text)?;
+        if self.inner.block_private && is_private_or_local_ip(ip) {
+            return Err(format!("address {} is private or local", ip));
+        }
+        Ok(text)
+    }
+
+    fn evaluate_addrs(&self, host: &str, addrs: &[SocketAddr]) -> std::result::Result<(), String> {
+        if !self.inner.block_private {
+            return Ok(());
+        }
+        match addrs.iter().find(|a| is_private_or_local_ip(a.ip())) {
+            Some(addr) => Err(format!(
+                "host '{}' resolved to private or local address {}",
+                host,
+                addr.ip()
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn message(&self, detail: &str) -> String {
+        match self.inner.tool {
+            Some(ref tool) => format!("Egress blocked for tool '{}': {}", tool, detail),
+            None => format!("Egress blocked: {}", detail),
+        }
+    }
+
+    fn audit(&self, detail: &str) {
+        log_audit_event(
+            AuditCategory::NetworkEgress,
+            AuditSeverity::Critical,
+            "egress_blocked",
+            &self.message(detail),
+            true,
+        );
+    }
+
+    fn violation(&self, detail: String) -> ZeptoError {
+        self.audit(&detail);
+        ZeptoError::SecurityViolation(self.message(&detail))
+    }
+}
+
+/// DNS resolver that fails lookups resolving to private or local addresses.
+struct EgressResolver {
+    rules: EgressRules,
+}
+
+impl Resolve for EgressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let rules = self.rules.clone();
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = lookup_host((host.as_str(), 0)).await?.collect();
+            if let Err(detail) = rules.evaluate_addrs(&host, &addrs) {
+                rules.audit(&detail);
+                return Err(Box::new(EgressViolation(rules.message(&detail))) as _);
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+enum UrlHost {
+    Domain(String),
+    Ip(IpAddr),
+}
+
+/// Extract a URL's host as a normalized domain or an IP address.
+fn parse_host(url: &Url) -> Option<UrlHost> {
+    let host = url.host_str()?;
+    // `Url::host_str()` returns IPv6 addresses in brackets
+    let unbracketed = host
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .unwrap_or(host);
+    if let Ok(ip) = unbracketed.parse::<IpAddr>() {
+        return Some(UrlHost::Ip(ip));
+    }
+    Some(UrlHost::Domain(
+        host.trim_end_matches('.').to_ascii_lowercase(),
+    ))
+}
+
+/// Lowercase a domain entry and strip a leading `*.` and trailing dot.
+fn normalize_domain(entry: &str) -> Option<String> {
+    let domain = entry.trim().to_ascii_lowercase();
+    let domain = domain.strip_prefix("*.").unwrap_or(&domain);
+    let domain = domain.trim_end_matches('.');
+    (!domain.is_empty()).then(|| domain.to_string())
+}
+
+fn domain_matches(host: &str, pattern: &str) -> bool {
+    host == pattern
+        || host
+            .strip_suffix(pattern)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+fn is_local_hostname(host: &str) -> bool {
+    host == "localhost" || host.ends_with(".localhost") || host.ends_with(".local")
+}
+
+/// Whether an address is private, loopback, link-local or otherwise not
+/// publicly routable.
+pub fn is_private_or_local_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(addr) => is_private_or_local_ipv4(addr),
+        IpAddr::V6(addr) => match addr.to_ipv4_mapped() {
+            Some(mapped) => is_private_or_local_ipv4(mapped),
+            None => is_private_or_local_ipv6(addr),
+        },
+    }
+}
+
+fn is_private_or_local_ipv4(addr: Ipv4Addr) -> bool {
+    let octets = addr.octets();
+    addr.is_private()
+        || addr.is_loopback()
+        || addr.is_link_local()
+        || addr.is_broadcast()
+        || addr.is_documentation()
+        || addr.is_unspecified()
+        || octets[0] == 0
+        // Carrier-grade NAT (100.64.0.0/10)
+        || (octets[0] == 100 && (octets[1] & 0xc0) == 64)
+}
+
+fn is_private_or_local_ipv6(addr: Ipv6Addr) -> bool {
+    let first = addr.segments()[0];
+
+    addr.is_loopback()
+        || addr.is_unspecified()
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        || (first & 0xff00) == 0xff00
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    fn policy(config: EgressConfig) -> EgressPolicy {
+        EgressPolicy::from_config(&config)
+    }
+
+    /// Serve one HTTP response redirecting to `location`, returning the base URL.
+    async fn redirect_server(location: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf).await;
+            let response = format!(
+                "HTTP/1.1 302 Found\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                location
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+        format!("http://{}/", addr)
+    }
+
+    #[test]
+    fn test_default_blocks_private_and_local() {
+        let rules = EgressRules::default();
+        for blocked in [
+            "http://localhost/",
+            "http://app.local/",
+            "http://api.localhost:3000/",
+            "http://127.0.0.1/",
+            "http://10.1.2.3/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://100.64.0.1/",
+            "http://[::1]/",
+            "http://[fe80::1]/",
+            "http://[::ffff:127.0.0.1]/",
+        ] {
+            assert!(
+                rules.check_url(&url(blocked)).is_err(),
+                "{} should be blocked",
+                blocked
+            );
+        }
+        assert!(rules.check_url(&url("https://example.com/")).is_ok());
+        assert!(rules.check_url(&url("http://93.184.216.34/")).is_ok());
+    }
+
+    #[test]
+    fn test_domain_lists() {
+        let policy = policy(EgressConfig {
+            allow_domains: vec!["*.example.com".to_string(), "api.test.org".to_string()],
+            deny_domains: vec!["secret.example.com".to_string()],
+            ..Default::default()
+        });
+        let rules = policy.for_tool("web_fetch");
+
+        assert!(rules.check_url(&url("https://example.com/")).is_ok());
+        assert!(rules.check_url(&url("https://docs.EXAMPLE.com./x")).is_ok());
+        assert!(rules.check_url(&url("https://api.test.org/")).is_ok());
+        assert!(rules.check_url(&url("https://test.org/")).is_err());
+        assert!(rules.check_url(&url("https://notexample.com/")).is_err());
+        assert!(rules
+            .check_url(&url("https://a.secret.example.com/"))
+            .is_err());
+        // IP literals do not match domain entries
+        assert!(rules.check_url(&url("http://93.184.216.34/")).is_err());
+    }
+
+    #[test]
+    fn test_allowed_ports() {
+        let rules = policy(EgressConfig {
+            allowed_ports: vec![443],
+            ..Default::default()
+        })
+        .for_tool("web_fetch");
+
+        assert!(rules.check_url(&url("https://example.com/")).is_ok());
+        assert!(rules.check_url(&url("http://example.com/")).is_err());
+        assert!(rules.check_url(&url("https://example.com:8443/")).is_err());
+    }
+
+    #[test]
+    fn test_tool_overrides() {
+        let mut tools = HashMap::new();
+        tools.insert(
+            "r8r".to_string(),
+            EgressToolOverride {
+                allow_domains: Some(vec!["localhost".to_string()]),
+                block_private: Some(false),
+                allowed_ports: Some(vec![8080]),
+                ..Default::default()
+            },
+        );
+        tools.insert(
+            "web_fetch".to_string(),
+            EgressToolOverride {
+                allow_domains: Some(Vec::new()),
+                deny_domains: vec!["evil.com".to_string()],
+                ..Default::default()
+            },
+        );
+        let policy = policy(EgressConfig {
+            allow_domains: vec!["example.com".to_string()],
+            deny_domains: vec!["bad.example.com".to_string()],
+            tools,
+            ..Default::default()
+        });
+
+        let r8r = policy.for_tool("r8r");
+        assert!(r8r.check_url(&url("http://localhost:8080/")).is_ok());
+        assert!(r8r.check_url(&url("http://localhost:9000/")).is_err());
+        assert!(r8r.check_url(&url("http://example.com:8080/")).is_err());
+
+        let web = policy.for_tool("web_fetch");
+        assert!(web.check_url(&url("https://anything.org/")).is_ok());
+        assert!(web.check_url(&url("https://evil.com/")).is_err());
+        // Deny lists are additive
+        assert!(web.check_url(&url("https://bad.example.com/")).is_err());
+        assert!(web.check_url(&url("http://10.0.0.1/")).is_err());
+
+        let other = policy.for_tool("google_sheets");
+        assert!(other.check_url(&url("https://anything.org/")).is_err());
+    }
+
+    #[test]
+    fn test_pin_tool_endpoint() {
+        let mut pinned = EgressPolicy::default();
+        pinned
+            .pin_tool_endpoint("r8r", "http://localhost:8080")
+            .unwrap();
+        let r8r = pinned.for_tool("r8r");
+        assert!(r8r.check_url(&url("http://localhost:8080/api")).is_ok());
+        assert!(r8r.check_url(&url("http://localhost:9000/")).is_err());
+        assert!(r8r.check_url(&url("http://10.0.0.1:8080/")).is_err());
+        assert!(r8r.check_url(&url("https://example.com/")).is_err());
+        // Other tools keep the default policy
+        assert!(pinned
+            .for_tool("web_fetch")
+            .check_url(&url("http://localhost:8080/"))
+            .is_err());
+
+        let mut ip = EgressPolicy::default();
+        ip.pin_tool_endpoint("r8r", "http://[::1]:9000/").unwrap();
+        assert!(ip
+            .for_tool("r8r")
+            .check_url(&url("http://[::1]:9000/"))
+            .is_ok());
+
+        // Global denies still apply, and an explicit override wins
+        let mut tools = HashMap::new();
+        tools.insert("r8r".to_string(), EgressToolOverride::default());
+        let mut explicit = policy(EgressConfig {
+            deny_domains: vec!["workflows.internal".to_string()],
+            tools,
+            ..Default::default()
+        });
+        explicit
+            .pin_tool_endpoint("r8r", "http://localhost:8080")
+            .unwrap();
+        assert!(explicit
+            .for_tool("r8r")
+            .check_url(&url("http://localhost:8080/"))
+            .is_err());
+        let mut denied = policy(EgressConfig {
+            deny_domains: vec!["workflows.internal".to_string()],
+            ..Default::default()
+        });
+        denied
+            .pin_tool_endpoint("r8r", "http://workflows.internal:8080")
+            .unwrap();
+        assert!(denied
+            .for_tool("r8r")
+            .check_url(&url("http://workflows.internal:8080/"))
+            .is_err());
+
+        assert!(EgressPolicy::default()
+            .pin_tool_endpoint("r8r", "not a url")
+            .is_err());
+    }
+
+    #[test]
+    fn test_private_allowed_when_disabled() {
+        let rules = policy(EgressConfig {
+            block_private: false,
+            ..Default::default()
+        })
+        .for_tool("mcp");
+        assert!(rules.check_url(&url("http://localhost:3000/")).is_ok());
+        assert!(rules.check_url(&url("http://10.0.0.5/")).is_ok());
+    }
+
+    #[test]
+    fn test_violation_message_names_tool() {
+        let err = EgressPolicy::default()
+            .for_tool("r8r")
+            .check_url(&url("http://localhost:8080/"))
+            .unwrap_err();
+        assert!(matches!(err, ZeptoError::SecurityViolation(_)));
+        assert!(err.to_string().contains("tool 'r8r'"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_blocks_names_resolving_to_private() {
+        // Bypass the hostname check to exercise the post-resolution check
+        let rules = EgressRules::default();
+        let addrs = vec!["127.0.0.1:80".parse().unwrap()];
+        assert!(rules.evaluate_addrs("internal.example", &addrs).is_err());
+        let addrs = vec!["93.184.216.34:80".parse().unwrap()];
+        assert!(rules.evaluate_addrs("example.com", &addrs).is_ok());
+
+        let resolver = EgressResolver {
+            rules: rules.clone(),
+        };
+        let name: Name = "localhost".parse().unwrap();
+        assert!(resolver.resolve(name).await.is_err());
+
+        assert!(rules
+            .resolve(&url("http://127.0.0.1/"))
+            .await
+            .unwrap_err()
+            .to_string()
+            .contains("private"));
+    }
+
+    #[tokio::test]
+    async fn test_client_resolver_error_maps_to_security_violation() {
+        let rules = EgressRules::default();
+        let client = rules.client_builder().build().unwrap();
+        // Skips `check_url`, so the resolver is what rejects the request
+        let err = client.get("http://localhost:1/").send().await.unwrap_err();
+        let err = rules.map_request_error(err, "Request failed");
+        assert!(matches!(err, ZeptoError::SecurityViolation(_)), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_client_checks_redirect_hops() {
+        let base = redirect_server("http://blocked.example.com/next").await;
+        let rules = policy(EgressConfig {
+            block_private: false,
+            deny_domains: vec!["blocked.example.com".to_string()],
+            ..Default::default()
+        })
+        .for_tool("web_fetch");
+        rules.check_url_str(&base).unwrap();
+
+        let client = rules.client_builder().build().unwrap();
+        let err = client.get(&base).send().await.unwrap_err();
+        let err = rules.map_request_error(err, "Request failed");
+        assert!(
+            err.to_string().contains("blocked.example.com"),
+            "unexpected error: {}",
+            err
+        );
+        assert!(matches!(err, ZeptoError::SecurityViolation(_)));
+    }
+
+    #[tokio::test]
+    async fn test_check_command_urls() {
+        let rules = EgressRules::default();
+        assert!(rules
+            .check_command_urls("curl -s 'http://169.254.169.254/latest/meta-data/'")
+            .await
+            .is_err());
+        assert!(rules
+            .check_command_urls("curl http://localhost:8080/api | jq .")
+            .await
+            .is_err());
+        assert!(rules.check_command_urls("echo no urls here").await.is_ok());
+    }
+}
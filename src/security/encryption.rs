@@ -18,6 +18,9 @@ use base64::Engine;
 use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
 use chacha20poly1305::{AeadCore, XChaCha20Poly1305, XNonce};
 
+use std::sync::Arc;
+
+use crate::config::Config;
 use crate::error::{Result, ZeptoError};
 
 // ============================================================================
@@ -317,6 +320,9 @@ pub fn is_secret_field(field_name: &str) -> bool {
             | "verification_token"
             | "service_account_base64"
             | "webhook_verify_token"
+            | "password"
+            | "callback_secret"
+            | "secret"
     )
 }
 
@@ -333,21 +339,8 @@ pub fn is_secret_field(field_name: &str) -> bool {
 /// 64-character hex, or if no key source is available.
 pub fn resolve_master_key(interactive: bool) -> Result<SecretEncryption> {
     // 1. Try environment variable (hex-encoded 32 bytes = 64 hex chars)
-    if let Ok(hex_key) = std::env::var("ZEPTOCLAW_MASTER_KEY") {
-        let bytes = hex::decode(hex_key.trim()).map_err(|e| {
-            ZeptoError::Config(format!("ZEPTOCLAW_MASTER_KEY is not valid hex: {e}"))
-        })?;
-
-        if bytes.len() != 32 {
-            return Err(ZeptoError::Config(format!(
-                "ZEPTOCLAW_MASTER_KEY must be 64 hex chars (32 bytes), got {} bytes",
-                bytes.len()
-            )));
-        }
-
-        let mut key = [0u8; 32];
-        key.copy_from_slice(&bytes);
-        return Ok(SecretEncryption::from_raw_key(&key));
+    if let Some(enc) = key_from_env("ZEPTOCLAW_MASTER_KEY")? {
+        return Ok(enc);
     }
 
     // 2. Interactive passphrase prompt
@@ -368,6 +361,131 @@ pub fn resolve_master_key(interactive: bool) -> Result<SecretEncryption> {
     ))
 }
 
+/// Read the replacement key for `secrets rotate` from
+/// `ZEPTOCLAW_NEW_MASTER_KEY`, if set.
+///
+/// Encrypted data stores are opened with a raw key, so rotating them requires
+/// the new key in this form rather than a passphrase.
+///
+/// # Errors
+///
+/// Returns `ZeptoError::Config` if the variable is set but is not 64 hex chars.
+pub fn resolve_new_master_key() -> Result<Option<SecretEncryption>> {
+    key_from_env("ZEPTOCLAW_NEW_MASTER_KEY")
+}
+
+/// Parse a hex-encoded 32-byte key from an environment variable.
+fn key_from_env(var: &str) -> Result<Option<SecretEncryption>> {
+    let Ok(hex_key) = std::env::var(var) else {
+        return Ok(None);
+    };
+
+    let bytes = hex::decode(hex_key.trim())
+        .map_err(|e| ZeptoError::Config(format!("{var} is not valid hex: {e}")))?;
+
+    if bytes.len() != 32 {
+        return Err(ZeptoError::Config(format!(
+            "{var} must be 64 hex chars (32 bytes), got {} bytes",
+            bytes.len()
+        )));
+    }
+
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&bytes);
+    Ok(Some(SecretEncryption::from_raw_key(&key)))
+}
+
+// ============================================================================
+// StoreEncryption
+// ============================================================================
+
+/// At-rest encryption for persisted data stores (sessions, long-term memory,
+/// reminders, cron jobs).
+///
+/// An enabled store writes each file as a single `ENC[...]` envelope. Reads
+/// accept both envelopes and plaintext JSON, so existing files migrate
+/// transparently on their next write. A disabled store writes plaintext and
+/// refuses to read envelopes.
+#[derive(Clone, Default)]
+pub struct StoreEncryption {
+    cipher: Option<Arc<SecretEncryption>>,
+}
+
+impl std::fmt::Debug for StoreEncryption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StoreEncryption")
+            .field("enabled", &self.is_enabled())
+            .finish()
+    }
+}
+
+impl StoreEncryption {
+    /// Plaintext storage.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Encrypt stores with the given key.
+    pub fn new(encryption: SecretEncryption) -> Self {
+        Self {
+            cipher: Some(Arc::new(encryption)),
+        }
+    }
+
+    /// Build from `storage.encrypt_at_rest`.
+    ///
+    /// Data stores are read without a prompt, so the key must come from
+    /// `ZEPTOCLAW_MASTER_KEY`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZeptoError::Config` if encryption is enabled but no valid
+    /// key is set.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        if !config.storage.encrypt_at_rest {
+            return Ok(Self::disabled());
+        }
+        let key = resolve_master_key(false).map_err(|e| {
+            ZeptoError::Config(format!("storage.encrypt_at_rest is enabled but {e}"))
+        })?;
+        Ok(Self::new(key))
+    }
+
+    /// Whether writes are encrypted.
+    pub fn is_enabled(&self) -> bool {
+        self.cipher.is_some()
+    }
+
+    /// Prepare serialized store content for writing.
+    pub fn seal(&self, plaintext: &str) -> Result<String> {
+        match self.cipher {
+            Some(ref cipher) => cipher.encrypt(plaintext),
+            None => Ok(plaintext.to_string()),
+        }
+    }
+
+    /// Recover serialized store content read from disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ZeptoError::Config` if the content is an envelope and no key
+    /// is configured, or if decryption fails.
+    pub fn open(&self, stored: &str) -> Result<String> {
+        let trimmed = stored.trim();
+        if !SecretEncryption::is_encrypted(trimmed) {
+            return Ok(stored.to_string());
+        }
+        match self.cipher {
+            Some(ref cipher) => cipher.decrypt(trimmed),
+            None => Err(ZeptoError::Config(
+                "data store is encrypted: enable storage.encrypt_at_rest and set \
+                 ZEPTOCLAW_MASTER_KEY"
+                    .into(),
+            )),
+        }
+    }
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -553,6 +671,9 @@ mod tests {
             "verification_token",
             "service_account_base64",
             "webhook_verify_token",
+            "password",
+            "callback_secret",
+            "secret",
         ];
         for field in &secret_fields {
             assert!(
@@ -579,4 +700,47 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_store_encryption_disabled_passthrough() {
+        let store = StoreEncryption::disabled();
+        assert!(!store.is_enabled());
+        assert_eq!(store.seal("{\"a\":1}").unwrap(), "{\"a\":1}");
+        assert_eq!(store.open("{\"a\":1}").unwrap(), "{\"a\":1}");
+    }
+
+    #[test]
+    fn test_store_encryption_roundtrip() {
+        let store = StoreEncryption::new(SecretEncryption::from_raw_key(&[3u8; 32]));
+        assert!(store.is_enabled());
+        let sealed = store.seal("{\"a\":1}").unwrap();
+        assert!(SecretEncryption::is_encrypted(&sealed));
+        assert_eq!(store.open(&sealed).unwrap(), "{\"a\":1}");
+        // Trailing whitespace from manual edits is tolerated.
+        assert_eq!(store.open(&format!("{sealed}\n")).unwrap(), "{\"a\":1}");
+    }
+
+    #[test]
+    fn test_store_encryption_reads_plaintext() {
+        let store = StoreEncryption::new(SecretEncryption::from_raw_key(&[3u8; 32]));
+        assert_eq!(
+            store.open("{\"legacy\":true}").unwrap(),
+            "{\"legacy\":true}"
+        );
+    }
+
+    #[test]
+    fn test_store_encryption_requires_key_for_envelope() {
+        let sealed = StoreEncryption::new(SecretEncryption::from_raw_key(&[3u8; 32]))
+            .seal("{}")
+            .unwrap();
+        let err = StoreEncryption::disabled().open(&sealed).unwrap_err();
+        assert!(err.to_string().contains("storage.encrypt_at_rest"));
+    }
+
+    #[test]
+    fn test_store_encryption_from_config_disabled() {
+        let config = Config::default();
+        assert!(!StoreEncryption::from_config(&config).unwrap().is_enabled());
+    }
 }
@@ -3,12 +3,17 @@
 //! This module provides security utilities including path validation
 //! and command filtering to prevent malicious tool execution.
 
+pub mod egress;
 pub mod encryption;
 pub mod mount;
 pub mod path;
 pub mod shell;
+pub mod shell_parser;
+pub mod shell_policy;
 
-pub use encryption::{is_secret_field, resolve_master_key, SecretEncryption};
+pub use egress::{EgressPolicy, EgressRules};
+pub use encryption::{is_secret_field, resolve_master_key, SecretEncryption, StoreEncryption};
 pub use mount::{validate_extra_mounts, validate_mount_not_blocked, DEFAULT_BLOCKED_PATTERNS};
 pub use path::{validate_path_in_workspace, SafePath};
 pub use shell::ShellSecurityConfig;
+pub use shell_policy::{DenialReason, ShellPolicy, ShellPolicyDenial};
@@ -1,13 +1,18 @@
 //! Shell command security utilities
 //!
 //! Provides command filtering to prevent dangerous shell operations.
-//! Uses regex-based pattern matching to prevent bypass attacks.
+//! Uses regex-based pattern matching to prevent bypass attacks, optionally
+//! followed by an allowlist [`ShellPolicy`] that parses the command.
+
+use std::sync::Arc;
 
 use regex::Regex;
 
 use crate::audit::{log_audit_event, AuditCategory, AuditSeverity};
 use crate::error::{Result, ZeptoError};
 
+use super::shell_policy::ShellPolicy;
+
 /// Regex patterns that are blocked for security reasons.
 /// These are compiled once and matched against commands.
 ///
@@ -75,6 +80,8 @@ pub struct ShellSecurityConfig {
     literal_patterns: Vec<String>,
     /// Whether to enable security checks (can be disabled for trusted environments)
     pub enabled: bool,
+    /// Allowlist policy checked after the blocklist, if configured
+    policy: Option<Arc<ShellPolicy>>,
 }
 
 impl Default for ShellSecurityConfig {
@@ -104,6 +111,7 @@ impl ShellSecurityConfig {
             compiled_patterns,
             literal_patterns,
             enabled: true,
+            policy: None,
         }
     }
 
@@ -116,6 +124,7 @@ impl ShellSecurityConfig {
             compiled_patterns: Vec::new(),
             literal_patterns: Vec::new(),
             enabled: false,
+            policy: None,
         }
     }
 
@@ -133,11 +142,26 @@ impl ShellSecurityConfig {
         self
     }
 
+    /// Require commands to also pass an allowlist policy.
+    pub fn with_policy(mut self, policy: ShellPolicy) -> Self {
+        self.policy = Some(Arc::new(policy));
+        self
+    }
+
     /// Check if a command is allowed.
     ///
     /// Returns `Ok(())` if the command is safe to execute,
     /// or `Err(SecurityViolation)` if it matches a blocked pattern.
     pub fn validate_command(&self, command: &str) -> Result<()> {
+        self.validate_command_in_channel(command, None)
+    }
+
+    /// Check if a command is allowed when requested from `channel`.
+    ///
+    /// Runs the blocklist, then the allowlist policy (with the channel's
+    /// rules) if one is configured. Policy denials are returned as
+    /// `SecurityViolation` carrying the JSON explanation.
+    pub fn validate_command_in_channel(&self, command: &str, channel: Option<&str>) -> Result<()> {
         if !self.enabled {
             return Ok(());
         }
@@ -181,6 +205,24 @@ impl ShellSecurityConfig {
             }
         }
 
+        // Check allowlist policy
+        if let Some(ref policy) = self.policy {
+            if let Err(denial) = policy.check(command, channel) {
+                log_audit_event(
+                    AuditCategory::ShellSecurity,
+                    AuditSeverity::Critical,
+                    "command_blocked_policy",
+                    &format!(
+                        "Command blocked by shell policy in channel '{}': {}",
+                        channel.unwrap_or("-"),
+                        denial.detail
+                    ),
+                    true,
+                );
+                return Err(ZeptoError::SecurityViolation(denial.to_string()));
+            }
+        }
+
         Ok(())
     }
 }
@@ -414,6 +456,32 @@ mod tests {
             .is_err());
     }
 
+    #[test]
+    fn test_policy_checked_after_blocklist() {
+        let policy = ShellPolicy::from_config(&crate::config::ShellToolConfig {
+            allow: vec![crate::config::ShellAllowRule {
+                binary: "cat".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        })
+        .unwrap();
+        let config = ShellSecurityConfig::new().with_policy(policy);
+
+        assert!(config.validate_command("cat notes.txt").is_ok());
+        let err = config.validate_command("ls").unwrap_err().to_string();
+        assert!(err.contains("binary_not_allowed"));
+        // The blocklist still applies to allowed binaries
+        let err = config
+            .validate_command("cat /etc/shadow")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("prohibited path"));
+        assert!(config
+            .validate_command_in_channel("cat a | cat", Some("cli"))
+            .is_ok());
+    }
+
     #[test]
     fn test_safe_scripting_allowed() {
         let config = ShellSecurityConfig::new();
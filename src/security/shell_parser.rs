@@ -0,0 +1,875 @@
+//! A conservative parser for POSIX shell commands
+//!
+//! Parses the subset of shell syntax an agent normally produces into an AST:
+//! lists (`;`, `&`, `&&`, `||`, newlines), pipelines, subshells, simple
+//! commands with assignments and redirects, and command substitutions
+//! (`$(...)` and backticks) nested inside words.
+//!
+//! Anything it cannot analyse statically (compound commands such as `if` or
+//! `for`, function definitions, here-documents, process substitution,
+//! arithmetic commands, `$'...'` quoting) is reported as
+//! [`ParseError::Unsupported`] so callers can reject it rather than guess.
+
+use std::fmt;
+
+/// A parsed shell command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShellNode {
+    /// Pipelines joined by `;`, `&`, `&&`, `||` or newlines
+    List(Vec<ShellNode>),
+    /// Commands connected with `|`
+    Pipeline {
+        /// Whether the pipeline is prefixed with `!`
+        negated: bool,
+        /// The commands in the pipeline
+        commands: Vec<ShellNode>,
+    },
+    /// A `( ... )` subshell with its redirects
+    Subshell {
+        /// The commands run in the subshell
+        body: Box<ShellNode>,
+        /// Redirects applied to the subshell
+        redirects: Vec<Redirect>,
+    },
+    /// A simple command
+    Simple(SimpleCommand),
+}
+
+/// A command name with arguments, prefix assignments and redirects.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimpleCommand {
+    /// `NAME=value` words before the command name
+    pub assignments: Vec<Word>,
+    /// The command name followed by its arguments
+    pub words: Vec<Word>,
+    /// Redirects anywhere in the command
+    pub redirects: Vec<Redirect>,
+}
+
+/// A shell word after quote removal.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Word {
+    /// Literal text with quotes removed; expansions are kept verbatim
+    pub text: String,
+    /// Whether the value depends on expansions (variables, substitutions,
+    /// arithmetic, brace, tilde and glob expansion) and cannot be known
+    /// before running
+    pub dynamic: bool,
+    /// Commands run by `$(...)` or backtick substitutions in this word
+    pub substitutions: Vec<ShellNode>,
+}
+
+/// An I/O redirect such as `> out.txt` or `2>&1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redirect {
+    /// File descriptor prefix, if any (the `2` in `2>&1`)
+    pub fd: Option<u32>,
+    /// Operator: `<`, `>`, `>>`, `>|`, `<>`, `<&` or `>&`
+    pub op: String,
+    /// Target file or file descriptor
+    pub target: Word,
+}
+
+impl Redirect {
+    /// Whether this duplicates or closes a file descriptor (`2>&1`, `>&-`)
+    /// rather than opening a file.
+    pub fn is_fd_duplication(&self) -> bool {
+        (self.op == ">&" || self.op == "<&")
+            && !self.target.dynamic
+            && (self.target.text == "-" || self.target.text.chars().all(|c| c.is_ascii_digit()))
+    }
+}
+
+/// Why a command could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The command is malformed (e.g. an unterminated quote)
+    Syntax(String),
+    /// The command uses syntax this parser deliberately does not analyse
+    Unsupported(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Syntax(msg) => write!(f, "syntax error: {}", msg),
+            ParseError::Unsupported(msg) => write!(f, "unsupported shell construct: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Reserved words that start compound commands.
+const RESERVED_WORDS: &[&str] = &[
+    "if", "then", "else", "elif", "fi", "for", "while", "until", "do", "done", "case", "esac",
+    "select", "function", "{", "}", "[[", "]]", "coproc",
+];
+
+/// Parses a shell command line.
+pub fn parse(input: &str) -> std::result::Result<ShellNode, ParseError> {
+    let mut parser = Parser {
+        chars: input.chars().collect(),
+        pos: 0,
+    };
+    let node = parser.parse_list(None)?;
+    if parser.pos < parser.chars.len() {
+        return Err(ParseError::Syntax(format!(
+            "unexpected '{}'",
+            parser.chars[parser.pos]
+        )));
+    }
+    Ok(node)
+}
+
+impl ShellNode {
+    /// Every simple command in the tree, including those inside subshells
+    /// and command substitutions.
+    pub fn simple_commands(&self) -> Vec<&SimpleCommand> {
+        let mut out = Vec::new();
+        self.collect_simple(&mut out);
+        out
+    }
+
+    /// Every redirect in the tree (on simple commands and subshells).
+    pub fn redirects(&self) -> Vec<&Redirect> {
+        let mut out = Vec::new();
+        for command in self.simple_commands() {
+            out.extend(command.redirects.iter());
+        }
+        self.collect_subshell_redirects(&mut out);
+        out
+    }
+
+    fn collect_simple<'a>(&'a self, out: &mut Vec<&'a SimpleCommand>) {
+        match self {
+            ShellNode::List(items) => items.iter().for_each(|n| n.collect_simple(out)),
+            ShellNode::Pipeline { commands, .. } => {
+                commands.iter().for_each(|n| n.collect_simple(out))
+            }
+            ShellNode::Subshell { body, redirects } => {
+                body.collect_simple(out);
+                for redirect in redirects {
+                    collect_word_substitutions(&redirect.target, out);
+                }
+            }
+            ShellNode::Simple(command) => {
+                out.push(command);
+                let words = command
+                    .assignments
+                    .iter()
+                    .chain(&command.words)
+                    .chain(command.redirects.iter().map(|r| &r.target));
+                for word in words {
+                    collect_word_substitutions(word, out);
+                }
+            }
+        }
+    }
+
+    fn collect_subshell_redirects<'a>(&'a self, out: &mut Vec<&'a Redirect>) {
+        match self {
+            ShellNode::List(items) => items.iter().for_each(|n| n.collect_subshell_redirects(out)),
+            ShellNode::Pipeline { commands, .. } => commands
+                .iter()
+                .for_each(|n| n.collect_subshell_redirects(out)),
+            ShellNode::Subshell { body, redirects } => {
+                out.extend(redirects.iter());
+                body.collect_subshell_redirects(out);
+            }
+            ShellNode::Simple(_) => {}
+        }
+    }
+}
+
+fn collect_word_substitutions<'a>(word: &'a Word, out: &mut Vec<&'a SimpleCommand>) {
+    for node in &word.substitutions {
+        node.collect_simple(out);
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        s.chars()
+            .enumerate()
+            .all(|(i, c)| self.peek_at(i) == Some(c))
+    }
+
+    fn skip_blanks(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == ' ' || c == '\t' {
+                self.pos += 1;
+            } else if c == '\\' && self.peek_at(1) == Some('\n') {
+                self.pos += 2;
+            } else if c == '#' {
+                self.skip_comment();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn skip_blanks_and_newlines(&mut self) {
+        loop {
+            self.skip_blanks();
+            if self.peek() == Some('\n') {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '\n' {
+                break;
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn parse_list(&mut self, end: Option<char>) -> std::result::Result<ShellNode, ParseError> {
+        let mut items = Vec::new();
+        loop {
+            self.skip_blanks_and_newlines();
+            match self.peek() {
+                None => break,
+                Some(c) if Some(c) == end => break,
+                _ => {}
+            }
+
+            items.push(self.parse_pipeline()?);
+
+            self.skip_blanks();
+            if self.starts_with("&&") || self.starts_with("||") {
+                self.pos += 2;
+                self.skip_blanks_and_newlines();
+                if self.peek().is_none() || self.peek() == end {
+                    return Err(ParseError::Syntax(
+                        "expected a command after '&&' or '||'".to_string(),
+                    ));
+                }
+            } else if self.starts_with(";;") {
+                return Err(ParseError::Syntax("unexpected ';;'".to_string()));
+            } else if matches!(self.peek(), Some(';') | Some('&') | Some('\n')) {
+                self.pos += 1;
+            } else if self.peek().is_none() || self.peek() == end {
+                break;
+            } else {
+                return Err(ParseError::Syntax(format!(
+                    "unexpected '{}'",
+                    self.peek().unwrap_or_default()
+                )));
+            }
+        }
+
+        if items.is_empty() {
+            return Err(ParseError::Syntax("empty command".to_string()));
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            ShellNode::List(items)
+        })
+    }
+
+    fn parse_pipeline(&mut self) -> std::result::Result<ShellNode, ParseError> {
+        self.skip_blanks();
+        let mut negated = false;
+        if self.peek() == Some('!') && matches!(self.peek_at(1), Some(' ') | Some('\t')) {
+            negated = true;
+            self.pos += 1;
+        }
+
+        let mut commands = vec![self.parse_command()?];
+        loop {
+            self.skip_blanks();
+            if self.peek() == Some('|') && self.peek_at(1) != Some('|') {
+                if self.peek_at(1) == Some('&') {
+                    return Err(ParseError::Unsupported("'|&' pipes".to_string()));
+                }
+                self.pos += 1;
+                self.skip_blanks_and_newlines();
+                commands.push(self.parse_command()?);
+            } else {
+                break;
+            }
+        }
+
+        Ok(if commands.len() == 1 && !negated {
+            commands.remove(0)
+        } else {
+            ShellNode::Pipeline { negated, commands }
+        })
+    }
+
+    fn parse_command(&mut self) -> std::result::Result<ShellNode, ParseError> {
+        self.skip_blanks();
+        if self.peek() == Some('(') {
+            if self.peek_at(1) == Some('(') {
+                return Err(ParseError::Unsupported(
+                    "arithmetic command '(( ))'".to_string(),
+                ));
+            }
+            self.pos += 1;
+            let body = self.parse_list(Some(')'))?;
+            if self.peek() != Some(')') {
+                return Err(ParseError::Syntax("unterminated subshell".to_string()));
+            }
+            self.pos += 1;
+
+            let mut redirects = Vec::new();
+            loop {
+                self.skip_blanks();
+                match self.try_parse_redirect()? {
+                    Some(redirect) => redirects.push(redirect),
+                    None => break,
+                }
+            }
+            return Ok(ShellNode::Subshell {
+                body: Box::new(body),
+                redirects,
+            });
+        }
+        self.parse_simple()
+    }
+
+    fn parse_simple(&mut self) -> std::result::Result<ShellNode, ParseError> {
+        let mut command = SimpleCommand::default();
+        loop {
+            self.skip_blanks();
+            match self.peek() {
+                None | Some('|') | Some(';') | Some(')') | Some('\n') => break,
+                Some('&') => {
+                    if self.peek_at(1) == Some('>') {
+                        return Err(ParseError::Unsupported("'&>' redirects".to_string()));
+                    }
+                    break;
+                }
+                Some('(') => {
+                    return Err(ParseError::Unsupported("function definitions".to_string()))
+                }
+                _ => {}
+            }
+
+            if let Some(redirect) = self.try_parse_redirect()? {
+                command.redirects.push(redirect);
+                continue;
+            }
+
+            let word = self.parse_word()?;
+            if command.words.is_empty() {
+                if RESERVED_WORDS.contains(&word.text.as_str()) && !word.dynamic {
+                    return Err(ParseError::Unsupported(format!(
+                        "compound command '{}'",
+                        word.text
+                    )));
+                }
+                if is_assignment(&word.text) {
+                    command.assignments.push(word);
+                    continue;
+                }
+            }
+            command.words.push(word);
+        }
+
+        if command.words.is_empty()
+            && command.assignments.is_empty()
+            && command.redirects.is_empty()
+        {
+            return Err(ParseError::Syntax(format!(
+                "expected a command{}",
+                self.peek()
+                    .map(|c| format!(" before '{}'", c))
+                    .unwrap_or_default()
+            )));
+        }
+        Ok(ShellNode::Simple(command))
+    }
+
+    /// Parses a redirect at the current position, if there is one.
+    fn try_parse_redirect(&mut self) -> std::result::Result<Option<Redirect>, ParseError> {
+        let start = self.pos;
+        let mut digits = String::new();
+        while let Some(c) = self.peek().filter(|c| c.is_ascii_digit()) {
+            digits.push(c);
+            self.pos += 1;
+        }
+
+        let op = match (self.peek(), self.peek_at(1)) {
+            (Some('<'), Some('<')) => {
+                return Err(ParseError::Unsupported(
+                    "here-documents and here-strings".to_string(),
+                ))
+            }
+            (Some('<'), Some('(')) | (Some('>'), Some('(')) => {
+                return Err(ParseError::Unsupported("process substitution".to_string()))
+            }
+            (Some('<'), Some('&')) => "<&",
+            (Some('<'), Some('>')) => "<>",
+            (Some('<'), _) => "<",
+            (Some('>'), Some('>')) => ">>",
+            (Some('>'), Some('&')) => ">&",
+            (Some('>'), Some('|')) => ">|",
+            (Some('>'), _) => ">",
+            _ => {
+                self.pos = start;
+                return Ok(None);
+            }
+        };
+        self.pos += op.len();
+        self.skip_blanks();
+
+        if matches!(
+            self.peek(),
+            None | Some('|')
+                | Some('&')
+                | Some(';')
+                | Some('<')
+                | Some('>')
+                | Some('(')
+                | Some(')')
+                | Some('\n')
+        ) {
+            return Err(ParseError::Syntax(format!(
+                "missing redirect target after '{}'",
+                op
+            )));
+        }
+        let target = self.parse_word()?;
+
+        Ok(Some(Redirect {
+            fd: digits.parse().ok(),
+            op: op.to_string(),
+            target,
+        }))
+    }
+
+    fn parse_word(&mut self) -> std::result::Result<Word, ParseError> {
+        let mut word = Word::default();
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            match c {
+                ' ' | '\t' | '\n' | '|' | '&' | ';' | '<' | '>' | '(' | ')' => break,
+                '\'' => {
+                    self.pos += 1;
+                    loop {
+                        match self.peek() {
+                            None => {
+                                return Err(ParseError::Syntax(
+                                    "unterminated single quote".to_string(),
+                                ))
+                            }
+                            Some('\'') => {
+                                self.pos += 1;
+                                break;
+                            }
+                            Some(c) => {
+                                word.text.push(c);
+                                self.pos += 1;
+                            }
+                        }
+                    }
+                }
+                '"' => {
+                    self.pos += 1;
+                    self.parse_double_quoted(&mut word)?;
+                }
+                '\\' => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some('\n') => self.pos += 1,
+                        Some(c) => {
+                            word.text.push(c);
+                            self.pos += 1;
+                        }
+                        None => word.text.push('\\'),
+                    }
+                }
+                '$' => self.parse_dollar(&mut word)?,
+                '`' => self.parse_backticks(&mut word)?,
+                '{' => {
+                    // Brace expansion (`{a,b}`) can turn one word into many
+                    word.dynamic = true;
+                    word.text.push(c);
+                    self.pos += 1;
+                }
+                '~' => {
+                    // Tilde expansion at the start of a word or of an
+                    // assignment value (`~`, `~user`, `PATH=a:~/bin`)
+                    if self.pos == start || word.text.ends_with(['=', ':']) {
+                        word.dynamic = true;
+                    }
+                    word.text.push(c);
+                    self.pos += 1;
+                }
+                '*' | '?' => {
+                    word.dynamic = true;
+                    word.text.push(c);
+                    self.pos += 1;
+                }
+                '[' => {
+                    // A bracket expression needs a closing `]` in the same
+                    // word; a lone `[` (the `test` builtin) stays literal
+                    if self.closes_bracket() {
+                        word.dynamic = true;
+                    }
+                    word.text.push(c);
+                    self.pos += 1;
+                }
+                _ => {
+                    word.text.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+        Ok(word)
+    }
+
+    /// Whether a `]` follows the `[` at the cursor before the word ends.
+    fn closes_bracket(&self) -> bool {
+        (1..)
+            .map_while(|offset| self.peek_at(offset))
+            .take_while(|c| {
+                !matches!(
+                    c,
+                    ' ' | '\t' | '\n' | '|' | '&' | ';' | '<' | '>' | '(' | ')'
+                )
+            })
+            .any(|c| c == ']')
+    }
+
+    fn parse_double_quoted(&mut self, word: &mut Word) -> std::result::Result<(), ParseError> {
+        loop {
+            match self.peek() {
+                None => return Err(ParseError::Syntax("unterminated double quote".to_string())),
+                Some('"') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(c @ ('$' | '`' | '"' | '\\')) => {
+                            word.text.push(c);
+                            self.pos += 1;
+                        }
+                        Some('\n') => self.pos += 1,
+                        _ => word.text.push('\\'),
+                    }
+                }
+                Some('$') => self.parse_dollar(word)?,
+                Some('`') => self.parse_backticks(word)?,
+                Some(c) => {
+                    word.text.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    /// Parses `$...` expansions. The cursor is on the `$`.
+    fn parse_dollar(&mut self, word: &mut Word) -> std::result::Result<(), ParseError> {
+        let start = self.pos;
+        match self.peek_at(1) {
+            Some('(') if self.peek_at(2) == Some('(') => {
+                self.pos += 3;
+                let inner = self.scan_balanced('(', ')', 2)?;
+                if inner.contains('`') || inner.contains("$(") {
+                    return Err(ParseError::Unsupported(
+                        "command substitution inside arithmetic expansion".to_string(),
+                    ));
+                }
+            }
+            Some('(') => {
+                self.pos += 2;
+                let node = self.parse_list(Some(')'))?;
+                if self.peek() != Some(')') {
+                    return Err(ParseError::Syntax(
+                        "unterminated command substitution".to_string(),
+                    ));
+                }
+                self.pos += 1;
+                word.substitutions.push(node);
+            }
+            Some('{') => {
+                self.pos += 2;
+                let inner = self.scan_balanced('{', '}', 1)?;
+                if inner.contains('`') || inner.contains("$(") {
+                    return Err(ParseError::Unsupported(
+                        "command substitution inside parameter expansion".to_string(),
+                    ));
+                }
+            }
+            Some('\'') => {
+                return Err(ParseError::Unsupported("$'...' quoting".to_string()));
+            }
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                self.pos += 1;
+                while self
+                    .peek()
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+                {
+                    self.pos += 1;
+                }
+            }
+            Some(c) if c.is_ascii_digit() || "@*#?-$!".contains(c) => {
+                self.pos += 2;
+            }
+            _ => {
+                // A lone `$` is literal
+                word.text.push('$');
+                self.pos += 1;
+                return Ok(());
+            }
+        }
+
+        word.dynamic = true;
+        word.text.extend(&self.chars[start..self.pos]);
+        Ok(())
+    }
+
+    /// Parses a backtick substitution. The cursor is on the opening backtick.
+    fn parse_backticks(&mut self, word: &mut Word) -> std::result::Result<(), ParseError> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut inner = String::new();
+        loop {
+            match self.peek() {
+                None => {
+                    return Err(ParseError::Syntax(
+                        "unterminated backtick substitution".to_string(),
+                    ))
+                }
+                Some('`') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some('\\') => {
+                    match self.peek_at(1) {
+                        Some('`') => {
+                            return Err(ParseError::Unsupported(
+                                "nested backtick substitution".to_string(),
+                            ))
+                        }
+                        Some(c @ ('$' | '\\')) => inner.push(c),
+                        Some(c) => {
+                            inner.push('\\');
+                            inner.push(c);
+                        }
+                        None => inner.push('\\'),
+                    }
+                    self.pos += 2;
+                }
+                Some(c) => {
+                    inner.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+
+        word.substitutions.push(parse(&inner)?);
+        word.dynamic = true;
+        word.text.extend(&self.chars[start..self.pos]);
+        Ok(())
+    }
+
+    /// Skips to the bracket matching an already consumed opening one,
+    /// returning the text in between. `closers` is how many closing
+    /// brackets end the construct (2 for `$(( ))`).
+    fn scan_balanced(
+        &mut self,
+        open: char,
+        close: char,
+        closers: usize,
+    ) -> std::result::Result<String, ParseError> {
+        let mut depth = 0usize;
+        let mut inner = String::new();
+        while let Some(c) = self.peek() {
+            if c == open {
+                depth += 1;
+            } else if c == close {
+                if depth == 0 {
+                    if (1..closers).all(|i| self.peek_at(i) == Some(close)) {
+                        self.pos += closers;
+                        return Ok(inner);
+                    }
+                    return Err(ParseError::Syntax(format!("unbalanced '{}'", close)));
+                }
+                depth -= 1;
+            }
+            inner.push(c);
+            self.pos += 1;
+        }
+        Err(ParseError::Syntax(format!("missing '{}'", close)))
+    }
+}
+
+fn is_assignment(text: &str) -> bool {
+    match text.split_once('=') {
+        Some((name, _)) => {
+            let mut chars = name.chars();
+            chars
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(command: &SimpleCommand) -> Vec<&str> {
+        command.words.iter().map(|w| w.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_simple_command_with_quotes() {
+        let node = parse(r#"git commit -m "fix: it's done" 'a b' c\ d"#).unwrap();
+        let commands = node.simple_commands();
+        assert_eq!(commands.len(), 1);
+        assert_eq!(
+            words(commands[0]),
+            vec!["git", "commit", "-m", "fix: it's done", "a b", "c d"]
+        );
+        assert!(commands[0].words.iter().all(|w| !w.dynamic));
+    }
+
+    #[test]
+    fn test_parse_lists_and_pipelines() {
+        let node = parse("ls -la | grep foo && echo ok; cat a || true &\nwc -l").unwrap();
+        let names: Vec<&str> = node
+            .simple_commands()
+            .iter()
+            .map(|c| c.words[0].text.as_str())
+            .collect();
+        assert_eq!(names, vec!["ls", "grep", "echo", "cat", "true", "wc"]);
+        assert!(matches!(node, ShellNode::List(ref items) if items.len() == 5));
+    }
+
+    #[test]
+    fn test_parse_subshell_and_substitutions() {
+        let node = parse("(cd sub && make) > log; echo $(whoami) `date`").unwrap();
+        let names: Vec<&str> = node
+            .simple_commands()
+            .iter()
+            .map(|c| c.words[0].text.as_str())
+            .collect();
+        assert_eq!(names, vec!["cd", "make", "echo", "whoami", "date"]);
+        assert_eq!(node.redirects().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_redirects() {
+        let node = parse("make 2>&1 >out.txt < in.txt").unwrap();
+        let command = node.simple_commands()[0];
+        assert_eq!(words(command), vec!["make"]);
+        assert_eq!(command.redirects.len(), 3);
+        assert!(command.redirects[0].is_fd_duplication());
+        assert_eq!(command.redirects[0].fd, Some(2));
+        assert_eq!(command.redirects[1].op, ">");
+        assert_eq!(command.redirects[1].target.text, "out.txt");
+        assert!(!command.redirects[1].is_fd_duplication());
+    }
+
+    #[test]
+    fn test_parse_marks_dynamic_words() {
+        let node = parse("echo $HOME \"${USER}\" $((1+2)) {a,b} plain").unwrap();
+        let dynamic: Vec<bool> = node.simple_commands()[0]
+            .words
+            .iter()
+            .map(|w| w.dynamic)
+            .collect();
+        assert_eq!(dynamic, vec![false, true, true, true, true, false]);
+    }
+
+    #[test]
+    fn test_parse_marks_tilde_and_globs_dynamic() {
+        let node =
+            parse("cat ~root/.ssh/id_rsa ~/../x a~b '~' *.rs '*' \"a?\" f? [ab] [ x]").unwrap();
+        let dynamic: Vec<bool> = node.simple_commands()[0]
+            .words
+            .iter()
+            .map(|w| w.dynamic)
+            .collect();
+        assert_eq!(
+            dynamic,
+            vec![false, true, true, false, false, true, false, false, true, true, false, false]
+        );
+
+        let node = parse("PATH=/bin:~/bin env").unwrap();
+        assert!(node.simple_commands()[0].assignments[0].dynamic);
+    }
+
+    #[test]
+    fn test_parse_assignments() {
+        let node = parse("FOO=bar BAZ=1 env").unwrap();
+        let command = node.simple_commands()[0];
+        assert_eq!(command.assignments.len(), 2);
+        assert_eq!(words(command), vec!["env"]);
+    }
+
+    #[test]
+    fn test_parse_comments() {
+        let node = parse("echo hi # rm -rf /").unwrap();
+        assert_eq!(words(node.simple_commands()[0]), vec!["echo", "hi"]);
+    }
+
+    #[test]
+    fn test_parse_rejects_unsupported_constructs() {
+        for input in [
+            "if true; then echo; fi",
+            "for f in *; do rm $f; done",
+            "f() { rm -rf /; }",
+            "{ echo; }",
+            "cat <<EOF\nhi\nEOF",
+            "diff <(ls a) <(ls b)",
+            "echo $'\\x41'",
+            "((x++))",
+            "echo ${x:-$(rm -rf /)}",
+            "ls &> out",
+        ] {
+            assert!(
+                matches!(parse(input), Err(ParseError::Unsupported(_))),
+                "{} should be unsupported",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_syntax_errors() {
+        for input in [
+            "echo 'open",
+            "echo \"open",
+            "ls |",
+            "a &&",
+            "(ls",
+            "echo $(ls",
+            "",
+        ] {
+            assert!(
+                matches!(parse(input), Err(ParseError::Syntax(_))),
+                "{:?} should be a syntax error",
+                input
+            );
+        }
+    }
+}
@@ -0,0 +1,505 @@
+//! Allowlist shell policy
+//!
+//! Parses commands with [`shell_parser`](super::shell_parser) and permits
+//! them only when every command in the tree, including those in pipelines,
+//! subshells and command substitutions, runs an allowed binary with
+//! allowed arguments. Rules can be overridden per channel.
+//!
+//! Unlike the regex blocklist this fails closed: syntax the parser cannot
+//! analyse, dynamic command names and unapproved redirects are all denied.
+//! Denials carry a [`ShellPolicyDenial`] that serializes to JSON so the
+//! model can see exactly which part of the command was rejected.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::config::{ShellAllowRule, ShellToolConfig};
+use crate::error::{Result, ZeptoError};
+
+use super::shell_parser::{self, ParseError, Redirect, SimpleCommand};
+
+/// Why a command was denied by the shell policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DenialReason {
+    /// The command could not be parsed
+    ParseError,
+    /// The command uses syntax the policy cannot analyse
+    UnsupportedConstruct,
+    /// The command runs a binary that is not on the allowlist
+    BinaryNotAllowed,
+    /// An argument does not match the binary's allowed patterns
+    ArgumentNotAllowed,
+    /// The command name comes from an expansion
+    DynamicCommand,
+    /// An argument comes from an expansion and the binary restricts arguments
+    DynamicArgument,
+    /// The command redirects to or from a file
+    RedirectNotAllowed,
+    /// The command sets environment variables
+    AssignmentNotAllowed,
+}
+
+/// A structured explanation of a policy denial.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ShellPolicyDenial {
+    /// Machine-readable reason
+    pub reason: DenialReason,
+    /// The simple command (or construct) that was rejected
+    pub command: String,
+    /// Human-readable detail
+    pub detail: String,
+    /// Binaries allowed in this channel
+    pub allowed_binaries: Vec<String>,
+}
+
+impl fmt::Display for ShellPolicyDenial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = serde_json::to_string(self).map_err(|_| fmt::Error)?;
+        write!(f, "Command denied by shell policy: {}", json)
+    }
+}
+
+/// A rule with its argument patterns compiled.
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    binary: String,
+    args: Vec<Regex>,
+    deny_args: Vec<Regex>,
+}
+
+impl CompiledRule {
+    fn compile(rule: &ShellAllowRule) -> Result<Self> {
+        if rule.binary.trim().is_empty() {
+            return Err(ZeptoError::Config(
+                "tools.shell: allow rule has an empty binary".to_string(),
+            ));
+        }
+        Ok(Self {
+            binary: rule.binary.clone(),
+            args: compile_patterns(&rule.binary, &rule.args)?,
+            deny_args: compile_patterns(&rule.binary, &rule.deny_args)?,
+        })
+    }
+
+    /// Checks the arguments of a command already known to run this binary.
+    fn check_args(
+        &self,
+        command: &SimpleCommand,
+    ) -> std::result::Result<(), (DenialReason, String)> {
+        let restricted = !self.args.is_empty() || !self.deny_args.is_empty();
+        for arg in command.words.iter().skip(1) {
+            if arg.dynamic {
+                if restricted {
+                    return Err((
+                        DenialReason::DynamicArgument,
+                        format!(
+                            "argument '{}' uses an expansion, so it cannot be checked against the rules for '{}'",
+                            arg.text, self.binary
+                        ),
+                    ));
+                }
+                continue;
+            }
+            if let Some(pattern) = self.deny_args.iter().find(|p| p.is_match(&arg.text)) {
+                return Err((
+                    DenialReason::ArgumentNotAllowed,
+                    format!(
+                        "argument '{}' matches denied pattern '{}' for '{}'",
+                        arg.text,
+                        strip_anchors(pattern),
+                        self.binary
+                    ),
+                ));
+            }
+            if !self.args.is_empty() && !self.args.iter().any(|p| p.is_match(&arg.text)) {
+                return Err((
+                    DenialReason::ArgumentNotAllowed,
+                    format!(
+                        "argument '{}' does not match any allowed pattern for '{}' ({})",
+                        arg.text,
+                        self.binary,
+                        self.args
+                            .iter()
+                            .map(strip_anchors)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compiles argument patterns anchored to match whole arguments.
+fn compile_patterns(binary: &str, patterns: &[String]) -> Result<Vec<Regex>> {
+    patterns
+        .iter()
+        .map(|p| {
+            Regex::new(&format!("^(?:{})$", p)).map_err(|e| {
+                ZeptoError::Config(format!(
+                    "tools.shell: invalid argument pattern '{}' for '{}': {}",
+                    p, binary, e
+                ))
+            })
+        })
+        .collect()
+}
+
+fn strip_anchors(pattern: &Regex) -> &str {
+    let s = pattern.as_str();
+    s.strip_prefix("^(?:")
+        .and_then(|s| s.strip_suffix(")$"))
+        .unwrap_or(s)
+}
+
+/// A compiled allowlist shell policy.
+#[derive(Debug, Clone)]
+pub struct ShellPolicy {
+    /// Rules used when the channel has no override
+    rules: Vec<CompiledRule>,
+    /// Per-channel rules that replace `rules`
+    channels: HashMap<String, Vec<CompiledRule>>,
+    /// Whether file redirects are allowed
+    allow_redirects: bool,
+}
+
+impl ShellPolicy {
+    /// Compile a policy from `tools.shell` configuration.
+    ///
+    /// Returns `ZeptoError::Config` if a rule has an empty binary or an
+    /// argument pattern is not a valid regex.
+    pub fn from_config(config: &ShellToolConfig) -> Result<Self> {
+        let compile = |rules: &[ShellAllowRule]| -> Result<Vec<CompiledRule>> {
+            rules.iter().map(CompiledRule::compile).collect()
+        };
+        let channels = config
+            .channels
+            .iter()
+            .map(|(name, rules)| Ok((name.clone(), compile(rules)?)))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            rules: compile(&config.allow)?,
+            channels,
+            allow_redirects: config.allow_redirects,
+        })
+    }
+
+    /// Check a command for the given channel.
+    pub fn check(
+        &self,
+        command: &str,
+        channel: Option<&str>,
+    ) -> std::result::Result<(), ShellPolicyDenial> {
+        let rules = channel
+            .and_then(|c| self.channels.get(c))
+            .unwrap_or(&self.rules);
+        let deny = |reason: DenialReason, command: String, detail: String| ShellPolicyDenial {
+            reason,
+            command,
+            detail,
+            allowed_binaries: allowed_binaries(rules),
+        };
+
+        let tree = shell_parser::parse(command).map_err(|e| match e {
+            ParseError::Syntax(_) => {
+                deny(DenialReason::ParseError, command.to_string(), e.to_string())
+            }
+            ParseError::Unsupported(_) => deny(
+                DenialReason::UnsupportedConstruct,
+                command.to_string(),
+                e.to_string(),
+            ),
+        })?;
+
+        for redirect in tree.redirects() {
+            if !self.redirect_allowed(redirect) {
+                return Err(deny(
+                    DenialReason::RedirectNotAllowed,
+                    describe_redirect(redirect),
+                    "redirects to or from files are disabled (tools.shell.allow_redirects)"
+                        .to_string(),
+                ));
+            }
+        }
+
+        for simple in tree.simple_commands() {
+            let text = describe_command(simple);
+            if !simple.assignments.is_empty() {
+                return Err(deny(
+                    DenialReason::AssignmentNotAllowed,
+                    text,
+                    "setting environment variables is not allowed".to_string(),
+                ));
+            }
+            let Some(name) = simple.words.first() else {
+                // A bare redirect such as `> file`, already checked above
+                continue;
+            };
+            if name.dynamic {
+                return Err(deny(
+                    DenialReason::DynamicCommand,
+                    text,
+                    format!("command name '{}' uses an expansion", name.text),
+                ));
+            }
+
+            let candidates: Vec<&CompiledRule> =
+                rules.iter().filter(|r| r.binary == name.text).collect();
+            if candidates.is_empty() {
+                return Err(deny(
+                    DenialReason::BinaryNotAllowed,
+                    text,
+                    format!("'{}' is not an allowed binary", name.text),
+                ));
+            }
+
+            // Several rules for one binary: any of them may accept the command
+            let mut first_error = None;
+            for rule in &candidates {
+                match rule.check_args(simple) {
+                    Ok(()) => {
+                        first_error = None;
+                        break;
+                    }
+                    Err(e) => {
+                        first_error.get_or_insert(e);
+                    }
+                }
+            }
+            if let Some((reason, detail)) = first_error {
+                return Err(deny(reason, text, detail));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn redirect_allowed(&self, redirect: &Redirect) -> bool {
+        if redirect.is_fd_duplication() {
+            return true;
+        }
+        if !redirect.target.dynamic && redirect.target.text == "/dev/null" {
+            return true;
+        }
+        self.allow_redirects && !redirect.target.dynamic
+    }
+}
+
+fn allowed_binaries(rules: &[CompiledRule]) -> Vec<String> {
+    let mut binaries: Vec<String> = rules.iter().map(|r| r.binary.clone()).collect();
+    binaries.sort();
+    binaries.dedup();
+    binaries
+}
+
+fn describe_command(command: &SimpleCommand) -> String {
+    command
+        .assignments
+        .iter()
+        .chain(&command.words)
+        .map(|w| w.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn describe_redirect(redirect: &Redirect) -> String {
+    format!(
+        "{}{} {}",
+        redirect.fd.map(|fd| fd.to_string()).unwrap_or_default(),
+        redirect.op,
+        redirect.target.text
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ShellPolicyMode;
+
+    fn rule(binary: &str, args: &[&str], deny_args: &[&str]) -> ShellAllowRule {
+        ShellAllowRule {
+            binary: binary.to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            deny_args: deny_args.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn policy() -> ShellPolicy {
+        let mut config = ShellToolConfig {
+            mode: ShellPolicyMode::Allowlist,
+            allow: vec![
+                rule("ls", &[], &[]),
+                rule("grep", &[], &[]),
+                rule("echo", &[], &[]),
+                rule("git", &["status|log|diff", "--oneline", "-n", r"\d+"], &[]),
+                rule("rm", &[], &["-[a-zA-Z]*[rf][a-zA-Z]*", "/.*"]),
+            ],
+            ..Default::default()
+        };
+        config
+            .channels
+            .insert("telegram".to_string(), vec![rule("echo", &[], &[])]);
+        ShellPolicy::from_config(&config).unwrap()
+    }
+
+    fn reason(command: &str) -> DenialReason {
+        policy().check(command, None).unwrap_err().reason
+    }
+
+    #[test]
+    fn test_allowed_commands() {
+        let policy = policy();
+        for command in [
+            "ls -la",
+            "ls | grep foo",
+            "git log --oneline -n 5",
+            "(ls && echo done) 2>&1",
+            "echo $(ls) `git status`",
+            "ls > /dev/null",
+            "rm notes.txt",
+            "echo $HOME",
+        ] {
+            assert!(
+                policy.check(command, None).is_ok(),
+                "{} should be allowed",
+                command
+            );
+        }
+    }
+
+    #[test]
+    fn test_binary_not_allowed() {
+        assert_eq!(reason("curl example.com"), DenialReason::BinaryNotAllowed);
+        assert_eq!(reason("ls | sh"), DenialReason::BinaryNotAllowed);
+        assert_eq!(reason("echo $(cat secret)"), DenialReason::BinaryNotAllowed);
+        assert_eq!(reason("(ls; wget x)"), DenialReason::BinaryNotAllowed);
+        // Paths must be allowed explicitly
+        assert_eq!(reason("/bin/ls"), DenialReason::BinaryNotAllowed);
+    }
+
+    #[test]
+    fn test_argument_patterns() {
+        assert_eq!(reason("git push"), DenialReason::ArgumentNotAllowed);
+        assert_eq!(reason("git log -n five"), DenialReason::ArgumentNotAllowed);
+        assert_eq!(reason("rm -rf build"), DenialReason::ArgumentNotAllowed);
+        assert_eq!(reason("rm /etc/hosts"), DenialReason::ArgumentNotAllowed);
+        // Patterns match whole arguments
+        assert_eq!(reason("git statusx"), DenialReason::ArgumentNotAllowed);
+    }
+
+    #[test]
+    fn test_dynamic_words() {
+        assert_eq!(reason("$CMD -la"), DenialReason::DynamicCommand);
+        assert_eq!(reason("git log $FLAGS"), DenialReason::DynamicArgument);
+        assert_eq!(reason("rm {a,-rf}"), DenialReason::DynamicArgument);
+        // Tilde and glob expansion happen after the check, so they are dynamic
+        assert_eq!(
+            reason("rm ~/../../etc/passwd"),
+            DenialReason::DynamicArgument
+        );
+        assert_eq!(
+            reason("rm ~root/.ssh/id_rsa"),
+            DenialReason::DynamicArgument
+        );
+        assert_eq!(reason("rm *"), DenialReason::DynamicArgument);
+        assert_eq!(reason("rm notes.tx?"), DenialReason::DynamicArgument);
+        assert!(policy().check("ls *.rs ~/notes", None).is_ok());
+        assert!(policy().check("rm '*'", None).is_ok());
+    }
+
+    #[test]
+    fn test_redirects_and_assignments() {
+        assert_eq!(reason("ls > out.txt"), DenialReason::RedirectNotAllowed);
+        assert_eq!(reason("(ls) > out.txt"), DenialReason::RedirectNotAllowed);
+        assert_eq!(reason("PATH=/tmp ls"), DenialReason::AssignmentNotAllowed);
+
+        let config = ShellToolConfig {
+            allow: vec![rule("ls", &[], &[])],
+            allow_redirects: true,
+            ..Default::default()
+        };
+        let policy = ShellPolicy::from_config(&config).unwrap();
+        assert!(policy.check("ls > out.txt", None).is_ok());
+        assert_eq!(
+            policy.check("ls > $OUT", None).unwrap_err().reason,
+            DenialReason::RedirectNotAllowed
+        );
+    }
+
+    #[test]
+    fn test_unanalysable_commands_denied() {
+        assert_eq!(
+            reason("for f in *; do ls $f; done"),
+            DenialReason::UnsupportedConstruct
+        );
+        assert_eq!(
+            reason("cat <<EOF\nx\nEOF"),
+            DenialReason::UnsupportedConstruct
+        );
+        assert_eq!(reason("echo 'unterminated"), DenialReason::ParseError);
+    }
+
+    #[test]
+    fn test_channel_override() {
+        let policy = policy();
+        assert!(policy.check("echo hi", Some("telegram")).is_ok());
+        let denial = policy.check("ls", Some("telegram")).unwrap_err();
+        assert_eq!(denial.reason, DenialReason::BinaryNotAllowed);
+        assert_eq!(denial.allowed_binaries, vec!["echo".to_string()]);
+        // Channels without an override use the global rules
+        assert!(policy.check("ls", Some("slack")).is_ok());
+    }
+
+    #[test]
+    fn test_multiple_rules_for_one_binary() {
+        let config = ShellToolConfig {
+            allow: vec![rule("git", &["status"], &[]), rule("git", &["log"], &[])],
+            ..Default::default()
+        };
+        let policy = ShellPolicy::from_config(&config).unwrap();
+        assert!(policy.check("git status", None).is_ok());
+        assert!(policy.check("git log", None).is_ok());
+        assert!(policy.check("git push", None).is_err());
+    }
+
+    #[test]
+    fn test_denial_serializes_to_json() {
+        let denial = policy().check("ls; curl x", None).unwrap_err();
+        assert_eq!(denial.command, "curl x");
+        let message = denial.to_string();
+        let json: serde_json::Value = serde_json::from_str(
+            message
+                .strip_prefix("Command denied by shell policy: ")
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(json["reason"], "binary_not_allowed");
+        assert_eq!(json["command"], "curl x");
+        assert!(json["allowed_binaries"]
+            .as_array()
+            .unwrap()
+            .contains(&serde_json::json!("git")));
+    }
+
+    #[test]
+    fn test_invalid_pattern_rejected() {
+        let config = ShellToolConfig {
+            allow: vec![rule("git", &["("], &[])],
+            ..Default::default()
+        };
+        let err = ShellPolicy::from_config(&config).unwrap_err();
+        assert!(matches!(err, ZeptoError::Config(_)));
+
+        let config = ShellToolConfig {
+            allow: vec![rule(" ", &[], &[])],
+            ..Default::default()
+        };
+        assert!(ShellPolicy::from_config(&config).is_err());
+    }
+}
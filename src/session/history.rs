@@ -12,6 +12,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::config::Config;
 use crate::error::{Result, ZeptoError};
+use crate::security::encryption::StoreEncryption;
 use crate::session::{Message, Role, Session};
 
 /// Metadata for a saved CLI conversation.
@@ -36,6 +37,8 @@ pub struct ConversationEntry {
 /// provides listing, search, and cleanup operations.
 pub struct ConversationHistory {
     storage_path: PathBuf,
+    /// Decrypts session files stored with at-rest encryption
+    encryption: StoreEncryption,
 }
 
 impl ConversationHistory {
@@ -49,7 +52,10 @@ impl ConversationHistory {
     pub fn new() -> Result<Self> {
         let storage_path = Config::dir().join("sessions");
         std::fs::create_dir_all(&storage_path)?;
-        Ok(Self { storage_path })
+        Ok(Self {
+            storage_path,
+            encryption: StoreEncryption::disabled(),
+        })
     }
 
     /// Create a new `ConversationHistory` with a custom storage path.
@@ -61,7 +67,16 @@ impl ConversationHistory {
     /// Returns an error if the directory cannot be created.
     pub fn with_path(path: PathBuf) -> Result<Self> {
         std::fs::create_dir_all(&path)?;
-        Ok(Self { storage_path: path })
+        Ok(Self {
+            storage_path: path,
+            encryption: StoreEncryption::disabled(),
+        })
+    }
+
+    /// Read session files written with at-rest encryption (builder pattern).
+    pub fn with_encryption(mut self, encryption: StoreEncryption) -> Self {
+        self.encryption = encryption;
+        self
     }
 
     /// List all CLI conversations, sorted by `last_updated` descending (newest first).
@@ -93,7 +108,10 @@ impl ConversationHistory {
             }
 
             // Read and parse the session
-            let content = match std::fs::read_to_string(&path) {
+            let content = match std::fs::read_to_string(&path)
+                .map_err(ZeptoError::from)
+                .and_then(|c| self.encryption.open(&c))
+            {
                 Ok(c) => c,
                 Err(_) => continue,
             };
@@ -30,12 +30,13 @@ pub mod history;
 pub mod types;
 
 pub use history::ConversationHistory;
-pub use types::{Message, Role, Session, ToolCall};
+pub use types::{MediaPart, Message, Role, Session, ThinkingBlock, ToolCall};
 
 use crate::config::Config;
 use crate::error::Result;
+use crate::security::encryption::StoreEncryption;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -54,12 +55,15 @@ use tokio::sync::RwLock;
 ///
 /// When created with `new()`, sessions are persisted to disk in the
 /// `~/.zeptoclaw/sessions/` directory. Use `new_memory()` for testing
-/// or when persistence is not needed.
+/// or when persistence is not needed. Session files are encrypted when a
+/// [`StoreEncryption`] is set with `with_encryption()`.
 pub struct SessionManager {
     /// In-memory cache of sessions
     sessions: Arc<RwLock<HashMap<String, Session>>>,
     /// Optional path for file-based persistence
     storage_path: Option<PathBuf>,
+    /// At-rest encryption for session files
+    encryption: StoreEncryption,
 }
 
 impl SessionManager {
@@ -84,6 +88,7 @@ impl SessionManager {
         Ok(Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             storage_path: Some(storage_path),
+            encryption: StoreEncryption::disabled(),
         })
     }
 
@@ -102,6 +107,7 @@ impl SessionManager {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             storage_path: None,
+            encryption: StoreEncryption::disabled(),
         }
     }
 
@@ -126,9 +132,26 @@ impl SessionManager {
         Ok(Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             storage_path: Some(path),
+            encryption: StoreEncryption::disabled(),
         })
     }
 
+    /// Encrypt session files at rest (builder pattern).
+    ///
+    /// Plaintext session files are still read and are encrypted the next
+    /// time they are saved.
+    pub fn with_encryption(mut self, encryption: StoreEncryption) -> Self {
+        self.encryption = encryption;
+        self
+    }
+
+    /// Read and decode a session file.
+    async fn read_session(&self, file_path: &Path) -> Result<Session> {
+        let content = tokio::fs::read_to_string(file_path).await?;
+        let content = self.encryption.open(&content)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
     /// Get an existing session or create a new one.
     ///
     /// If the session exists in memory, it is returned immediately.
@@ -166,8 +189,7 @@ impl SessionManager {
         if let Some(ref storage_path) = self.storage_path {
             let file_path = storage_path.join(format!("{}.json", Self::sanitize_key(key)));
             if file_path.exists() {
-                let content = tokio::fs::read_to_string(&file_path).await?;
-                let session: Session = serde_json::from_str(&content)?;
+                let session = self.read_session(&file_path).await?;
 
                 // Cache it in memory
                 let mut sessions = self.sessions.write().await;
@@ -208,8 +230,7 @@ impl SessionManager {
         if let Some(ref storage_path) = self.storage_path {
             let file_path = storage_path.join(format!("{}.json", Self::sanitize_key(key)));
             if file_path.exists() {
-                let content = tokio::fs::read_to_string(&file_path).await?;
-                let session: Session = serde_json::from_str(&content)?;
+                let session = self.read_session(&file_path).await?;
 
                 // Cache it in memory
                 let mut sessions = self.sessions.write().await;
@@ -253,6 +274,7 @@ impl SessionManager {
         if let Some(ref storage_path) = self.storage_path {
             let file_path = storage_path.join(format!("{}.json", Self::sanitize_key(&session.key)));
             let content = serde_json::to_string_pretty(session)?;
+            let content = self.encryption.seal(&content)?;
             tokio::fs::write(&file_path, content).await?;
         }
 
@@ -337,11 +359,9 @@ impl SessionManager {
                 let path = entry.path();
                 if path.extension().map(|e| e == "json").unwrap_or(false) {
                     // Read the session file to get the actual key
-                    if let Ok(content) = tokio::fs::read_to_string(&path).await {
-                        if let Ok(session) = serde_json::from_str::<Session>(&content) {
-                            if !keys.contains(&session.key) {
-                                keys.push(session.key);
-                            }
+                    if let Ok(session) = self.read_session(&path).await {
+                        if !keys.contains(&session.key) {
+                            keys.push(session.key);
                         }
                     }
                 }
@@ -458,6 +478,7 @@ impl Clone for SessionManager {
         Self {
             sessions: Arc::clone(&self.sessions),
             storage_path: self.storage_path.clone(),
+            encryption: self.encryption.clone(),
         }
     }
 }
@@ -595,6 +616,61 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_file_persistence_encrypted() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage_path = temp_dir.path().to_path_buf();
+        let key = crate::security::SecretEncryption::from_raw_key(&[0x42u8; 32]);
+        let encryption = StoreEncryption::new(key);
+
+        {
+            let manager = SessionManager::with_path(storage_path.clone())
+                .unwrap()
+                .with_encryption(encryption.clone());
+            let mut session = manager.get_or_create("secret").await.unwrap();
+            session.add_message(Message::user("Sensitive message"));
+            manager.save(&session).await.unwrap();
+        }
+
+        let raw = std::fs::read_to_string(storage_path.join("secret.json")).unwrap();
+        assert!(raw.starts_with("ENC["));
+        assert!(!raw.contains("Sensitive message"));
+
+        let manager = SessionManager::with_path(storage_path.clone())
+            .unwrap()
+            .with_encryption(encryption);
+        let session = manager.get("secret").await.unwrap().unwrap();
+        assert_eq!(session.messages[0].content, "Sensitive message");
+
+        let plain = SessionManager::with_path(storage_path).unwrap();
+        assert!(plain.get("secret").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_session_migrates_on_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage_path = temp_dir.path().to_path_buf();
+
+        {
+            let manager = SessionManager::with_path(storage_path.clone()).unwrap();
+            let mut session = manager.get_or_create("legacy").await.unwrap();
+            session.add_message(Message::user("Old message"));
+            manager.save(&session).await.unwrap();
+        }
+
+        let key = crate::security::SecretEncryption::from_raw_key(&[0x42u8; 32]);
+        let manager = SessionManager::with_path(storage_path.clone())
+            .unwrap()
+            .with_encryption(StoreEncryption::new(key));
+        let mut session = manager.get("legacy").await.unwrap().unwrap();
+        assert_eq!(session.messages[0].content, "Old message");
+
+        session.add_message(Message::assistant("New reply"));
+        manager.save(&session).await.unwrap();
+        let raw = std::fs::read_to_string(storage_path.join("legacy.json")).unwrap();
+        assert!(raw.starts_with("ENC["));
+    }
+
     #[tokio::test]
     async fn test_file_persistence_delete() {
         let temp_dir = TempDir::new().unwrap();
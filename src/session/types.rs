@@ -134,6 +134,14 @@ pub struct Message {
     /// ID of the tool call this message is responding to (for tool results)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_call_id: Option<String>,
+    /// Reasoning blocks the assistant produced before this message. Kept on
+    /// tool-use turns so providers that verify them can continue the turn.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub thinking: Vec<ThinkingBlock>,
+    /// Files sent inline with this message (images, PDFs), for providers
+    /// that accept them. Not persisted with the session.
+    #[serde(default, skip_serializing)]
+    pub media: Vec<MediaPart>,
 }
 
 impl Message {
@@ -155,6 +163,8 @@ impl Message {
             content: content.to_string(),
             tool_calls: None,
             tool_call_id: None,
+            thinking: Vec::new(),
+            media: Vec::new(),
         }
     }
 
@@ -176,6 +186,8 @@ impl Message {
             content: content.to_string(),
             tool_calls: None,
             tool_call_id: None,
+            thinking: Vec::new(),
+            media: Vec::new(),
         }
     }
 
@@ -199,6 +211,8 @@ impl Message {
             content: content.to_string(),
             tool_calls: None,
             tool_call_id: None,
+            thinking: Vec::new(),
+            media: Vec::new(),
         }
     }
 
@@ -224,6 +238,8 @@ impl Message {
             content: content.to_string(),
             tool_calls: None,
             tool_call_id: Some(tool_call_id.to_string()),
+            thinking: Vec::new(),
+            media: Vec::new(),
         }
     }
 
@@ -247,9 +263,41 @@ impl Message {
             content: content.to_string(),
             tool_calls: Some(tool_calls),
             tool_call_id: None,
+            thinking: Vec::new(),
+            media: Vec::new(),
         }
     }
 
+    /// Attach reasoning blocks to this message.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::session::{Message, ThinkingBlock};
+    ///
+    /// let msg = Message::assistant("Done")
+    ///     .with_thinking(vec![ThinkingBlock::new("Check the file first", Some("sig"))]);
+    /// assert_eq!(msg.thinking.len(), 1);
+    /// ```
+    pub fn with_thinking(mut self, thinking: Vec<ThinkingBlock>) -> Self {
+        self.thinking = thinking;
+        self
+    }
+
+    /// Attach inline files to this message.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::session::{MediaPart, Message};
+    ///
+    /// let msg = Message::user("What is in this picture?")
+    ///     .with_media(vec![MediaPart::new("image/png", &[0x89, b'P', b'N', b'G'])]);
+    /// assert_eq!(msg.media[0].mime_type, "image/png");
+    /// ```
+    pub fn with_media(mut self, media: Vec<MediaPart>) -> Self {
+        self.media = media;
+        self
+    }
+
     /// Check if this message has tool calls.
     pub fn has_tool_calls(&self) -> bool {
         self.tool_calls
@@ -264,6 +312,63 @@ impl Message {
     }
 }
 
+/// A reasoning block produced by a model with extended thinking.
+///
+/// Anthropic signs thinking blocks and requires them, unmodified, on the
+/// assistant turn that requested a tool when the tool results are sent back.
+/// Redacted blocks carry encrypted data instead of readable text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThinkingBlock {
+    /// Reasoning text, or the encrypted payload for redacted blocks
+    pub content: String,
+    /// Provider signature used to verify the block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Whether the provider redacted this block
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub redacted: bool,
+}
+
+impl ThinkingBlock {
+    /// Create a readable thinking block.
+    pub fn new(content: &str, signature: Option<&str>) -> Self {
+        Self {
+            content: content.to_string(),
+            signature: signature.map(str::to_string),
+            redacted: false,
+        }
+    }
+
+    /// Create a redacted thinking block from its encrypted payload.
+    pub fn redacted(data: &str) -> Self {
+        Self {
+            content: data.to_string(),
+            signature: None,
+            redacted: true,
+        }
+    }
+}
+
+/// A file sent inline with a message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaPart {
+    /// MIME type, e.g. `image/png` or `application/pdf`
+    pub mime_type: String,
+    /// Base64-encoded file content
+    pub data: String,
+}
+
+impl MediaPart {
+    /// Create a media part from raw bytes.
+    pub fn new(mime_type: &str, bytes: &[u8]) -> Self {
+        use base64::Engine;
+        Self {
+            mime_type: mime_type.to_string(),
+            data: base64::engine::general_purpose::STANDARD.encode(bytes),
+        }
+    }
+}
+
 /// The role of a message sender in a conversation.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
@@ -492,5 +597,23 @@ mod tests {
         // tool_calls and tool_call_id should not be in JSON when None
         assert!(!json.contains("tool_calls"));
         assert!(!json.contains("tool_call_id"));
+        assert!(!json.contains("thinking"));
+    }
+
+    #[test]
+    fn test_thinking_roundtrip() {
+        let msg = Message::assistant_with_tools("", vec![ToolCall::new("1", "shell", "{}")])
+            .with_thinking(vec![
+                ThinkingBlock::new("Run ls", Some("sig")),
+                ThinkingBlock::redacted("opaque"),
+            ]);
+        let json = serde_json::to_string(&msg).unwrap();
+        let parsed: Message = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.thinking, msg.thinking);
+
+        // Sessions saved before thinking support still load
+        let legacy: Message =
+            serde_json::from_str(r#"{"role":"assistant","content":"Hi"}"#).unwrap();
+        assert!(legacy.thinking.is_empty());
     }
 }
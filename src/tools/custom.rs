@@ -11,7 +11,7 @@ use tracing::debug;
 
 use crate::config::CustomToolDef;
 use crate::error::{Result, ZeptoError};
-use crate::security::ShellSecurityConfig;
+use crate::security::{EgressRules, ShellSecurityConfig};
 
 use super::types::{Tool, ToolContext};
 
@@ -41,6 +41,7 @@ fn interpolate(template: &str, args: &HashMap<String, String>) -> String {
 pub struct CustomTool {
     def: CustomToolDef,
     security: ShellSecurityConfig,
+    egress: Option<EgressRules>,
 }
 
 impl CustomTool {
@@ -49,8 +50,15 @@ impl CustomTool {
         Self {
             def,
             security: ShellSecurityConfig::default(),
+            egress: None,
         }
     }
+
+    /// Check URLs in the interpolated command against the given egress rules.
+    pub fn with_egress(mut self, egress: EgressRules) -> Self {
+        self.egress = Some(egress);
+        self
+    }
 }
 
 #[async_trait]
@@ -117,6 +125,11 @@ impl Tool for CustomTool {
             )));
         }
 
+        // Best-effort egress check for URLs passed to curl, wget, etc.
+        if let Some(ref egress) = self.egress {
+            egress.check_command_urls(&command).await?;
+        }
+
         debug!(tool = %self.def.name, command = %command, "Executing custom tool");
 
         // Determine timeout (clamp to minimum to prevent zero-duration timeouts)
@@ -198,6 +211,7 @@ mod tests {
             workspace: Some(std::env::temp_dir().to_string_lossy().to_string()),
             channel: None,
             chat_id: None,
+            session_key: None,
         }
     }
 
@@ -429,4 +443,24 @@ mod tests {
         // Output should be capped near MAX_OUTPUT_BYTES
         assert!(result.len() <= MAX_OUTPUT_BYTES + 100);
     }
+
+    #[tokio::test]
+    async fn test_execute_egress_blocks_private_urls() {
+        let tool = CustomTool::new(simple_def("fetch", "echo {{url}}"))
+            .with_egress(EgressRules::default());
+        let err = tool
+            .execute(
+                json!({"url": "http://169.254.169.254/latest/meta-data/"}),
+                &test_ctx(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ZeptoError::SecurityViolation(_)), "{}", err);
+
+        let result = tool
+            .execute(json!({"url": "not-a-url"}), &test_ctx())
+            .await
+            .unwrap();
+        assert_eq!(result, "not-a-url");
+    }
 }
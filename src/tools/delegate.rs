@@ -183,12 +183,18 @@ impl Tool for DelegateTool {
         let sub_bus = Arc::new(MessageBus::new());
         let context_builder = ContextBuilder::new().with_system_prompt(&system_prompt);
 
-        let sub_agent = AgentLoop::with_context_builder(
-            self.config.clone(),
-            session_manager,
-            sub_bus,
-            context_builder,
-        );
+        let mut sub_config = self.config.clone();
+        if let Some(rc) = role_config {
+            if rc.tool_choice.is_some() {
+                sub_config.agents.defaults.tool_choice = rc.tool_choice.clone();
+            }
+            if rc.parallel_tool_calls.is_some() {
+                sub_config.agents.defaults.parallel_tool_calls = rc.parallel_tool_calls;
+            }
+        }
+
+        let sub_agent =
+            AgentLoop::with_context_builder(sub_config, session_manager, sub_bus, context_builder);
 
         // Set the same LLM provider via the ProviderRef wrapper
         sub_agent
@@ -243,6 +249,14 @@ impl LLMProvider for ProviderRef {
         self.0.default_model()
     }
 
+    fn supports_forced_tool_choice(&self) -> bool {
+        self.0.supports_forced_tool_choice()
+    }
+
+    fn supports_response_schema(&self) -> bool {
+        self.0.supports_response_schema()
+    }
+
     async fn chat(
         &self,
         messages: Vec<Message>,
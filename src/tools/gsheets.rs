@@ -6,6 +6,7 @@ use reqwest::Client;
 use serde_json::{json, Value};
 
 use crate::error::{Result, ZeptoError};
+use crate::security::EgressRules;
 
 use super::{Tool, ToolContext};
 
@@ -16,6 +17,7 @@ const SHEETS_API_BASE: &str = "https://sheets.googleapis.com/v4/spreadsheets";
 pub struct GoogleSheetsTool {
     client: Client,
     access_token: String,
+    egress: Option<EgressRules>,
 }
 
 impl GoogleSheetsTool {
@@ -24,6 +26,31 @@ impl GoogleSheetsTool {
         Self {
             client: Client::new(),
             access_token: access_token.to_string(),
+            egress: None,
+        }
+    }
+
+    /// Restrict requests (including redirects) with the given egress rules.
+    pub fn with_egress(mut self, egress: EgressRules) -> Self {
+        self.client = egress
+            .client_builder()
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        self.egress = Some(egress);
+        self
+    }
+
+    fn check_egress(&self, endpoint: &str) -> Result<()> {
+        match self.egress {
+            Some(ref egress) => egress.check_url_str(endpoint),
+            None => Ok(()),
+        }
+    }
+
+    fn request_error(&self, e: reqwest::Error) -> ZeptoError {
+        match self.egress {
+            Some(ref egress) => egress.map_request_error(e, "Google Sheets request failed"),
+            None => ZeptoError::Tool(format!("Google Sheets request failed: {}", e)),
         }
     }
 
@@ -77,13 +104,14 @@ impl GoogleSheetsTool {
             "{}/{}/values/{}",
             SHEETS_API_BASE, spreadsheet_id, encoded_range
         );
+        self.check_egress(&endpoint)?;
         let response = self
             .client
             .get(endpoint)
             .header("Authorization", format!("Bearer {}", self.access_token))
             .send()
             .await
-            .map_err(|e| ZeptoError::Tool(format!("Google Sheets request failed: {}", e)))?;
+            .map_err(|e| self.request_error(e))?;
 
         let status = response.status();
         let body: Value = response
@@ -159,6 +187,7 @@ impl GoogleSheetsTool {
         endpoint: &str,
         values: Vec<Vec<String>>,
     ) -> Result<String> {
+        self.check_egress(endpoint)?;
         let body = json!({ "values": values });
         let request = match method {
             "POST" => self.client.post(endpoint),
@@ -177,7 +206,7 @@ impl GoogleSheetsTool {
             .json(&body)
             .send()
             .await
-            .map_err(|e| ZeptoError::Tool(format!("Google Sheets request failed: {}", e)))?;
+            .map_err(|e| self.request_error(e))?;
 
         let status = response.status();
         let payload: Value = response
@@ -4,6 +4,8 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use crate::security::EgressRules;
+
 use super::protocol::*;
 
 /// MCP client for communicating with MCP servers over HTTP.
@@ -18,6 +20,10 @@ pub struct McpClient {
     tools_cache: Arc<RwLock<Option<Vec<McpTool>>>>,
     /// Server name for logging and tool prefixing.
     server_name: String,
+    /// Request timeout, kept so the HTTP client can be rebuilt.
+    timeout_secs: u64,
+    /// Egress rules checked before each request, if configured.
+    egress: Option<EgressRules>,
 }
 
 impl McpClient {
@@ -34,9 +40,22 @@ impl McpClient {
             next_id: AtomicU64::new(1),
             tools_cache: Arc::new(RwLock::new(None)),
             server_name: name.to_string(),
+            timeout_secs,
+            egress: None,
         }
     }
 
+    /// Restrict requests (including redirects) with the given egress rules.
+    pub fn with_egress(mut self, egress: EgressRules) -> Self {
+        self.http = egress
+            .client_builder()
+            .timeout(std::time::Duration::from_secs(self.timeout_secs))
+            .build()
+            .unwrap_or_default();
+        self.egress = Some(egress);
+        self
+    }
+
     /// Get the next unique request ID.
     fn next_request_id(&self) -> u64 {
         self.next_id.fetch_add(1, Ordering::Relaxed)
@@ -54,13 +73,22 @@ impl McpClient {
 
     /// Send a JSON-RPC request and return the response.
     async fn send_request(&self, request: &McpRequest) -> Result<McpResponse, String> {
+        if let Some(ref egress) = self.egress {
+            egress.check_url_str(&self.url).map_err(|e| e.to_string())?;
+        }
+
         let resp = self
             .http
             .post(&self.url)
             .json(request)
             .send()
             .await
-            .map_err(|e| format!("HTTP request failed: {}", e))?;
+            .map_err(|e| match self.egress {
+                Some(ref egress) => egress
+                    .map_request_error(e, "HTTP request failed")
+                    .to_string(),
+                None => format!("HTTP request failed: {}", e),
+            })?;
 
         let status = resp.status();
         if !status.is_success() {
@@ -272,4 +300,12 @@ mod tests {
         let cache = client.tools_cache.read().await;
         assert!(cache.is_none(), "Cache should start as None");
     }
+
+    #[tokio::test]
+    async fn test_egress_blocks_private_server() {
+        let client =
+            McpClient::new("test", "http://127.0.0.1:1", 5).with_egress(EgressRules::default());
+        let err = client.initialize().await.unwrap_err();
+        assert!(err.contains("Egress blocked"), "got: {}", err);
+    }
 }
@@ -65,6 +65,10 @@ impl Tool for McpToolWrapper {
         self.description()
     }
 
+    fn untrusted_output(&self) -> bool {
+        true
+    }
+
     fn parameters(&self) -> serde_json::Value {
         self.input_schema.clone()
     }
@@ -24,6 +24,8 @@ const ALLOWED_CHANNELS: &[&str] = &[
     "webhook",
     "whatsapp",
     "whatsapp_cloud",
+    "matrix",
+    "email",
 ];
 
 /// Tool for sending outbound messages to channels.
@@ -340,6 +342,8 @@ mod tests {
             "webhook",
             "whatsapp",
             "whatsapp_cloud",
+            "matrix",
+            "email",
         ] {
             let bus = Arc::new(MessageBus::new());
             let tool = MessageTool::new(bus.clone());
@@ -68,6 +68,7 @@ pub mod plugin;
 pub mod r8r;
 mod registry;
 pub mod reminder;
+pub(crate) mod schema;
 #[cfg(feature = "screenshot")]
 pub mod screenshot;
 pub mod shell;
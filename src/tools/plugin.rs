@@ -40,6 +40,7 @@ use std::time::Duration;
 
 use crate::error::{Result, ZeptoError};
 use crate::plugins::PluginToolDef;
+use crate::security::EgressRules;
 
 use super::types::{Tool, ToolContext};
 
@@ -68,6 +69,8 @@ pub struct PluginTool {
     def: PluginToolDef,
     /// Name of the plugin that provides this tool (for logging).
     plugin_name: String,
+    /// Egress rules for URLs in the interpolated command, if configured.
+    egress: Option<EgressRules>,
 }
 
 impl PluginTool {
@@ -76,9 +79,16 @@ impl PluginTool {
         Self {
             def,
             plugin_name: plugin_name.to_string(),
+            egress: None,
         }
     }
 
+    /// Check URLs in the interpolated command against the given egress rules.
+    pub fn with_egress(mut self, egress: EgressRules) -> Self {
+        self.egress = Some(egress);
+        self
+    }
+
     /// Interpolate `{{param_name}}` placeholders in a command template.
     ///
     /// All parameter values are shell-escaped to prevent command injection.
@@ -123,6 +133,10 @@ impl Tool for PluginTool {
         let command = Self::interpolate(&self.def.command, &args);
         let timeout = Duration::from_secs(self.def.effective_timeout());
 
+        if let Some(ref egress) = self.egress {
+            egress.check_command_urls(&command).await?;
+        }
+
         tracing::debug!(
             plugin = %self.plugin_name,
             tool = %self.def.name,
@@ -35,6 +35,7 @@ use std::time::Duration;
 use tracing::{debug, info, warn};
 
 use crate::error::{Result, ZeptoError};
+use crate::security::EgressRules;
 
 use super::{Tool, ToolContext};
 
@@ -52,6 +53,7 @@ const DEFAULT_TIMEOUT_SECS: u64 = 300;
 pub struct R8rTool {
     endpoint: String,
     client: Client,
+    egress: Option<EgressRules>,
 }
 
 impl R8rTool {
@@ -60,7 +62,22 @@ impl R8rTool {
     /// # Arguments
     /// * `endpoint` - The r8r server endpoint (e.g., "http://localhost:8080")
     pub fn new(endpoint: &str) -> Self {
-        let client = match Client::builder()
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            client: Self::build_client(Client::builder()),
+            egress: None,
+        }
+    }
+
+    /// Restrict requests (including redirects) with the given egress rules.
+    pub fn with_egress(mut self, egress: EgressRules) -> Self {
+        self.client = Self::build_client(egress.client_builder());
+        self.egress = Some(egress);
+        self
+    }
+
+    fn build_client(builder: reqwest::ClientBuilder) -> Client {
+        match builder
             .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
             .build()
         {
@@ -72,14 +89,14 @@ impl R8rTool {
                 );
                 Client::new()
             }
-        };
-
-        Self {
-            endpoint: endpoint.trim_end_matches('/').to_string(),
-            client,
         }
     }
 
+    /// The configured r8r endpoint.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
     /// Create a new R8r tool with default endpoint (localhost:8080).
     pub fn default_endpoint() -> Self {
         Self::new(DEFAULT_R8R_ENDPOINT)
@@ -99,6 +116,14 @@ impl R8rTool {
         Self {
             endpoint: endpoint.trim_end_matches('/').to_string(),
             client,
+            egress: None,
+        }
+    }
+
+    fn request_error(&self, e: reqwest::Error) -> ZeptoError {
+        match self.egress {
+            Some(ref egress) => egress.map_request_error(e, "Failed to connect to r8r"),
+            None => ZeptoError::Tool(format!("Failed to connect to r8r: {}", e)),
         }
     }
 }
@@ -213,6 +238,10 @@ impl Tool for R8rTool {
     async fn execute(&self, args: Value, _ctx: &ToolContext) -> Result<String> {
         let action = args.get("action").and_then(|v| v.as_str()).unwrap_or("run");
 
+        if let Some(ref egress) = self.egress {
+            egress.check_url_str(&self.endpoint)?;
+        }
+
         match action {
             "list" => self.list_workflows().await,
             "show" => {
@@ -303,7 +332,7 @@ impl R8rTool {
             .get(&url)
             .send()
             .await
-            .map_err(|e| ZeptoError::Tool(format!("Failed to connect to r8r: {}", e)))?;
+            .map_err(|e| self.request_error(e))?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -350,7 +379,7 @@ impl R8rTool {
             .get(&url)
             .send()
             .await
-            .map_err(|e| ZeptoError::Tool(format!("Failed to connect to r8r: {}", e)))?;
+            .map_err(|e| self.request_error(e))?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -392,7 +421,7 @@ impl R8rTool {
             .get(&url)
             .send()
             .await
-            .map_err(|e| ZeptoError::Tool(format!("Failed to connect to r8r: {}", e)))?;
+            .map_err(|e| self.request_error(e))?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -470,7 +499,7 @@ impl R8rTool {
             .json(&body)
             .send()
             .await
-            .map_err(|e| ZeptoError::Tool(format!("Failed to connect to r8r: {}", e)))?;
+            .map_err(|e| self.request_error(e))?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -502,7 +531,7 @@ impl R8rTool {
             .json(&body)
             .send()
             .await
-            .map_err(|e| ZeptoError::Tool(format!("Failed to connect to r8r: {}", e)))?;
+            .map_err(|e| self.request_error(e))?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -555,7 +584,7 @@ impl R8rTool {
             .json(&request)
             .send()
             .await
-            .map_err(|e| ZeptoError::Tool(format!("Failed to connect to r8r: {}", e)))?;
+            .map_err(|e| self.request_error(e))?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -778,4 +807,15 @@ mod tests {
             assert!(output.contains("completed") || output.contains("Execution ID"));
         }
     }
+
+    #[tokio::test]
+    async fn test_r8r_egress_blocks_local_endpoint() {
+        let tool = R8rTool::new("http://localhost:8080").with_egress(EgressRules::default());
+        let ctx = ToolContext::new();
+        let err = tool
+            .execute(json!({"action": "list"}), &ctx)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ZeptoError::SecurityViolation(_)), "{}", err);
+    }
 }
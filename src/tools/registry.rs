@@ -7,7 +7,7 @@ use std::collections::HashMap;
 use std::time::Instant;
 
 use serde_json::Value;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use crate::error::{Result, ZeptoError};
 use crate::providers::ToolDefinition;
@@ -137,7 +137,10 @@ impl ToolRegistry {
     ///
     /// # Returns
     /// The tool's output as a string, or an error if the tool is not found
-    /// or execution fails.
+    /// or execution fails. Arguments are checked against the tool's
+    /// `parameters()` schema first; a mismatch returns
+    /// `ZeptoError::InvalidArguments` naming each offending field, and the
+    /// tool is not run.
     ///
     /// # Example
     /// ```
@@ -164,6 +167,14 @@ impl ToolRegistry {
             .get(name)
             .ok_or_else(|| ZeptoError::NotFound(format!("Tool not found: {}", name)))?;
 
+        if let Some(parse_error) = args.get("_parse_error").and_then(Value::as_str) {
+            return Err(ZeptoError::InvalidArguments(parse_error.to_string()));
+        }
+        if let Err(errors) = super::schema::validate(&tool.parameters(), &args) {
+            warn!(tool = name, errors = ?errors, "Tool arguments failed schema validation");
+            return Err(ZeptoError::InvalidArguments(errors.join("; ")));
+        }
+
         let start = Instant::now();
 
         match tool.execute(args, ctx).await {
@@ -471,10 +482,13 @@ mod tests {
         let mut registry = ToolRegistry::new();
         registry.register(Box::new(EchoTool));
 
-        // Execute without message argument - should return default
-        let result = registry.execute("echo", json!({})).await;
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "(no message)");
+        // Execute without the required message - rejected before the tool runs
+        let err = registry.execute("echo", json!({})).await.unwrap_err();
+        assert!(matches!(err, ZeptoError::InvalidArguments(_)));
+        assert_eq!(
+            err.to_string(),
+            "Invalid arguments: missing required field 'message'"
+        );
     }
 
     #[tokio::test]
@@ -482,10 +496,37 @@ mod tests {
         let mut registry = ToolRegistry::new();
         registry.register(Box::new(EchoTool));
 
-        // Execute with null message - should return default
+        // A null required field counts as missing
         let result = registry.execute("echo", json!({"message": null})).await;
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "(no message)");
+        assert!(matches!(result, Err(ZeptoError::InvalidArguments(_))));
+    }
+
+    #[tokio::test]
+    async fn test_registry_execute_wrong_type() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(EchoTool));
+
+        let err = registry
+            .execute("echo", json!({"message": 42}))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid arguments: field 'message' must be a string, got integer"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_registry_execute_reports_parse_error() {
+        let mut registry = ToolRegistry::new();
+        registry.register(Box::new(EchoTool));
+
+        let args = json!({"_parse_error": "Invalid arguments JSON: EOF while parsing"});
+        let err = registry.execute("echo", args).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid arguments: Invalid arguments JSON: EOF while parsing"
+        );
     }
 
     #[test]
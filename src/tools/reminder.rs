@@ -17,6 +17,7 @@ use tokio::sync::Mutex;
 use crate::config::Config;
 use crate::cron::{CronPayload, CronSchedule, CronService};
 use crate::error::{Result, ZeptoError};
+use crate::security::encryption::StoreEncryption;
 
 use super::{Tool, ToolContext};
 
@@ -116,18 +117,29 @@ pub struct ReminderStore {
     entries: HashMap<String, ReminderEntry>,
     storage_path: PathBuf,
     next_id: u64,
+    encryption: StoreEncryption,
 }
 
 impl ReminderStore {
     /// Create a new store at the default path (`~/.zeptoclaw/reminders.json`).
     pub fn new() -> Result<Self> {
-        let path = Config::dir().join("reminders.json");
-        Self::with_path(path)
+        Self::with_path(Self::default_path())
+    }
+
+    /// Default location of the reminders file.
+    pub fn default_path() -> PathBuf {
+        Config::dir().join("reminders.json")
     }
 
     /// Create a store at a custom path. Useful for testing.
     pub fn with_path(path: PathBuf) -> Result<Self> {
-        let entries = Self::load(&path)?;
+        Self::open(path, StoreEncryption::disabled())
+    }
+
+    /// Create a store at a custom path with at-rest encryption. A plaintext
+    /// file is read as-is and encrypted on the next save.
+    pub fn open(path: PathBuf, encryption: StoreEncryption) -> Result<Self> {
+        let entries = Self::load(&path, &encryption)?;
         // Derive next_id from the highest existing numeric suffix.
         let max_id = entries
             .keys()
@@ -138,6 +150,7 @@ impl ReminderStore {
             entries,
             storage_path: path,
             next_id: max_id + 1,
+            encryption,
         })
     }
 
@@ -293,6 +306,7 @@ impl ReminderStore {
 
         let json = serde_json::to_string_pretty(&self.entries)
             .map_err(|e| ZeptoError::Tool(format!("Failed to serialize reminders: {}", e)))?;
+        let json = self.encryption.seal(&json)?;
 
         std::fs::write(&self.storage_path, json).map_err(|e| {
             ZeptoError::Tool(format!(
@@ -305,7 +319,10 @@ impl ReminderStore {
         Ok(())
     }
 
-    fn load(path: &PathBuf) -> Result<HashMap<String, ReminderEntry>> {
+    fn load(
+        path: &PathBuf,
+        encryption: &StoreEncryption,
+    ) -> Result<HashMap<String, ReminderEntry>> {
         if !path.exists() {
             return Ok(HashMap::new());
         }
@@ -321,6 +338,7 @@ impl ReminderStore {
         if content.trim().is_empty() {
             return Ok(HashMap::new());
         }
+        let content = encryption.open(&content)?;
 
         let entries: HashMap<String, ReminderEntry> = serde_json::from_str(&content)
             .map_err(|e| ZeptoError::Tool(format!("Failed to parse reminders JSON: {}", e)))?;
@@ -847,6 +865,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_store_encrypted_roundtrip() {
+        let dir = TempDir::new().expect("failed to create temp dir");
+        let path = dir.path().join("reminders.json");
+        let key = crate::security::SecretEncryption::from_raw_key(&[9u8; 32]);
+        let encryption = StoreEncryption::new(key);
+
+        {
+            let mut store = ReminderStore::open(path.clone(), encryption.clone()).unwrap();
+            store
+                .add("Call the bank", None, "work", None, None)
+                .unwrap();
+        }
+
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(raw.starts_with("ENC["));
+        assert!(!raw.contains("Call the bank"));
+
+        let store = ReminderStore::open(path, encryption).unwrap();
+        assert_eq!(store.get("r1").unwrap().title, "Call the bank");
+    }
+
     #[test]
     fn test_store_complete() {
         let (mut store, _dir) = temp_store();
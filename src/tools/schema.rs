@@ -0,0 +1,362 @@
+//! JSON Schema validation for tool arguments
+//!
+//! Checks LLM-supplied arguments against a tool's `parameters()` schema
+//! before the tool runs. Only the keywords tools actually use are
+//! supported (`type`, `properties`, `required`, `additionalProperties`,
+//! `enum`, `const`, `items`, `minimum`, `maximum`, `anyOf`, `oneOf`);
+//! anything else, including `$ref`, is ignored rather than rejected.
+//!
+//! Errors are phrased for the model, naming the offending field so it can
+//! correct the call on the next turn.
+
+use serde_json::{Map, Value};
+
+/// Validate `args` against `schema`.
+///
+/// Returns every problem found, not just the first. Optional properties
+/// sent as `null` are treated as absent, since many models emit them that
+/// way for fields they mean to omit.
+pub(crate) fn validate(schema: &Value, args: &Value) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    check(schema, args, "", &mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn check(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    for keyword in ["anyOf", "oneOf"] {
+        let Some(branches) = schema.get(keyword).and_then(Value::as_array) else {
+            continue;
+        };
+        let matched = branches
+            .iter()
+            .filter(|branch| {
+                let mut branch_errors = Vec::new();
+                check(branch, value, path, &mut branch_errors);
+                branch_errors.is_empty()
+            })
+            .count();
+        if matched == 0 {
+            errors.push(format!(
+                "{} does not match any of the allowed forms",
+                describe(path)
+            ));
+            return;
+        }
+        if keyword == "oneOf" && matched > 1 {
+            errors.push(format!(
+                "{} matches {} of the allowed forms, expected exactly one",
+                describe(path),
+                matched
+            ));
+            return;
+        }
+    }
+
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| has_type(value, t)) {
+            errors.push(format!(
+                "{} must be {}, got {}",
+                describe(path),
+                with_article(&allowed.join(" or ")),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            let listed: Vec<String> = options.iter().map(Value::to_string).collect();
+            errors.push(format!(
+                "{} must be one of {}, got {}",
+                describe(path),
+                listed.join(", "),
+                value
+            ));
+            return;
+        }
+    }
+
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            errors.push(format!(
+                "{} must be {}, got {}",
+                describe(path),
+                expected,
+                value
+            ));
+            return;
+        }
+    }
+
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+            if n < min {
+                errors.push(format!(
+                    "{} must be at least {}, got {}",
+                    describe(path),
+                    min,
+                    value
+                ));
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+            if n > max {
+                errors.push(format!(
+                    "{} must be at most {}, got {}",
+                    describe(path),
+                    max,
+                    value
+                ));
+            }
+        }
+    }
+
+    match value {
+        Value::Object(fields) => check_object(schema, fields, path, errors),
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    check(item_schema, item, &format!("{}[{}]", path, i), errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_object(
+    schema: &Map<String, Value>,
+    fields: &Map<String, Value>,
+    path: &str,
+    errors: &mut Vec<String>,
+) {
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    for name in &required {
+        if fields.get(*name).is_none_or(Value::is_null) {
+            errors.push(format!(
+                "missing required field '{}'",
+                join_path(path, name)
+            ));
+        }
+    }
+
+    for (name, value) in fields {
+        let field_path = join_path(path, name);
+        match properties.and_then(|p| p.get(name)) {
+            // Null required fields were already reported as missing.
+            Some(_) if value.is_null() => {}
+            Some(prop_schema) => check(prop_schema, value, &field_path, errors),
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    let mut known: Vec<&str> = properties
+                        .map(|p| p.keys().map(String::as_str).collect())
+                        .unwrap_or_default();
+                    known.sort_unstable();
+                    errors.push(format!(
+                        "unknown field '{}' (expected one of: {})",
+                        field_path,
+                        known.join(", ")
+                    ));
+                }
+                Some(extra @ Value::Object(_)) => check(extra, value, &field_path, errors),
+                _ => {}
+            },
+        }
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        // Unknown type names are not ours to enforce.
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn with_article(type_list: &str) -> String {
+    let article = if type_list.starts_with(['a', 'e', 'i', 'o', 'u']) {
+        "an"
+    } else {
+        "a"
+    };
+    format!("{} {}", article, type_list)
+}
+
+fn describe(path: &str) -> String {
+    if path.is_empty() {
+        "arguments".to_string()
+    } else {
+        format!("field '{}'", path)
+    }
+}
+
+fn join_path(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", parent, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {"type": "string"},
+                "limit": {"type": "integer", "minimum": 1},
+                "mode": {"type": "string", "enum": ["read", "write"]},
+                "tags": {"type": "array", "items": {"type": "string"}},
+                "options": {
+                    "type": "object",
+                    "properties": {"recursive": {"type": "boolean"}},
+                    "additionalProperties": false
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    #[test]
+    fn test_valid_arguments_pass() {
+        let args = json!({
+            "path": "notes.md",
+            "limit": 10,
+            "mode": "read",
+            "tags": ["a", "b"],
+            "options": {"recursive": true}
+        });
+        assert!(validate(&schema(), &args).is_ok());
+    }
+
+    #[test]
+    fn test_missing_required_field() {
+        let errors = validate(&schema(), &json!({"limit": 5})).unwrap_err();
+        assert_eq!(errors, vec!["missing required field 'path'"]);
+
+        let errors = validate(&schema(), &json!({"path": null})).unwrap_err();
+        assert_eq!(errors, vec!["missing required field 'path'"]);
+    }
+
+    #[test]
+    fn test_wrong_type() {
+        let errors = validate(&schema(), &json!({"path": "a", "limit": "5"})).unwrap_err();
+        assert_eq!(errors, vec!["field 'limit' must be an integer, got string"]);
+
+        let errors = validate(&schema(), &json!(["a"])).unwrap_err();
+        assert_eq!(errors, vec!["arguments must be an object, got array"]);
+    }
+
+    #[test]
+    fn test_enum_mismatch() {
+        let errors = validate(&schema(), &json!({"path": "a", "mode": "append"})).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![r#"field 'mode' must be one of "read", "write", got "append""#]
+        );
+    }
+
+    #[test]
+    fn test_nested_paths_and_unknown_fields() {
+        let args = json!({
+            "path": "a",
+            "tags": ["ok", 3],
+            "options": {"recursive": "yes", "depth": 2}
+        });
+        let errors = validate(&schema(), &args).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                "unknown field 'options.depth' (expected one of: recursive)",
+                "field 'options.recursive' must be a boolean, got string",
+                "field 'tags[1]' must be a string, got integer",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_optional_null_and_integral_float_accepted() {
+        let args = json!({"path": "a", "mode": null, "limit": 3.0});
+        assert!(validate(&schema(), &args).is_ok());
+    }
+
+    #[test]
+    fn test_minimum_and_any_of() {
+        let errors = validate(&schema(), &json!({"path": "a", "limit": 0})).unwrap_err();
+        assert_eq!(errors, vec!["field 'limit' must be at least 1, got 0"]);
+
+        let schema = json!({
+            "type": "object",
+            "properties": {"id": {"anyOf": [{"type": "string"}, {"type": "integer"}]}}
+        });
+        assert!(validate(&schema, &json!({"id": 7})).is_ok());
+        let errors = validate(&schema, &json!({"id": true})).unwrap_err();
+        assert_eq!(
+            errors,
+            vec!["field 'id' does not match any of the allowed forms"]
+        );
+    }
+
+    #[test]
+    fn test_one_of_requires_exactly_one_match() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"n": {"oneOf": [{"type": "integer"}, {"type": "number"}]}}
+        });
+        assert!(validate(&schema, &json!({"n": 1.5})).is_ok());
+        let errors = validate(&schema, &json!({"n": 2})).unwrap_err();
+        assert_eq!(
+            errors,
+            vec!["field 'n' matches 2 of the allowed forms, expected exactly one"]
+        );
+        let errors = validate(&schema, &json!({"n": "2"})).unwrap_err();
+        assert_eq!(
+            errors,
+            vec!["field 'n' does not match any of the allowed forms"]
+        );
+    }
+}
@@ -25,8 +25,9 @@ use super::{Tool, ToolContext};
 ///
 /// # Security
 /// This tool validates commands against a configurable blocklist to prevent
-/// dangerous operations. Use `ShellTool::permissive()` to disable security
-/// checks in trusted environments.
+/// dangerous operations, and against an allowlist policy for the calling
+/// channel when one is configured. Use `ShellTool::permissive()` to disable
+/// security checks in trusted environments.
 ///
 /// # Example
 /// ```rust
@@ -144,7 +145,8 @@ impl Tool for ShellTool {
             .ok_or_else(|| ZeptoError::Tool("Missing 'command' argument".into()))?;
 
         // Security check
-        self.security_config.validate_command(command)?;
+        self.security_config
+            .validate_command_in_channel(command, ctx.channel.as_deref())?;
 
         let timeout_secs = args.get("timeout").and_then(|v| v.as_u64()).unwrap_or(60);
 
@@ -159,6 +161,11 @@ impl Tool for ShellTool {
                 .with_mount(workspace_path.clone(), workspace_path, false);
         }
 
+        // Runtimes with persistent sessions keep one shell per agent session
+        if let Some(ref session_key) = ctx.session_key {
+            container_config = container_config.with_session(session_key);
+        }
+
         // Execute command via runtime
         let output = self
             .runtime
@@ -470,4 +477,37 @@ mod tests {
         let tool = ShellTool::permissive();
         assert_eq!(tool.runtime_name(), "native");
     }
+
+    #[tokio::test]
+    async fn test_shell_persistent_session_keeps_state() {
+        use crate::runtime::SessionRuntime;
+
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        let runtime = Arc::new(SessionRuntime::new(
+            Arc::new(NativeRuntime::new()),
+            std::time::Duration::from_secs(60),
+        ));
+        let tool = ShellTool::with_runtime(runtime);
+        let ctx = ToolContext::new()
+            .with_workspace(dir.path().to_str().unwrap())
+            .with_session_key("cli:test");
+
+        tool.execute(json!({"command": "cd sub"}), &ctx)
+            .await
+            .unwrap();
+        let result = tool
+            .execute(json!({"command": "basename \"$PWD\""}), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(result.trim(), "sub");
+
+        // A different session starts in the workspace root.
+        let other = ctx.clone().with_session_key("cli:other");
+        let result = tool
+            .execute(json!({"command": "ls"}), &other)
+            .await
+            .unwrap();
+        assert_eq!(result.trim(), "sub");
+    }
 }
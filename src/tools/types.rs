@@ -77,6 +77,14 @@ pub trait Tool: Send + Sync {
     fn compact_description(&self) -> &str {
         self.description()
     }
+
+    /// Whether this tool returns third-party content (web pages, search
+    /// results, remote APIs) that may carry prompt injections.
+    ///
+    /// Such output is quarantined when `safety.quarantine` is enabled.
+    fn untrusted_output(&self) -> bool {
+        false
+    }
 }
 
 /// Context provided to tools during execution.
@@ -91,6 +99,8 @@ pub struct ToolContext {
     pub chat_id: Option<String>,
     /// The workspace directory for file operations
     pub workspace: Option<String>,
+    /// The agent session key (e.g., "telegram:123456")
+    pub session_key: Option<String>,
 }
 
 impl ToolContext {
@@ -145,6 +155,21 @@ impl ToolContext {
         self.workspace = Some(workspace.to_string());
         self
     }
+
+    /// Set the session key.
+    ///
+    /// # Example
+    /// ```
+    /// use zeptoclaw::tools::ToolContext;
+    ///
+    /// let ctx = ToolContext::new()
+    ///     .with_session_key("telegram:123456");
+    /// assert_eq!(ctx.session_key.as_deref(), Some("telegram:123456"));
+    /// ```
+    pub fn with_session_key(mut self, session_key: &str) -> Self {
+        self.session_key = Some(session_key.to_string());
+        self
+    }
 }
 
 #[cfg(test)]
@@ -4,7 +4,6 @@
 //! - `web_search`: search the web with Brave Search API.
 //! - `web_fetch`: fetch URL content and extract readable text.
 
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::time::Duration;
 
 use async_trait::async_trait;
@@ -12,9 +11,9 @@ use regex::Regex;
 use reqwest::{Client, Url};
 use serde::Deserialize;
 use serde_json::{json, Value};
-use tokio::net::lookup_host;
 
 use crate::error::{Result, ZeptoError};
+use crate::security::EgressRules;
 
 use super::{Tool, ToolContext};
 
@@ -88,6 +87,10 @@ impl Tool for WebSearchTool {
         "Web search"
     }
 
+    fn untrusted_output(&self) -> bool {
+        true
+    }
+
     fn parameters(&self) -> Value {
         json!({
             "type": "object",
@@ -187,23 +190,35 @@ impl Tool for WebSearchTool {
 pub struct WebFetchTool {
     client: Client,
     max_chars: usize,
+    egress: EgressRules,
 }
 
 impl WebFetchTool {
-    /// Create a new web fetch tool.
+    /// Create a new web fetch tool with the default egress rules.
     pub fn new() -> Self {
-        let client = Client::builder()
-            .redirect(reqwest::redirect::Policy::limited(5))
-            .timeout(Duration::from_secs(30))
-            .build()
-            .unwrap_or_else(|_| Client::new());
-
+        let egress = EgressRules::default();
         Self {
-            client,
+            client: Self::build_client(&egress),
             max_chars: DEFAULT_MAX_FETCH_CHARS,
+            egress,
         }
     }
 
+    /// Use the given egress rules for requests and redirects.
+    pub fn with_egress(mut self, egress: EgressRules) -> Self {
+        self.client = Self::build_client(&egress);
+        self.egress = egress;
+        self
+    }
+
+    fn build_client(egress: &EgressRules) -> Client {
+        egress
+            .client_builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_else(|_| Client::new())
+    }
+
     /// Create with a custom maximum output size.
     pub fn with_max_chars(max_chars: usize) -> Self {
         let mut tool = Self::new();
@@ -257,6 +272,10 @@ impl Tool for WebFetchTool {
         "Fetch URL"
     }
 
+    fn untrusted_output(&self) -> bool {
+        true
+    }
+
     fn parameters(&self) -> Value {
         json!({
             "type": "object",
@@ -296,17 +315,10 @@ impl Tool for WebFetchTool {
             }
         }
 
-        if is_blocked_host(&parsed) {
-            return Err(ZeptoError::SecurityViolation(
-                "Blocked URL host (local or private network)".to_string(),
-            ));
-        }
-
-        // DNS-based SSRF check: resolve the hostname before making the
-        // request and verify none of the resolved IPs are private/local.
-        // The returned address is used to pin the connection, preventing
-        // DNS rebinding attacks between this check and the actual request.
-        let pinned = resolve_and_check_host(&parsed).await?;
+        // Host, port and IP literal checks. Redirect hops and resolved
+        // addresses are checked by the client built from the same rules, so
+        // DNS rebinding cannot swap in a private address after this check.
+        self.egress.check_url(&parsed)?;
 
         let max_chars = args
             .get("max_chars")
@@ -315,35 +327,13 @@ impl Tool for WebFetchTool {
             .unwrap_or(self.max_chars)
             .clamp(MIN_FETCH_CHARS, MAX_FETCH_CHARS);
 
-        // Build a client that pins the DNS resolution to the IP we already
-        // validated, so the HTTP library cannot re-resolve to a different
-        // (potentially private) address.
-        let client = if let Some((host, addr)) = pinned {
-            Client::builder()
-                .redirect(reqwest::redirect::Policy::limited(5))
-                .timeout(Duration::from_secs(30))
-                .resolve(&host, addr)
-                .build()
-                .unwrap_or_else(|_| self.client.clone())
-        } else {
-            self.client.clone()
-        };
-
-        let response = client
+        let response = self
+            .client
             .get(parsed.clone())
             .header("User-Agent", WEB_USER_AGENT)
             .send()
             .await
-            .map_err(|e| ZeptoError::Tool(format!("Web fetch failed: {}", e)))?;
-
-        // SSRF redirect check: after reqwest follows redirects, validate
-        // that the final destination URL is not a blocked host.
-        if is_blocked_host(response.url()) {
-            return Err(ZeptoError::SecurityViolation(format!(
-                "Redirect destination is blocked (local or private network): {}",
-                response.url()
-            )));
-        }
+            .map_err(|e| self.egress.map_request_error(e, "Web fetch failed"))?;
 
         let status = response.status();
         let final_url = response.url().to_string();
@@ -462,29 +452,10 @@ async fn read_body_limited(response: reqwest::Response, max_bytes: usize) -> Res
 }
 
 /// Check whether a URL's host is a blocked (local/private) address.
-/// Used by both `WebFetchTool` and the watch command to prevent SSRF.
+/// Used by the watch command and screenshot tool to prevent SSRF; see
+/// [`EgressRules`] for the configurable policy.
 pub fn is_blocked_host(url: &Url) -> bool {
-    let Some(host_str) = url.host_str() else {
-        return true;
-    };
-
-    let host = host_str.to_ascii_lowercase();
-    if host == "localhost" || host.ends_with(".local") {
-        return true;
-    }
-
-    // Try parsing as IP directly first, then try stripping IPv6 brackets.
-    // `Url::host_str()` returns IPv6 addresses with surrounding brackets
-    // (e.g. "[::1]"), which `IpAddr::parse` does not accept.
-    let ip_str = host
-        .strip_prefix('[')
-        .and_then(|s| s.strip_suffix(']'))
-        .unwrap_or(&host);
-    if let Ok(ip) = ip_str.parse::<IpAddr>() {
-        return is_private_or_local_ip(ip);
-    }
-
-    false
+    EgressRules::default().check_url(url).is_err()
 }
 
 /// Resolve a URL's hostname via DNS and check whether any of the resolved IPs
@@ -496,71 +467,14 @@ pub fn is_blocked_host(url: &Url) -> bool {
 /// connection to it, preventing DNS rebinding attacks where a second DNS
 /// lookup (by the HTTP client) returns a different, private IP.
 pub async fn resolve_and_check_host(url: &Url) -> Result<Option<(String, std::net::SocketAddr)>> {
-    let host = url
-        .host_str()
-        .ok_or_else(|| ZeptoError::SecurityViolation("URL has no host".to_string()))?;
-
-    // IP literals are already checked by `is_blocked_host`, skip DNS lookup.
-    if host.parse::<IpAddr>().is_ok() {
-        return Ok(None);
-    }
-
-    let port = url.port_or_known_default().unwrap_or(443);
-    let lookup_addr = format!("{}:{}", host, port);
-
-    let addrs: Vec<std::net::SocketAddr> = lookup_host(&lookup_addr)
-        .await
-        .map_err(|e| ZeptoError::Tool(format!("DNS lookup failed for '{}': {}", host, e)))?
-        .collect();
-
-    for addr in &addrs {
-        if is_private_or_local_ip(addr.ip()) {
-            return Err(ZeptoError::SecurityViolation(format!(
-                "DNS for '{}' resolved to private/local IP {}",
-                host,
-                addr.ip()
-            )));
-        }
-    }
-
-    // Return the first safe address so the caller can pin the connection,
-    // preventing DNS rebinding between this check and the actual request.
-    Ok(addrs
-        .into_iter()
-        .next()
-        .map(|addr| (host.to_string(), addr)))
-}
-
-fn is_private_or_local_ip(ip: IpAddr) -> bool {
-    match ip {
-        IpAddr::V4(addr) => is_private_or_local_ipv4(addr),
-        IpAddr::V6(addr) => is_private_or_local_ipv6(addr),
-    }
-}
-
-fn is_private_or_local_ipv4(addr: Ipv4Addr) -> bool {
-    addr.is_private()
-        || addr.is_loopback()
-        || addr.is_link_local()
-        || addr.is_broadcast()
-        || addr.is_documentation()
-        || addr.is_unspecified()
-        || addr.octets()[0] == 0
-}
-
-fn is_private_or_local_ipv6(addr: Ipv6Addr) -> bool {
-    let first = addr.segments()[0];
-
-    addr.is_loopback()
-        || addr.is_unspecified()
-        || (first & 0xfe00) == 0xfc00
-        || (first & 0xffc0) == 0xfe80
-        || (first & 0xff00) == 0xff00
+    EgressRules::default().resolve(url).await
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::security::egress::is_private_or_local_ip;
+    use std::net::IpAddr;
 
     #[test]
     fn test_web_search_tool_properties() {
@@ -5,6 +5,7 @@ use reqwest::Client;
 use serde_json::{json, Value};
 
 use crate::error::{Result, ZeptoError};
+use crate::security::EgressRules;
 
 use super::{Tool, ToolContext};
 
@@ -16,6 +17,7 @@ pub struct WhatsAppTool {
     access_token: String,
     default_language: String,
     client: Client,
+    egress: Option<EgressRules>,
 }
 
 impl WhatsAppTool {
@@ -26,6 +28,7 @@ impl WhatsAppTool {
             access_token: access_token.to_string(),
             default_language: "ms".to_string(),
             client: Client::new(),
+            egress: None,
         }
     }
 
@@ -40,8 +43,19 @@ impl WhatsAppTool {
             access_token: access_token.to_string(),
             default_language: default_language.to_string(),
             client: Client::new(),
+            egress: None,
         }
     }
+
+    /// Restrict requests (including redirects) with the given egress rules.
+    pub fn with_egress(mut self, egress: EgressRules) -> Self {
+        self.client = egress
+            .client_builder()
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        self.egress = Some(egress);
+        self
+    }
 }
 
 #[async_trait]
@@ -158,6 +172,9 @@ impl Tool for WhatsAppTool {
         };
 
         let endpoint = format!("{}/{}/messages", WHATSAPP_API_BASE, self.phone_number_id);
+        if let Some(ref egress) = self.egress {
+            egress.check_url_str(&endpoint)?;
+        }
         let response = self
             .client
             .post(endpoint)
@@ -166,7 +183,10 @@ impl Tool for WhatsAppTool {
             .json(&payload)
             .send()
             .await
-            .map_err(|e| ZeptoError::Tool(format!("WhatsApp request failed: {}", e)))?;
+            .map_err(|e| match self.egress {
+                Some(ref egress) => egress.map_request_error(e, "WhatsApp request failed"),
+                None => ZeptoError::Tool(format!("WhatsApp request failed: {}", e)),
+            })?;
 
         let status = response.status();
         let body: Value = response
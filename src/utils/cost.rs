@@ -10,13 +10,46 @@ use std::sync::Mutex;
 
 use serde::{Deserialize, Serialize};
 
+use crate::providers::Usage;
+
 /// Pricing for a single LLM model, expressed in USD per million tokens.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ModelPricing {
     /// Cost per 1 000 000 input (prompt) tokens in USD.
     pub input_cost_per_million: f64,
     /// Cost per 1 000 000 output (completion) tokens in USD.
     pub output_cost_per_million: f64,
+    /// Cost per 1 000 000 input tokens written to the prompt cache in USD.
+    /// Defaults to the input price.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write_cost_per_million: Option<f64>,
+    /// Cost per 1 000 000 input tokens read from the prompt cache in USD.
+    /// Defaults to the input price.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_cost_per_million: Option<f64>,
+}
+
+impl ModelPricing {
+    /// Anthropic list pricing: cache writes cost 1.25x input, reads 0.1x.
+    fn anthropic(input: f64, output: f64) -> Self {
+        Self {
+            input_cost_per_million: input,
+            output_cost_per_million: output,
+            cache_write_cost_per_million: Some(input * 1.25),
+            cache_read_cost_per_million: Some(input * 0.1),
+        }
+    }
+
+    /// OpenAI list pricing: caching is automatic and cached reads cost 0.5x
+    /// input.
+    fn openai(input: f64, output: f64) -> Self {
+        Self {
+            input_cost_per_million: input,
+            output_cost_per_million: output,
+            cache_write_cost_per_million: None,
+            cache_read_cost_per_million: Some(input * 0.5),
+        }
+    }
 }
 
 /// Returns a static map of known model pricing.
@@ -29,62 +62,29 @@ pub fn default_pricing() -> HashMap<String, ModelPricing> {
     // Anthropic Claude models
     m.insert(
         "claude-sonnet-4-5-20250929".to_string(),
-        ModelPricing {
-            input_cost_per_million: 3.0,
-            output_cost_per_million: 15.0,
-        },
+        ModelPricing::anthropic(3.0, 15.0),
     );
     m.insert(
         "claude-3-5-sonnet-20241022".to_string(),
-        ModelPricing {
-            input_cost_per_million: 3.0,
-            output_cost_per_million: 15.0,
-        },
+        ModelPricing::anthropic(3.0, 15.0),
     );
     m.insert(
         "claude-opus-4-6".to_string(),
-        ModelPricing {
-            input_cost_per_million: 15.0,
-            output_cost_per_million: 75.0,
-        },
+        ModelPricing::anthropic(15.0, 75.0),
     );
     m.insert(
         "claude-3-opus-20240229".to_string(),
-        ModelPricing {
-            input_cost_per_million: 15.0,
-            output_cost_per_million: 75.0,
-        },
+        ModelPricing::anthropic(15.0, 75.0),
     );
     m.insert(
         "claude-3-haiku-20240307".to_string(),
-        ModelPricing {
-            input_cost_per_million: 0.25,
-            output_cost_per_million: 1.25,
-        },
+        ModelPricing::anthropic(0.25, 1.25),
     );
 
     // OpenAI models
-    m.insert(
-        "gpt-5.1".to_string(),
-        ModelPricing {
-            input_cost_per_million: 2.5,
-            output_cost_per_million: 10.0,
-        },
-    );
-    m.insert(
-        "gpt-4o-mini".to_string(),
-        ModelPricing {
-            input_cost_per_million: 0.15,
-            output_cost_per_million: 0.6,
-        },
-    );
-    m.insert(
-        "gpt-4-turbo".to_string(),
-        ModelPricing {
-            input_cost_per_million: 10.0,
-            output_cost_per_million: 30.0,
-        },
-    );
+    m.insert("gpt-5.1".to_string(), ModelPricing::openai(2.5, 10.0));
+    m.insert("gpt-4o-mini".to_string(), ModelPricing::openai(0.15, 0.6));
+    m.insert("gpt-4-turbo".to_string(), ModelPricing::openai(10.0, 30.0));
 
     m
 }
@@ -98,6 +98,22 @@ pub fn estimate_cost(
     prompt_tokens: u32,
     completion_tokens: u32,
     custom_pricing: &HashMap<String, ModelPricing>,
+) -> Option<f64> {
+    estimate_usage_cost(
+        model,
+        &Usage::new(prompt_tokens, completion_tokens),
+        custom_pricing,
+    )
+}
+
+/// Estimate the cost of a single LLM call in USD, pricing prompt-cache
+/// writes and reads separately from regular input tokens.
+///
+/// Returns `None` if the model is unknown.
+pub fn estimate_usage_cost(
+    model: &str,
+    usage: &Usage,
+    custom_pricing: &HashMap<String, ModelPricing>,
 ) -> Option<f64> {
     // Resolve the lookup in two steps so that the owned `defaults` HashMap
     // lives long enough for the borrow returned by `.get()`.
@@ -105,9 +121,20 @@ pub fn estimate_cost(
     let pricing = custom_pricing.get(model).or_else(|| defaults.get(model));
 
     pricing.map(|p| {
-        let input_cost = (prompt_tokens as f64 / 1_000_000.0) * p.input_cost_per_million;
-        let output_cost = (completion_tokens as f64 / 1_000_000.0) * p.output_cost_per_million;
-        input_cost + output_cost
+        let per_token = |tokens: u32, per_million: f64| (tokens as f64 / 1_000_000.0) * per_million;
+        let input_cost = per_token(usage.uncached_prompt_tokens(), p.input_cost_per_million);
+        let cache_write_cost = per_token(
+            usage.cache_creation_tokens,
+            p.cache_write_cost_per_million
+                .unwrap_or(p.input_cost_per_million),
+        );
+        let cache_read_cost = per_token(
+            usage.cache_read_tokens,
+            p.cache_read_cost_per_million
+                .unwrap_or(p.input_cost_per_million),
+        );
+        let output_cost = per_token(usage.completion_tokens, p.output_cost_per_million);
+        input_cost + cache_write_cost + cache_read_cost + output_cost
     })
 }
 
@@ -154,13 +181,17 @@ impl CostTracker {
     /// Estimates cost (if the model is known) and accumulates it under both
     /// the provider name and the model name.
     pub fn record(&self, provider: &str, model: &str, prompt_tokens: u32, completion_tokens: u32) {
-        let cost = estimate_cost(
+        self.record_usage(
+            provider,
             model,
-            prompt_tokens,
-            completion_tokens,
-            &self.custom_pricing,
-        )
-        .unwrap_or(0.0);
+            &Usage::new(prompt_tokens, completion_tokens),
+        );
+    }
+
+    /// Record a single LLM call from its reported usage, pricing prompt-cache
+    /// writes and reads at their own rates.
+    pub fn record_usage(&self, provider: &str, model: &str, usage: &Usage) {
+        let cost = estimate_usage_cost(model, usage, &self.custom_pricing).unwrap_or(0.0);
 
         let mut state = self.state.lock().unwrap();
         state.total_cost += cost;
@@ -310,6 +341,7 @@ mod tests {
             ModelPricing {
                 input_cost_per_million: 100.0,
                 output_cost_per_million: 200.0,
+                ..Default::default()
             },
         );
         // With custom pricing: 1000/1M * 100 + 500/1M * 200 = 0.1 + 0.1 = 0.2
@@ -325,6 +357,7 @@ mod tests {
             ModelPricing {
                 input_cost_per_million: 1.0,
                 output_cost_per_million: 2.0,
+                ..Default::default()
             },
         );
         let cost = estimate_cost("my-custom-model", 1_000_000, 1_000_000, &custom).unwrap();
@@ -444,6 +477,7 @@ mod tests {
             ModelPricing {
                 input_cost_per_million: 5.0,
                 output_cost_per_million: 20.0,
+                ..Default::default()
             },
         );
         let config = CostConfig {
@@ -466,6 +500,7 @@ mod tests {
         let pricing = ModelPricing {
             input_cost_per_million: 3.0,
             output_cost_per_million: 15.0,
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&pricing).unwrap();
@@ -491,6 +526,7 @@ mod tests {
             ModelPricing {
                 input_cost_per_million: 10.0,
                 output_cost_per_million: 50.0,
+                ..Default::default()
             },
         );
         let tracker = CostTracker::new_with_pricing(custom);
@@ -499,4 +535,61 @@ mod tests {
         // 1M/1M * 10 + 1M/1M * 50 = 60.0
         assert!((tracker.total_cost() - 60.0).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_estimate_usage_cost_prices_anthropic_cache() {
+        let custom = HashMap::new();
+        // claude-sonnet-4-5: $3/M input, $3.75/M cache write, $0.30/M cache read
+        // 1M prompt = 200k uncached + 300k written + 500k read
+        //   200k * 3 + 300k * 3.75 + 500k * 0.3 = 0.6 + 1.125 + 0.15 = 1.875
+        let usage = Usage::new(1_000_000, 0).with_cache_tokens(300_000, 500_000);
+        let cost = estimate_usage_cost("claude-sonnet-4-5-20250929", &usage, &custom).unwrap();
+        assert!((cost - 1.875).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_estimate_usage_cost_prices_openai_cached_reads() {
+        let custom = HashMap::new();
+        // gpt-5.1: $2.5/M input, cached reads at half price
+        // 1M prompt = 600k uncached + 400k read = 1.5 + 0.5 = 2.0
+        let usage = Usage::new(1_000_000, 0).with_cache_tokens(0, 400_000);
+        let cost = estimate_usage_cost("gpt-5.1", &usage, &custom).unwrap();
+        assert!((cost - 2.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_estimate_usage_cost_cache_defaults_to_input_price() {
+        let mut custom = HashMap::new();
+        custom.insert(
+            "flat".to_string(),
+            ModelPricing {
+                input_cost_per_million: 1.0,
+                output_cost_per_million: 2.0,
+                ..Default::default()
+            },
+        );
+        let usage = Usage::new(1_000_000, 0).with_cache_tokens(250_000, 250_000);
+        let cost = estimate_usage_cost("flat", &usage, &custom).unwrap();
+        assert!((cost - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_cost_tracker_record_usage_matches_plain_record_without_cache() {
+        let tracker = CostTracker::new();
+        tracker.record_usage(
+            "anthropic",
+            "claude-sonnet-4-5-20250929",
+            &Usage::new(1000, 500),
+        );
+        assert!((tracker.total_cost() - 0.0105).abs() < 1e-10);
+
+        let cached = CostTracker::new();
+        cached.record_usage(
+            "anthropic",
+            "claude-sonnet-4-5-20250929",
+            &Usage::new(1000, 500).with_cache_tokens(0, 1000),
+        );
+        // 1000 read * 0.3/M + 500 * 15/M = 0.0003 + 0.0075
+        assert!((cached.total_cost() - 0.0078).abs() < 1e-10);
+    }
 }
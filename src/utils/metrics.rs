@@ -8,6 +8,8 @@ use std::collections::HashMap;
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
+use crate::providers::Usage;
+
 /// Per-tool execution statistics.
 #[derive(Debug, Clone, Default)]
 pub struct ToolMetrics {
@@ -53,6 +55,12 @@ pub struct MetricsCollector {
     session_start: Instant,
     total_tokens_in: Mutex<u64>,
     total_tokens_out: Mutex<u64>,
+    total_cache_write: Mutex<u64>,
+    total_cache_read: Mutex<u64>,
+    /// Model routing decisions, keyed by `(tier, model)`.
+    routes: Mutex<HashMap<(String, String), u64>>,
+    /// Tool calls rejected by argument schema validation, keyed by tool name.
+    schema_failures: Mutex<HashMap<String, u64>>,
 }
 
 impl MetricsCollector {
@@ -63,6 +71,10 @@ impl MetricsCollector {
             session_start: Instant::now(),
             total_tokens_in: Mutex::new(0),
             total_tokens_out: Mutex::new(0),
+            total_cache_write: Mutex::new(0),
+            total_cache_read: Mutex::new(0),
+            routes: Mutex::new(HashMap::new()),
+            schema_failures: Mutex::new(HashMap::new()),
         }
     }
 
@@ -97,6 +109,55 @@ impl MetricsCollector {
         *self.total_tokens_out.lock().unwrap() += output_tokens;
     }
 
+    /// Records token and prompt-cache counts from a provider response.
+    pub fn record_usage(&self, usage: &Usage) {
+        self.record_tokens(usage.prompt_tokens as u64, usage.completion_tokens as u64);
+        self.record_cache_tokens(
+            usage.cache_creation_tokens as u64,
+            usage.cache_read_tokens as u64,
+        );
+    }
+
+    /// Adds to the running prompt-cache totals. These are portions of the
+    /// input tokens passed to [`record_tokens`](Self::record_tokens).
+    pub fn record_cache_tokens(&self, cache_write_tokens: u64, cache_read_tokens: u64) {
+        *self.total_cache_write.lock().unwrap() += cache_write_tokens;
+        *self.total_cache_read.lock().unwrap() += cache_read_tokens;
+    }
+
+    /// Records a model routing decision.
+    pub fn record_route(&self, tier: &str, model: &str) {
+        let mut routes = self.routes.lock().unwrap();
+        *routes
+            .entry((tier.to_string(), model.to_string()))
+            .or_default() += 1;
+    }
+
+    /// Returns routing decision counts keyed by `(tier, model)`.
+    pub fn route_counts(&self) -> HashMap<(String, String), u64> {
+        self.routes.lock().unwrap().clone()
+    }
+
+    /// Records a tool call whose arguments failed schema validation.
+    ///
+    /// The tool never ran, so this is tracked apart from
+    /// [`record_tool_call`](Self::record_tool_call) and does not affect
+    /// call counts or latency.
+    pub fn record_schema_failure(&self, tool_name: &str) {
+        let mut failures = self.schema_failures.lock().unwrap();
+        *failures.entry(tool_name.to_string()).or_default() += 1;
+    }
+
+    /// Returns schema validation failure counts keyed by tool name.
+    pub fn schema_failure_counts(&self) -> HashMap<String, u64> {
+        self.schema_failures.lock().unwrap().clone()
+    }
+
+    /// Returns the total number of schema validation failures.
+    pub fn total_schema_failures(&self) -> u64 {
+        self.schema_failures.lock().unwrap().values().sum()
+    }
+
     /// Returns a clone of the metrics for a specific tool, or `None` if the
     /// tool has never been called.
     pub fn tool_metrics(&self, tool_name: &str) -> Option<ToolMetrics> {
@@ -122,6 +183,13 @@ impl MetricsCollector {
         (input, output)
     }
 
+    /// Returns the running prompt-cache totals as `(written, read)`.
+    pub fn total_cache_tokens(&self) -> (u64, u64) {
+        let write = *self.total_cache_write.lock().unwrap();
+        let read = *self.total_cache_read.lock().unwrap();
+        (write, read)
+    }
+
     /// Returns the elapsed time since the collector was created.
     pub fn session_duration(&self) -> Duration {
         self.session_start.elapsed()
@@ -190,6 +258,17 @@ impl MetricsCollector {
             "Session: {}s | Tools: {} calls ({} errors) | Tokens: {} in / {} out",
             session_secs, total_calls, total_errors, tokens_in, tokens_out,
         );
+        let (cache_write, cache_read) = self.total_cache_tokens();
+        if cache_write > 0 || cache_read > 0 {
+            summary.push_str(&format!(
+                " (cache: {} written / {} read)",
+                cache_write, cache_read
+            ));
+        }
+        let schema_failures = self.total_schema_failures();
+        if schema_failures > 0 {
+            summary.push_str(&format!(" | Schema failures: {}", schema_failures));
+        }
 
         // Sort tools by call_count descending.
         let mut entries: Vec<_> = tools.iter().collect();
@@ -303,6 +382,36 @@ mod tests {
         assert_eq!(collector.total_tokens(), (1500, 800));
     }
 
+    #[test]
+    fn test_record_cache_tokens() {
+        let collector = MetricsCollector::new();
+        assert_eq!(collector.total_cache_tokens(), (0, 0));
+        assert!(!collector.summary().contains("cache:"));
+
+        collector.record_tokens(2000, 100);
+        collector.record_cache_tokens(1500, 0);
+        collector.record_cache_tokens(0, 1800);
+
+        assert_eq!(collector.total_cache_tokens(), (1500, 1800));
+        assert!(collector
+            .summary()
+            .contains("Tokens: 2000 in / 100 out (cache: 1500 written / 1800 read)"));
+    }
+
+    #[test]
+    fn test_record_schema_failure() {
+        let collector = MetricsCollector::new();
+
+        collector.record_schema_failure("read_file");
+        collector.record_schema_failure("read_file");
+        collector.record_schema_failure("shell");
+
+        assert_eq!(collector.total_schema_failures(), 3);
+        assert_eq!(collector.schema_failure_counts()["read_file"], 2);
+        assert_eq!(collector.total_tool_calls(), 0);
+        assert!(collector.summary().contains("| Schema failures: 3"));
+    }
+
     #[test]
     fn test_total_tool_calls() {
         let collector = MetricsCollector::new();
@@ -61,11 +61,15 @@ pub fn render(collector: &MetricsCollector, format: &TelemetryFormat) -> String
 /// Metric families emitted:
 /// - `zeptoclaw_tool_calls_total` (counter)
 /// - `zeptoclaw_tool_errors_total` (counter)
+/// - `zeptoclaw_tool_schema_failures_total` (counter)
 /// - `zeptoclaw_tool_duration_seconds_sum` (counter)
 /// - `zeptoclaw_tool_duration_seconds_min` (gauge)
 /// - `zeptoclaw_tool_duration_seconds_max` (gauge)
 /// - `zeptoclaw_tokens_input_total` (counter)
 /// - `zeptoclaw_tokens_output_total` (counter)
+/// - `zeptoclaw_tokens_cache_write_total` (counter)
+/// - `zeptoclaw_tokens_cache_read_total` (counter)
+/// - `zeptoclaw_router_decisions_total` (counter)
 /// - `zeptoclaw_session_duration_seconds` (gauge)
 pub fn render_prometheus(collector: &MetricsCollector) -> String {
     let mut out = String::new();
@@ -93,6 +97,19 @@ pub fn render_prometheus(collector: &MetricsCollector) -> String {
         ));
     }
 
+    // --- tool schema failures total ---
+    let schema_failures: BTreeMap<_, _> = collector.schema_failure_counts().into_iter().collect();
+    out.push_str(
+        "# HELP zeptoclaw_tool_schema_failures_total Tool calls rejected by argument schema validation.\n",
+    );
+    out.push_str("# TYPE zeptoclaw_tool_schema_failures_total counter\n");
+    for (name, count) in &schema_failures {
+        out.push_str(&format!(
+            "zeptoclaw_tool_schema_failures_total{{tool=\"{}\"}} {}\n",
+            name, count,
+        ));
+    }
+
     // --- tool duration sum ---
     out.push_str(
         "# HELP zeptoclaw_tool_duration_seconds_sum Cumulative tool call duration in seconds.\n",
@@ -143,6 +160,37 @@ pub fn render_prometheus(collector: &MetricsCollector) -> String {
     out.push_str("# TYPE zeptoclaw_tokens_output_total counter\n");
     out.push_str(&format!("zeptoclaw_tokens_output_total {}\n", tokens_out));
 
+    let (cache_write, cache_read) = collector.total_cache_tokens();
+
+    out.push_str(
+        "# HELP zeptoclaw_tokens_cache_write_total Input tokens written to the prompt cache.\n",
+    );
+    out.push_str("# TYPE zeptoclaw_tokens_cache_write_total counter\n");
+    out.push_str(&format!(
+        "zeptoclaw_tokens_cache_write_total {}\n",
+        cache_write
+    ));
+
+    out.push_str(
+        "# HELP zeptoclaw_tokens_cache_read_total Input tokens read from the prompt cache.\n",
+    );
+    out.push_str("# TYPE zeptoclaw_tokens_cache_read_total counter\n");
+    out.push_str(&format!(
+        "zeptoclaw_tokens_cache_read_total {}\n",
+        cache_read
+    ));
+
+    // --- model routing ---
+    let routes: BTreeMap<_, _> = collector.route_counts().into_iter().collect();
+    out.push_str("# HELP zeptoclaw_router_decisions_total Requests routed to each model tier.\n");
+    out.push_str("# TYPE zeptoclaw_router_decisions_total counter\n");
+    for ((tier, model), count) in &routes {
+        out.push_str(&format!(
+            "zeptoclaw_router_decisions_total{{tier=\"{}\",model=\"{}\"}} {}\n",
+            tier, model, count,
+        ));
+    }
+
     // --- session duration ---
     out.push_str("# HELP zeptoclaw_session_duration_seconds Session uptime in seconds.\n");
     out.push_str("# TYPE zeptoclaw_session_duration_seconds gauge\n");
@@ -168,8 +216,12 @@ pub fn render_prometheus(collector: &MetricsCollector) -> String {
 ///       "max_duration_seconds": 0.5
 ///     }
 ///   },
+///   "tool_schema_failures": { "read_file": 2 },
 ///   "tokens_input_total": 1500,
 ///   "tokens_output_total": 800,
+///   "tokens_cache_write_total": 0,
+///   "tokens_cache_read_total": 1200,
+///   "router_decisions": { "simple": { "claude-haiku-4-5": 3 } },
 ///   "session_duration_seconds": 45.0
 /// }
 /// ```
@@ -192,11 +244,23 @@ pub fn render_json(collector: &MetricsCollector) -> String {
     }
 
     let (tokens_in, tokens_out) = collector.total_tokens();
+    let (cache_write, cache_read) = collector.total_cache_tokens();
+
+    let mut routes: BTreeMap<String, BTreeMap<String, u64>> = BTreeMap::new();
+    for ((tier, model), count) in collector.route_counts() {
+        routes.entry(tier).or_default().insert(model, count);
+    }
+
+    let schema_failures: BTreeMap<_, _> = collector.schema_failure_counts().into_iter().collect();
 
     let root = serde_json::json!({
         "tools": tools_json,
+        "tool_schema_failures": schema_failures,
         "tokens_input_total": tokens_in,
         "tokens_output_total": tokens_out,
+        "tokens_cache_write_total": cache_write,
+        "tokens_cache_read_total": cache_read,
+        "router_decisions": routes,
         "session_duration_seconds": collector.session_duration().as_secs_f64(),
     });
 
@@ -329,11 +393,14 @@ mod tests {
         let expected_families = [
             "zeptoclaw_tool_calls_total",
             "zeptoclaw_tool_errors_total",
+            "zeptoclaw_tool_schema_failures_total",
             "zeptoclaw_tool_duration_seconds_sum",
             "zeptoclaw_tool_duration_seconds_min",
             "zeptoclaw_tool_duration_seconds_max",
             "zeptoclaw_tokens_input_total",
             "zeptoclaw_tokens_output_total",
+            "zeptoclaw_tokens_cache_write_total",
+            "zeptoclaw_tokens_cache_read_total",
             "zeptoclaw_session_duration_seconds",
         ];
 
@@ -365,6 +432,8 @@ mod tests {
         assert_eq!(parsed["tools"], serde_json::json!({}));
         assert_eq!(parsed["tokens_input_total"], 0);
         assert_eq!(parsed["tokens_output_total"], 0);
+        assert_eq!(parsed["tokens_cache_write_total"], 0);
+        assert_eq!(parsed["tokens_cache_read_total"], 0);
         assert!(parsed["session_duration_seconds"].as_f64().unwrap() >= 0.0);
     }
 
@@ -391,6 +460,35 @@ mod tests {
         assert_eq!(parsed["tokens_output_total"], 200);
     }
 
+    #[test]
+    fn test_render_router_decisions() {
+        let collector = MetricsCollector::new();
+        collector.record_route("simple", "claude-haiku-4-5");
+        collector.record_route("simple", "claude-haiku-4-5");
+        collector.record_route("complex", "claude-opus-4-6");
+
+        let prom = render_prometheus(&collector);
+        assert!(prom.contains(
+            "zeptoclaw_router_decisions_total{tier=\"simple\",model=\"claude-haiku-4-5\"} 2"
+        ));
+
+        let parsed: serde_json::Value = serde_json::from_str(&render_json(&collector)).unwrap();
+        assert_eq!(parsed["router_decisions"]["complex"]["claude-opus-4-6"], 1);
+    }
+
+    #[test]
+    fn test_render_schema_failures() {
+        let collector = MetricsCollector::new();
+        collector.record_schema_failure("read_file");
+        collector.record_schema_failure("read_file");
+
+        let prom = render_prometheus(&collector);
+        assert!(prom.contains("zeptoclaw_tool_schema_failures_total{tool=\"read_file\"} 2"));
+
+        let parsed: serde_json::Value = serde_json::from_str(&render_json(&collector)).unwrap();
+        assert_eq!(parsed["tool_schema_failures"]["read_file"], 2);
+    }
+
     // -- render dispatches correctly --
 
     #[test]
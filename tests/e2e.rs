@@ -111,6 +111,7 @@ impl LLMProvider for MockToolCallingProvider {
                     r#"{"message": "e2e-tool-test"}"#,
                 )],
                 usage: None,
+                thinking: Vec::new(),
             })
         } else {
             // Subsequent call: return final text
@@ -209,11 +210,8 @@ impl LLMProvider for MockTokenCountingProvider {
         Ok(LLMResponse {
             content: format!("response {}", count),
             tool_calls: vec![],
-            usage: Some(zeptoclaw::providers::Usage {
-                prompt_tokens: 500,
-                completion_tokens: 200,
-                total_tokens: 700,
-            }),
+            usage: Some(zeptoclaw::providers::Usage::new(500, 200)),
+            thinking: Vec::new(),
         })
     }
 }